@@ -22,11 +22,35 @@
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
 
+use goose::extraction::GooseExtraction;
 use goose::prelude::*;
+use goose::validation::GooseValidation;
 
+use lazy_static::lazy_static;
 use rand::Rng;
 use regex::Regex;
 
+lazy_static! {
+    /// Extracts the form_build_id from the user login form.
+    static ref LOGIN_FORM: GooseExtraction = GooseExtraction::new()
+        .regex(
+            "form_build_id",
+            r#"name="form_build_id" value=['"](.*?)['"]"#,
+        )
+        .unwrap();
+    /// Extracts the form_build_id, form_token and form_id from the comment form.
+    static ref COMMENT_FORM: GooseExtraction = GooseExtraction::new()
+        .regex(
+            "form_build_id",
+            r#"name="form_build_id" value=['"](.*?)['"]"#,
+        )
+        .unwrap()
+        .regex("form_token", r#"name="form_token" value=['"](.*?)['"]"#)
+        .unwrap()
+        .regex("form_id", r#"name="form_id" value=['"](.*?)['"]"#)
+        .unwrap();
+}
+
 #[tokio::main]
 async fn main() -> Result<(), GooseError> {
     GooseAttack::initialize()?
@@ -152,67 +176,24 @@ async fn drupal_memcache_profile_page(user: &mut GooseUser) -> GooseTaskResult {
 
 /// Log in.
 async fn drupal_memcache_login(user: &mut GooseUser) -> GooseTaskResult {
-    let mut goose = user.get("/user").await?;
-
-    match goose.response {
-        Ok(response) => {
-            // Copy the headers so we have them for logging if there are errors.
-            let headers = &response.headers().clone();
-            match response.text().await {
-                Ok(html) => {
-                    let re = Regex::new(r#"name="form_build_id" value=['"](.*?)['"]"#).unwrap();
-                    let form_build_id = match re.captures(&html) {
-                        Some(f) => f,
-                        None => {
-                            // This will automatically get written to the error log if enabled, and will
-                            // be displayed to stdout if `-v` is enabled when running the load test.
-                            return user.set_failure(
-                                "login: no form_build_id on page: /user page",
-                                &mut goose.request,
-                                Some(headers),
-                                Some(&html),
-                            );
-                        }
-                    };
-
-                    // Log the user in.
-                    let uid: usize = rand::thread_rng().gen_range(3..5_002);
-                    let username = format!("user{}", uid);
-                    let params = [
-                        ("name", username.as_str()),
-                        ("pass", "12345"),
-                        ("form_build_id", &form_build_id[1]),
-                        ("form_id", "user_login"),
-                        ("op", "Log+in"),
-                    ];
-                    let request_builder = user.goose_post("/user")?;
-                    let _goose = user.goose_send(request_builder.form(&params), None).await;
-                    // @TODO: verify that we actually logged in.
-                }
-                Err(e) => {
-                    // This will automatically get written to the error log if enabled, and will
-                    // be displayed to stdout if `-v` is enabled when running the load test.
-                    return user.set_failure(
-                        &format!("login: unexpected error when loading /user page: {}", e),
-                        &mut goose.request,
-                        Some(headers),
-                        None,
-                    );
-                }
-            }
-        }
-        // Goose will catch this error.
-        Err(e) => {
-            // This will automatically get written to the error log if enabled, and will
-            // be displayed to stdout if `-v` is enabled when running the load test.
-            return user.set_failure(
-                &format!("login: no response from server: {}", e),
-                &mut goose.request,
-                None,
-                None,
-            );
-        }
-    }
+    // Extract the form_build_id from the user login form.
+    let goose = user.get("/user").await?;
+    goose.extract(user, &LOGIN_FORM).await?;
+
+    // Log the user in.
+    let uid: usize = rand::thread_rng().gen_range(3..5_002);
+    let username = format!("user{}", uid);
+    let form_build_id = user.get_variable("form_build_id").unwrap_or_default();
+    let params = [
+        ("name", username.as_str()),
+        ("pass", "12345"),
+        ("form_build_id", form_build_id),
+        ("form_id", "user_login"),
+        ("op", "Log+in"),
+    ];
+    let request_builder = user.goose_post("/user")?;
+    let _goose = user.goose_send(request_builder.form(&params), None).await;
+    // @TODO: verify that we actually logged in.
 
     Ok(())
 }
@@ -223,162 +204,34 @@ async fn drupal_memcache_post_comment(user: &mut GooseUser) -> GooseTaskResult {
     let node_path = format!("node/{}", &nid);
     let comment_path = format!("/comment/reply/{}", &nid);
 
-    let mut goose = user.get(&node_path).await?;
-
-    match goose.response {
-        Ok(response) => {
-            // Copy the headers so we have them for logging if there are errors.
-            let headers = &response.headers().clone();
-            match response.text().await {
-                Ok(html) => {
-                    // Extract the form_build_id from the user login form.
-                    let re = Regex::new(r#"name="form_build_id" value=['"](.*?)['"]"#).unwrap();
-                    let form_build_id = match re.captures(&html) {
-                        Some(f) => f,
-                        None => {
-                            // This will automatically get written to the error log if enabled, and will
-                            // be displayed to stdout if `-v` is enabled when running the load test.
-                            return user.set_failure(
-                                &format!("post_comment: no form_build_id found on {}", &node_path),
-                                &mut goose.request,
-                                Some(headers),
-                                Some(&html),
-                            );
-                        }
-                    };
-
-                    let re = Regex::new(r#"name="form_token" value=['"](.*?)['"]"#).unwrap();
-                    let form_token = match re.captures(&html) {
-                        Some(f) => f,
-                        None => {
-                            // This will automatically get written to the error log if enabled, and will
-                            // be displayed to stdout if `-v` is enabled when running the load test.
-                            return user.set_failure(
-                                &format!("post_comment: no form_token found on {}", &node_path),
-                                &mut goose.request,
-                                Some(headers),
-                                Some(&html),
-                            );
-                        }
-                    };
-
-                    let re = Regex::new(r#"name="form_id" value=['"](.*?)['"]"#).unwrap();
-                    let form_id = match re.captures(&html) {
-                        Some(f) => f,
-                        None => {
-                            // This will automatically get written to the error log if enabled, and will
-                            // be displayed to stdout if `-v` is enabled when running the load test.
-                            return user.set_failure(
-                                &format!("post_comment: no form_id found on {}", &node_path),
-                                &mut goose.request,
-                                Some(headers),
-                                Some(&html),
-                            );
-                        }
-                    };
-                    // Optionally uncomment to log form_id, form_build_id, and form_token, together with
-                    // the full body of the page. This is useful when modifying the load test.
-                    /*
-                    user.log_debug(
-                        &format!(
-                            "form_id: {}, form_build_id: {}, form_token: {}",
-                            &form_id[1], &form_build_id[1], &form_token[1]
-                        ),
-                        Some(&goose.request),
-                        Some(headers),
-                        Some(&html),
-                    );
-                    */
-
-                    let comment_body = "this is a test comment body";
-                    let params = [
-                        ("subject", "this is a test comment subject"),
-                        ("comment_body[und][0][value]", comment_body),
-                        ("comment_body[und][0][format]", "filtered_html"),
-                        ("form_build_id", &form_build_id[1]),
-                        ("form_token", &form_token[1]),
-                        ("form_id", &form_id[1]),
-                        ("op", "Save"),
-                    ];
-
-                    // Post the comment.
-                    let request_builder = user.goose_post(&comment_path)?;
-                    let mut goose = user.goose_send(request_builder.form(&params), None).await?;
-
-                    // Verify that the comment posted.
-                    match goose.response {
-                        Ok(response) => {
-                            // Copy the headers so we have them for logging if there are errors.
-                            let headers = &response.headers().clone();
-                            match response.text().await {
-                                Ok(html) => {
-                                    if !html.contains(&comment_body) {
-                                        // This will automatically get written to the error log if enabled, and will
-                                        // be displayed to stdout if `-v` is enabled when running the load test.
-                                        return user.set_failure(
-                                            &format!("post_comment: no comment showed up after posting to {}", &comment_path),
-                                            &mut goose.request,
-                                            Some(headers),
-                                            Some(&html),
-                                        );
-                                    }
-                                }
-                                Err(e) => {
-                                    // This will automatically get written to the error log if enabled, and will
-                                    // be displayed to stdout if `-v` is enabled when running the load test.
-                                    return user.set_failure(
-                                        &format!(
-                                            "post_comment: unexpected error when posting to {}: {}",
-                                            &comment_path, e
-                                        ),
-                                        &mut goose.request,
-                                        Some(headers),
-                                        None,
-                                    );
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            // This will automatically get written to the error log if enabled, and will
-                            // be displayed to stdout if `-v` is enabled when running the load test.
-                            return user.set_failure(
-                                &format!(
-                                    "post_comment: no response when posting to {}: {}",
-                                    &comment_path, e
-                                ),
-                                &mut goose.request,
-                                None,
-                                None,
-                            );
-                        }
-                    }
-                }
-                Err(e) => {
-                    // This will automatically get written to the error log if enabled, and will
-                    // be displayed to stdout if `-v` is enabled when running the load test.
-                    return user.set_failure(
-                        &format!("post_comment: no text when loading {}: {}", &node_path, e),
-                        &mut goose.request,
-                        None,
-                        None,
-                    );
-                }
-            }
-        }
-        Err(e) => {
-            // This will automatically get written to the error log if enabled, and will
-            // be displayed to stdout if `-v` is enabled when running the load test.
-            return user.set_failure(
-                &format!(
-                    "post_comment: no response when loading {}: {}",
-                    &node_path, e
-                ),
-                &mut goose.request,
-                None,
-                None,
-            );
-        }
-    }
+    // Extract the form_build_id, form_token and form_id from the comment form.
+    let goose = user.get(&node_path).await?;
+    goose.extract(user, &COMMENT_FORM).await?;
+
+    let comment_body = "this is a test comment body";
+    let params = [
+        ("subject", "this is a test comment subject"),
+        ("comment_body[und][0][value]", comment_body),
+        ("comment_body[und][0][format]", "filtered_html"),
+        (
+            "form_build_id",
+            user.get_variable("form_build_id").unwrap_or_default(),
+        ),
+        (
+            "form_token",
+            user.get_variable("form_token").unwrap_or_default(),
+        ),
+        ("form_id", user.get_variable("form_id").unwrap_or_default()),
+        ("op", "Save"),
+    ];
+
+    // Post the comment.
+    let request_builder = user.goose_post(&comment_path)?.form(&params);
+    let goose = user.goose_send(request_builder, None).await?;
+
+    // Verify that the comment posted.
+    let validation = GooseValidation::new().body_contains(comment_body);
+    goose.validate(user, &validation).await?;
 
     Ok(())
 }
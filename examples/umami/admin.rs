@@ -44,7 +44,7 @@ pub async fn log_in(user: &mut GooseUser) -> GooseTaskResult {
                     }
 
                     // Load all static elements on the page, as a real user would.
-                    common::load_static_elements(user, &html).await;
+                    common::load_static_elements(user, &goose.request.final_url, &html).await;
 
                     // Scrape the HTML to get the values needed in order to POST to the
                     // log in form.
@@ -139,7 +139,7 @@ pub async fn edit_article(user: &mut GooseUser) -> GooseTaskResult {
                     }
 
                     // Load all static elements on the page, as a real user would.
-                    common::load_static_elements(user, &html).await;
+                    common::load_static_elements(user, &goose.request.final_url, &html).await;
 
                     // Scrape the HTML to get the values needed in order to POST to the
                     // log in form.
@@ -4,6 +4,7 @@ use goose::prelude::*;
 use rand::prelude::IteratorRandom;
 use rand::seq::SliceRandom;
 use regex::Regex;
+use url::Url;
 
 /// The Umami website defines three content types.
 pub enum ContentType {
@@ -420,31 +421,11 @@ pub fn valid_title(html: &str, title: &str) -> bool {
     html.contains(&("<title>".to_string() + title))
 }
 
-/// Finds all local static elements on the page and loads them asynchronously.
-/// This default profile only has local assets, so we can use simple patterns.
-pub async fn load_static_elements(user: &mut GooseUser, html: &str) {
-    // Use a regular expression to find all src=<foo> in the HTML, where foo
-    // is the URL to image and js assets.
-    // @TODO: parse HTML5 srcset= also
-    let image = Regex::new(r#"src="(.*?)""#).unwrap();
-    let mut urls = Vec::new();
-    for url in image.captures_iter(html) {
-        if url[1].starts_with("/sites") || url[1].starts_with("/core") {
-            urls.push(url[1].to_string());
-        }
-    }
-
-    // Use a regular expression to find all href=<foo> in the HTML, where foo
-    // is the URL to css assets.
-    let css = Regex::new(r#"href="(/sites/default/files/css/.*?)""#).unwrap();
-    for url in css.captures_iter(html) {
-        urls.push(url[1].to_string());
-    }
-
-    // Load all the static assets found on the page.
-    for asset in &urls {
-        let _ = user.get_named(asset, "static asset").await;
-    }
+/// Loads all local images, scripts and stylesheets found on the page, like a browser.
+/// Relative URLs are relative to the URL the page was loaded from.
+pub async fn load_static_elements(user: &mut GooseUser, page_url: &str, html: &str) {
+    let page_url = Url::parse(page_url).unwrap_or_else(|_| user.base_url.clone());
+    let _ = user.load_static_assets(&page_url, html).await;
 }
 
 /// Validate the HTML response, confirming the expected title was returned, then load
@@ -469,7 +450,7 @@ pub async fn validate_and_load_static_assets(
                         );
                     }
 
-                    load_static_elements(user, &html).await;
+                    load_static_elements(user, &goose.request.final_url, &html).await;
                 }
                 Err(e) => {
                     return user.set_failure(
@@ -537,7 +518,7 @@ pub async fn anonymous_contact_form(user: &mut GooseUser, english: bool) -> Goos
                     }
 
                     // Load all static elements on the page, as a real user would.
-                    load_static_elements(user, &html).await;
+                    load_static_elements(user, &goose.request.final_url, &html).await;
 
                     // Scrape the HTML to get the values needed in order to POST to the
                     // contact form.
@@ -610,7 +591,7 @@ pub async fn anonymous_contact_form(user: &mut GooseUser, english: bool) -> Goos
 
                     // Either way, a "real" user would still load all static elements on
                     // the returned page.
-                    load_static_elements(user, &html).await;
+                    load_static_elements(user, &goose.request.final_url, &html).await;
                 }
                 Err(e) => {
                     return user.set_failure(
@@ -669,7 +650,7 @@ pub async fn search(user: &mut GooseUser, english: bool) -> GooseTaskResult {
                     }
 
                     // Load all static elements on the page, as a real user would.
-                    load_static_elements(user, &html).await;
+                    load_static_elements(user, &goose.request.final_url, &html).await;
 
                     // Scrape the HTML to get the values needed in order to POST to the
                     // search form.
@@ -744,7 +725,7 @@ pub async fn search(user: &mut GooseUser, english: bool) -> GooseTaskResult {
                             Some(&html),
                         );
                     }
-                    load_static_elements(user, &html).await;
+                    load_static_elements(user, &goose.request.final_url, &html).await;
 
                     // @TODO: get all href="" inside class="search-result__title" and load random node
                 }
@@ -0,0 +1,152 @@
+//! Optional open-model executor, starting iterations at a fixed arrival rate.
+//!
+//! By default each [`GooseUser`](../goose/struct.GooseUser.html) is a closed loop, only
+//! starting its next [`GooseTask`](../goose/struct.GooseTask.html) after the previous one
+//! completes. When configured with `--arrival-rate`, the parent instead schedules
+//! iterations through all of a [`GooseTaskSet`](../goose/struct.GooseTaskSet.html)'s
+//! tasks at a fixed rate, and each iteration is run by an idle
+//! [`GooseUser`](../goose/struct.GooseUser.html) from a pre-launched pool. If no
+//! [`GooseUser`](../goose/struct.GooseUser.html) is idle the pool grows, up to
+//! `--max-users`, after which iterations are dropped. Delayed and dropped iterations are
+//! tracked in [`GooseIterationMetrics`](../metrics/struct.GooseIterationMetrics.html).
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::metrics::GooseIterationMetrics;
+use crate::util;
+use crate::{AttackPhase, GooseAttack, GooseAttackRunState, GooseError};
+
+/// Shared by all [`GooseUser`](../goose/struct.GooseUser.html)s in the pool to receive
+/// iterations from the parent.
+#[derive(Clone, Debug)]
+pub(crate) struct GooseIterationReceiver {
+    /// Each message received is one iteration to run.
+    pub(crate) receiver: flume::Receiver<()>,
+    /// How many [`GooseUser`](../goose/struct.GooseUser.html)s are currently running an
+    /// iteration.
+    pub(crate) busy_users: Arc<AtomicUsize>,
+}
+
+/// The parent process side of starting iterations at a fixed arrival rate.
+impl GooseAttack {
+    // Returns the configured arrival rate, if any.
+    pub(crate) fn get_arrival_rate(&self) -> Option<f32> {
+        // The arrival rate is validated in GooseConfiguration::validate().
+        self.configuration
+            .arrival_rate
+            .as_ref()
+            .and_then(|r| r.parse::<f32>().ok())
+    }
+
+    // If an arrival rate is configured, create the channel used to send iterations to
+    // GooseUsers and prepare to collect iteration metrics.
+    pub(crate) fn initialize_iterations(
+        &mut self,
+        goose_attack_run_state: &mut GooseAttackRunState,
+    ) {
+        if let Some(rate) = self.get_arrival_rate() {
+            let (iterations_tx, iterations_rx) = flume::unbounded();
+            goose_attack_run_state.iterations_tx = Some(iterations_tx);
+            goose_attack_run_state.iterations_rx = Some(GooseIterationReceiver {
+                receiver: iterations_rx,
+                busy_users: Arc::new(AtomicUsize::new(0)),
+            });
+            self.metrics.iterations = Some(GooseIterationMetrics {
+                rate,
+                ..Default::default()
+            });
+        } else {
+            goose_attack_run_state.iterations_tx = None;
+            goose_attack_run_state.iterations_rx = None;
+        }
+    }
+
+    // Start all iterations that are due, growing the pool of GooseUsers if necessary.
+    fn start_iterations(
+        &mut self,
+        goose_attack_run_state: &mut GooseAttackRunState,
+    ) -> Result<(), GooseError> {
+        // Arrival rate is required to get here, so unwrap() is safe.
+        let rate = self.get_arrival_rate().unwrap() as f64;
+        // The first iteration starts immediately, then one every 1/rate seconds.
+        let due = (self.started.unwrap().elapsed().as_secs_f64() * rate) as usize + 1;
+
+        // Channels are created when the arrival rate is configured, so unwrap() is safe.
+        let iterations_tx = goose_attack_run_state.iterations_tx.clone().unwrap();
+        let busy_users = goose_attack_run_state
+            .iterations_rx
+            .as_ref()
+            .unwrap()
+            .busy_users
+            .clone();
+        let max_users = self
+            .configuration
+            .max_users
+            .unwrap_or_else(|| self.configuration.users.unwrap());
+
+        while self.metrics.iterations.as_ref().unwrap().scheduled < due {
+            let pool = goose_attack_run_state.user_channels.len();
+            // GooseUsers already running an iteration, plus iterations queued for the next
+            // idle GooseUser.
+            let waiting = busy_users.load(Ordering::SeqCst) + iterations_tx.len();
+
+            let iteration_metrics = self.metrics.iterations.as_mut().unwrap();
+            iteration_metrics.scheduled += 1;
+            if pool > waiting {
+                // There's an idle GooseUser to run the iteration.
+            } else if pool < max_users {
+                // Grow the pool, the new GooseUser runs the iteration after starting up.
+                iteration_metrics.delayed += 1;
+                let thread_user = self.next_weighted_user()?;
+                self.launch_user(goose_attack_run_state, thread_user);
+            } else {
+                // The pool is full, drop the iteration.
+                iteration_metrics.dropped += 1;
+                debug!("no idle users, dropping iteration");
+                continue;
+            }
+
+            if iterations_tx.send(()).is_err() {
+                // All GooseUsers have exited.
+                self.metrics.iterations.as_mut().unwrap().dropped += 1;
+            }
+        }
+
+        let iteration_metrics = self.metrics.iterations.as_mut().unwrap();
+        iteration_metrics.peak_users = iteration_metrics
+            .peak_users
+            .max(goose_attack_run_state.user_channels.len());
+
+        Ok(())
+    }
+
+    // In the Running phase, start iterations at the configured arrival rate until the
+    // run_time timer expires (or the test is canceled).
+    pub(crate) async fn monitor_arrival_rate(
+        &mut self,
+        goose_attack_run_state: &mut GooseAttackRunState,
+    ) -> Result<(), GooseError> {
+        // Exit if run_time timer expires.
+        if util::timer_expired(self.started.unwrap(), self.run_time) {
+            self.set_attack_phase(goose_attack_run_state, AttackPhase::Stopping);
+            self.metrics.stopping = Some(chrono::Local::now());
+            return Ok(());
+        }
+
+        self.start_iterations(goose_attack_run_state)?;
+
+        // Sleep until the next iteration is due, waking up at least twice a second.
+        let rate = self.get_arrival_rate().unwrap() as f64;
+        let scheduled = self.metrics.iterations.as_ref().unwrap().scheduled;
+        let next_iteration = std::time::Duration::from_secs_f64(scheduled as f64 / rate);
+        let sleep_duration = next_iteration
+            .saturating_sub(self.started.unwrap().elapsed())
+            .min(std::time::Duration::from_millis(500));
+        debug!("sleeping {:?}...", sleep_duration);
+        tokio::time::sleep(sleep_duration).await;
+        goose_attack_run_state.drift_timer = tokio::time::Instant::now();
+
+        Ok(())
+    }
+}
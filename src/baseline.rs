@@ -0,0 +1,322 @@
+//! Optional comparison of a load test against a saved baseline.
+//!
+//! A baseline is a JSON summary of an earlier load test, written with the `--summary-json`
+//! run-time option. When a baseline is loaded with the `--baseline` run-time option, or
+//! with [`GooseDefault::Baseline`](../config/enum.GooseDefault.html#variant.Baseline), the
+//! final metrics of each request and task are compared against the baseline. How much
+//! requests per second, average, 95th and 99th percentile times, and failure rates
+//! changed is displayed with the metrics and in the html report, and stored in
+//! [`GooseMetrics::baseline`](../metrics/struct.GooseMetrics.html#structfield.baseline).
+//!
+//! A change is a regression if it's worse than the baseline by more than the tolerance,
+//! 10% by default, configured with the `--baseline-tolerance` run-time option. For
+//! example with the default tolerance, a request is a regression if its requests per
+//! second dropped by more than 10%, or if its average response time grew by more than 10%.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::summary::{GooseSummary, GooseSummaryTimes, SUMMARY_SCHEMA_VERSION};
+use crate::{GooseAttack, GooseError};
+
+/// Response time percentiles compared against a baseline, always included in summaries.
+pub(crate) const BASELINE_PERCENTILES: [f64; 2] = [95.0, 99.0];
+
+/// Tolerance in percent used if `--baseline-tolerance` isn't configured.
+pub(crate) const DEFAULT_BASELINE_TOLERANCE: usize = 10;
+
+/// How one metric changed compared to the baseline.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GooseBaselineDelta {
+    /// The value in the baseline, or None if it's not in the baseline.
+    pub baseline: Option<f64>,
+    /// The value in this load test.
+    pub current: f64,
+    /// Whether the value is worse than the baseline by more than the tolerance.
+    pub regression: bool,
+}
+impl GooseBaselineDelta {
+    // Compare a value against the baseline. If `higher_is_worse` a regression is an
+    // increase, otherwise it's a decrease.
+    fn new(baseline: Option<f64>, current: f64, higher_is_worse: bool, tolerance: usize) -> Self {
+        let tolerance = tolerance as f64 / 100.0;
+        let regression = match baseline {
+            Some(baseline) if higher_is_worse => {
+                current > baseline && current > baseline * (1.0 + tolerance)
+            }
+            Some(baseline) => current < baseline * (1.0 - tolerance),
+            None => false,
+        };
+        GooseBaselineDelta {
+            baseline,
+            current,
+            regression,
+        }
+    }
+
+    /// The change from the baseline in percent, or None if it's not in the baseline or the
+    /// baseline was 0.
+    pub fn change(&self) -> Option<f64> {
+        match self.baseline {
+            Some(baseline) if baseline != 0.0 => Some((self.current - baseline) * 100.0 / baseline),
+            Some(_) if self.current == 0.0 => Some(0.0),
+            _ => None,
+        }
+    }
+}
+impl fmt::Display for GooseBaselineDelta {
+    // Display the change from the baseline.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.baseline, self.change()) {
+            (_, Some(change)) => write!(f, "{:+.1}%", change),
+            (Some(_), None) => write!(f, "from 0"),
+            (None, _) => write!(f, "-"),
+        }
+    }
+}
+
+/// How one request or task changed compared to the baseline.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GooseBaselineRow {
+    /// The request as displayed in the metrics, ie `GET /`, or the task index and name.
+    pub name: String,
+    /// Requests (or task runs) per second.
+    pub requests_per_second: GooseBaselineDelta,
+    /// Average response (or run) time in milliseconds.
+    pub average: GooseBaselineDelta,
+    /// 95th percentile response (or run) time in milliseconds.
+    pub p95: GooseBaselineDelta,
+    /// 99th percentile response (or run) time in milliseconds.
+    pub p99: GooseBaselineDelta,
+    /// Percentage of requests (or task runs) that failed.
+    pub failure_rate: GooseBaselineDelta,
+}
+impl GooseBaselineRow {
+    fn new(
+        name: String,
+        baseline: Option<(f64, &GooseSummaryTimes, f64)>,
+        current: (f64, &GooseSummaryTimes, f64),
+        tolerance: usize,
+    ) -> Self {
+        let (requests_per_second, times, failure_rate) = current;
+        GooseBaselineRow {
+            name,
+            requests_per_second: GooseBaselineDelta::new(
+                baseline.map(|(requests_per_second, _, _)| requests_per_second),
+                requests_per_second,
+                false,
+                tolerance,
+            ),
+            average: GooseBaselineDelta::new(
+                baseline.map(|(_, times, _)| times.average),
+                times.average,
+                true,
+                tolerance,
+            ),
+            p95: GooseBaselineDelta::new(
+                baseline.and_then(|(_, times, _)| percentile(times, "p95")),
+                percentile(times, "p95").unwrap_or(0.0),
+                true,
+                tolerance,
+            ),
+            p99: GooseBaselineDelta::new(
+                baseline.and_then(|(_, times, _)| percentile(times, "p99")),
+                percentile(times, "p99").unwrap_or(0.0),
+                true,
+                tolerance,
+            ),
+            failure_rate: GooseBaselineDelta::new(
+                baseline.map(|(_, _, failure_rate)| failure_rate),
+                failure_rate,
+                true,
+                tolerance,
+            ),
+        }
+    }
+
+    /// Whether any metric is worse than the baseline by more than the tolerance.
+    pub fn regression(&self) -> bool {
+        self.requests_per_second.regression
+            || self.average.regression
+            || self.p95.regression
+            || self.p99.regression
+            || self.failure_rate.regression
+    }
+}
+
+/// The final metrics of a load test compared against a baseline.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GooseBaselineComparison {
+    /// The path of the baseline.
+    pub baseline: String,
+    /// The tolerance in percent before a change is a regression.
+    pub tolerance: usize,
+    /// Each request, followed by all requests aggregated together.
+    pub requests: Vec<GooseBaselineRow>,
+    /// Each task.
+    pub tasks: Vec<GooseBaselineRow>,
+}
+impl GooseBaselineComparison {
+    /// Compare the summary of a load test against a baseline.
+    pub fn new(
+        path: &str,
+        baseline: &GooseSummary,
+        current: &GooseSummary,
+        tolerance: usize,
+    ) -> Self {
+        let mut requests = Vec::new();
+        for request in current.requests.iter().chain(Some(&current.aggregated)) {
+            let matching = baseline
+                .requests
+                .iter()
+                .chain(Some(&baseline.aggregated))
+                .find(|b| b.method == request.method && b.name == request.name);
+            let name = match request.method.as_ref() {
+                Some(method) => format!("{} {}", method, request.name),
+                None => request.name.to_string(),
+            };
+            requests.push(GooseBaselineRow::new(
+                name,
+                matching.map(|b| (b.requests_per_second, &b.response_times, b.failure_rate)),
+                (
+                    request.requests_per_second,
+                    &request.response_times,
+                    request.failure_rate,
+                ),
+                tolerance,
+            ));
+        }
+
+        let mut tasks = Vec::new();
+        for task in &current.tasks {
+            let matching = baseline
+                .tasks
+                .iter()
+                .find(|b| b.index == task.index && b.taskset == task.taskset);
+            let name = if task.name.is_empty() {
+                task.index.to_string()
+            } else {
+                format!("{} {}", task.index, task.name)
+            };
+            tasks.push(GooseBaselineRow::new(
+                name,
+                matching.map(|b| (b.runs_per_second, &b.run_times, b.failure_rate)),
+                (task.runs_per_second, &task.run_times, task.failure_rate),
+                tolerance,
+            ));
+        }
+
+        GooseBaselineComparison {
+            baseline: path.to_string(),
+            tolerance,
+            requests,
+            tasks,
+        }
+    }
+
+    /// How many requests and tasks regressed compared to the baseline.
+    pub fn regressions(&self) -> usize {
+        self.requests
+            .iter()
+            .chain(self.tasks.iter())
+            .filter(|row| row.regression())
+            .count()
+    }
+}
+
+/// Look up a percentile in summarized times.
+fn percentile(times: &GooseSummaryTimes, key: &str) -> Option<f64> {
+    times.percentiles.get(key).copied()
+}
+
+impl GooseAttack {
+    /// Load the baseline, if configured.
+    pub(crate) fn configure_baseline(&mut self) -> Result<(), GooseError> {
+        let path = match self.configuration.baseline.as_ref() {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+
+        let invalid = |detail: String| GooseError::InvalidOption {
+            option: "--baseline".to_string(),
+            value: path.to_string(),
+            detail,
+        };
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| invalid(format!("Failed to read baseline: {}", e)))?;
+        let baseline: serde_json::Value = serde_json::from_str(&contents)
+            .map_err(|e| invalid(format!("Failed to parse baseline: {}", e)))?;
+        // Check the schema version first, as newer summaries may not parse.
+        let schema_version = baseline["schema_version"].as_u64().unwrap_or(0);
+        if schema_version > SUMMARY_SCHEMA_VERSION as u64 {
+            return Err(invalid(format!(
+                "Baseline schema version {} is newer than the supported version {}.",
+                schema_version, SUMMARY_SCHEMA_VERSION
+            )));
+        }
+        self.baseline = Some(
+            serde_json::from_value(baseline)
+                .map_err(|e| invalid(format!("Failed to parse baseline: {}", e)))?,
+        );
+
+        Ok(())
+    }
+
+    /// Compare the final metrics against the baseline, if configured.
+    pub(crate) fn compare_baseline(&mut self) {
+        if let (Some(baseline), Some(path)) =
+            (self.baseline.as_ref(), self.configuration.baseline.as_ref())
+        {
+            let current = GooseSummary::new(&self.metrics, &self.configuration);
+            let tolerance = self
+                .configuration
+                .baseline_tolerance
+                .unwrap_or(DEFAULT_BASELINE_TOLERANCE);
+            self.metrics.baseline = Some(GooseBaselineComparison::new(
+                path, baseline, &current, tolerance,
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn deltas() {
+        // Response times: higher is worse.
+        let delta = GooseBaselineDelta::new(Some(100.0), 105.0, true, 10);
+        assert!(!delta.regression);
+        assert_eq!(delta.to_string(), "+5.0%");
+        let delta = GooseBaselineDelta::new(Some(100.0), 125.0, true, 10);
+        assert!(delta.regression);
+        assert_eq!(delta.to_string(), "+25.0%");
+        let delta = GooseBaselineDelta::new(Some(100.0), 50.0, true, 10);
+        assert!(!delta.regression);
+        assert_eq!(delta.to_string(), "-50.0%");
+
+        // Requests per second: lower is worse.
+        assert!(!GooseBaselineDelta::new(Some(100.0), 95.0, false, 10).regression);
+        assert!(GooseBaselineDelta::new(Some(100.0), 85.0, false, 10).regression);
+        assert!(!GooseBaselineDelta::new(Some(100.0), 200.0, false, 10).regression);
+
+        // Any increase from 0 is a regression.
+        let delta = GooseBaselineDelta::new(Some(0.0), 1.5, true, 10);
+        assert!(delta.regression);
+        assert_eq!(delta.change(), None);
+        assert_eq!(delta.to_string(), "from 0");
+        let delta = GooseBaselineDelta::new(Some(0.0), 0.0, true, 10);
+        assert!(!delta.regression);
+        assert_eq!(delta.to_string(), "+0.0%");
+
+        // Values missing from the baseline can't regress.
+        let delta = GooseBaselineDelta::new(None, 125.0, true, 10);
+        assert!(!delta.regression);
+        assert_eq!(delta.to_string(), "-");
+
+        // The tolerance is configurable.
+        assert!(!GooseBaselineDelta::new(Some(100.0), 125.0, true, 50).regression);
+        assert!(GooseBaselineDelta::new(Some(100.0), 101.0, true, 0).regression);
+    }
+}
@@ -4,20 +4,31 @@
 //! the [`GooseConfiguration`] structure.
 //!
 //! Goose can be configured programmatically with [`GooseDefaultType::set_default`].
+//!
+//! Run-time options can also be loaded from a configuration file with `--config` (see
+//! [`config_file`](../config_file/index.html)), or set with `GOOSE_` environment variables
+//! (see [`config_env`](../config_env/index.html)).
 
 use gumdrop::Options;
 use serde::{Deserialize, Serialize};
 use simplelog::*;
 use std::path::PathBuf;
+use std::str::FromStr;
 
+use crate::load_profile::GooseLoadProfile;
 use crate::logger::GooseLogFormat;
-use crate::metrics::GooseCoordinatedOmissionMitigation;
+use crate::metrics::{self, GooseCoordinatedOmissionMitigation};
+use crate::sink;
+use crate::threshold::GooseThreshold;
 use crate::util;
 use crate::{GooseAttack, GooseError};
 
 /// Constant defining Goose's default port when running a Gaggle.
 const DEFAULT_PORT: &str = "5115";
 
+/// Constant defining how often to record metrics snapshots for the html report charts.
+const DEFAULT_REPORT_SNAPSHOT_INTERVAL: usize = 5;
+
 /// Runtime options available when launching a Goose load test.
 ///
 /// Custom defaults can be programmatically set for most of these options using the
@@ -42,21 +53,38 @@ const DEFAULT_PORT: &str = "5115";
 /// -V, --version              Prints version information
 /// -l, --list                 Lists all tasks and exits
 ///
+/// --config NAME              Loads options from a TOML, YAML or JSON file
 /// -H, --host HOST            Defines host to load test (ie http://10.21.32.33)
 /// -u, --users USERS          Sets concurrent users (default: number of CPUs)
 /// -r, --hatch-rate RATE      Sets per-second user hatch rate (default: 1)
 /// -t, --run-time TIME        Stops after (30s, 20m, 3h, 1h30m, etc)
+/// --test-plan PLAN           Defines a load profile of stages (ie "10,30s;100,5m;0,1m")
+/// --arrival-rate RATE        Starts task set iterations per second (open model)
+/// --max-users USERS          Sets maximum users for --arrival-rate (default: --users)
 /// -G, --goose-log NAME       Enables Goose log file and sets name
 /// -g, --log-level            Sets Goose log level (-g, -gg, etc)
 /// -v, --verbose              Sets Goose verbosity (-v, -vv, etc)
 ///
 /// Metrics:
 /// --running-metrics TIME     How often to optionally print running metrics
+/// --snapshot-interval TIME   How often to optionally record metrics snapshots
 /// --no-reset-metrics         Doesn't reset metrics after all users have started
 /// --no-metrics               Doesn't track metrics
 /// --no-task-metrics          Doesn't track task metrics
 /// --no-error-summary         Doesn't display an error summary
+/// --percentiles LIST         Sets response time percentiles (default: 50,75,98,99,99.9,99.99)
 /// --report-file NAME         Create an html-formatted report
+/// --summary-json NAME        Create a JSON summary of the load test
+/// --summary-csv NAME         Create a CSV summary of the load test
+/// --junit-xml NAME           Create a JUnit XML report for CI systems
+/// --statsd HOST:PORT         Sends metrics to StatsD over UDP
+/// --influxdb URL             Sends metrics to InfluxDB over UDP or HTTP
+/// --sink-tags TAGS           Sets tags for StatsD and InfluxDB (ie env=test,region=eu)
+/// --sink-flush-interval TIME How often to flush StatsD and InfluxDB metrics (default: 1)
+/// --threshold RULE           Fails load test if threshold isn't met (ie "errors<1%")
+/// --threshold-abort          Stops load test once a threshold can't be met
+/// --baseline NAME            Compares metrics against a JSON summary
+/// --baseline-tolerance PCT   Sets baseline regression tolerance (default: 10%)
 /// -R, --request-log NAME     Sets request log file name
 /// --request-format FORMAT    Sets request log format (csv, json, raw, pretty)
 /// --request-body             Include the request body in the request log
@@ -76,6 +104,9 @@ const DEFAULT_PORT: &str = "5115";
 /// --no-websocket             Doesn't enable WebSocket Controller
 /// --websocket-host HOST      Sets WebSocket Controller host (default: 0.0.0.0)
 /// --websocket-port PORT      Sets WebSocket Controller TCP port (default: 5117)
+/// --prometheus               Enables Prometheus metrics endpoint
+/// --prometheus-host HOST     Sets Prometheus metrics host (default: 0.0.0.0)
+/// --prometheus-port PORT     Sets Prometheus metrics TCP port (default: 5118)
 /// --no-autostart             Doesn't automatically start load test
 /// --no-gzip                  Doesn't set the gzip Accept-Encoding header
 /// --co-mitigation STRATEGY   Sets coordinated omission mitigation strategy
@@ -108,6 +139,9 @@ pub struct GooseConfiguration {
     #[options(short = "l", help = "Lists all tasks and exits\n")]
     pub list: bool,
 
+    /// Loads options from a TOML, YAML or JSON file
+    #[options(no_short, meta = "NAME")]
+    pub config: Option<String>,
     /// Defines host to load test (ie http://10.21.32.33)
     #[options(short = "H")]
     pub host: String,
@@ -123,6 +157,15 @@ pub struct GooseConfiguration {
     /// Stops load test after (30s, 20m, 3h, 1h30m, etc)
     #[options(short = "t", meta = "TIME")]
     pub run_time: String,
+    /// Defines a load profile of stages (ie "10,30s;100,5m;0,1m")
+    #[options(no_short, meta = "PLAN")]
+    pub test_plan: Option<GooseLoadProfile>,
+    /// Starts task set iterations per second (open model)
+    #[options(no_short, meta = "RATE")]
+    pub arrival_rate: Option<String>,
+    /// Sets maximum users for --arrival-rate (default: --users)
+    #[options(no_short, meta = "USERS")]
+    pub max_users: Option<usize>,
     /// Enables Goose log file and sets name
     #[options(short = "G", meta = "NAME")]
     pub goose_log: String,
@@ -141,6 +184,9 @@ pub struct GooseConfiguration {
     /// How often to optionally print running metrics
     #[options(no_short, meta = "TIME")]
     pub running_metrics: Option<usize>,
+    /// How often to optionally record metrics snapshots
+    #[options(no_short, meta = "TIME")]
+    pub snapshot_interval: Option<usize>,
     /// Doesn't reset metrics after all users have started
     #[options(no_short)]
     pub no_reset_metrics: bool,
@@ -153,9 +199,45 @@ pub struct GooseConfiguration {
     /// Doesn't display an error summary
     #[options(no_short)]
     pub no_error_summary: bool,
+    /// Sets response time percentiles (default: 50,75,98,99,99.9,99.99)
+    #[options(no_short, meta = "LIST")]
+    pub percentiles: Option<String>,
     /// Create an html-formatted report
     #[options(no_short, meta = "NAME")]
     pub report_file: String,
+    /// Create a JSON summary of the load test
+    #[options(no_short, meta = "NAME")]
+    pub summary_json: String,
+    /// Create a CSV summary of the load test
+    #[options(no_short, meta = "NAME")]
+    pub summary_csv: String,
+    /// Create a JUnit XML report for CI systems
+    #[options(no_short, meta = "NAME")]
+    pub junit_xml: String,
+    /// Sends metrics to StatsD over UDP
+    #[options(no_short, meta = "HOST:PORT")]
+    pub statsd: Option<String>,
+    /// Sends metrics to InfluxDB over UDP or HTTP
+    #[options(no_short, meta = "URL")]
+    pub influxdb: Option<String>,
+    /// Sets tags for StatsD and InfluxDB (ie env=test,region=eu)
+    #[options(no_short, meta = "TAGS")]
+    pub sink_tags: Option<String>,
+    /// How often to flush StatsD and InfluxDB metrics (default: 1)
+    #[options(no_short, meta = "TIME")]
+    pub sink_flush_interval: Option<usize>,
+    /// Fails load test if threshold isn't met (ie "errors<1%")
+    #[options(no_short, meta = "RULE")]
+    pub threshold: Vec<String>,
+    /// Stops load test once a threshold can't be met
+    #[options(no_short)]
+    pub threshold_abort: bool,
+    /// Compares metrics against a JSON summary
+    #[options(no_short, meta = "NAME")]
+    pub baseline: Option<String>,
+    /// Sets baseline regression tolerance (default: 10%)
+    #[options(no_short, meta = "PCT")]
+    pub baseline_tolerance: Option<usize>,
     /// Sets request log file name
     #[options(short = "R", meta = "NAME")]
     pub request_log: String,
@@ -209,6 +291,15 @@ pub struct GooseConfiguration {
     /// Sets WebSocket Controller TCP port (default: 5117)
     #[options(no_short, meta = "PORT")]
     pub websocket_port: u16,
+    /// Enables Prometheus metrics endpoint
+    #[options(no_short)]
+    pub prometheus: bool,
+    /// Sets Prometheus metrics host (default: 0.0.0.0)
+    #[options(no_short, meta = "HOST")]
+    pub prometheus_host: String,
+    /// Sets Prometheus metrics TCP port (default: 5118)
+    #[options(no_short, meta = "PORT")]
+    pub prometheus_port: u16,
     /// Doesn't automatically start load test
     #[options(no_short)]
     pub no_autostart: bool,
@@ -269,6 +360,12 @@ pub(crate) struct GooseDefaults {
     pub startup_time: Option<usize>,
     /// An optional default number of seconds for the test to run.
     pub run_time: Option<usize>,
+    /// An optional default load profile.
+    pub test_plan: Option<GooseLoadProfile>,
+    /// An optional default number of iterations to start per second.
+    pub arrival_rate: Option<String>,
+    /// An optional default maximum number of users when starting iterations at a fixed rate.
+    pub max_users: Option<usize>,
     /// An optional default log level.
     pub log_level: Option<u8>,
     /// An optional default for the goose log file name.
@@ -277,6 +374,8 @@ pub(crate) struct GooseDefaults {
     pub verbose: Option<u8>,
     /// An optional default for printing running metrics.
     pub running_metrics: Option<usize>,
+    /// An optional default for how often to record metrics snapshots.
+    pub snapshot_interval: Option<usize>,
     /// An optional default for not resetting metrics after all users started.
     pub no_reset_metrics: Option<bool>,
     /// An optional default for not tracking metrics.
@@ -285,8 +384,30 @@ pub(crate) struct GooseDefaults {
     pub no_task_metrics: Option<bool>,
     /// An optional default for not displaying an error summary.
     pub no_error_summary: Option<bool>,
+    /// An optional default list of response time percentiles to display.
+    pub percentiles: Option<String>,
     /// An optional default for the html-formatted report file name.
     pub report_file: Option<String>,
+    /// An optional default for the JSON summary file name.
+    pub summary_json: Option<String>,
+    /// An optional default for the CSV summary file name.
+    pub summary_csv: Option<String>,
+    /// An optional default for the JUnit XML report file name.
+    pub junit_xml: Option<String>,
+    /// An optional default for the StatsD server to send metrics to.
+    pub statsd: Option<String>,
+    /// An optional default for the InfluxDB server to send metrics to.
+    pub influxdb: Option<String>,
+    /// An optional default for tags added to metrics sent to StatsD or InfluxDB.
+    pub sink_tags: Option<String>,
+    /// An optional default for how often to flush metrics to StatsD or InfluxDB.
+    pub sink_flush_interval: Option<usize>,
+    /// An optional default for stopping the load test once a threshold can't be met.
+    pub threshold_abort: Option<bool>,
+    /// An optional default for the JSON summary to compare metrics against.
+    pub baseline: Option<String>,
+    /// An optional default for the tolerance of regressions compared to the baseline.
+    pub baseline_tolerance: Option<usize>,
     /// An optional default for the requests log file name.
     pub request_log: Option<String>,
     /// An optional default for the requests log file format.
@@ -337,6 +458,12 @@ pub(crate) struct GooseDefaults {
     pub websocket_host: Option<String>,
     /// An optional default for port WebSocket Controller listens on.
     pub websocket_port: Option<u16>,
+    /// An optional default for enabling the Prometheus metrics endpoint.
+    pub prometheus: Option<bool>,
+    /// An optional default for host Prometheus metrics endpoint listens on.
+    pub prometheus_host: Option<String>,
+    /// An optional default for port Prometheus metrics endpoint listens on.
+    pub prometheus_port: Option<u16>,
     /// An optional default for host Manager listens on.
     pub manager_bind_host: Option<String>,
     /// An optional default for port Manager listens on.
@@ -365,6 +492,12 @@ pub enum GooseDefault {
     StartupTime,
     /// An optional default number of seconds for the test to run.
     RunTime,
+    /// An optional default load profile.
+    TestPlan,
+    /// An optional default number of iterations to start per second.
+    ArrivalRate,
+    /// An optional default maximum number of users when starting iterations at a fixed rate.
+    MaxUsers,
     /// An optional default log level.
     LogLevel,
     /// An optional default for the log file name.
@@ -373,6 +506,8 @@ pub enum GooseDefault {
     Verbose,
     /// An optional default for printing running metrics.
     RunningMetrics,
+    /// An optional default for how often to record metrics snapshots.
+    SnapshotInterval,
     /// An optional default for not resetting metrics after all users started.
     NoResetMetrics,
     /// An optional default for not tracking metrics.
@@ -381,8 +516,30 @@ pub enum GooseDefault {
     NoTaskMetrics,
     /// An optional default for not displaying an error summary.
     NoErrorSummary,
+    /// An optional default for stopping the load test once a threshold can't be met.
+    ThresholdAbort,
+    /// An optional default for the JSON summary to compare metrics against.
+    Baseline,
+    /// An optional default for the tolerance of regressions compared to the baseline.
+    BaselineTolerance,
+    /// An optional default list of response time percentiles to display.
+    Percentiles,
     /// An optional default for the report file name.
     ReportFile,
+    /// An optional default for the JSON summary file name.
+    SummaryJson,
+    /// An optional default for the CSV summary file name.
+    SummaryCsv,
+    /// An optional default for the JUnit XML report file name.
+    JunitXml,
+    /// An optional default for the StatsD server to send metrics to.
+    Statsd,
+    /// An optional default for the InfluxDB server to send metrics to.
+    InfluxDb,
+    /// An optional default for tags added to metrics sent to StatsD or InfluxDB.
+    SinkTags,
+    /// An optional default for how often to flush metrics to StatsD or InfluxDB.
+    SinkFlushInterval,
     /// An optional default for the request log file name.
     RequestLog,
     /// An optional default for the request log file format.
@@ -433,6 +590,12 @@ pub enum GooseDefault {
     WebSocketHost,
     /// An optional default for port WebSocket Controller listens on.
     WebSocketPort,
+    /// An optional default for enabling the Prometheus metrics endpoint.
+    Prometheus,
+    /// An optional default for host Prometheus metrics endpoint listens on.
+    PrometheusHost,
+    /// An optional default for port Prometheus metrics endpoint listens on.
+    PrometheusPort,
     /// An optional default for host Manager listens on.
     ManagerBindHost,
     /// An optional default for port Manager listens on.
@@ -477,13 +640,24 @@ pub enum GooseDefault {
 /// The following run-time options can be configured with a custom default using a
 /// borrowed string slice ([`&str`]):
 ///  - [`GooseDefault::Host`]
+///  - [`GooseDefault::TestPlan`]
+///  - [`GooseDefault::ArrivalRate`]
 ///  - [`GooseDefault::GooseLog`]
+///  - [`GooseDefault::Percentiles`]
+///  - [`GooseDefault::Statsd`]
+///  - [`GooseDefault::InfluxDb`]
+///  - [`GooseDefault::SinkTags`]
+///  - [`GooseDefault::SummaryJson`]
+///  - [`GooseDefault::SummaryCsv`]
+///  - [`GooseDefault::JunitXml`]
+///  - [`GooseDefault::Baseline`]
 ///  - [`GooseDefault::RequestFormat`]
 ///  - [`GooseDefault::TaskLog`]
 ///  - [`GooseDefault::ErrorLog`]
 ///  - [`GooseDefault::DebugLog`]
 ///  - [`GooseDefault::TelnetHost`]
 ///  - [`GooseDefault::WebSocketHost`]
+///  - [`GooseDefault::PrometheusHost`]
 ///  - [`GooseDefault::ManagerBindHost`]
 ///  - [`GooseDefault::ManagerHost`]
 ///
@@ -493,13 +667,18 @@ pub enum GooseDefault {
 ///  - [`GooseDefault::HatchRate`]
 ///  - [`GooseDefault::StartupTime`]
 ///  - [`GooseDefault::RunTime`]
+///  - [`GooseDefault::MaxUsers`]
 ///  - [`GooseDefault::RunningMetrics`]
+///  - [`GooseDefault::SnapshotInterval`]
+///  - [`GooseDefault::SinkFlushInterval`]
+///  - [`GooseDefault::BaselineTolerance`]
 ///  - [`GooseDefault::LogLevel`]
 ///  - [`GooseDefault::Verbose`]
 ///  - [`GooseDefault::ThrottleRequests`]
 ///  - [`GooseDefault::ExpectWorkers`]
 ///  - [`GooseDefault::TelnetPort`]
 ///  - [`GooseDefault::WebSocketPort`]
+///  - [`GooseDefault::PrometheusPort`]
 ///  - [`GooseDefault::ManagerBindPort`]
 ///  - [`GooseDefault::ManagerPort`]
 ///
@@ -510,9 +689,11 @@ pub enum GooseDefault {
 ///  - [`GooseDefault::NoTaskMetrics`]
 ///  - [`GooseDefault::RequestBody`]
 ///  - [`GooseDefault::NoErrorSummary`]
+///  - [`GooseDefault::ThresholdAbort`]
 ///  - [`GooseDefault::NoDebugBody`]
 ///  - [`GooseDefault::NoTelnet`]
 ///  - [`GooseDefault::NoWebSocket`]
+///  - [`GooseDefault::Prometheus`]
 ///  - [`GooseDefault::NoAutoStart`]
 ///  - [`GooseDefault::NoGzip`]
 ///  - [`GooseDefault::StatusCodes`]
@@ -559,19 +740,45 @@ impl GooseDefaultType<&str> for GooseAttack {
         match key {
             // Set valid defaults.
             GooseDefault::HatchRate => self.defaults.hatch_rate = Some(value.to_string()),
+            GooseDefault::ArrivalRate => self.defaults.arrival_rate = Some(value.to_string()),
             GooseDefault::Host => self.defaults.host = Some(value.to_string()),
             GooseDefault::GooseLog => self.defaults.goose_log = Some(value.to_string()),
             GooseDefault::ReportFile => self.defaults.report_file = Some(value.to_string()),
+            GooseDefault::Statsd => self.defaults.statsd = Some(value.to_string()),
+            GooseDefault::InfluxDb => self.defaults.influxdb = Some(value.to_string()),
+            GooseDefault::SinkTags => self.defaults.sink_tags = Some(value.to_string()),
+            GooseDefault::SummaryJson => self.defaults.summary_json = Some(value.to_string()),
+            GooseDefault::SummaryCsv => self.defaults.summary_csv = Some(value.to_string()),
+            GooseDefault::JunitXml => self.defaults.junit_xml = Some(value.to_string()),
+            GooseDefault::Baseline => self.defaults.baseline = Some(value.to_string()),
+            GooseDefault::Percentiles => self.defaults.percentiles = Some(value.to_string()),
             GooseDefault::RequestLog => self.defaults.request_log = Some(value.to_string()),
             GooseDefault::TaskLog => self.defaults.task_log = Some(value.to_string()),
             GooseDefault::ErrorLog => self.defaults.error_log = Some(value.to_string()),
             GooseDefault::DebugLog => self.defaults.debug_log = Some(value.to_string()),
             GooseDefault::TelnetHost => self.defaults.telnet_host = Some(value.to_string()),
             GooseDefault::WebSocketHost => self.defaults.websocket_host = Some(value.to_string()),
+            GooseDefault::PrometheusHost => self.defaults.prometheus_host = Some(value.to_string()),
             GooseDefault::ManagerBindHost => {
                 self.defaults.manager_bind_host = Some(value.to_string())
             }
             GooseDefault::ManagerHost => self.defaults.manager_host = Some(value.to_string()),
+            GooseDefault::TestPlan => {
+                self.defaults.test_plan = match GooseLoadProfile::from_str(value) {
+                    Ok(test_plan) => Some(test_plan),
+                    Err(e) => {
+                        let detail = match e {
+                            GooseError::InvalidOption { detail, .. } => detail,
+                            _ => e.to_string(),
+                        };
+                        return Err(GooseError::InvalidOption {
+                            option: format!("GooseDefault::{:?}", key),
+                            value: value.to_string(),
+                            detail,
+                        });
+                    }
+                }
+            }
             // Otherwise display a helpful and explicit error.
             GooseDefault::Users
             | GooseDefault::StartupTime
@@ -582,8 +789,10 @@ impl GooseDefaultType<&str> for GooseAttack {
             | GooseDefault::ExpectWorkers
             | GooseDefault::TelnetPort
             | GooseDefault::WebSocketPort
+            | GooseDefault::PrometheusPort
             | GooseDefault::ManagerBindPort
-            | GooseDefault::ManagerPort => {
+            | GooseDefault::ManagerPort
+            | GooseDefault::MaxUsers => {
                 return Err(GooseError::InvalidOption {
                     option: format!("GooseDefault::{:?}", key),
                     value: value.to_string(),
@@ -594,14 +803,19 @@ impl GooseDefaultType<&str> for GooseAttack {
                 });
             }
             GooseDefault::RunningMetrics
+            | GooseDefault::SnapshotInterval
+            | GooseDefault::SinkFlushInterval
+            | GooseDefault::BaselineTolerance
             | GooseDefault::NoResetMetrics
             | GooseDefault::NoMetrics
             | GooseDefault::NoTaskMetrics
             | GooseDefault::RequestBody
             | GooseDefault::NoErrorSummary
+            | GooseDefault::ThresholdAbort
             | GooseDefault::NoDebugBody
             | GooseDefault::NoTelnet
             | GooseDefault::NoWebSocket
+            | GooseDefault::Prometheus
             | GooseDefault::NoAutoStart
             | GooseDefault::NoGzip
             | GooseDefault::StatusCodes
@@ -652,13 +866,18 @@ impl GooseDefaultType<usize> for GooseAttack {
             GooseDefault::Users => self.defaults.users = Some(value),
             GooseDefault::StartupTime => self.defaults.startup_time = Some(value),
             GooseDefault::RunTime => self.defaults.run_time = Some(value),
+            GooseDefault::MaxUsers => self.defaults.max_users = Some(value),
             GooseDefault::RunningMetrics => self.defaults.running_metrics = Some(value),
+            GooseDefault::SnapshotInterval => self.defaults.snapshot_interval = Some(value),
+            GooseDefault::SinkFlushInterval => self.defaults.sink_flush_interval = Some(value),
+            GooseDefault::BaselineTolerance => self.defaults.baseline_tolerance = Some(value),
             GooseDefault::LogLevel => self.defaults.log_level = Some(value as u8),
             GooseDefault::Verbose => self.defaults.verbose = Some(value as u8),
             GooseDefault::ThrottleRequests => self.defaults.throttle_requests = Some(value),
             GooseDefault::ExpectWorkers => self.defaults.expect_workers = Some(value),
             GooseDefault::TelnetPort => self.defaults.telnet_port = Some(value as u16),
             GooseDefault::WebSocketPort => self.defaults.websocket_port = Some(value as u16),
+            GooseDefault::PrometheusPort => self.defaults.prometheus_port = Some(value as u16),
             GooseDefault::ManagerBindPort => self.defaults.manager_bind_port = Some(value as u16),
             GooseDefault::ManagerPort => self.defaults.manager_port = Some(value as u16),
             // Otherwise display a helpful and explicit error.
@@ -666,14 +885,25 @@ impl GooseDefaultType<usize> for GooseAttack {
             | GooseDefault::HatchRate
             | GooseDefault::GooseLog
             | GooseDefault::ReportFile
+            | GooseDefault::Statsd
+            | GooseDefault::InfluxDb
+            | GooseDefault::SinkTags
+            | GooseDefault::SummaryJson
+            | GooseDefault::SummaryCsv
+            | GooseDefault::JunitXml
+            | GooseDefault::Baseline
+            | GooseDefault::Percentiles
             | GooseDefault::RequestLog
             | GooseDefault::TaskLog
             | GooseDefault::ErrorLog
             | GooseDefault::DebugLog
             | GooseDefault::TelnetHost
             | GooseDefault::WebSocketHost
+            | GooseDefault::PrometheusHost
             | GooseDefault::ManagerBindHost
-            | GooseDefault::ManagerHost => {
+            | GooseDefault::ManagerHost
+            | GooseDefault::TestPlan
+            | GooseDefault::ArrivalRate => {
                 return Err(GooseError::InvalidOption {
                     option: format!("GooseDefault::{:?}", key),
                     value: format!("{}", value),
@@ -688,9 +918,11 @@ impl GooseDefaultType<usize> for GooseAttack {
             | GooseDefault::NoTaskMetrics
             | GooseDefault::RequestBody
             | GooseDefault::NoErrorSummary
+            | GooseDefault::ThresholdAbort
             | GooseDefault::NoDebugBody
             | GooseDefault::NoTelnet
             | GooseDefault::NoWebSocket
+            | GooseDefault::Prometheus
             | GooseDefault::NoAutoStart
             | GooseDefault::NoGzip
             | GooseDefault::StatusCodes
@@ -743,9 +975,11 @@ impl GooseDefaultType<bool> for GooseAttack {
             GooseDefault::NoTaskMetrics => self.defaults.no_task_metrics = Some(value),
             GooseDefault::RequestBody => self.defaults.request_body = Some(value),
             GooseDefault::NoErrorSummary => self.defaults.no_error_summary = Some(value),
+            GooseDefault::ThresholdAbort => self.defaults.threshold_abort = Some(value),
             GooseDefault::NoDebugBody => self.defaults.no_debug_body = Some(value),
             GooseDefault::NoTelnet => self.defaults.no_telnet = Some(value),
             GooseDefault::NoWebSocket => self.defaults.no_websocket = Some(value),
+            GooseDefault::Prometheus => self.defaults.prometheus = Some(value),
             GooseDefault::NoAutoStart => self.defaults.no_autostart = Some(value),
             GooseDefault::NoGzip => self.defaults.no_gzip = Some(value),
             GooseDefault::StatusCodes => self.defaults.status_codes = Some(value),
@@ -757,15 +991,29 @@ impl GooseDefaultType<bool> for GooseAttack {
             GooseDefault::Host
             | GooseDefault::GooseLog
             | GooseDefault::ReportFile
+            | GooseDefault::Statsd
+            | GooseDefault::InfluxDb
+            | GooseDefault::SinkTags
+            | GooseDefault::SummaryJson
+            | GooseDefault::SummaryCsv
+            | GooseDefault::JunitXml
+            | GooseDefault::Baseline
+            | GooseDefault::Percentiles
             | GooseDefault::RequestLog
             | GooseDefault::TaskLog
             | GooseDefault::RunningMetrics
+            | GooseDefault::SnapshotInterval
+            | GooseDefault::SinkFlushInterval
+            | GooseDefault::BaselineTolerance
             | GooseDefault::ErrorLog
             | GooseDefault::DebugLog
             | GooseDefault::TelnetHost
             | GooseDefault::WebSocketHost
+            | GooseDefault::PrometheusHost
             | GooseDefault::ManagerBindHost
-            | GooseDefault::ManagerHost => {
+            | GooseDefault::ManagerHost
+            | GooseDefault::TestPlan
+            | GooseDefault::ArrivalRate => {
                 return Err(GooseError::InvalidOption {
                     option: format!("GooseDefault::{:?}", key),
                     value: format!("{}", value),
@@ -785,8 +1033,10 @@ impl GooseDefaultType<bool> for GooseAttack {
             | GooseDefault::ExpectWorkers
             | GooseDefault::TelnetPort
             | GooseDefault::WebSocketPort
+            | GooseDefault::PrometheusPort
             | GooseDefault::ManagerBindPort
-            | GooseDefault::ManagerPort => {
+            | GooseDefault::ManagerPort
+            | GooseDefault::MaxUsers => {
                 return Err(GooseError::InvalidOption {
                     option: format!("GooseDefault::{:?}", key),
                     value: format!("{}", value),
@@ -838,9 +1088,11 @@ impl GooseDefaultType<GooseCoordinatedOmissionMitigation> for GooseAttack {
             | GooseDefault::NoTaskMetrics
             | GooseDefault::RequestBody
             | GooseDefault::NoErrorSummary
+            | GooseDefault::ThresholdAbort
             | GooseDefault::NoDebugBody
             | GooseDefault::NoTelnet
             | GooseDefault::NoWebSocket
+            | GooseDefault::Prometheus
             | GooseDefault::NoAutoStart
             | GooseDefault::NoGzip
             | GooseDefault::StatusCodes
@@ -860,15 +1112,29 @@ impl GooseDefaultType<GooseCoordinatedOmissionMitigation> for GooseAttack {
             GooseDefault::Host
             | GooseDefault::GooseLog
             | GooseDefault::ReportFile
+            | GooseDefault::Statsd
+            | GooseDefault::InfluxDb
+            | GooseDefault::SinkTags
+            | GooseDefault::SummaryJson
+            | GooseDefault::SummaryCsv
+            | GooseDefault::JunitXml
+            | GooseDefault::Baseline
+            | GooseDefault::Percentiles
             | GooseDefault::RequestLog
             | GooseDefault::TaskLog
             | GooseDefault::RunningMetrics
+            | GooseDefault::SnapshotInterval
+            | GooseDefault::SinkFlushInterval
+            | GooseDefault::BaselineTolerance
             | GooseDefault::ErrorLog
             | GooseDefault::DebugLog
             | GooseDefault::TelnetHost
             | GooseDefault::WebSocketHost
+            | GooseDefault::PrometheusHost
             | GooseDefault::ManagerBindHost
-            | GooseDefault::ManagerHost => {
+            | GooseDefault::ManagerHost
+            | GooseDefault::TestPlan
+            | GooseDefault::ArrivalRate => {
                 return Err(GooseError::InvalidOption {
                     option: format!("GooseDefault::{:?}", key),
                     value: format!("{:?}", value),
@@ -888,8 +1154,10 @@ impl GooseDefaultType<GooseCoordinatedOmissionMitigation> for GooseAttack {
             | GooseDefault::ExpectWorkers
             | GooseDefault::TelnetPort
             | GooseDefault::WebSocketPort
+            | GooseDefault::PrometheusPort
             | GooseDefault::ManagerBindPort
-            | GooseDefault::ManagerPort => {
+            | GooseDefault::ManagerPort
+            | GooseDefault::MaxUsers => {
                 return Err(GooseError::InvalidOption {
                     option: format!("GooseDefault::{:?}", key),
                     value: format!("{:?}", value),
@@ -934,9 +1202,11 @@ impl GooseDefaultType<GooseLogFormat> for GooseAttack {
             | GooseDefault::NoTaskMetrics
             | GooseDefault::RequestBody
             | GooseDefault::NoErrorSummary
+            | GooseDefault::ThresholdAbort
             | GooseDefault::NoDebugBody
             | GooseDefault::NoTelnet
             | GooseDefault::NoWebSocket
+            | GooseDefault::Prometheus
             | GooseDefault::NoAutoStart
             | GooseDefault::NoGzip
             | GooseDefault::StatusCodes
@@ -956,15 +1226,29 @@ impl GooseDefaultType<GooseLogFormat> for GooseAttack {
             GooseDefault::Host
             | GooseDefault::GooseLog
             | GooseDefault::ReportFile
+            | GooseDefault::Statsd
+            | GooseDefault::InfluxDb
+            | GooseDefault::SinkTags
+            | GooseDefault::SummaryJson
+            | GooseDefault::SummaryCsv
+            | GooseDefault::JunitXml
+            | GooseDefault::Baseline
+            | GooseDefault::Percentiles
             | GooseDefault::RequestLog
             | GooseDefault::TaskLog
             | GooseDefault::RunningMetrics
+            | GooseDefault::SnapshotInterval
+            | GooseDefault::SinkFlushInterval
+            | GooseDefault::BaselineTolerance
             | GooseDefault::ErrorLog
             | GooseDefault::DebugLog
             | GooseDefault::TelnetHost
             | GooseDefault::WebSocketHost
+            | GooseDefault::PrometheusHost
             | GooseDefault::ManagerBindHost
-            | GooseDefault::ManagerHost => {
+            | GooseDefault::ManagerHost
+            | GooseDefault::TestPlan
+            | GooseDefault::ArrivalRate => {
                 return Err(GooseError::InvalidOption {
                     option: format!("GooseDefault::{:?}", key),
                     value: format!("{:?}", value),
@@ -984,8 +1268,10 @@ impl GooseDefaultType<GooseLogFormat> for GooseAttack {
             | GooseDefault::ExpectWorkers
             | GooseDefault::TelnetPort
             | GooseDefault::WebSocketPort
+            | GooseDefault::PrometheusPort
             | GooseDefault::ManagerBindPort
-            | GooseDefault::ManagerPort => {
+            | GooseDefault::ManagerPort
+            | GooseDefault::MaxUsers => {
                 return Err(GooseError::InvalidOption {
                     option: format!("GooseDefault::{:?}", key),
                     value: format!("{:?}", value),
@@ -1153,6 +1439,24 @@ impl GooseConfigure<GooseLogFormat> for GooseConfiguration {
         None
     }
 }
+impl GooseConfigure<GooseLoadProfile> for GooseConfiguration {
+    /// Use [`GooseValue`] to set a [`GooseLoadProfile`] value.
+    fn get_value(&self, values: Vec<GooseValue<GooseLoadProfile>>) -> Option<GooseLoadProfile> {
+        for value in values {
+            if let Some(v) = value.value {
+                if value.filter {
+                    continue;
+                } else {
+                    if !value.message.is_empty() {
+                        info!("{} = {:?}", value.message, v)
+                    }
+                    return Some(v);
+                }
+            }
+        }
+        None
+    }
+}
 impl GooseConfigure<GooseCoordinatedOmissionMitigation> for GooseConfiguration {
     /// Use [`GooseValue`] to set a [`GooseCoordinatedOmissionMitigation`] value.
     fn get_value(
@@ -1276,6 +1580,22 @@ impl GooseConfiguration {
             ])
             .unwrap_or(false);
 
+        // Configure `test_plan`.
+        self.test_plan = self.get_value(vec![
+            // Use --test-plan if set.
+            GooseValue {
+                value: self.test_plan.clone(),
+                filter: self.test_plan.is_none(),
+                message: "test_plan",
+            },
+            // Otherwise use GooseDefault if set and not on Worker.
+            GooseValue {
+                value: defaults.test_plan.clone(),
+                filter: defaults.test_plan.is_none() || self.worker,
+                message: "test_plan",
+            },
+        ]);
+
         // Configure `users`.
         self.users = self.get_value(vec![
             // Use --users if set.
@@ -1284,12 +1604,18 @@ impl GooseConfiguration {
                 filter: false,
                 message: "users",
             },
-            // Otherwise use GooseDefault if set and not on Worker.
+            // Otherwise use GooseDefault if set, not on Worker, and no load profile.
             GooseValue {
                 value: defaults.users,
-                filter: defaults.users.is_none() || self.worker,
+                filter: defaults.users.is_none() || self.worker || self.test_plan.is_some(),
                 message: "users",
             },
+            // Otherwise use the most users running at once in the load profile.
+            GooseValue {
+                value: self.test_plan.as_ref().map(|p| p.max_users()),
+                filter: self.worker,
+                message: "users defaulted to maximum users in test_plan",
+            },
             // Otherwise use detected number of CPUs if not on Worker.
             GooseValue {
                 value: Some(num_cpus::get()),
@@ -1310,7 +1636,9 @@ impl GooseConfiguration {
                 // Otherwise use GooseDefault if set and not on Worker.
                 GooseValue {
                     value: defaults.startup_time,
-                    filter: defaults.startup_time.is_none() || self.worker,
+                    filter: defaults.startup_time.is_none()
+                        || self.worker
+                        || self.test_plan.is_some(),
                     message: "startup_time",
                 },
             ])
@@ -1328,7 +1656,7 @@ impl GooseConfiguration {
                 // Otherwise use GooseDefault if set and not on Worker.
                 GooseValue {
                     value: defaults.run_time,
-                    filter: defaults.run_time.is_none() || self.worker,
+                    filter: defaults.run_time.is_none() || self.worker || self.test_plan.is_some(),
                     message: "run_time",
                 },
             ])
@@ -1346,12 +1674,46 @@ impl GooseConfiguration {
                 // Otherwise use GooseDefault if set and not on Worker.
                 GooseValue {
                     value: Some(util::get_hatch_rate(defaults.hatch_rate.clone())),
-                    filter: defaults.hatch_rate.is_none() || self.worker,
+                    filter: defaults.hatch_rate.is_none()
+                        || self.worker
+                        || self.test_plan.is_some(),
                     message: "hatch_rate",
                 },
             ])
             .map(|v| v.to_string());
 
+        // Configure `arrival_rate`.
+        self.arrival_rate = self.get_value(vec![
+            // Use --arrival-rate if set.
+            GooseValue {
+                value: self.arrival_rate.clone(),
+                filter: self.arrival_rate.is_none(),
+                message: "arrival_rate",
+            },
+            // Otherwise use GooseDefault if set and not on Worker.
+            GooseValue {
+                value: defaults.arrival_rate.clone(),
+                filter: defaults.arrival_rate.is_none() || self.worker,
+                message: "arrival_rate",
+            },
+        ]);
+
+        // Configure `max_users`.
+        self.max_users = self.get_value(vec![
+            // Use --max-users if set.
+            GooseValue {
+                value: self.max_users,
+                filter: self.max_users.is_none(),
+                message: "max_users",
+            },
+            // Otherwise use GooseDefault if set and not on Worker.
+            GooseValue {
+                value: defaults.max_users,
+                filter: defaults.max_users.is_none() || self.worker,
+                message: "max_users",
+            },
+        ]);
+
         // Configure `running_metrics`.
         self.running_metrics = self.get_value(vec![
             // Use --running-metrics if set.
@@ -1368,6 +1730,22 @@ impl GooseConfiguration {
             },
         ]);
 
+        // Configure `snapshot_interval`.
+        self.snapshot_interval = self.get_value(vec![
+            // Use --snapshot-interval if set.
+            GooseValue {
+                value: self.snapshot_interval,
+                filter: self.snapshot_interval.is_none(),
+                message: "snapshot_interval",
+            },
+            // Otherwise use GooseDefault if set.
+            GooseValue {
+                value: defaults.snapshot_interval,
+                filter: defaults.snapshot_interval.is_none() || self.worker,
+                message: "snapshot_interval",
+            },
+        ]);
+
         // Configure `no_reset_metrics`.
         self.no_reset_metrics = self
             .get_value(vec![
@@ -1422,6 +1800,56 @@ impl GooseConfiguration {
             ])
             .unwrap_or(false);
 
+        // Configure `threshold_abort`.
+        self.threshold_abort = self
+            .get_value(vec![
+                // Use --threshold-abort if set.
+                GooseValue {
+                    value: Some(self.threshold_abort),
+                    filter: !self.threshold_abort,
+                    message: "threshold_abort",
+                },
+                // Otherwise use GooseDefault if set.
+                GooseValue {
+                    value: defaults.threshold_abort,
+                    filter: defaults.threshold_abort.is_none() || self.worker,
+                    message: "threshold_abort",
+                },
+            ])
+            .unwrap_or(false);
+
+        // Configure `baseline`.
+        self.baseline = self.get_value(vec![
+            // Use --baseline if set.
+            GooseValue {
+                value: self.baseline.clone(),
+                filter: self.baseline.is_none(),
+                message: "baseline",
+            },
+            // Otherwise use GooseDefault if set and not Worker.
+            GooseValue {
+                value: defaults.baseline.clone(),
+                filter: defaults.baseline.is_none() || self.worker,
+                message: "baseline",
+            },
+        ]);
+
+        // Configure `baseline_tolerance`.
+        self.baseline_tolerance = self.get_value(vec![
+            // Use --baseline-tolerance if set.
+            GooseValue {
+                value: self.baseline_tolerance,
+                filter: self.baseline_tolerance.is_none(),
+                message: "baseline_tolerance",
+            },
+            // Otherwise use GooseDefault if set and not Worker.
+            GooseValue {
+                value: defaults.baseline_tolerance,
+                filter: defaults.baseline_tolerance.is_none() || self.worker,
+                message: "baseline_tolerance",
+            },
+        ]);
+
         // Configure `no_error_summary`.
         self.no_error_summary = self
             .get_value(vec![
@@ -1440,6 +1868,22 @@ impl GooseConfiguration {
             ])
             .unwrap_or(false);
 
+        // Configure `percentiles`.
+        self.percentiles = self.get_value(vec![
+            // Use --percentiles if set.
+            GooseValue {
+                value: self.percentiles.clone(),
+                filter: self.percentiles.is_none(),
+                message: "percentiles",
+            },
+            // Otherwise use GooseDefault if set and not on Worker.
+            GooseValue {
+                value: defaults.percentiles.clone(),
+                filter: defaults.percentiles.is_none() || self.worker,
+                message: "percentiles",
+            },
+        ]);
+
         // Configure `report_file`.
         self.report_file = match self.get_value(vec![
             // Use --report-file if set.
@@ -1459,6 +1903,137 @@ impl GooseConfiguration {
             None => "".to_string(),
         };
 
+        // Configure `summary_json`.
+        self.summary_json = match self.get_value(vec![
+            // Use --summary-json if set.
+            GooseValue {
+                value: Some(self.summary_json.to_string()),
+                filter: self.summary_json.is_empty(),
+                message: "summary_json",
+            },
+            // Otherwise use GooseDefault if set and not Worker.
+            GooseValue {
+                value: defaults.summary_json.clone(),
+                filter: defaults.summary_json.is_none() || self.worker,
+                message: "summary_json",
+            },
+        ]) {
+            Some(v) => v,
+            None => "".to_string(),
+        };
+
+        // Configure `summary_csv`.
+        self.summary_csv = match self.get_value(vec![
+            // Use --summary-csv if set.
+            GooseValue {
+                value: Some(self.summary_csv.to_string()),
+                filter: self.summary_csv.is_empty(),
+                message: "summary_csv",
+            },
+            // Otherwise use GooseDefault if set and not Worker.
+            GooseValue {
+                value: defaults.summary_csv.clone(),
+                filter: defaults.summary_csv.is_none() || self.worker,
+                message: "summary_csv",
+            },
+        ]) {
+            Some(v) => v,
+            None => "".to_string(),
+        };
+
+        // Configure `junit_xml`.
+        self.junit_xml = match self.get_value(vec![
+            // Use --junit-xml if set.
+            GooseValue {
+                value: Some(self.junit_xml.to_string()),
+                filter: self.junit_xml.is_empty(),
+                message: "junit_xml",
+            },
+            // Otherwise use GooseDefault if set and not Worker.
+            GooseValue {
+                value: defaults.junit_xml.clone(),
+                filter: defaults.junit_xml.is_none() || self.worker,
+                message: "junit_xml",
+            },
+        ]) {
+            Some(v) => v,
+            None => "".to_string(),
+        };
+
+        // Configure `statsd`.
+        self.statsd = self.get_value(vec![
+            // Use --statsd if set.
+            GooseValue {
+                value: self.statsd.clone(),
+                filter: self.statsd.is_none(),
+                message: "statsd",
+            },
+            // Otherwise use GooseDefault if set and not on Manager or Worker.
+            GooseValue {
+                value: defaults.statsd.clone(),
+                filter: defaults.statsd.is_none() || self.manager || self.worker,
+                message: "statsd",
+            },
+        ]);
+
+        // Configure `influxdb`.
+        self.influxdb = self.get_value(vec![
+            // Use --influxdb if set.
+            GooseValue {
+                value: self.influxdb.clone(),
+                filter: self.influxdb.is_none(),
+                message: "influxdb",
+            },
+            // Otherwise use GooseDefault if set and not on Manager or Worker.
+            GooseValue {
+                value: defaults.influxdb.clone(),
+                filter: defaults.influxdb.is_none() || self.manager || self.worker,
+                message: "influxdb",
+            },
+        ]);
+
+        // Configure `sink_tags`.
+        self.sink_tags = self.get_value(vec![
+            // Use --sink-tags if set.
+            GooseValue {
+                value: self.sink_tags.clone(),
+                filter: self.sink_tags.is_none(),
+                message: "sink_tags",
+            },
+            // Otherwise use GooseDefault if set and not on Manager or Worker.
+            GooseValue {
+                value: defaults.sink_tags.clone(),
+                filter: defaults.sink_tags.is_none() || self.manager || self.worker,
+                message: "sink_tags",
+            },
+        ]);
+
+        // Configure `sink_flush_interval`.
+        self.sink_flush_interval = self.get_value(vec![
+            // Use --sink-flush-interval if set.
+            GooseValue {
+                value: self.sink_flush_interval,
+                filter: self.sink_flush_interval.is_none(),
+                message: "sink_flush_interval",
+            },
+            // Otherwise use GooseDefault if set and not on Manager or Worker.
+            GooseValue {
+                value: defaults.sink_flush_interval,
+                filter: defaults.sink_flush_interval.is_none() || self.manager || self.worker,
+                message: "sink_flush_interval",
+            },
+        ]);
+
+        // The html report charts are built from snapshots, so record them even if
+        // `snapshot_interval` isn't configured.
+        if self.snapshot_interval.is_none()
+            && !self.report_file.is_empty()
+            && !self.no_metrics
+            && !self.worker
+        {
+            self.snapshot_interval = Some(DEFAULT_REPORT_SNAPSHOT_INTERVAL);
+        }
+
         // Configure `no_debug_body`.
         self.no_debug_body = self
             .get_value(vec![
@@ -1543,6 +2118,24 @@ impl GooseConfiguration {
             ])
             .unwrap_or(false);
 
+        // Configure `prometheus`.
+        self.prometheus = self
+            .get_value(vec![
+                // Use --prometheus if set.
+                GooseValue {
+                    value: Some(self.prometheus),
+                    filter: !self.prometheus,
+                    message: "prometheus",
+                },
+                // Otherwise use GooseDefault if set and not on Manager or Worker.
+                GooseValue {
+                    value: defaults.prometheus,
+                    filter: defaults.prometheus.is_none() || self.manager || self.worker,
+                    message: "prometheus",
+                },
+            ])
+            .unwrap_or(false);
+
         // Configure `no_autostart`.
         self.no_autostart = self
             .get_value(vec![
@@ -1810,6 +2403,24 @@ impl GooseConfiguration {
                     detail: "`configuration.no_autostart` can not be set on the Manager."
                         .to_string(),
                 });
+            } else if self.prometheus {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.prometheus`".to_string(),
+                    value: true.to_string(),
+                    detail: "`configuration.prometheus` can not be set on the Manager.".to_string(),
+                });
+            } else if let Some(statsd) = self.statsd.as_ref() {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.statsd`".to_string(),
+                    value: statsd.to_string(),
+                    detail: "`configuration.statsd` can not be set on the Manager.".to_string(),
+                });
+            } else if let Some(influxdb) = self.influxdb.as_ref() {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.influxdb`".to_string(),
+                    value: influxdb.to_string(),
+                    detail: "`configuration.influxdb` can not be set on the Manager.".to_string(),
+                });
             } else if !self.report_file.is_empty() {
                 return Err(GooseError::InvalidOption {
                     option: "`configuration.report_file`".to_string(),
@@ -1918,6 +2529,14 @@ impl GooseConfiguration {
                     detail: "`configuration.running_metrics` can not be set in Worker mode."
                         .to_string(),
                 });
+            // Can't set `snapshot_interval` on Worker.
+            } else if self.snapshot_interval.is_some() {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.snapshot_interval`".to_string(),
+                    value: self.snapshot_interval.as_ref().unwrap().to_string(),
+                    detail: "`configuration.snapshot_interval` can not be set in Worker mode."
+                        .to_string(),
+                });
             // Can't set `no_reset_metrics` on Worker.
             } else if self.no_reset_metrics {
                 return Err(GooseError::InvalidOption {
@@ -1941,6 +2560,36 @@ impl GooseConfiguration {
                     detail: "`configuration.no_task_metrics` can not be set in Worker mode."
                         .to_string(),
                 });
+            // Can't set `threshold` on Worker.
+            } else if !self.threshold.is_empty() {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.threshold`".to_string(),
+                    value: self.threshold.join(" "),
+                    detail: "`configuration.threshold` can not be set in Worker mode.".to_string(),
+                });
+            // Can't set `threshold_abort` on Worker.
+            } else if self.threshold_abort {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.threshold_abort`".to_string(),
+                    value: true.to_string(),
+                    detail: "`configuration.threshold_abort` can not be set in Worker mode."
+                        .to_string(),
+                });
+            // Can't set `baseline` on Worker.
+            } else if let Some(baseline) = self.baseline.as_ref() {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.baseline`".to_string(),
+                    value: baseline.to_string(),
+                    detail: "`configuration.baseline` can not be set in Worker mode.".to_string(),
+                });
+            // Can't set `baseline_tolerance` on Worker.
+            } else if let Some(baseline_tolerance) = self.baseline_tolerance {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.baseline_tolerance`".to_string(),
+                    value: baseline_tolerance.to_string(),
+                    detail: "`configuration.baseline_tolerance` can not be set in Worker mode."
+                        .to_string(),
+                });
             // Can't set `no_error_summary` on Worker.
             } else if self.no_error_summary {
                 return Err(GooseError::InvalidOption {
@@ -1957,6 +2606,50 @@ impl GooseConfiguration {
                     detail: "`configuration.status_codes` can not be set in Worker mode."
                         .to_string(),
                 });
+            // Can't set `prometheus` on Worker.
+            } else if self.prometheus {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.prometheus`".to_string(),
+                    value: true.to_string(),
+                    detail: "`configuration.prometheus` can not be set in Worker mode.".to_string(),
+                });
+            // Can't set `statsd` on Worker.
+            } else if let Some(statsd) = self.statsd.as_ref() {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.statsd`".to_string(),
+                    value: statsd.to_string(),
+                    detail: "`configuration.statsd` can not be set in Worker mode.".to_string(),
+                });
+            // Can't set `influxdb` on Worker.
+            } else if let Some(influxdb) = self.influxdb.as_ref() {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.influxdb`".to_string(),
+                    value: influxdb.to_string(),
+                    detail: "`configuration.influxdb` can not be set in Worker mode.".to_string(),
+                });
+            // Can't set `summary_json` on Worker.
+            } else if !self.summary_json.is_empty() {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.summary_json`".to_string(),
+                    value: self.summary_json.to_string(),
+                    detail: "`configuration.summary_json` can not be set in Worker mode."
+                        .to_string(),
+                });
+            // Can't set `summary_csv` on Worker.
+            } else if !self.summary_csv.is_empty() {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.summary_csv`".to_string(),
+                    value: self.summary_csv.to_string(),
+                    detail: "`configuration.summary_csv` can not be set in Worker mode."
+                        .to_string(),
+                });
+            // Can't set `junit_xml` on Worker.
+            } else if !self.junit_xml.is_empty() {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.junit_xml`".to_string(),
+                    value: self.junit_xml.to_string(),
+                    detail: "`configuration.junit_xml` can not be set in Worker mode.".to_string(),
+                });
             // Can't set `no_autostart` on Worker.
             } else if self.no_autostart {
                 return Err(GooseError::InvalidOption {
@@ -2046,6 +2739,113 @@ impl GooseConfiguration {
             }
         }
 
+        // Validate `test_plan`.
+        if let Some(test_plan) = self.test_plan.as_ref() {
+            // Load profiles are not yet supported in a Gaggle.
+            if self.manager || self.worker {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.test_plan`".to_string(),
+                    value: format!("{:?}", test_plan),
+                    detail: "`configuration.test_plan` can not be set in Manager or Worker mode."
+                        .to_string(),
+                });
+            // The load profile defines how quickly users are launched.
+            } else if self.hatch_rate.is_some() {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.test_plan`".to_string(),
+                    value: format!("{:?}", test_plan),
+                    detail:
+                        "`configuration.test_plan` can not be set with `configuration.hatch_rate`."
+                            .to_string(),
+                });
+            } else if self.startup_time != "0" {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.test_plan`".to_string(),
+                    value: format!("{:?}", test_plan),
+                    detail: "`configuration.test_plan` can not be set with `configuration.startup_time`.".to_string(),
+                });
+            // The load profile defines how long the load test runs.
+            } else if self.run_time != "0" {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.test_plan`".to_string(),
+                    value: format!("{:?}", test_plan),
+                    detail:
+                        "`configuration.test_plan` can not be set with `configuration.run_time`."
+                            .to_string(),
+                });
+            // The load profile defines how many users are launched.
+            } else if self.users != Some(test_plan.max_users()) {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.test_plan`".to_string(),
+                    value: format!("{:?}", test_plan),
+                    detail: "`configuration.test_plan` can not be set with `configuration.users`."
+                        .to_string(),
+                });
+            }
+        }
+
+        // Validate `arrival_rate`.
+        if let Some(arrival_rate) = self.arrival_rate.as_ref() {
+            match arrival_rate.parse::<f32>() {
+                Ok(rate) if rate > 0.0 && rate.is_finite() => (),
+                _ => {
+                    return Err(GooseError::InvalidOption {
+                        option: "`configuration.arrival_rate`".to_string(),
+                        value: arrival_rate.to_string(),
+                        detail: "`configuration.arrival_rate` must be a number greater than 0."
+                            .to_string(),
+                    });
+                }
+            }
+
+            // Iterations are started at a fixed rate on a single process.
+            if self.manager || self.worker {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.arrival_rate`".to_string(),
+                    value: arrival_rate.to_string(),
+                    detail:
+                        "`configuration.arrival_rate` can not be set in Manager or Worker mode."
+                            .to_string(),
+                });
+            // The load profile and arrival rate both control how much load is generated.
+            } else if self.test_plan.is_some() {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.arrival_rate`".to_string(),
+                    value: arrival_rate.to_string(),
+                    detail: "`configuration.arrival_rate` can not be set with `configuration.test_plan`.".to_string(),
+                });
+            // Iterations don't wait for each other, so there's no omission to mitigate.
+            } else if self
+                .co_mitigation
+                .as_ref()
+                .unwrap_or(&GooseCoordinatedOmissionMitigation::Disabled)
+                != &GooseCoordinatedOmissionMitigation::Disabled
+            {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.arrival_rate`".to_string(),
+                    value: arrival_rate.to_string(),
+                    detail: "`configuration.arrival_rate` can not be set with `configuration.co_mitigation`.".to_string(),
+                });
+            }
+        }
+
+        // Validate `max_users`.
+        if let Some(max_users) = self.max_users.as_ref() {
+            if self.arrival_rate.is_none() {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.max_users`".to_string(),
+                    value: max_users.to_string(),
+                    detail: "`configuration.max_users` can not be set without `configuration.arrival_rate`.".to_string(),
+                });
+            } else if self.users.is_some() && max_users < self.users.as_ref().unwrap() {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.max_users`".to_string(),
+                    value: max_users.to_string(),
+                    detail: "`configuration.max_users` can not be set to a value smaller than `configuration.users`.".to_string(),
+                });
+            }
+        }
+
         // Validate `users`.
         if let Some(users) = self.users.as_ref() {
             if users == &0 {
@@ -2089,6 +2889,22 @@ impl GooseConfiguration {
                     value: true.to_string(),
                     detail: "`configuration.no_metrics` can not be set with `configuration.status_codes`.".to_string(),
                 });
+            // Metrics can't be exported to Prometheus if they're disabled.
+            } else if self.prometheus {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.no_metrics`".to_string(),
+                    value: true.to_string(),
+                    detail:
+                        "`configuration.no_metrics` can not be set with `configuration.prometheus`."
+                            .to_string(),
+                });
+            // Metrics can't be sent to StatsD or InfluxDB if they're disabled.
+            } else if self.statsd.is_some() || self.influxdb.is_some() {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.no_metrics`".to_string(),
+                    value: true.to_string(),
+                    detail: "`configuration.no_metrics` can not be set with `configuration.statsd` or `configuration.influxdb`.".to_string(),
+                });
             // Request log can't be written if metrics are disabled.
             } else if !self.request_log.is_empty() {
                 return Err(GooseError::InvalidOption {
@@ -2123,6 +2939,48 @@ impl GooseConfiguration {
                         "`configuration.report_file` can not be set with `configuration.no_metrics`."
                             .to_string(),
                 });
+            // Summary can't be written if metrics are disabled.
+            } else if !self.summary_json.is_empty() {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.summary_json`".to_string(),
+                    value: self.summary_json.to_string(),
+                    detail: "`configuration.summary_json` can not be set with `configuration.no_metrics`."
+                        .to_string(),
+                });
+            // Summary can't be written if metrics are disabled.
+            } else if !self.summary_csv.is_empty() {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.summary_csv`".to_string(),
+                    value: self.summary_csv.to_string(),
+                    detail: "`configuration.summary_csv` can not be set with `configuration.no_metrics`."
+                        .to_string(),
+                });
+            // Metrics can't be compared to a baseline if they're disabled.
+            } else if let Some(baseline) = self.baseline.as_ref() {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.baseline`".to_string(),
+                    value: baseline.to_string(),
+                    detail:
+                        "`configuration.baseline` can not be set with `configuration.no_metrics`."
+                            .to_string(),
+                });
+            // JUnit report can't be written if metrics are disabled.
+            } else if !self.junit_xml.is_empty() {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.junit_xml`".to_string(),
+                    value: self.junit_xml.to_string(),
+                    detail:
+                        "`configuration.junit_xml` can not be set with `configuration.no_metrics`."
+                            .to_string(),
+                });
+            // Snapshots can't be recorded if metrics are disabled.
+            } else if let Some(snapshot_interval) = self.snapshot_interval {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.snapshot_interval`".to_string(),
+                    value: snapshot_interval.to_string(),
+                    detail: "`configuration.snapshot_interval` can not be set with `configuration.no_metrics`."
+                        .to_string(),
+                });
             // Coordinated Omission Mitigation can't be enabled if metrics are disabled.
             } else if self.co_mitigation.as_ref().unwrap()
                 != &GooseCoordinatedOmissionMitigation::Disabled
@@ -2136,6 +2994,91 @@ impl GooseConfiguration {
             }
         }
 
+        // Validate `percentiles`.
+        if let Some(percentiles) = self.percentiles.as_ref() {
+            if let Err(detail) = metrics::parse_percentiles(percentiles) {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.percentiles`".to_string(),
+                    value: percentiles.to_string(),
+                    detail,
+                });
+            }
+        }
+
+        // Validate `snapshot_interval`.
+        if self.snapshot_interval == Some(0) {
+            return Err(GooseError::InvalidOption {
+                option: "`configuration.snapshot_interval`".to_string(),
+                value: "0".to_string(),
+                detail: "`configuration.snapshot_interval` must be greater than 0.".to_string(),
+            });
+        }
+
+        // Validate `statsd`.
+        if let Some(statsd) = self.statsd.as_ref() {
+            if let Err(detail) = sink::parse_statsd_address(statsd) {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.statsd`".to_string(),
+                    value: statsd.to_string(),
+                    detail,
+                });
+            }
+        }
+
+        // Validate `influxdb`.
+        if let Some(influxdb) = self.influxdb.as_ref() {
+            if let Err(detail) = sink::parse_influxdb_url(influxdb) {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.influxdb`".to_string(),
+                    value: influxdb.to_string(),
+                    detail,
+                });
+            }
+        }
+
+        // Validate `sink_tags`.
+        if let Some(sink_tags) = self.sink_tags.as_ref() {
+            if let Err(detail) = sink::parse_tags(sink_tags) {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.sink_tags`".to_string(),
+                    value: sink_tags.to_string(),
+                    detail,
+                });
+            }
+        }
+
+        // Validate `threshold`.
+        for threshold in &self.threshold {
+            if let Err(detail) = threshold.parse::<GooseThreshold>() {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.threshold`".to_string(),
+                    value: threshold.to_string(),
+                    detail,
+                });
+            }
+        }
+
+        // Validate `sink_flush_interval`.
+        if self.sink_flush_interval == Some(0) {
+            return Err(GooseError::InvalidOption {
+                option: "`configuration.sink_flush_interval`".to_string(),
+                value: "0".to_string(),
+                detail: "`configuration.sink_flush_interval` must be greater than 0.".to_string(),
+            });
+        }
+
+        // Baseline tolerance is only used when comparing to a baseline.
+        if let Some(baseline_tolerance) = self.baseline_tolerance {
+            if self.baseline.is_none() {
+                return Err(GooseError::InvalidOption {
+                    option: "`configuration.baseline_tolerance`".to_string(),
+                    value: baseline_tolerance.to_string(),
+                    detail: "`configuration.baseline_tolerance` can only be set with `configuration.baseline`."
+                        .to_string(),
+                });
+            }
+        }
+
         // Can't disable autostart if there's no Controller enabled.
         if self.no_autostart && self.no_telnet && self.no_websocket {
             return Err(GooseError::InvalidOption {
@@ -2250,11 +3193,19 @@ mod test {
         let host = "http://example.com/".to_string();
         let users: usize = 10;
         let run_time: usize = 10;
+        let test_plan = "10,30s;10,1m;0,10s".to_string();
         let hatch_rate = "2".to_string();
+        let arrival_rate = "5".to_string();
+        let max_users: usize = 20;
         let log_level: usize = 1;
         let goose_log = "custom-goose.log".to_string();
         let verbose: usize = 0;
         let report_file = "custom-goose-report.html".to_string();
+        let summary_json = "custom-goose-summary.json".to_string();
+        let summary_csv = "custom-goose-summary.csv".to_string();
+        let junit_xml = "custom-goose-junit.xml".to_string();
+        let baseline = "custom-goose-baseline.json".to_string();
+        let percentiles = "50,90,99".to_string();
         let request_log = "custom-goose-request.log".to_string();
         let task_log = "custom-goose-task.log".to_string();
         let debug_log = "custom-goose-debug.log".to_string();
@@ -2265,6 +3216,11 @@ mod test {
         let manager_bind_port: usize = 1221;
         let manager_host = "127.0.0.1".to_string();
         let manager_port: usize = 1221;
+        let prometheus_host = "127.0.0.1".to_string();
+        let prometheus_port: usize = 9118;
+        let statsd = "127.0.0.1:8125".to_string();
+        let influxdb = "http://127.0.0.1:8086/write?db=goose".to_string();
+        let sink_tags = "env=test".to_string();
 
         let goose_attack = GooseAttack::initialize()
             .unwrap()
@@ -2274,8 +3230,14 @@ mod test {
             .unwrap()
             .set_default(GooseDefault::RunTime, run_time)
             .unwrap()
+            .set_default(GooseDefault::TestPlan, test_plan.as_str())
+            .unwrap()
             .set_default(GooseDefault::HatchRate, hatch_rate.as_str())
             .unwrap()
+            .set_default(GooseDefault::ArrivalRate, arrival_rate.as_str())
+            .unwrap()
+            .set_default(GooseDefault::MaxUsers, max_users)
+            .unwrap()
             .set_default(GooseDefault::LogLevel, log_level)
             .unwrap()
             .set_default(GooseDefault::GooseLog, goose_log.as_str())
@@ -2284,6 +3246,8 @@ mod test {
             .unwrap()
             .set_default(GooseDefault::RunningMetrics, 15)
             .unwrap()
+            .set_default(GooseDefault::SnapshotInterval, 10)
+            .unwrap()
             .set_default(GooseDefault::NoResetMetrics, true)
             .unwrap()
             .set_default(GooseDefault::NoMetrics, true)
@@ -2292,16 +3256,44 @@ mod test {
             .unwrap()
             .set_default(GooseDefault::NoErrorSummary, true)
             .unwrap()
+            .set_default(GooseDefault::ThresholdAbort, true)
+            .unwrap()
+            .set_default(GooseDefault::Percentiles, percentiles.as_str())
+            .unwrap()
             .set_default(GooseDefault::NoTelnet, true)
             .unwrap()
             .set_default(GooseDefault::NoWebSocket, true)
             .unwrap()
+            .set_default(GooseDefault::Prometheus, true)
+            .unwrap()
+            .set_default(GooseDefault::PrometheusHost, prometheus_host.as_str())
+            .unwrap()
+            .set_default(GooseDefault::PrometheusPort, prometheus_port)
+            .unwrap()
             .set_default(GooseDefault::NoAutoStart, true)
             .unwrap()
             .set_default(GooseDefault::NoGzip, true)
             .unwrap()
             .set_default(GooseDefault::ReportFile, report_file.as_str())
             .unwrap()
+            .set_default(GooseDefault::SummaryJson, summary_json.as_str())
+            .unwrap()
+            .set_default(GooseDefault::SummaryCsv, summary_csv.as_str())
+            .unwrap()
+            .set_default(GooseDefault::JunitXml, junit_xml.as_str())
+            .unwrap()
+            .set_default(GooseDefault::Baseline, baseline.as_str())
+            .unwrap()
+            .set_default(GooseDefault::BaselineTolerance, 5)
+            .unwrap()
+            .set_default(GooseDefault::Statsd, statsd.as_str())
+            .unwrap()
+            .set_default(GooseDefault::InfluxDb, influxdb.as_str())
+            .unwrap()
+            .set_default(GooseDefault::SinkTags, sink_tags.as_str())
+            .unwrap()
+            .set_default(GooseDefault::SinkFlushInterval, 5)
+            .unwrap()
             .set_default(GooseDefault::RequestLog, request_log.as_str())
             .unwrap()
             .set_default(GooseDefault::RequestFormat, GooseLogFormat::Raw)
@@ -2352,23 +3344,44 @@ mod test {
 
         assert!(goose_attack.defaults.host == Some(host));
         assert!(goose_attack.defaults.users == Some(users));
+        assert!(
+            goose_attack.defaults.test_plan
+                == Some(GooseLoadProfile::from_str(&test_plan).unwrap())
+        );
         assert!(goose_attack.defaults.run_time == Some(run_time));
         assert!(goose_attack.defaults.hatch_rate == Some(hatch_rate));
+        assert!(goose_attack.defaults.arrival_rate == Some(arrival_rate));
+        assert!(goose_attack.defaults.max_users == Some(max_users));
         assert!(goose_attack.defaults.log_level == Some(log_level as u8));
         assert!(goose_attack.defaults.goose_log == Some(goose_log));
         assert!(goose_attack.defaults.request_body == Some(true));
         assert!(goose_attack.defaults.no_debug_body == Some(true));
         assert!(goose_attack.defaults.verbose == Some(verbose as u8));
         assert!(goose_attack.defaults.running_metrics == Some(15));
+        assert!(goose_attack.defaults.snapshot_interval == Some(10));
         assert!(goose_attack.defaults.no_reset_metrics == Some(true));
         assert!(goose_attack.defaults.no_metrics == Some(true));
         assert!(goose_attack.defaults.no_task_metrics == Some(true));
         assert!(goose_attack.defaults.no_error_summary == Some(true));
+        assert!(goose_attack.defaults.threshold_abort == Some(true));
+        assert!(goose_attack.defaults.percentiles == Some(percentiles));
         assert!(goose_attack.defaults.no_telnet == Some(true));
         assert!(goose_attack.defaults.no_websocket == Some(true));
+        assert!(goose_attack.defaults.prometheus == Some(true));
+        assert!(goose_attack.defaults.prometheus_host == Some(prometheus_host));
+        assert!(goose_attack.defaults.prometheus_port == Some(prometheus_port as u16));
         assert!(goose_attack.defaults.no_autostart == Some(true));
         assert!(goose_attack.defaults.no_gzip == Some(true));
         assert!(goose_attack.defaults.report_file == Some(report_file));
+        assert!(goose_attack.defaults.summary_json == Some(summary_json));
+        assert!(goose_attack.defaults.summary_csv == Some(summary_csv));
+        assert!(goose_attack.defaults.junit_xml == Some(junit_xml));
+        assert!(goose_attack.defaults.baseline == Some(baseline));
+        assert!(goose_attack.defaults.baseline_tolerance == Some(5));
+        assert!(goose_attack.defaults.statsd == Some(statsd));
+        assert!(goose_attack.defaults.influxdb == Some(influxdb));
+        assert!(goose_attack.defaults.sink_tags == Some(sink_tags));
+        assert!(goose_attack.defaults.sink_flush_interval == Some(5));
         assert!(goose_attack.defaults.request_log == Some(request_log));
         assert!(goose_attack.defaults.request_format == Some(GooseLogFormat::Raw));
         assert!(goose_attack.defaults.error_log == Some(error_log));
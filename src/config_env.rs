@@ -0,0 +1,176 @@
+//! Optionally loads run-time options from environment variables.
+//!
+//! Any run-time option can be set with a `GOOSE_` environment variable named after the
+//! [`GooseConfiguration`] field in upper case, for example `GOOSE_HOST`, `GOOSE_USERS` or
+//! `GOOSE_RUN_TIME`. This is convenient when running load tests in containers, for
+//! example to point Gaggle Workers at their Manager with `GOOSE_WORKER=true` and
+//! `GOOSE_MANAGER_HOST`, without changing the command line.
+//!
+//! Values are written the same way as on the command line. Flags such as
+//! `GOOSE_NO_METRICS` are enabled with `true` or `1`, counted flags such as `GOOSE_VERBOSE`
+//! are set with a number, and options that can be set multiple times such as
+//! `GOOSE_THRESHOLD` are set with a `;` separated list. Empty variables are ignored, as are
+//! `GOOSE_` variables that don't match an option.
+//!
+//! Options set on the command line take precedence over environment variables, which take
+//! precedence over options set in the configuration file loaded with `--config` (which can
+//! itself be set with `GOOSE_CONFIG`), followed by defaults set with
+//! [`GooseDefaultType::set_default`](../config/trait.GooseDefaultType.html#tymethod.set_default).
+
+use serde_json::Value;
+
+use crate::config::GooseConfiguration;
+use crate::config_file::{default_options, GooseOptionError, COUNTED_OPTIONS};
+use crate::GooseError;
+
+/// The prefix of environment variables that set options.
+const ENVIRONMENT_PREFIX: &str = "GOOSE_";
+
+/// Options that can't be set with an environment variable.
+const EXCLUDED_OPTIONS: [&str; 3] = ["help", "version", "list"];
+
+impl GooseConfiguration {
+    /// Load options from `GOOSE_` environment variables. Options already set on the command
+    /// line are not changed.
+    pub(crate) fn load_environment(&mut self) -> Result<(), GooseError> {
+        // Variables that aren't valid UTF-8 can't set options, so they're ignored rather
+        // than panicking as `std::env::vars()` would.
+        self.load_variables(std::env::vars_os().filter_map(|(variable, value)| {
+            Some((variable.into_string().ok()?, value.into_string().ok()?))
+        }))
+    }
+
+    /// Load options from the `GOOSE_` variables in an environment.
+    fn load_variables(
+        &mut self,
+        variables: impl Iterator<Item = (String, String)>,
+    ) -> Result<(), GooseError> {
+        let defaults = default_options();
+        for (variable, value) in variables {
+            let field = match variable.strip_prefix(ENVIRONMENT_PREFIX) {
+                Some(field) => field.to_lowercase(),
+                None => continue,
+            };
+            // Ignore variables that aren't options, which may be used by other software.
+            let default = match defaults.get(&field) {
+                Some(default) if !EXCLUDED_OPTIONS.contains(&field.as_str()) => default,
+                _ => continue,
+            };
+            if value.is_empty() {
+                continue;
+            }
+
+            let invalid = |detail: String| GooseError::InvalidOption {
+                option: variable.to_string(),
+                value: value.to_string(),
+                detail,
+            };
+            let value = if default.is_boolean() {
+                match value.to_lowercase().as_str() {
+                    "true" | "1" => Value::Bool(true),
+                    "false" | "0" => Value::Bool(false),
+                    _ => {
+                        return Err(invalid(format!(
+                            "{} must be true, false, 1 or 0.",
+                            variable
+                        )))
+                    }
+                }
+            } else if COUNTED_OPTIONS.contains(&field.as_str()) {
+                match value.parse::<u64>() {
+                    Ok(count) => Value::from(count),
+                    Err(_) => return Err(invalid(format!("{} must be a number.", variable))),
+                }
+            } else if default.is_array() {
+                Value::Array(
+                    value
+                        .split(';')
+                        .map(|item| item.trim())
+                        .filter(|item| !item.is_empty())
+                        .map(|item| Value::String(item.to_string()))
+                        .collect(),
+                )
+            } else {
+                Value::String(value.to_string())
+            };
+
+            // Parse each option separately, so errors name the variable.
+            self.merge_options(vec![(field, value)], &EXCLUDED_OPTIONS)
+                .map_err(|e| match e {
+                    GooseOptionError::Unknown(_) => {
+                        invalid(format!("{} isn't a run-time option.", variable))
+                    }
+                    GooseOptionError::Invalid(_, e) => {
+                        invalid(format!("Invalid value for {}: {}", variable, e))
+                    }
+                })?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use gumdrop::Options;
+
+    fn load(args: &[&str], variables: &[(&str, &str)]) -> Result<GooseConfiguration, GooseError> {
+        let mut configuration = GooseConfiguration::parse_args_default(args).unwrap();
+        configuration.load_variables(
+            variables
+                .iter()
+                .map(|(variable, value)| (variable.to_string(), value.to_string())),
+        )?;
+        Ok(configuration)
+    }
+
+    #[test]
+    fn environment() {
+        let configuration = load(
+            &["--users", "5"],
+            &[
+                ("GOOSE_HOST", "http://local.dev"),
+                ("GOOSE_USERS", "10"),
+                ("GOOSE_RUN_TIME", "5m"),
+                ("GOOSE_NO_RESET_METRICS", "true"),
+                ("GOOSE_STATUS_CODES", "0"),
+                ("GOOSE_VERBOSE", "2"),
+                ("GOOSE_THRESHOLD", "GET /:p95<300ms; errors<1%"),
+                ("GOOSE_HATCH_RATE", ""),
+                // Variables that aren't options are ignored.
+                ("GOOSE_DBSTRING", "postgres://"),
+                ("GOOSE_HELP", "true"),
+                ("PATH", "/bin"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(configuration.host, "http://local.dev");
+        // Options set on the command line take precedence.
+        assert_eq!(configuration.users, Some(5));
+        assert_eq!(configuration.run_time, "5m");
+        assert!(configuration.no_reset_metrics);
+        assert!(!configuration.status_codes);
+        assert_eq!(configuration.verbose, 2);
+        assert_eq!(
+            configuration.threshold,
+            vec!["GET /:p95<300ms".to_string(), "errors<1%".to_string()]
+        );
+        assert_eq!(configuration.hatch_rate, None);
+        assert!(!configuration.help);
+
+        for variables in &[
+            [("GOOSE_USERS", "many")],
+            [("GOOSE_NO_METRICS", "yes")],
+            [("GOOSE_VERBOSE", "v")],
+            [("GOOSE_CO_MITIGATION", "sometimes")],
+        ] {
+            match load(&[], variables) {
+                Err(GooseError::InvalidOption { option, .. }) => {
+                    assert_eq!(option, variables[0].0)
+                }
+                _ => panic!("{} was not rejected", variables[0].0),
+            }
+        }
+    }
+}
@@ -0,0 +1,345 @@
+//! Optionally loads run-time options from a configuration file.
+//!
+//! Any run-time option can be set in a TOML, YAML or JSON file loaded with the `--config`
+//! run-time option, for example to keep the options of several load test profiles under
+//! version control. The format is determined by the file extension: `.toml`, `.yaml` or
+//! `.yml`, or `.json`.
+//!
+//! Each option is set with its name, either as the long run-time option without leading
+//! dashes (ie `run-time`) or as the [`GooseConfiguration`] field (ie `run_time`). Values
+//! are written the same way as on the command line, and can be strings or numbers. Flags
+//! such as `no-metrics` are set with `true`, counted flags such as `verbose` with a number,
+//! and options that can be set multiple times such as `threshold` with a list:
+//!
+//! ```toml
+//! host = "http://local.dev"
+//! users = 100
+//! hatch-rate = 10
+//! run-time = "5m"
+//! no-reset-metrics = true
+//! verbose = 1
+//! threshold = ["GET /:p95<300ms", "errors<1%"]
+//! ```
+//!
+//! Options set on the command line or with `GOOSE_` environment variables (see
+//! [`config_env`](../config_env/index.html)) take precedence over options set in the
+//! configuration file, which take precedence over defaults set with
+//! [`GooseDefaultType::set_default`](../config/trait.GooseDefaultType.html#tymethod.set_default).
+//!
+//! All options are set at the top level: tables and nested mappings are rejected.
+
+use gumdrop::Options;
+use serde_json::Value;
+use std::path::Path;
+
+use crate::config::GooseConfiguration;
+use crate::GooseError;
+
+/// Options that are counted, ie `-vv`, rather than taking a value.
+pub(crate) const COUNTED_OPTIONS: [&str; 2] = ["log_level", "verbose"];
+
+/// Options that can't be set in a configuration file.
+const EXCLUDED_OPTIONS: [&str; 4] = ["config", "help", "version", "list"];
+
+impl GooseConfiguration {
+    /// Load options from the configuration file, if configured. Options already set on
+    /// the command line are not changed.
+    pub(crate) fn load_config_file(&mut self) -> Result<(), GooseError> {
+        let path = match self.config.as_ref() {
+            Some(path) => path.to_string(),
+            None => return Ok(()),
+        };
+        let invalid = |detail: String| GooseError::InvalidOption {
+            option: "--config".to_string(),
+            value: path.to_string(),
+            detail,
+        };
+
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|e| invalid(format!("Failed to read configuration file: {}", e)))?;
+        let extension = Path::new(&path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+        let options = match extension.as_str() {
+            "toml" => parse_toml(&contents),
+            "yaml" | "yml" => parse_yaml(&contents),
+            "json" => parse_json(&contents),
+            _ => {
+                Err("unsupported file extension, expected .toml, .yaml, .yml or .json".to_string())
+            }
+        }
+        .map_err(|e| invalid(format!("Failed to parse {}: {}", path, e)))?;
+
+        self.merge_options(options, &EXCLUDED_OPTIONS)
+            .map_err(|e| match e {
+                GooseOptionError::Unknown(key) => {
+                    invalid(format!("{}: unknown option `{}`", path, key))
+                }
+                GooseOptionError::Invalid(key, e) => {
+                    invalid(format!("{}: invalid value for `{}`: {}", path, key, e))
+                }
+            })
+    }
+
+    /// Set options that weren't already set, parsing each the same way as on the command
+    /// line.
+    pub(crate) fn merge_options(
+        &mut self,
+        options: Vec<(String, Value)>,
+        excluded: &[&str],
+    ) -> Result<(), GooseOptionError> {
+        // Options that weren't set have their default value.
+        let defaults = default_options();
+        let mut configuration = serde_json::to_value(&*self).expect("unexpected serde failure");
+
+        for (key, value) in options {
+            let field = key.replace('-', "_");
+            if excluded.contains(&field.as_str()) || defaults.get(&field).is_none() {
+                return Err(GooseOptionError::Unknown(key));
+            }
+
+            let multiple = defaults[&field].is_array();
+            let parsed = option_args(&field, &value, multiple).and_then(|args| {
+                GooseConfiguration::parse_args_default(&args).map_err(|e| e.to_string())
+            });
+            let parsed = match parsed {
+                Ok(parsed) => serde_json::to_value(parsed).expect("unexpected serde failure"),
+                Err(e) => return Err(GooseOptionError::Invalid(key, e)),
+            };
+
+            if configuration[&field] == defaults[&field] {
+                configuration[&field] = parsed[&field].clone();
+            }
+        }
+
+        *self = serde_json::from_value(configuration).expect("unexpected serde failure");
+        Ok(())
+    }
+}
+
+/// Why an option couldn't be set.
+#[derive(Debug)]
+pub(crate) enum GooseOptionError {
+    /// The option doesn't exist.
+    Unknown(String),
+    /// The option and why its value is invalid.
+    Invalid(String, String),
+}
+
+/// The default value of each option, ie when not set on the command line.
+pub(crate) fn default_options() -> Value {
+    let empty: &[&str] = &[];
+    serde_json::to_value(GooseConfiguration::parse_args_default(empty).unwrap())
+        .expect("unexpected serde failure")
+}
+
+/// Convert an option from a configuration file to command line arguments.
+fn option_args(field: &str, value: &Value, multiple: bool) -> Result<Vec<String>, String> {
+    let flag = format!("--{}", field.replace('_', "-"));
+    let mut args = Vec::new();
+    match value {
+        // Flags are set with a boolean.
+        Value::Bool(true) => args.push(flag),
+        Value::Bool(false) | Value::Null => {}
+        // Counted flags are set with a number.
+        Value::Number(number) if COUNTED_OPTIONS.contains(&field) => {
+            let count = number
+                .as_u64()
+                .ok_or_else(|| format!("expected a count, found {}", number))?;
+            for _ in 0..count {
+                args.push(flag.to_string());
+            }
+        }
+        // Options that can be set multiple times are set with a list.
+        Value::Array(values) if multiple => {
+            for value in values {
+                args.push(flag.to_string());
+                args.push(scalar_to_string(value)?);
+            }
+        }
+        value => {
+            args.push(flag);
+            args.push(scalar_to_string(value)?);
+        }
+    }
+    Ok(args)
+}
+
+/// Convert a string or number to a command line argument.
+fn scalar_to_string(value: &Value) -> Result<String, String> {
+    match value {
+        Value::String(string) => Ok(string.to_string()),
+        Value::Number(number) => Ok(number.to_string()),
+        Value::Array(_) => Err(format!("expected a single value, found {}", value)),
+        _ => Err(format!("expected a string or number, found {}", value)),
+    }
+}
+
+/// Parse options from a JSON object.
+fn parse_json(contents: &str) -> Result<Vec<(String, Value)>, String> {
+    let value = serde_json::from_str::<Value>(contents).map_err(|e| e.to_string())?;
+    into_options(value, "an object")
+}
+
+/// Parse options from a TOML document.
+fn parse_toml(contents: &str) -> Result<Vec<(String, Value)>, String> {
+    // Parsing a TOML value first rejects duplicate keys.
+    let value = toml::from_str::<toml::Value>(contents).map_err(|e| e.to_string())?;
+    into_options(
+        serde_json::to_value(value).map_err(|e| e.to_string())?,
+        "a table",
+    )
+}
+
+/// Parse options from a YAML document.
+fn parse_yaml(contents: &str) -> Result<Vec<(String, Value)>, String> {
+    let value = serde_yaml::from_str::<Value>(contents).map_err(|e| e.to_string())?;
+    into_options(value, "a mapping")
+}
+
+/// Convert a parsed document to options, set at its top level. A null document, ie `~`
+/// in YAML, sets no options.
+fn into_options(value: Value, expected: &str) -> Result<Vec<(String, Value)>, String> {
+    match value {
+        Value::Object(object) => Ok(object.into_iter().collect()),
+        Value::Null => Ok(Vec::new()),
+        _ => Err(format!("expected {} of options", expected)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn toml() {
+        let options = parse_toml(
+            r#"
+# A load test profile.
+host = "http://local.dev" # Trailing comment.
+users = 1_000
+hatch-rate = 2.5
+'run-time' = '5m'
+no-reset-metrics = true
+request-log = 'C:\logs\requests.log'
+debug-log = """
+debug.log"""
+threshold = [
+    "GET /:p95<300ms",
+    "errors<1%", # The # isn't a comment in a string.
+]
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            options,
+            vec![
+                ("debug-log".to_string(), json!("debug.log")),
+                ("hatch-rate".to_string(), json!(2.5)),
+                ("host".to_string(), json!("http://local.dev")),
+                ("no-reset-metrics".to_string(), json!(true)),
+                ("request-log".to_string(), json!(r"C:\logs\requests.log")),
+                ("run-time".to_string(), json!("5m")),
+                (
+                    "threshold".to_string(),
+                    json!(["GET /:p95<300ms", "errors<1%"])
+                ),
+                ("users".to_string(), json!(1000)),
+            ]
+        );
+
+        // Tables and dotted keys are nested, so they aren't options.
+        assert_eq!(
+            parse_toml("[options]\nusers = 1").unwrap(),
+            vec![("options".to_string(), json!({"users": 1}))]
+        );
+        assert_eq!(
+            parse_toml("options.users = 1").unwrap(),
+            vec![("options".to_string(), json!({"users": 1}))]
+        );
+        assert!(parse_toml("users = 1\nusers = 2").is_err());
+        assert!(parse_toml("host = http://local.dev").is_err());
+        assert!(parse_toml("threshold = [\"errors<1%\"").is_err());
+        assert!(parse_toml("users").is_err());
+        assert!(parse_toml("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn yaml() {
+        let options = parse_yaml(
+            r#"
+---
+# A load test profile.
+host: http://local.dev # Trailing comment.
+users: 1000
+hatch-rate: "10"
+run-time: '5m'
+running-metrics: 'it''s 15'
+no-reset-metrics: true
+request-log: ~
+threshold:
+- "GET /:p95<300ms"
+- errors<1%
+percentiles: [
+  50,
+  99.9,
+]
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            options,
+            vec![
+                ("hatch-rate".to_string(), json!("10")),
+                ("host".to_string(), json!("http://local.dev")),
+                ("no-reset-metrics".to_string(), json!(true)),
+                ("percentiles".to_string(), json!([50, 99.9])),
+                ("request-log".to_string(), json!(null)),
+                ("run-time".to_string(), json!("5m")),
+                ("running-metrics".to_string(), json!("it's 15")),
+                (
+                    "threshold".to_string(),
+                    json!(["GET /:p95<300ms", "errors<1%"])
+                ),
+                ("users".to_string(), json!(1000)),
+            ]
+        );
+
+        // Nested mappings aren't options.
+        assert_eq!(
+            parse_yaml("options:\n  users: 1").unwrap(),
+            vec![("options".to_string(), json!({"users": 1}))]
+        );
+        assert!(parse_yaml("- users").is_err());
+        assert!(parse_yaml("users: [1").is_err());
+        assert!(parse_yaml("~").unwrap().is_empty());
+    }
+
+    #[test]
+    fn args() {
+        assert_eq!(
+            option_args("users", &json!(10), false).unwrap(),
+            vec!["--users", "10"]
+        );
+        assert_eq!(
+            option_args("no_metrics", &json!(true), false).unwrap(),
+            vec!["--no-metrics"]
+        );
+        assert!(option_args("no_metrics", &json!(false), false)
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            option_args("verbose", &json!(2), false).unwrap(),
+            vec!["--verbose", "--verbose"]
+        );
+        assert_eq!(
+            option_args("threshold", &json!(["p95<1s", "errors<1%"]), true).unwrap(),
+            vec!["--threshold", "p95<1s", "--threshold", "errors<1%"]
+        );
+        assert!(option_args("host", &json!(["a", "b"]), false).is_err());
+        assert!(option_args("host", &json!({"url": "http://local.dev"}), false).is_err());
+    }
+}
@@ -73,7 +73,10 @@ pub enum GooseControllerCommand {
     /// users 100
     /// ```
     ///
-    /// Goose must be idle to process this command.
+    /// If the load test is running, [`GooseUser`](../goose/struct.GooseUser.html)s are
+    /// launched or gracefully stopped at the configured `hatch_rate` until the new number
+    /// of users is running. Goose must be idle to process this command if a load profile
+    /// or arrival rate is configured.
     Users,
     /// Configure how quickly new [`GooseUser`](../goose/struct.GooseUser.html)s are launched.
     ///
@@ -133,6 +136,18 @@ pub enum GooseControllerCommand {
     ///
     /// This command can be run at any time.
     MetricsJson,
+    /// Display the current [`GooseMetric`](../metrics/struct.GooseMetrics.html)s in the
+    /// Prometheus text format.
+    ///
+    /// # Example
+    /// Returns the current Goose metrics in the Prometheus text format.
+    /// ```notest
+    /// prometheus
+    /// ```
+    ///
+    /// This command can be run at any time. The same metrics are served by the optional
+    /// `--prometheus` HTTP endpoint.
+    Prometheus,
     /// Displays a list of all commands supported by the Controller.
     ///
     /// # Example
@@ -171,8 +186,31 @@ pub enum GooseControllerCommand {
     /// stop
     /// ```
     ///
-    /// Goose must be running (or starting) to process this command.
+    /// Goose must be running (or starting, or paused) to process this command.
     Stop,
+    /// Pause a running test, without stopping any
+    /// [`GooseUser`](../goose/struct.GooseUser.html)s.
+    ///
+    /// # Example
+    /// Pauses a running load test. Each user finishes its current task and then waits,
+    /// keeping its session and cookies.
+    /// ```notest
+    /// pause
+    /// ```
+    ///
+    /// Goose must be running (or starting) to process this command. Time spent paused
+    /// doesn't count towards the configured `run_time`, or the duration of the load test.
+    Pause,
+    /// Resume a paused test.
+    ///
+    /// # Example
+    /// Resumes a paused load test, continuing with the same sessions.
+    /// ```notest
+    /// resume
+    /// ```
+    ///
+    /// Goose must be paused to process this command.
+    Resume,
     /// Tell the load test to shut down (which will disconnect the controller).
     ///
     /// # Example
@@ -203,6 +241,8 @@ pub(crate) enum GooseControllerResponseMessage {
     Config(Box<GooseConfiguration>),
     /// A response containing current load test metrics.
     Metrics(Box<GooseMetrics>),
+    /// A response containing current load test metrics in the Prometheus text format.
+    Prometheus(String),
 }
 
 /// The request that's passed from the controller to the parent thread.
@@ -464,6 +504,16 @@ impl GooseControllerState {
                 command: GooseControllerCommand::Stop,
                 value: None,
             })
+        } else if matches.matched(GooseControllerCommand::Pause as usize) {
+            Ok(GooseControllerRequestMessage {
+                command: GooseControllerCommand::Pause,
+                value: None,
+            })
+        } else if matches.matched(GooseControllerCommand::Resume as usize) {
+            Ok(GooseControllerRequestMessage {
+                command: GooseControllerCommand::Resume,
+                value: None,
+            })
         } else if matches.matched(GooseControllerCommand::Shutdown as usize) {
             Ok(GooseControllerRequestMessage {
                 command: GooseControllerCommand::Shutdown,
@@ -489,6 +539,11 @@ impl GooseControllerState {
                 command: GooseControllerCommand::MetricsJson,
                 value: None,
             })
+        } else if matches.matched(GooseControllerCommand::Prometheus as usize) {
+            Ok(GooseControllerRequestMessage {
+                command: GooseControllerCommand::Prometheus,
+                value: None,
+            })
         } else if matches.matched(GooseControllerCommand::Host as usize) {
             // Perform a second regex to capture the host value.
             let caps = self.captures[GooseControllerCommand::Host as usize]
@@ -609,7 +664,7 @@ impl GooseControllerState {
                 if let GooseControllerResponseMessage::Bool(true) = response {
                     Ok("users configured".to_string())
                 } else {
-                    Err("load test not idle, failed to reconfigure users".to_string())
+                    Err("failed to reconfigure users, load test must be idle or running without --test-plan or --arrival-rate".to_string())
                 }
             }
             GooseControllerCommand::HatchRate => {
@@ -654,6 +709,13 @@ impl GooseControllerState {
                     Err("error loading metrics".to_string())
                 }
             }
+            GooseControllerCommand::Prometheus => {
+                if let GooseControllerResponseMessage::Prometheus(metrics) = response {
+                    Ok(metrics)
+                } else {
+                    Err("error loading metrics".to_string())
+                }
+            }
             GooseControllerCommand::Start => {
                 if let GooseControllerResponseMessage::Bool(true) = response {
                     Ok("load test started".to_string())
@@ -672,6 +734,20 @@ impl GooseControllerState {
                     Err("load test not running, failed to stop".to_string())
                 }
             }
+            GooseControllerCommand::Pause => {
+                if let GooseControllerResponseMessage::Bool(true) = response {
+                    Ok("load test paused".to_string())
+                } else {
+                    Err("load test not running, failed to pause".to_string())
+                }
+            }
+            GooseControllerCommand::Resume => {
+                if let GooseControllerResponseMessage::Bool(true) = response {
+                    Ok("load test resumed".to_string())
+                } else {
+                    Err("load test not paused, failed to resume".to_string())
+                }
+            }
             GooseControllerCommand::Shutdown => {
                 if let GooseControllerResponseMessage::Bool(true) = response {
                     Ok("load test shut down".to_string())
@@ -1005,6 +1081,9 @@ pub(crate) async fn controller_main(
         r"(?i)^(metrics|stats)$",
         // Display running metrics for the currently active load test in json.
         r"(?i)^(metricsjson|metrics-json|statsjson|stats-json)$",
+        // Display running metrics for the currently active load test in the Prometheus
+        // text format.
+        r"(?i)^prometheus$",
         // Provide a list of possible commands.
         r"(?i)^(help|\?)$",
         // Exit/quit the controller connection, does not affect load test.
@@ -1013,6 +1092,10 @@ pub(crate) async fn controller_main(
         r"(?i)^start$",
         // Stop an idle load test.
         r"(?i)^stop$",
+        // Pause a running load test.
+        r"(?i)^pause$",
+        // Resume a paused load test.
+        r"(?i)^resume$",
         // Shutdown the load test (which will cause the controller connection to quit).
         r"(?i)^shutdown$",
     ])
@@ -1077,6 +1160,8 @@ fn display_help() -> String {
  exit (quit)        exit controller
  start              start an idle load test
  stop               stop a running load test and return to idle state
+ pause              pause a running load test
+ resume             resume a paused load test
  shutdown           shutdown running load test (and exit controller)
  host HOST          set host to load test, ie http://localhost/
  users INT          set number of simulated users
@@ -1085,7 +1170,8 @@ fn display_help() -> String {
  config             display load test configuration
  config-json        display load test configuration in json format
  metrics            display metrics for current load test
- metrics-json       display metrics for current load test in json format",
+ metrics-json       display metrics for current load test in json format
+ prometheus         display metrics for current load test in prometheus format",
         env!("CARGO_PKG_NAME"),
         env!("CARGO_PKG_VERSION")
     )
@@ -1145,6 +1231,14 @@ impl GooseAttack {
                                 )),
                             );
                         }
+                        // Send back the running metrics in the Prometheus text format.
+                        GooseControllerCommand::Prometheus => {
+                            let metrics = self.prometheus_metrics();
+                            self.reply_to_controller(
+                                message,
+                                GooseControllerResponseMessage::Prometheus(metrics),
+                            );
+                        }
                         // Start the load test, and acknowledge command.
                         GooseControllerCommand::Start => {
                             // We can only start an idle load test.
@@ -1176,9 +1270,15 @@ impl GooseAttack {
                         }
                         // Stop the load test, and acknowledge command.
                         GooseControllerCommand::Stop => {
-                            // We can only stop a starting or running load test.
-                            if [AttackPhase::Starting, AttackPhase::Running]
-                                .contains(&self.attack_phase)
+                            // We can only stop a starting, running or paused load test.
+                            if [
+                                AttackPhase::Starting,
+                                AttackPhase::Running,
+                                AttackPhase::Increase,
+                                AttackPhase::Decrease,
+                                AttackPhase::Paused,
+                            ]
+                            .contains(&self.attack_phase)
                             {
                                 self.set_attack_phase(
                                     goose_attack_run_state,
@@ -1199,6 +1299,44 @@ impl GooseAttack {
                                 );
                             }
                         }
+                        // Pause the load test, and acknowledge command.
+                        GooseControllerCommand::Pause => {
+                            // We can only pause a starting or running load test.
+                            if [
+                                AttackPhase::Starting,
+                                AttackPhase::Running,
+                                AttackPhase::Increase,
+                                AttackPhase::Decrease,
+                            ]
+                            .contains(&self.attack_phase)
+                            {
+                                self.pause_attack(goose_attack_run_state);
+                                self.reply_to_controller(
+                                    message,
+                                    GooseControllerResponseMessage::Bool(true),
+                                );
+                            } else {
+                                self.reply_to_controller(
+                                    message,
+                                    GooseControllerResponseMessage::Bool(false),
+                                );
+                            }
+                        }
+                        // Resume the load test, and acknowledge command.
+                        GooseControllerCommand::Resume => {
+                            if self.attack_phase == AttackPhase::Paused {
+                                self.resume_attack(goose_attack_run_state);
+                                self.reply_to_controller(
+                                    message,
+                                    GooseControllerResponseMessage::Bool(true),
+                                );
+                            } else {
+                                self.reply_to_controller(
+                                    message,
+                                    GooseControllerResponseMessage::Bool(false),
+                                );
+                            }
+                        }
                         // Stop the load test, and acknowledge request.
                         GooseControllerCommand::Shutdown => {
                             // If load test is Idle, there are no metrics to display.
@@ -1244,7 +1382,17 @@ impl GooseAttack {
                             }
                         }
                         GooseControllerCommand::Users => {
-                            if self.attack_phase == AttackPhase::Idle {
+                            // A running load test can be scaled up or down, unless a load
+                            // profile or arrival rate is managing the number of users.
+                            let running = [
+                                AttackPhase::Running,
+                                AttackPhase::Increase,
+                                AttackPhase::Decrease,
+                            ]
+                            .contains(&self.attack_phase)
+                                && self.configuration.test_plan.is_none()
+                                && self.configuration.arrival_rate.is_none();
+                            if self.attack_phase == AttackPhase::Idle || running {
                                 // The controller uses a regular expression to validate that
                                 // this is a valid integer, so simply use it with further
                                 // validation.
@@ -1254,10 +1402,20 @@ impl GooseAttack {
                                         self.configuration.users, users
                                     );
                                     // Use expect() as Controller uses regex to validate this is an integer.
-                                    self.configuration.users = Some(
-                                        usize::from_str(users)
-                                            .expect("failed to convert string to usize"),
-                                    );
+                                    let users = usize::from_str(users)
+                                        .expect("failed to convert string to usize");
+                                    self.configuration.users = Some(users);
+                                    // Launch or stop users at the configured hatch_rate.
+                                    if running {
+                                        let hatch_rate = util::get_hatch_rate(
+                                            self.configuration.hatch_rate.clone(),
+                                        );
+                                        self.ramp_users(
+                                            goose_attack_run_state,
+                                            users,
+                                            (1_000.0 / hatch_rate) as usize,
+                                        );
+                                    }
                                     self.reply_to_controller(
                                         message,
                                         GooseControllerResponseMessage::Bool(true),
@@ -0,0 +1,141 @@
+//! Optional custom metrics, recording counters, gauges and timings from tasks.
+//!
+//! Not everything worth measuring during a load test is an HTTP request. A task can
+//! record its own named metrics, for example how many items were added to a cart, the
+//! queue depth it observed, or how long it took for a job to complete while polling for
+//! its status. There are three kinds of custom metrics:
+//!  - counters, incremented with [`GooseUser::increment_counter`], are displayed with
+//!    their total and rate per second;
+//!  - gauges, set with [`GooseUser::set_gauge`], are displayed with their last, minimum,
+//!    average and maximum value;
+//!  - timings, recorded with [`GooseUser::record_timing`], are displayed like task run
+//!    times, including percentiles.
+//!
+//! Custom metrics are sent to the parent process like all other metrics, and are
+//! stored in [`GooseMetrics::custom`](../metrics/struct.GooseMetrics.html#structfield.custom).
+//! They are included in the text metrics, the html report, the JSON, CSV and Prometheus
+//! formats, and are merged across Gaggle Workers.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use goose::prelude::*;
+//! use std::time::Instant;
+//!
+//! let mut task = task!(add_to_cart);
+//!
+//! async fn add_to_cart(user: &mut GooseUser) -> GooseTaskResult {
+//!     let started = Instant::now();
+//!     let _goose = user.post("/cart", "item=7").await?;
+//!     user.increment_counter("items added", 1)?;
+//!     user.set_gauge("queue depth", 12.0)?;
+//!     user.record_timing("item added", started.elapsed())?;
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use std::time::Duration;
+
+use crate::goose::{GooseTaskResult, GooseUser};
+use crate::metrics::{GooseCustomMetric, GooseCustomMetricValue, GooseMetric};
+
+impl GooseUser {
+    /// Increment a custom counter.
+    ///
+    /// Counters are displayed with their total, and their rate per second.
+    ///
+    /// # Example
+    /// ```rust
+    /// use goose::prelude::*;
+    ///
+    /// let mut task = task!(add_to_cart);
+    ///
+    /// async fn add_to_cart(user: &mut GooseUser) -> GooseTaskResult {
+    ///     let _goose = user.post("/cart", "item=7&quantity=3").await?;
+    ///     user.increment_counter("items added", 3)
+    /// }
+    /// ```
+    pub fn increment_counter(&self, name: &str, increment: u64) -> GooseTaskResult {
+        self.send_custom_metric(name, GooseCustomMetricValue::Counter(increment))
+    }
+
+    /// Set a custom gauge.
+    ///
+    /// Gauges are displayed with their last, minimum, average and maximum value.
+    ///
+    /// # Example
+    /// ```rust
+    /// use goose::prelude::*;
+    ///
+    /// let mut task = task!(check_queue);
+    ///
+    /// async fn check_queue(user: &mut GooseUser) -> GooseTaskResult {
+    ///     let goose = user.get("/queue/depth").await?;
+    ///     if let Ok(response) = goose.response {
+    ///         if let Ok(depth) = response.text().await?.trim().parse::<f64>() {
+    ///             user.set_gauge("queue depth", depth)?;
+    ///         }
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn set_gauge(&self, name: &str, value: f64) -> GooseTaskResult {
+        self.send_custom_metric(name, GooseCustomMetricValue::Gauge(value))
+    }
+
+    /// Record a custom timing.
+    ///
+    /// Timings are displayed like task run times, with their average, minimum, maximum and
+    /// percentiles.
+    ///
+    /// # Example
+    /// ```rust
+    /// use goose::prelude::*;
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let mut task = task!(run_job);
+    ///
+    /// async fn run_job(user: &mut GooseUser) -> GooseTaskResult {
+    ///     let started = Instant::now();
+    ///     let _goose = user.post("/job", "").await?;
+    ///     // Poll until the job is done.
+    ///     loop {
+    ///         let goose = user.get("/job/status").await?;
+    ///         if goose.response?.text().await? == "done" {
+    ///             break;
+    ///         }
+    ///         tokio::time::sleep(Duration::from_millis(100)).await;
+    ///     }
+    ///     user.record_timing("job completed", started.elapsed())
+    /// }
+    /// ```
+    pub fn record_timing(&self, name: &str, time: Duration) -> GooseTaskResult {
+        self.send_custom_metric(
+            name,
+            GooseCustomMetricValue::Timing(time.as_micros() as u64),
+        )
+    }
+
+    /// Send a custom metric to the parent.
+    fn send_custom_metric(&self, name: &str, value: GooseCustomMetricValue) -> GooseTaskResult {
+        if self.config.no_metrics {
+            return Ok(());
+        }
+
+        // Parent is not defined when running
+        // [`test_start`](../struct.GooseAttack.html#method.test_start),
+        // [`test_stop`](../struct.GooseAttack.html#method.test_stop), and during testing.
+        if let Some(parent) = self.channel_to_parent.as_ref() {
+            parent.send(GooseMetric::Custom(GooseCustomMetric::new(
+                self.started.elapsed().as_millis(),
+                name,
+                value,
+                self.weighted_users_index,
+            )))?;
+        }
+
+        Ok(())
+    }
+}
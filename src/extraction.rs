@@ -0,0 +1,501 @@
+//! Optional extraction of values from responses into per-user variables.
+//!
+//! Dynamic sites often return values that must be sent back with later requests, such as
+//! the `form_build_id` and `form_token` of a Drupal form, a CSRF token, or the id of a
+//! newly created resource. A [`GooseExtraction`] describes where to find each value: a
+//! regular expression, a JSON pointer or path, a CSS selector, or a header. Extracting
+//! from a [`GooseResponse`](../goose/struct.GooseResponse.html) with
+//! [`GooseResponse::extract`](../goose/struct.GooseResponse.html#method.extract) reads the
+//! body, finds every value, and stores them as named variables on the
+//! [`GooseUser`](../goose/struct.GooseUser.html). Later requests read them with
+//! [`GooseUser::get_variable`](../goose/struct.GooseUser.html#method.get_variable), or
+//! reference them in paths with
+//! [`GooseUser::expand_variables`](../goose/struct.GooseUser.html#method.expand_variables).
+//!
+//! If any value isn't found, no variables are stored and the request is marked as a
+//! failure with an error such as `form_token not found by css input[name="form_token"]:
+//! /node/1`.
+//!
+//! ## Example
+//!
+//! A `GooseExtraction` compiles its regular expressions and CSS selectors when it is
+//! built, so build it once rather than each time a task runs:
+//!
+//! ```rust
+//! use goose::prelude::*;
+//! use goose::extraction::GooseExtraction;
+//! use lazy_static::lazy_static;
+//!
+//! lazy_static! {
+//!     static ref COMMENT_FORM: GooseExtraction = GooseExtraction::new()
+//!         .regex("form_build_id", r#"name="form_build_id" value=['"](.*?)['"]"#)
+//!         .unwrap()
+//!         .css_attribute("form_token", r#"input[name="form_token"]"#, "value")
+//!         .unwrap();
+//! }
+//!
+//! let mut task = task!(post_comment);
+//!
+//! async fn post_comment(user: &mut GooseUser) -> GooseTaskResult {
+//!     let goose = user.get("/node/1").await?;
+//!     goose.extract(user, &COMMENT_FORM).await?;
+//!
+//!     let comment = user.expand_variables(
+//!         "form_build_id={{form_build_id}}&form_token={{form_token}}&op=Save",
+//!     );
+//!     let _goose = user.post("/comment/reply/1", &comment).await?;
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use regex::Regex;
+use reqwest::header;
+use scraper::{Html, Selector};
+use serde_json::Value;
+
+use crate::goose::{GooseResponse, GooseTaskError, GooseUser};
+use crate::validation::{fail, GooseValidatedResponse, GooseValidation};
+use crate::GooseError;
+
+/// Where to find a value in a response.
+#[derive(Clone, Debug)]
+enum GooseExtractor {
+    /// The first capture group of the regular expression, or the whole match if it has
+    /// no capture groups.
+    Regex(Regex),
+    /// The value at the JSON pointer.
+    Json(String),
+    /// An attribute of the first element matching the CSS selector.
+    CssAttribute(String, Selector, String),
+    /// The text of the first element matching the CSS selector.
+    CssText(String, Selector),
+    /// The value of the header.
+    Header(String),
+}
+impl GooseExtractor {
+    /// Describes the extractor in errors.
+    fn describe(&self) -> String {
+        match self {
+            GooseExtractor::Regex(regex) => format!("regex /{}/", regex),
+            GooseExtractor::Json(pointer) => format!("json {}", pointer),
+            GooseExtractor::CssAttribute(selector, _, attribute) => {
+                format!("css {} attribute {}", selector, attribute)
+            }
+            GooseExtractor::CssText(selector, _) => format!("css {}", selector),
+            GooseExtractor::Header(name) => format!("header {}", name),
+        }
+    }
+}
+
+/// A description of values to extract from a response, see the
+/// [module documentation](index.html).
+///
+/// Values are added with builder methods, each stored in a variable with the given name.
+/// The same `GooseExtraction` can be used to extract from any number of responses.
+#[derive(Clone, Debug, Default)]
+pub struct GooseExtraction {
+    /// Variable names and where to find their values, in order.
+    extractors: Vec<(String, GooseExtractor)>,
+}
+impl GooseExtraction {
+    /// Create an extraction without any values.
+    pub fn new() -> Self {
+        GooseExtraction::default()
+    }
+
+    /// Extract the first capture group of the regular expression from the body, or the
+    /// whole match if the regular expression has no capture groups.
+    ///
+    /// Returns [`GooseError::InvalidPattern`](../enum.GooseError.html#variant.InvalidPattern)
+    /// if the regular expression is invalid.
+    pub fn regex(mut self, variable: &str, pattern: &str) -> Result<Self, GooseError> {
+        let regex = Regex::new(pattern).map_err(|e| GooseError::InvalidPattern {
+            pattern: pattern.to_string(),
+            detail: e.to_string(),
+        })?;
+        self.extractors
+            .push((variable.to_string(), GooseExtractor::Regex(regex)));
+        Ok(self)
+    }
+
+    /// Extract the value at the [JSON pointer](https://tools.ietf.org/html/rfc6901) from
+    /// a JSON body, for example `/user/roles/0`. Strings are stored without quotes, other
+    /// values as JSON.
+    pub fn json_pointer(mut self, variable: &str, pointer: &str) -> Self {
+        self.extractors.push((
+            variable.to_string(),
+            GooseExtractor::Json(pointer.to_string()),
+        ));
+        self
+    }
+
+    /// Extract the value at the JSON path from a JSON body, for example
+    /// `$.user.roles[0]`. Only paths to a single value are supported, made of keys and
+    /// array indexes.
+    ///
+    /// Returns [`GooseError::InvalidPattern`](../enum.GooseError.html#variant.InvalidPattern)
+    /// if the path is invalid.
+    pub fn json_path(self, variable: &str, path: &str) -> Result<Self, GooseError> {
+        match json_path_to_pointer(path) {
+            Some(pointer) => Ok(self.json_pointer(variable, &pointer)),
+            None => Err(GooseError::InvalidPattern {
+                pattern: path.to_string(),
+                detail: "expected keys and array indexes, such as $.user.roles[0]".to_string(),
+            }),
+        }
+    }
+
+    /// Extract an attribute of the first element in an HTML body matching the CSS
+    /// selector, for example the `value` of `input[name="form_token"]`.
+    ///
+    /// Returns [`GooseError::InvalidPattern`](../enum.GooseError.html#variant.InvalidPattern)
+    /// if the selector is invalid.
+    pub fn css_attribute(
+        mut self,
+        variable: &str,
+        selector: &str,
+        attribute: &str,
+    ) -> Result<Self, GooseError> {
+        let parsed_selector = parse_selector(selector)?;
+        self.extractors.push((
+            variable.to_string(),
+            GooseExtractor::CssAttribute(
+                selector.to_string(),
+                parsed_selector,
+                attribute.to_string(),
+            ),
+        ));
+        Ok(self)
+    }
+
+    /// Extract the text of the first element in an HTML body matching the CSS selector,
+    /// without leading and trailing whitespace.
+    ///
+    /// Returns [`GooseError::InvalidPattern`](../enum.GooseError.html#variant.InvalidPattern)
+    /// if the selector is invalid.
+    pub fn css_text(mut self, variable: &str, selector: &str) -> Result<Self, GooseError> {
+        let parsed_selector = parse_selector(selector)?;
+        self.extractors.push((
+            variable.to_string(),
+            GooseExtractor::CssText(selector.to_string(), parsed_selector),
+        ));
+        Ok(self)
+    }
+
+    /// Extract the value of the header. Header names aren't case sensitive.
+    pub fn header(mut self, variable: &str, name: &str) -> Self {
+        self.extractors.push((
+            variable.to_string(),
+            GooseExtractor::Header(name.to_string()),
+        ));
+        self
+    }
+
+    /// Returns the extracted variables, or describes the first value that wasn't found.
+    fn extract_values(
+        &self,
+        headers: &header::HeaderMap,
+        body: &str,
+    ) -> Result<Vec<(String, String)>, String> {
+        // Only parse the body once, and only if needed.
+        let mut json: Option<Option<Value>> = None;
+        let mut html: Option<Html> = None;
+
+        let mut values = Vec::with_capacity(self.extractors.len());
+        for (variable, extractor) in &self.extractors {
+            let value = match extractor {
+                GooseExtractor::Regex(regex) => regex.captures(body).and_then(|captures| {
+                    captures
+                        .get(1)
+                        .or_else(|| captures.get(0))
+                        .map(|value| value.as_str().to_string())
+                }),
+                GooseExtractor::Json(pointer) => json
+                    .get_or_insert_with(|| serde_json::from_str(body).ok())
+                    .as_ref()
+                    .and_then(|json| json.pointer(pointer))
+                    .map(|value| match value {
+                        Value::String(value) => value.clone(),
+                        _ => value.to_string(),
+                    }),
+                GooseExtractor::CssAttribute(_, selector, attribute) => html
+                    .get_or_insert_with(|| Html::parse_document(body))
+                    .select(selector)
+                    .next()
+                    .and_then(|element| element.value().attr(attribute))
+                    .map(|value| value.to_string()),
+                GooseExtractor::CssText(_, selector) => html
+                    .get_or_insert_with(|| Html::parse_document(body))
+                    .select(selector)
+                    .next()
+                    .map(|element| element.text().collect::<String>().trim().to_string()),
+                GooseExtractor::Header(name) => headers
+                    .get(name.as_str())
+                    .and_then(|value| value.to_str().ok())
+                    .map(|value| value.to_string()),
+            };
+            match value {
+                Some(value) => values.push((variable.to_string(), value)),
+                None => {
+                    return Err(format!(
+                        "{} not found by {}",
+                        variable,
+                        extractor.describe()
+                    ))
+                }
+            }
+        }
+
+        Ok(values)
+    }
+}
+
+impl GooseResponse {
+    /// Extract values from the response into per-user variables, marking the request as
+    /// a failure if any value isn't found. See the [`extraction`](../extraction/index.html)
+    /// module.
+    ///
+    /// Returns the response with its body, or [`GooseTaskError::RequestFailed`] if the
+    /// request failed or a value wasn't found. To also validate the response, use
+    /// [`GooseValidatedResponse::extract`] after
+    /// [`validate`](struct.GooseResponse.html#method.validate).
+    ///
+    /// # Example
+    /// ```rust
+    /// use goose::prelude::*;
+    /// use goose::extraction::GooseExtraction;
+    ///
+    /// let mut task = task!(create_order);
+    ///
+    /// async fn create_order(user: &mut GooseUser) -> GooseTaskResult {
+    ///     let extraction = GooseExtraction::new().json_pointer("order_id", "/order/id");
+    ///
+    ///     let goose = user.post("/orders", "item=1").await?;
+    ///     goose.extract(user, &extraction).await?;
+    ///     let path = user.expand_variables("/orders/{{order_id}}");
+    ///     let _goose = user.get(&path).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn extract(
+        self,
+        user: &mut GooseUser,
+        extraction: &GooseExtraction,
+    ) -> Result<GooseValidatedResponse, GooseTaskError> {
+        self.validate(user, &GooseValidation::new())
+            .await?
+            .extract(user, extraction)
+    }
+}
+
+impl GooseValidatedResponse {
+    /// Extract values from the validated response into per-user variables, marking the
+    /// request as a failure if any value isn't found.
+    ///
+    /// # Example
+    /// ```rust
+    /// use goose::prelude::*;
+    /// use goose::extraction::GooseExtraction;
+    /// use goose::validation::GooseValidation;
+    ///
+    /// let mut task = task!(login_page);
+    ///
+    /// async fn login_page(user: &mut GooseUser) -> GooseTaskResult {
+    ///     let validation = GooseValidation::new().title("Log in");
+    ///     let extraction = GooseExtraction::new()
+    ///         .css_attribute("form_build_id", r#"input[name="form_build_id"]"#, "value")
+    ///         .unwrap();
+    ///
+    ///     let goose = user.get("/user").await?;
+    ///     goose
+    ///         .validate(user, &validation)
+    ///         .await?
+    ///         .extract(user, &extraction)?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn extract(
+        mut self,
+        user: &mut GooseUser,
+        extraction: &GooseExtraction,
+    ) -> Result<GooseValidatedResponse, GooseTaskError> {
+        match extraction.extract_values(&self.headers, &self.body) {
+            Ok(values) => {
+                for (variable, value) in values {
+                    user.set_variable(&variable, &value);
+                }
+                Ok(self)
+            }
+            Err(failure) => Err(fail(
+                user,
+                &failure,
+                &mut self.request,
+                &self.headers,
+                Some(&self.body),
+            )),
+        }
+    }
+}
+
+/// Parses a CSS selector.
+fn parse_selector(selector: &str) -> Result<Selector, GooseError> {
+    Selector::parse(selector).map_err(|e| GooseError::InvalidPattern {
+        pattern: selector.to_string(),
+        detail: format!("{:?}", e),
+    })
+}
+
+/// Converts a JSON path such as `$.user.roles[0]` to a JSON pointer such as
+/// `/user/roles/0`, if valid.
+fn json_path_to_pointer(path: &str) -> Option<String> {
+    let mut pointer = String::new();
+    // Paths optionally start with `$`, and without it start with a key.
+    let mut first = !path.starts_with('$');
+    let mut remaining = path.strip_prefix('$').unwrap_or(path);
+    while !remaining.is_empty() {
+        let segment;
+        if let Some(bracketed) = remaining.strip_prefix('[') {
+            let end = bracketed.find(']')?;
+            segment = bracketed[..end].trim_matches(|c| c == '"' || c == '\'');
+            remaining = &bracketed[end + 1..];
+        } else {
+            let dotted = if first {
+                remaining
+            } else {
+                remaining.strip_prefix('.')?
+            };
+            let end = dotted.find(['.', '[']).unwrap_or(dotted.len());
+            segment = &dotted[..end];
+            remaining = &dotted[end..];
+        }
+        if segment.is_empty() {
+            return None;
+        }
+        first = false;
+        pointer.push('/');
+        pointer.push_str(&segment.replace('~', "~0").replace('/', "~1"));
+    }
+
+    Some(pointer)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn json_path() {
+        assert_eq!(json_path_to_pointer("$").unwrap(), "");
+        assert_eq!(json_path_to_pointer("$.user.id").unwrap(), "/user/id");
+        assert_eq!(json_path_to_pointer("user.id").unwrap(), "/user/id");
+        assert_eq!(
+            json_path_to_pointer("$.user.roles[0]").unwrap(),
+            "/user/roles/0"
+        );
+        assert_eq!(json_path_to_pointer("[1].id").unwrap(), "/1/id");
+        assert_eq!(
+            json_path_to_pointer("$['a/b'][\"c~d\"]").unwrap(),
+            "/a~1b/c~0d"
+        );
+        for invalid in &["$.", "$..id", "user..id", "$[0", "$[]", "$user"] {
+            assert!(json_path_to_pointer(invalid).is_none(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn extract_values() {
+        let mut headers = header::HeaderMap::new();
+        headers.insert("x-session", header::HeaderValue::from_static("abc"));
+        let html = r#"<html><body><form>
+            <input type="hidden" name="form_build_id" value="form-123" />
+            <input type="hidden" name="form_token" value='token-456' />
+            <h1 class="title"> Article </h1>
+        </form></body></html>"#;
+
+        let extraction = GooseExtraction::new()
+            .regex(
+                "form_build_id",
+                r#"name="form_build_id" value=['"](.*?)['"]"#,
+            )
+            .unwrap()
+            .regex("whole_match", r"token-\d+")
+            .unwrap()
+            .css_attribute("form_token", r#"input[name="form_token"]"#, "value")
+            .unwrap()
+            .css_text("title", "h1.title")
+            .unwrap()
+            .header("session", "X-Session");
+        assert_eq!(
+            extraction.extract_values(&headers, html).unwrap(),
+            vec![
+                ("form_build_id".to_string(), "form-123".to_string()),
+                ("whole_match".to_string(), "token-456".to_string()),
+                ("form_token".to_string(), "token-456".to_string()),
+                ("title".to_string(), "Article".to_string()),
+                ("session".to_string(), "abc".to_string()),
+            ]
+        );
+
+        // Only the first value that isn't found is described.
+        let extraction = GooseExtraction::new()
+            .css_attribute("form_token", r#"input[name="form_token"]"#, "id")
+            .unwrap()
+            .header("etag", "etag");
+        assert_eq!(
+            extraction.extract_values(&headers, html).unwrap_err(),
+            r#"form_token not found by css input[name="form_token"] attribute id"#
+        );
+        for (extraction, failure) in [
+            (
+                GooseExtraction::new().regex("nid", r"node/(\d+)").unwrap(),
+                r"nid not found by regex /node/(\d+)/",
+            ),
+            (
+                GooseExtraction::new().css_text("h2", "h2").unwrap(),
+                "h2 not found by css h2",
+            ),
+            (
+                GooseExtraction::new().header("etag", "etag"),
+                "etag not found by header etag",
+            ),
+            (
+                GooseExtraction::new().json_pointer("id", "/id"),
+                "id not found by json /id",
+            ),
+        ] {
+            assert_eq!(
+                extraction.extract_values(&headers, html).unwrap_err(),
+                failure
+            );
+        }
+
+        assert!(GooseExtraction::new().regex("invalid", "(").is_err());
+        assert!(GooseExtraction::new().css_text("invalid", "<>").is_err());
+        assert!(GooseExtraction::new().json_path("invalid", "$..").is_err());
+    }
+
+    #[test]
+    fn extract_json() {
+        let headers = header::HeaderMap::new();
+        let json = r#"{"user": {"id": 1, "name": "alice", "roles": ["admin"]}}"#;
+        let extraction = GooseExtraction::new()
+            .json_pointer("id", "/user/id")
+            .json_path("name", "$.user.name")
+            .unwrap()
+            .json_path("role", "user.roles[0]")
+            .unwrap()
+            .json_pointer("roles", "/user/roles");
+        assert_eq!(
+            extraction.extract_values(&headers, json).unwrap(),
+            vec![
+                ("id".to_string(), "1".to_string()),
+                ("name".to_string(), "alice".to_string()),
+                ("role".to_string(), "admin".to_string()),
+                ("roles".to_string(), r#"["admin"]"#.to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,538 @@
+//! Optional test data feeders, distributing records to users.
+//!
+//! A [`GooseFeeder`] is a named list of records, loaded from a CSV or JSON lines file, for
+//! example credentials to log in with, product IDs or search terms. A feeder is registered
+//! on a [`GooseAttack`](../struct.GooseAttack.html) with
+//! [`GooseAttack::register_feeder`](../struct.GooseAttack.html#method.register_feeder),
+//! making it available to all users, or on a [`GooseTaskSet`](../goose/struct.GooseTaskSet.html) with
+//! [`GooseTaskSet::register_feeder`](../goose/struct.GooseTaskSet.html#method.register_feeder),
+//! making it available only to users running that task set. Tasks get records with
+//! [`GooseUser::get_record`](../goose/struct.GooseUser.html#method.get_record).
+//!
+//! How records are handed out is controlled by the feeder's [`GooseFeederStrategy`]:
+//!  - [`GooseFeederStrategy::Sequential`] hands out each record in order, the next call by
+//!    any user getting the next record.
+//!  - [`GooseFeederStrategy::Random`] hands out a random record on each call.
+//!  - [`GooseFeederStrategy::UniquePerUser`] hands each user its own record, returned on
+//!    every call by that user.
+//!
+//! What happens once all records have been handed out by a sequential or unique per user
+//! feeder is controlled by the feeder's [`GooseFeederExhaustion`]: either the feeder starts
+//! over from the first record ([`GooseFeederExhaustion::Circular`]), or
+//! [`GooseUser::get_record`](../goose/struct.GooseUser.html#method.get_record) returns
+//! [`GooseTaskError::FeederExhausted`](../goose/enum.GooseTaskError.html#variant.FeederExhausted)
+//! and the load test stops ([`GooseFeederExhaustion::Stop`]).
+//!
+//! In a Gaggle, each Worker loads the feeder itself. The Manager assigns each Worker a
+//! partition of the records of sequential and unique per user feeders, so no two Workers
+//! hand out the same record. If there are more Workers than records, Workers without any
+//! records are exhausted from the start.
+//!
+//! ## Example
+//!
+//! With `users.csv` containing:
+//!
+//! ```text
+//! username,password
+//! alice,secret1
+//! bob,secret2
+//! ```
+//!
+//! ```rust,no_run
+//! use goose::prelude::*;
+//! use goose::feeder::{GooseFeeder, GooseFeederStrategy};
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), GooseError> {
+//!     GooseAttack::initialize()?
+//!         .register_feeder(
+//!             GooseFeeder::from_csv("users", "users.csv")?
+//!                 .set_strategy(GooseFeederStrategy::UniquePerUser),
+//!         )
+//!         .register_taskset(taskset!("LoadtestTasks")
+//!             .register_task(task!(login).set_on_start())
+//!         )
+//!         .execute()
+//!         .await?;
+//!
+//!     Ok(())
+//! }
+//!
+//! async fn login(user: &mut GooseUser) -> GooseTaskResult {
+//!     let record = user.get_record("users")?;
+//!     let params = [
+//!         ("username", record.get_str("username").unwrap_or_default()),
+//!         ("password", record.get_str("password").unwrap_or_default()),
+//!     ];
+//!     let request_builder = user.goose_post("/login")?;
+//!     let _goose = user.goose_send(request_builder.form(&params), None).await?;
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use rand::Rng;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::{GooseAttack, GooseError};
+
+/// How a [`GooseFeeder`] hands out records.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GooseFeederStrategy {
+    /// Hand out records in order, shared by all users (the default).
+    Sequential,
+    /// Hand out a random record on each call.
+    Random,
+    /// Hand each user its own record, returned on every call by that user.
+    UniquePerUser,
+}
+
+/// What a sequential or unique per user [`GooseFeeder`] does once all records have been
+/// handed out.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GooseFeederExhaustion {
+    /// Start over from the first record (the default).
+    Circular,
+    /// Stop handing out records, and stop the load test.
+    Stop,
+}
+
+/// A record handed out by a [`GooseFeeder`], mapping field names to values.
+///
+/// Values of records loaded from CSV files are always strings, values of records loaded
+/// from JSON lines files can be any JSON value.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct GooseRecord(Map<String, Value>);
+impl GooseRecord {
+    /// Returns the value of a field, or None if the record doesn't have the field.
+    pub fn get(&self, field: &str) -> Option<&Value> {
+        self.0.get(field)
+    }
+
+    /// Returns the value of a string field, or None if the record doesn't have the field
+    /// or the value isn't a string.
+    pub fn get_str(&self, field: &str) -> Option<&str> {
+        self.0.get(field).and_then(|value| value.as_str())
+    }
+
+    /// Returns all fields of the record.
+    pub fn fields(&self) -> &Map<String, Value> {
+        &self.0
+    }
+
+    /// Deserialize the record into a type, for example a struct with a field for each
+    /// field of the record.
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_value(Value::Object(self.0.clone()))
+    }
+}
+impl From<Map<String, Value>> for GooseRecord {
+    fn from(fields: Map<String, Value>) -> Self {
+        GooseRecord(fields)
+    }
+}
+
+/// A named list of records, handed out to users. See the [module documentation](index.html).
+#[derive(Debug)]
+pub struct GooseFeeder {
+    /// The name tasks get records from this feeder with.
+    name: String,
+    /// All records, in order.
+    records: Vec<GooseRecord>,
+    /// How records are handed out.
+    strategy: GooseFeederStrategy,
+    /// What happens once all records have been handed out.
+    exhaustion: GooseFeederExhaustion,
+    /// The index of the next record to hand out.
+    next: AtomicUsize,
+    /// Whether a feeder configured to stop ran out of records.
+    exhausted: AtomicBool,
+}
+impl GooseFeeder {
+    /// Create a feeder from a list of records.
+    ///
+    /// # Example
+    /// ```rust
+    /// use goose::feeder::GooseFeeder;
+    ///
+    /// let terms = ["rust", "goose", "load test"]
+    ///     .iter()
+    ///     .map(|term| {
+    ///         let mut record = serde_json::Map::new();
+    ///         record.insert("term".to_string(), serde_json::json!(term));
+    ///         record.into()
+    ///     })
+    ///     .collect();
+    /// let feeder = GooseFeeder::new("terms", terms);
+    /// ```
+    pub fn new(name: &str, records: Vec<GooseRecord>) -> Self {
+        trace!("new feeder: name: {}, records: {}", name, records.len());
+        GooseFeeder {
+            name: name.to_string(),
+            records,
+            strategy: GooseFeederStrategy::Sequential,
+            exhaustion: GooseFeederExhaustion::Circular,
+            next: AtomicUsize::new(0),
+            exhausted: AtomicBool::new(false),
+        }
+    }
+
+    /// Create a feeder from a CSV file. The first line names the fields, each following
+    /// line is a record.
+    pub fn from_csv<P: AsRef<Path>>(name: &str, path: P) -> Result<Self, GooseError> {
+        let contents = read_file(name, path.as_ref())?;
+        let records = parse_csv(&contents).map_err(|detail| invalid_feeder(name, path, detail))?;
+        Ok(GooseFeeder::new(name, records))
+    }
+
+    /// Create a feeder from a JSON lines file, in which each line is a JSON object.
+    pub fn from_json_lines<P: AsRef<Path>>(name: &str, path: P) -> Result<Self, GooseError> {
+        let contents = read_file(name, path.as_ref())?;
+        let records =
+            parse_json_lines(&contents).map_err(|detail| invalid_feeder(name, path, detail))?;
+        Ok(GooseFeeder::new(name, records))
+    }
+
+    /// Set how records are handed out, [`GooseFeederStrategy::Sequential`] by default.
+    pub fn set_strategy(mut self, strategy: GooseFeederStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Set what happens once all records have been handed out,
+    /// [`GooseFeederExhaustion::Circular`] by default.
+    pub fn set_exhaustion(mut self, exhaustion: GooseFeederExhaustion) -> Self {
+        self.exhaustion = exhaustion;
+        self
+    }
+
+    /// The name tasks get records from this feeder with.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// How records are handed out.
+    pub fn strategy(&self) -> GooseFeederStrategy {
+        self.strategy
+    }
+
+    /// How many records the feeder has.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Whether the feeder has no records.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Hand out the next record, or None if the feeder is exhausted.
+    pub(crate) fn next_record(&self) -> Option<GooseRecord> {
+        if self.records.is_empty() {
+            self.exhausted.store(true, Ordering::SeqCst);
+            return None;
+        }
+        let index = match self.strategy {
+            GooseFeederStrategy::Random => rand::thread_rng().gen_range(0..self.records.len()),
+            GooseFeederStrategy::Sequential | GooseFeederStrategy::UniquePerUser => {
+                let index = self.next.fetch_add(1, Ordering::SeqCst);
+                if index < self.records.len() {
+                    index
+                } else if self.exhaustion == GooseFeederExhaustion::Circular {
+                    index % self.records.len()
+                } else {
+                    self.exhausted.store(true, Ordering::SeqCst);
+                    return None;
+                }
+            }
+        };
+        Some(self.records[index].clone())
+    }
+
+    /// Whether a feeder configured to stop the load test ran out of records.
+    pub(crate) fn stop_load_test(&self) -> bool {
+        self.exhaustion == GooseFeederExhaustion::Stop && self.exhausted.load(Ordering::SeqCst)
+    }
+
+    /// Keep only one partition of the records of sequential and unique per user feeders,
+    /// so Gaggle Workers don't hand out the same records. Partitions are numbered from 0.
+    ///
+    /// If there are more partitions than records, some partitions are empty, and the
+    /// exhaustion policy of the feeder applies as soon as a record is requested.
+    #[cfg_attr(not(feature = "gaggle"), allow(dead_code))]
+    pub(crate) fn partition(&self, partition: usize, partitions: usize) -> Self {
+        let records = if self.strategy == GooseFeederStrategy::Random || partitions < 2 {
+            self.records.clone()
+        } else {
+            if partition >= self.records.len() {
+                warn!(
+                    "feeder {} has fewer records than Workers, partition {} is empty",
+                    self.name, partition
+                );
+            }
+            self.records
+                .iter()
+                .skip(partition)
+                .step_by(partitions)
+                .cloned()
+                .collect()
+        };
+        GooseFeeder::new(&self.name, records)
+            .set_strategy(self.strategy)
+            .set_exhaustion(self.exhaustion)
+    }
+}
+/// The load test hash only depends on how a feeder is configured, not on its records.
+impl Hash for GooseFeeder {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.strategy.hash(state);
+        self.exhaustion.hash(state);
+    }
+}
+
+impl GooseAttack {
+    /// The feeders available to a user running a task set, or to the user running
+    /// `test_start` and `test_stop` tasks if None. Feeders registered on the task set take
+    /// precedence over feeders with the same name registered on the load test.
+    pub(crate) fn user_feeders(
+        &self,
+        task_sets_index: Option<usize>,
+    ) -> HashMap<String, Arc<GooseFeeder>> {
+        let task_set_feeders = task_sets_index
+            .and_then(|index| self.task_sets.get(index))
+            .map(|task_set| task_set.feeders.iter())
+            .into_iter()
+            .flatten();
+        self.feeders
+            .iter()
+            .chain(task_set_feeders)
+            .map(|feeder| (feeder.name.to_string(), feeder.clone()))
+            .collect()
+    }
+
+    /// The name of a feeder configured to stop the load test that ran out of records, if any.
+    pub(crate) fn exhausted_feeder(&self) -> Option<&str> {
+        self.feeders
+            .iter()
+            .chain(
+                self.task_sets
+                    .iter()
+                    .flat_map(|task_set| task_set.feeders.iter()),
+            )
+            .find(|feeder| feeder.stop_load_test())
+            .map(|feeder| feeder.name())
+    }
+
+    /// Keep only one partition of the records of all feeders, as assigned to a Gaggle
+    /// Worker by the Manager.
+    #[cfg(feature = "gaggle")]
+    pub(crate) fn partition_feeders(&mut self, partition: usize, partitions: usize) {
+        for feeder in self.feeders.iter_mut() {
+            *feeder = Arc::new(feeder.partition(partition, partitions));
+        }
+        for task_set in self.task_sets.iter_mut() {
+            for feeder in task_set.feeders.iter_mut() {
+                *feeder = Arc::new(feeder.partition(partition, partitions));
+            }
+        }
+    }
+}
+
+/// Build an error for an invalid feeder file.
+fn invalid_feeder<P: AsRef<Path>>(name: &str, path: P, detail: String) -> GooseError {
+    GooseError::InvalidFeeder {
+        name: name.to_string(),
+        detail: format!("{}: {}", path.as_ref().display(), detail),
+    }
+}
+
+/// Read a feeder file.
+fn read_file(name: &str, path: &Path) -> Result<String, GooseError> {
+    std::fs::read_to_string(path)
+        .map_err(|e| invalid_feeder(name, path, format!("failed to read file: {}", e)))
+}
+
+/// Parse CSV records. The first line names the fields. Fields can be quoted with `"`, in
+/// which case they can contain commas, newlines, and quotes escaped as `""`. Empty lines
+/// are skipped.
+fn parse_csv(contents: &str) -> Result<Vec<GooseRecord>, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        // Rows with the wrong number of fields are reported below, with their line.
+        .flexible(true)
+        .from_reader(contents.trim_start_matches('\u{feff}').as_bytes());
+    let header = reader.headers().map_err(|e| e.to_string())?.clone();
+    if header.is_empty() {
+        return Err("no header".to_string());
+    }
+    let mut records = Vec::new();
+    for row in reader.records() {
+        let row = row.map_err(|e| e.to_string())?;
+        if row.len() != header.len() {
+            return Err(format!(
+                "line {}: expected {} fields, found {}",
+                row.position().map_or(0, |position| position.line()),
+                header.len(),
+                row.len()
+            ));
+        }
+        records.push(GooseRecord(
+            header
+                .iter()
+                .zip(row.iter())
+                .map(|(field, value)| (field.to_string(), Value::String(value.to_string())))
+                .collect(),
+        ));
+    }
+    if records.is_empty() {
+        return Err("no records".to_string());
+    }
+    Ok(records)
+}
+
+/// Parse JSON lines records, each line being a JSON object. Empty lines are skipped.
+fn parse_json_lines(contents: &str) -> Result<Vec<GooseRecord>, String> {
+    let mut records = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(line) {
+            Ok(Value::Object(fields)) => records.push(GooseRecord(fields)),
+            Ok(_) => return Err(format!("line {}: expected a JSON object", index + 1)),
+            Err(e) => return Err(format!("line {}: {}", index + 1, e)),
+        }
+    }
+    if records.is_empty() {
+        return Err("no records".to_string());
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    fn record(value: Value) -> GooseRecord {
+        match value {
+            Value::Object(fields) => GooseRecord(fields),
+            _ => unreachable!(),
+        }
+    }
+
+    fn records(count: usize) -> Vec<GooseRecord> {
+        (0..count).map(|id| record(json!({ "id": id }))).collect()
+    }
+
+    fn ids(feeder: &GooseFeeder, count: usize) -> Vec<Option<u64>> {
+        (0..count)
+            .map(|_| {
+                feeder
+                    .next_record()
+                    .map(|r| r.get("id").unwrap().as_u64().unwrap())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn csv() {
+        let records = parse_csv(
+            "\u{feff}username,password,note\r\nalice,secret1,\r\n\r\n\"bob\",\"a,b\",\"say \"\"hi\"\"\nthere\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            records,
+            vec![
+                record(json!({"username": "alice", "password": "secret1", "note": ""})),
+                record(json!({"username": "bob", "password": "a,b", "note": "say \"hi\"\nthere"})),
+            ]
+        );
+
+        assert_eq!(
+            parse_csv("a,b\n1,2\n3\n").unwrap_err(),
+            "line 3: expected 2 fields, found 1"
+        );
+        assert_eq!(parse_csv("a,b\n").unwrap_err(), "no records");
+        assert_eq!(parse_csv("").unwrap_err(), "no header");
+    }
+
+    #[test]
+    fn json_lines() {
+        let records = parse_json_lines("{\"id\": 1, \"tags\": [\"a\"]}\n\n{\"id\": 2}\n").unwrap();
+        assert_eq!(
+            records,
+            vec![
+                record(json!({"id": 1, "tags": ["a"]})),
+                record(json!({"id": 2}))
+            ]
+        );
+        assert_eq!(records[0].get_str("id"), None);
+        assert_eq!(records[0].get("id"), Some(&json!(1)));
+
+        assert_eq!(
+            parse_json_lines("{\"id\": 1}\n[1]\n").unwrap_err(),
+            "line 2: expected a JSON object"
+        );
+        assert!(parse_json_lines("{\"id\": 1\n")
+            .unwrap_err()
+            .starts_with("line 1: "));
+        assert_eq!(parse_json_lines("\n").unwrap_err(), "no records");
+    }
+
+    #[test]
+    fn strategies() {
+        // Sequential feeders start over by default.
+        let feeder = GooseFeeder::new("ids", records(3));
+        assert_eq!(
+            ids(&feeder, 5),
+            vec![Some(0), Some(1), Some(2), Some(0), Some(1)]
+        );
+        assert!(!feeder.stop_load_test());
+
+        // Or stop once exhausted.
+        let feeder =
+            GooseFeeder::new("ids", records(2)).set_exhaustion(GooseFeederExhaustion::Stop);
+        assert_eq!(ids(&feeder, 3), vec![Some(0), Some(1), None]);
+        assert!(feeder.stop_load_test());
+
+        // Random feeders are never exhausted.
+        let feeder = GooseFeeder::new("ids", records(3))
+            .set_strategy(GooseFeederStrategy::Random)
+            .set_exhaustion(GooseFeederExhaustion::Stop);
+        assert!(ids(&feeder, 10).iter().all(|id| id.unwrap() < 3));
+        assert!(!feeder.stop_load_test());
+    }
+
+    #[test]
+    fn partitions() {
+        let feeder = GooseFeeder::new("ids", records(5))
+            .set_strategy(GooseFeederStrategy::UniquePerUser)
+            .set_exhaustion(GooseFeederExhaustion::Stop);
+        let first = feeder.partition(0, 2);
+        assert_eq!(first.strategy(), GooseFeederStrategy::UniquePerUser);
+        assert_eq!(ids(&first, 4), vec![Some(0), Some(2), Some(4), None]);
+        assert_eq!(
+            ids(&feeder.partition(1, 2), 3),
+            vec![Some(1), Some(3), None]
+        );
+
+        // Records are never handed out by more than one partition, leaving some empty.
+        assert_eq!(ids(&feeder.partition(2, 6), 2), vec![Some(2), None]);
+        let empty = feeder.partition(5, 6);
+        assert!(empty.is_empty());
+        assert_eq!(ids(&empty, 1), vec![None]);
+        assert!(empty.stop_load_test());
+
+        // Random feeders aren't partitioned.
+        let feeder = GooseFeeder::new("ids", records(5)).set_strategy(GooseFeederStrategy::Random);
+        assert_eq!(feeder.partition(1, 2).len(), 5);
+    }
+}
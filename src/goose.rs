@@ -289,8 +289,10 @@
 
 use downcast_rs::{impl_downcast, Downcast};
 use http::method::Method;
-use reqwest::{header, Client, ClientBuilder, RequestBuilder, Response};
+use reqwest::{header, Client, ClientBuilder, Request, RequestBuilder, Response};
 use serde::{Deserialize, Serialize};
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 use std::time::Duration;
@@ -299,10 +301,15 @@ use std::{future::Future, pin::Pin, time::Instant};
 use tokio::sync::RwLock;
 use url::Url;
 
+use crate::feeder::{GooseFeeder, GooseFeederStrategy, GooseRecord};
 use crate::logger::GooseLog;
 use crate::metrics::{
     GooseCoordinatedOmissionMitigation, GooseMetric, GooseRawRequest, GooseRequestMetric,
 };
+use crate::static_assets::{GooseCachedAsset, GooseStaticAssets};
+use crate::transaction::GooseOpenTransaction;
+use crate::transport::GooseTransports;
+use crate::websocket::GooseWebSocket;
 use crate::{GooseConfiguration, GooseError, WeightedGooseTasks};
 
 /// By default Goose sets the following User-Agent header when making requests.
@@ -366,6 +373,35 @@ pub enum GooseTaskError {
         /// The unrecognized HTTP request method.
         method: Method,
     },
+    /// Attempted to get a record from a [`GooseFeeder`](../feeder/struct.GooseFeeder.html)
+    /// that isn't registered.
+    FeederNotFound {
+        /// The name of the feeder.
+        name: String,
+    },
+    /// A [`GooseFeeder`](../feeder/struct.GooseFeeder.html) configured to stop the load
+    /// test ran out of records.
+    FeederExhausted {
+        /// The name of the feeder.
+        name: String,
+    },
+    /// Attempted to end a transaction that hasn't begun, see
+    /// [`GooseUser::begin_transaction`].
+    TransactionNotStarted {
+        /// The name of the transaction.
+        name: String,
+    },
+    /// Wraps a [`tungstenite::Error`](https://docs.rs/tungstenite/*/tungstenite/error/enum.Error.html).
+    WebSocket(tokio_tungstenite::tungstenite::Error),
+    /// Attempted to use a WebSocket connection before opening one, see
+    /// [`GooseUser::websocket_connect`].
+    WebSocketNotConnected,
+    /// Attempted to send a request with a transport that isn't set, see
+    /// [`GooseUser::set_transport`].
+    TransportNotSet {
+        /// The type of the transport.
+        transport: String,
+    },
 }
 /// Implement a helper to provide a text description of all possible types of errors.
 impl GooseTaskError {
@@ -380,6 +416,12 @@ impl GooseTaskError {
             GooseTaskError::MetricsFailed { .. } => "failed to send metrics to parent thread",
             GooseTaskError::LoggerFailed { .. } => "failed to send log message to logger thread",
             GooseTaskError::InvalidMethod { .. } => "unrecognized HTTP request method",
+            GooseTaskError::FeederNotFound { .. } => "feeder not registered",
+            GooseTaskError::FeederExhausted { .. } => "feeder out of records",
+            GooseTaskError::TransactionNotStarted { .. } => "transaction not started",
+            GooseTaskError::WebSocket(_) => "tungstenite::Error",
+            GooseTaskError::WebSocketNotConnected => "WebSocket not connected",
+            GooseTaskError::TransportNotSet { .. } => "transport not set",
         }
     }
 }
@@ -395,6 +437,9 @@ impl fmt::Display for GooseTaskError {
             GooseTaskError::Url(ref source) => {
                 write!(f, "GooseTaskError: {} ({})", self.describe(), source)
             }
+            GooseTaskError::WebSocket(ref source) => {
+                write!(f, "GooseTaskError: {} ({})", self.describe(), source)
+            }
             GooseTaskError::RequestCanceled { ref source } => {
                 write!(f, "GooseTaskError: {} ({})", self.describe(), source)
             }
@@ -404,6 +449,14 @@ impl fmt::Display for GooseTaskError {
             GooseTaskError::LoggerFailed { ref source } => {
                 write!(f, "GooseTaskError: {} ({})", self.describe(), source)
             }
+            GooseTaskError::FeederNotFound { ref name }
+            | GooseTaskError::FeederExhausted { ref name }
+            | GooseTaskError::TransactionNotStarted { ref name } => {
+                write!(f, "GooseTaskError: {} ({})", self.describe(), name)
+            }
+            GooseTaskError::TransportNotSet { ref transport } => {
+                write!(f, "GooseTaskError: {} ({})", self.describe(), transport)
+            }
             _ => write!(f, "GooseTaskError: {}", self.describe()),
         }
     }
@@ -415,6 +468,7 @@ impl std::error::Error for GooseTaskError {
         match *self {
             GooseTaskError::Reqwest(ref source) => Some(source),
             GooseTaskError::Url(ref source) => Some(source),
+            GooseTaskError::WebSocket(ref source) => Some(source),
             GooseTaskError::RequestCanceled { ref source } => Some(source),
             GooseTaskError::MetricsFailed { ref source } => Some(source),
             GooseTaskError::LoggerFailed { ref source } => Some(source),
@@ -437,6 +491,13 @@ impl From<url::ParseError> for GooseTaskError {
     }
 }
 
+/// Auto-convert WebSocket errors.
+impl From<tokio_tungstenite::tungstenite::Error> for GooseTaskError {
+    fn from(err: tokio_tungstenite::tungstenite::Error) -> GooseTaskError {
+        GooseTaskError::WebSocket(err)
+    }
+}
+
 /// When the throttle is enabled and the load test ends, the throttle channel is
 /// shut down. This causes a
 /// [`flume::SendError`](https://docs.rs/flume/*/flume/struct.SendError.html),
@@ -490,6 +551,8 @@ pub struct GooseTaskSet {
     pub weighted_on_stop_tasks: WeightedGooseTasks,
     /// An optional default host to run this `GooseTaskSet` against.
     pub host: Option<String>,
+    /// Test data feeders available to users running this `GooseTaskSet`.
+    pub feeders: Vec<Arc<GooseFeeder>>,
 }
 impl GooseTaskSet {
     /// Creates a new [`GooseTaskSet`](./struct.GooseTaskSet.html). Once created, a
@@ -515,6 +578,7 @@ impl GooseTaskSet {
             weighted_on_start_tasks: Vec::new(),
             weighted_on_stop_tasks: Vec::new(),
             host: None,
+            feeders: Vec::new(),
         }
     }
 
@@ -591,6 +655,26 @@ impl GooseTaskSet {
         self
     }
 
+    /// Register a [`GooseFeeder`](../feeder/struct.GooseFeeder.html), making its records
+    /// available only to users running this task set with [`GooseUser::get_record`]. It
+    /// takes precedence over a feeder with the same name registered on the
+    /// [`GooseAttack`](../struct.GooseAttack.html).
+    ///
+    /// # Example
+    /// ```rust
+    /// use goose::prelude::*;
+    /// use goose::feeder::{GooseFeeder, GooseFeederStrategy};
+    ///
+    /// let mut example_tasks = taskset!("ExampleTasks").register_feeder(
+    ///     GooseFeeder::new("users", Vec::new()).set_strategy(GooseFeederStrategy::UniquePerUser),
+    /// );
+    /// ```
+    pub fn register_feeder(mut self, feeder: GooseFeeder) -> Self {
+        trace!("{} register_feeder: {}", self.name, feeder.name());
+        self.feeders.push(Arc::new(feeder));
+        self
+    }
+
     /// Configure a duration per task_set to pause after running each task. The length of the pause will be randomly
     /// selected from `min_wait` to `max_wait` inclusively.  For example, if `min_wait` is `Duration::from_secs(0)` and
     /// `max_wait` is `Duration::from_secs(2)`, the user will randomly sleep between 0 and 2_000 milliseconds after each task completes.
@@ -637,15 +721,15 @@ impl GooseTaskSet {
 /// worker processes.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum GooseUserCommand {
-    /// Tell worker process to pause load test.
+    /// Tell user thread to pause, or worker process to pause load test.
     Wait,
-    /// Tell worker process to start load test.
+    /// Tell paused user thread to resume, or worker process to start load test.
     Run,
     /// Tell user thread or worker process to exit.
     Exit,
 }
 
-/// Supported HTTP methods.
+/// Supported HTTP methods, WebSocket operations, and other protocols.
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, Ord, PartialOrd)]
 pub enum GooseMethod {
     Delete,
@@ -654,6 +738,18 @@ pub enum GooseMethod {
     Patch,
     Post,
     Put,
+    /// Opening a WebSocket connection, see [`GooseUser::websocket_connect`].
+    WsConnect,
+    /// Sending a WebSocket message, see [`GooseUser::websocket_send`].
+    WsSend,
+    /// Receiving a WebSocket message, see [`GooseUser::websocket_receive`].
+    WsReceive,
+    /// Sending a WebSocket message and receiving its reply, see
+    /// [`GooseUser::websocket_round_trip`].
+    WsRoundTrip,
+    /// A request of another protocol, sent by a
+    /// [`GooseTransport`](../transport/trait.GooseTransport.html).
+    Protocol(String),
 }
 /// Display method in upper case.
 impl fmt::Display for GooseMethod {
@@ -666,6 +762,11 @@ impl fmt::Display for GooseMethod {
             GooseMethod::Patch => write!(f, "PATCH"),
             GooseMethod::Post => write!(f, "POST"),
             GooseMethod::Put => write!(f, "PUT"),
+            GooseMethod::WsConnect => write!(f, "WS CONNECT"),
+            GooseMethod::WsSend => write!(f, "WS SEND"),
+            GooseMethod::WsReceive => write!(f, "WS RECEIVE"),
+            GooseMethod::WsRoundTrip => write!(f, "WS ROUNDTRIP"),
+            GooseMethod::Protocol(protocol) => write!(f, "{}", protocol),
         }
     }
 }
@@ -830,6 +931,38 @@ pub trait GooseUserData: Downcast + Send + Sync + 'static {}
 impl_downcast!(GooseUserData);
 impl<T: Send + Sync + 'static> GooseUserData for T {}
 
+/// Data shared by the [`GooseAttack`](../struct.GooseAttack.html) and all of its
+/// [`GooseUser`]s, storing one value of each type implementing the [`GooseUserData`]
+/// trait.
+///
+/// Global data is set with
+/// [`GooseAttack::set_global_data`](../struct.GooseAttack.html#method.set_global_data) or
+/// [`GooseUser::set_global_data`], and read with [`GooseUser::get_global_data`]. Values
+/// are shared behind an [`Arc`](https://doc.rust-lang.org/std/sync/struct.Arc.html), so
+/// reading is cheap. Global data is intended to be written rarely, typically by
+/// [`test_start`](../struct.GooseAttack.html#method.test_start), and read often.
+#[derive(Clone, Default)]
+pub(crate) struct GooseGlobalData(
+    Arc<std::sync::RwLock<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>>,
+);
+impl GooseGlobalData {
+    /// Store a value, replacing any value of the same type.
+    pub(crate) fn set<T: GooseUserData>(&self, data: T) {
+        // A panic while holding the lock can't leave the map inconsistent.
+        let mut global_data = self.0.write().unwrap_or_else(|e| e.into_inner());
+        global_data.insert(TypeId::of::<T>(), Arc::new(data));
+    }
+
+    /// Returns the value of a type, if set.
+    pub(crate) fn get<T: GooseUserData>(&self) -> Option<Arc<T>> {
+        let global_data = self.0.read().unwrap_or_else(|e| e.into_inner());
+        global_data
+            .get(&TypeId::of::<T>())
+            .cloned()
+            .and_then(|data| data.downcast::<T>().ok())
+    }
+}
+
 /// An individual user state, repeatedly running all [`GooseTask`](./struct.GooseTask.html)s
 /// in a specific [`GooseTaskSet`](./struct.GooseTaskSet.html).
 pub struct GooseUser {
@@ -869,6 +1002,27 @@ pub struct GooseUser {
     /// Optional per-user session data of a generic type implementing the
     /// [`GooseUserData`] trait.
     session_data: Option<Box<dyn GooseUserData>>,
+    /// Test data feeders available to this user, by name.
+    pub(crate) feeders: HashMap<String, Arc<GooseFeeder>>,
+    /// Records handed to this user by unique per user feeders, by feeder name.
+    feeder_records: HashMap<String, GooseRecord>,
+    /// Data shared by the load test and all users.
+    pub(crate) global_data: GooseGlobalData,
+    /// How to load the static assets of HTML pages, if enabled.
+    pub(crate) static_assets: Option<GooseStaticAssets>,
+    /// Validators of the static assets loaded by this user, by URL.
+    pub(crate) static_asset_cache: HashMap<String, GooseCachedAsset>,
+    /// Named values extracted from responses or set by tasks, by name.
+    variables: HashMap<String, String>,
+    /// Transactions that have begun but not yet ended, by name.
+    pub(crate) transactions: HashMap<String, GooseOpenTransaction>,
+    /// The WebSocket connection opened by this user, if any.
+    pub(crate) websocket: Option<GooseWebSocket>,
+    /// Transports sending requests of other protocols, by type.
+    pub(crate) transports: GooseTransports,
+    /// The channel gRPC calls are sent over, connected when first used.
+    #[cfg(feature = "grpc")]
+    pub(crate) grpc_channel: Option<tonic::transport::Channel>,
 }
 impl GooseUser {
     /// Create a new user state.
@@ -903,6 +1057,17 @@ impl GooseUser {
             slept: 0,
             task_name: None,
             session_data: None,
+            feeders: HashMap::new(),
+            feeder_records: HashMap::new(),
+            global_data: GooseGlobalData::default(),
+            static_assets: None,
+            static_asset_cache: HashMap::new(),
+            variables: HashMap::new(),
+            transactions: HashMap::new(),
+            websocket: None,
+            transports: HashMap::new(),
+            #[cfg(feature = "grpc")]
+            grpc_channel: None,
         })
     }
 
@@ -1069,6 +1234,203 @@ impl GooseUser {
         self.session_data.replace(Box::new(data));
     }
 
+    /// Returns global data of a type shared by the load test and all users, if set.
+    ///
+    /// Global data is set with
+    /// [`GooseAttack::set_global_data`](../struct.GooseAttack.html#method.set_global_data)
+    /// before the load test starts, or with [`GooseUser::set_global_data`], typically by
+    /// [`test_start`](../struct.GooseAttack.html#method.test_start). One value of each type
+    /// can be stored.
+    ///
+    /// # Example
+    /// ```rust
+    /// use goose::prelude::*;
+    ///
+    /// struct AuthToken(String);
+    ///
+    /// let mut task = task!(get_global_data_function);
+    ///
+    /// /// A very simple task that makes an authenticated GET request.
+    /// async fn get_global_data_function(user: &mut GooseUser) -> GooseTaskResult {
+    ///     let token = user.get_global_data::<AuthToken>().expect("Missing auth token!");
+    ///     let request_builder = user.goose_get("/")?;
+    ///     let _goose = user.goose_send(request_builder.bearer_auth(&token.0), None).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn get_global_data<T: GooseUserData>(&self) -> Option<Arc<T>> {
+        self.global_data.get::<T>()
+    }
+
+    /// Sets global data shared by the load test and all users.
+    ///
+    /// If global data of the same type already exists, it will be replaced. Users that
+    /// already read the old value keep their copy. Global data must be of a type
+    /// implementing the [`GooseUserData`] trait.
+    ///
+    /// When running in Gaggle mode, global data set by
+    /// [`test_start`](../struct.GooseAttack.html#method.test_start) is only visible on
+    /// the Manager, and global data set by a user is only visible on its Worker.
+    ///
+    /// # Example
+    /// ```rust
+    /// use goose::prelude::*;
+    ///
+    /// struct AuthToken(String);
+    ///
+    /// let mut task = task!(set_global_data_function);
+    ///
+    /// /// A very simple task that logs in and shares the auth token with all users.
+    /// async fn set_global_data_function(user: &mut GooseUser) -> GooseTaskResult {
+    ///     let goose = user.post("/login", "username=foo&password=bar").await?;
+    ///     let token = goose.response?.text().await?;
+    ///     user.set_global_data(AuthToken(token));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn set_global_data<T: GooseUserData>(&self, data: T) {
+        self.global_data.set(data);
+    }
+
+    /// Returns a record from a [`GooseFeeder`](../feeder/struct.GooseFeeder.html)
+    /// registered on the [`GooseAttack`](../struct.GooseAttack.html) or on this user's
+    /// [`GooseTaskSet`].
+    ///
+    /// Which record is returned depends on the feeder's
+    /// [`GooseFeederStrategy`](../feeder/enum.GooseFeederStrategy.html). Returns
+    /// [`GooseTaskError::FeederNotFound`] if no feeder with the name is registered, and
+    /// [`GooseTaskError::FeederExhausted`] if the feeder ran out of records and is
+    /// configured to stop the load test.
+    ///
+    /// # Example
+    /// ```rust
+    /// use goose::prelude::*;
+    ///
+    /// let mut task = task!(search);
+    ///
+    /// /// Search for a term from the "terms" feeder.
+    /// async fn search(user: &mut GooseUser) -> GooseTaskResult {
+    ///     let record = user.get_record("terms")?;
+    ///     let term = record.get_str("term").unwrap_or_default();
+    ///     let _goose = user.get(&format!("/search?q={}", term)).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn get_record(&mut self, feeder: &str) -> Result<GooseRecord, GooseTaskError> {
+        // Unique per user feeders always return the same record to the same user.
+        if let Some(record) = self.feeder_records.get(feeder) {
+            return Ok(record.clone());
+        }
+
+        let goose_feeder = match self.feeders.get(feeder) {
+            Some(goose_feeder) => goose_feeder,
+            None => {
+                return Err(GooseTaskError::FeederNotFound {
+                    name: feeder.to_string(),
+                })
+            }
+        };
+        let record = match goose_feeder.next_record() {
+            Some(record) => record,
+            None => {
+                return Err(GooseTaskError::FeederExhausted {
+                    name: feeder.to_string(),
+                })
+            }
+        };
+        if goose_feeder.strategy() == GooseFeederStrategy::UniquePerUser {
+            self.feeder_records
+                .insert(feeder.to_string(), record.clone());
+        }
+
+        Ok(record)
+    }
+
+    /// Returns the value of a per-user variable, if set.
+    ///
+    /// Variables are usually set by extracting values from a response with a
+    /// [`GooseExtraction`](../extraction/struct.GooseExtraction.html), and can also be set
+    /// with [`GooseUser::set_variable`]. They are kept for the lifetime of the user.
+    ///
+    /// # Example
+    /// ```rust
+    /// use goose::prelude::*;
+    /// use goose::extraction::GooseExtraction;
+    ///
+    /// let mut task = task!(edit_node);
+    ///
+    /// /// Load a node, then edit it with the form token found on the page.
+    /// async fn edit_node(user: &mut GooseUser) -> GooseTaskResult {
+    ///     let extraction = GooseExtraction::new()
+    ///         .css_attribute("form_token", r#"input[name="form_token"]"#, "value")
+    ///         .unwrap();
+    ///     let goose = user.get("/node/1").await?;
+    ///     goose.extract(user, &extraction).await?;
+    ///
+    ///     let form_token = user.get_variable("form_token").unwrap_or_default().to_string();
+    ///     let params = [("form_token", form_token.as_str()), ("op", "Save")];
+    ///     let request_builder = user.goose_post("/node/1/edit")?;
+    ///     let _goose = user.goose_send(request_builder.form(&params), None).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn get_variable(&self, name: &str) -> Option<&str> {
+        self.variables.get(name).map(|value| value.as_str())
+    }
+
+    /// Sets a per-user variable, replacing any value with the same name.
+    pub fn set_variable(&mut self, name: &str, value: &str) {
+        self.variables.insert(name.to_string(), value.to_string());
+    }
+
+    /// Replaces each `{{name}}` in the text with the value of the per-user variable of
+    /// the same name. References to variables that aren't set are left unchanged.
+    ///
+    /// # Example
+    /// ```rust
+    /// use goose::prelude::*;
+    ///
+    /// let mut task = task!(view_cart);
+    ///
+    /// /// View the cart of the current session.
+    /// async fn view_cart(user: &mut GooseUser) -> GooseTaskResult {
+    ///     user.set_variable("cart_id", "42");
+    ///     let path = user.expand_variables("/cart/{{cart_id}}");
+    ///     let _goose = user.get(&path).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn expand_variables(&self, text: &str) -> String {
+        let mut expanded = String::with_capacity(text.len());
+        let mut remaining = text;
+        while let Some(start) = remaining.find("{{") {
+            expanded.push_str(&remaining[..start]);
+            let reference = &remaining[start..];
+            match reference.find("}}") {
+                Some(end) => {
+                    let name = reference[2..end].trim();
+                    match self.variables.get(name) {
+                        Some(value) => expanded.push_str(value),
+                        None => expanded.push_str(&reference[..end + 2]),
+                    }
+                    remaining = &reference[end + 2..];
+                }
+                None => {
+                    expanded.push_str(reference);
+                    remaining = "";
+                }
+            }
+        }
+        expanded.push_str(remaining);
+
+        expanded
+    }
+
     /// A helper that prepends a `base_url` to all relative paths.
     ///
     /// A `base_url` is determined per user thread, using the following order
@@ -1553,7 +1915,64 @@ impl GooseUser {
         request_builder: RequestBuilder,
         request_name: Option<&str>,
     ) -> Result<GooseResponse, GooseTaskError> {
-        // If throttle-requests is enabled...
+        self.throttle_request().await?;
+
+        let started = Instant::now();
+        let request = request_builder.build()?;
+        let mut request_metric = self.new_request_metric(&request, request_name)?;
+
+        // Make the actual request.
+        let response = self.client.execute(request).await;
+        request_metric.set_response_time(started.elapsed());
+        record_response(&mut request_metric, &response);
+
+        // Load test user was redirected.
+        if response.is_ok()
+            && self.config.sticky_follow
+            && request_metric.raw.url != request_metric.final_url
+        {
+            let base_url = self.base_url.to_string();
+            // Check if the URL redirected started with the load test base_url.
+            if !request_metric.final_url.starts_with(&base_url) {
+                let redirected_url = Url::parse(&request_metric.final_url)?;
+                let redirected_base_url = redirected_url[..url::Position::BeforePath].to_string();
+                info!(
+                    "base_url for user {} redirected from {} to {}",
+                    self.weighted_users_index + 1,
+                    &base_url,
+                    &redirected_base_url
+                );
+                let _ = self.set_base_url(&redirected_base_url);
+            }
+        }
+
+        // If enabled, track the cadence between each time the same request is made while
+        // this GooseUser is running. If requests are blocked by the upstream server, this
+        // allows Goose to backfill the requests that should have been made based on
+        // cadence statistics.
+        request_metric.user_cadence = self
+            .coordinated_omission_mitigation(&request_metric)
+            .await?;
+
+        // Send a copy of the raw request object to the parent process if
+        // we're tracking metrics.
+        if !self.config.no_metrics {
+            self.send_request_metric_to_parent(request_metric.clone())?;
+        }
+
+        // If enabled, load the static assets of successfully loaded pages like a browser.
+        let response = match response {
+            Ok(response) if request_metric.success && self.static_assets.is_some() => {
+                self.load_static_assets_of(response).await?
+            }
+            response => response,
+        };
+
+        Ok(GooseResponse::new(request_metric, response))
+    }
+
+    /// If throttle-requests is enabled, wait until there's room to make another request.
+    pub(crate) async fn throttle_request(&self) -> GooseTaskResult {
         if self.is_throttled && self.throttle.is_some() {
             // ...wait until there's room to add a token to the throttle channel before proceeding.
             debug!("GooseUser: waiting on throttle");
@@ -1561,9 +1980,15 @@ impl GooseUser {
             self.throttle.clone().unwrap().send_async(true).await?;
         };
 
-        let started = Instant::now();
-        let request = request_builder.build()?;
+        Ok(())
+    }
 
+    /// Record information about a request before it is made.
+    pub(crate) fn new_request_metric(
+        &self,
+        request: &Request,
+        request_name: Option<&str>,
+    ) -> Result<GooseRequestMetric, GooseTaskError> {
         // String version of request path.
         let path = match Url::parse(&request.url().to_string()) {
             Ok(u) => u.path().to_string(),
@@ -1600,71 +2025,12 @@ impl GooseUser {
         let raw_request = GooseRawRequest::new(method, request.url().as_str(), headers, body);
 
         // Record information about the request.
-        let mut request_metric = GooseRequestMetric::new(
+        Ok(GooseRequestMetric::new(
             raw_request,
             request_name,
             self.started.elapsed().as_millis(),
             self.weighted_users_index,
-        );
-
-        // Make the actual request.
-        let response = self.client.execute(request).await;
-        request_metric.set_response_time(started.elapsed().as_millis());
-
-        match &response {
-            Ok(r) => {
-                let status_code = r.status();
-                debug!("{:?}: status_code {}", &path, status_code);
-                // @TODO: match/handle all is_foo() https://docs.rs/http/0.2.1/http/status/struct.StatusCode.html
-                if !status_code.is_success() {
-                    request_metric.success = false;
-                    request_metric.error = format!("{}: {}", status_code, request_name);
-                }
-                request_metric.set_status_code(Some(status_code));
-                request_metric.set_final_url(r.url().as_str());
-
-                // Load test user was redirected.
-                if self.config.sticky_follow && request_metric.raw.url != request_metric.final_url {
-                    let base_url = self.base_url.to_string();
-                    // Check if the URL redirected started with the load test base_url.
-                    if !request_metric.final_url.starts_with(&base_url) {
-                        let redirected_url = Url::parse(&request_metric.final_url)?;
-                        let redirected_base_url =
-                            redirected_url[..url::Position::BeforePath].to_string();
-                        info!(
-                            "base_url for user {} redirected from {} to {}",
-                            self.weighted_users_index + 1,
-                            &base_url,
-                            &redirected_base_url
-                        );
-                        let _ = self.set_base_url(&redirected_base_url);
-                    }
-                }
-            }
-            Err(e) => {
-                // @TODO: what can we learn from a reqwest error?
-                warn!("{:?}: {}", &path, e);
-                request_metric.success = false;
-                request_metric.set_status_code(None);
-                request_metric.error = clean_reqwest_error(e, request_name);
-            }
-        };
-
-        // If enabled, track the cadence between each time the same request is made while
-        // this GooseUser is running. If requests are blocked by the upstream server, this
-        // allows Goose to backfill the requests that should have been made based on
-        // cadence statistics.
-        request_metric.user_cadence = self
-            .coordinated_omission_mitigation(&request_metric)
-            .await?;
-
-        // Send a copy of the raw request object to the parent process if
-        // we're tracking metrics.
-        if !self.config.no_metrics {
-            self.send_request_metric_to_parent(request_metric.clone())?;
-        }
-
-        Ok(GooseResponse::new(request_metric, response))
+        ))
     }
 
     /// Tracks the time it takes for the current GooseUser to loop through all GooseTasks
@@ -1757,7 +2123,7 @@ impl GooseUser {
     /// loop through all GooseTasks by the current GooseUser. Through this mechanism, Goose is
     /// able to detect stalls on the upstream server being load tested, backfilling requests based
     /// on what statistically should have happened. Can be disabled with `--co-mitigation disabled`.
-    async fn coordinated_omission_mitigation(
+    pub(crate) async fn coordinated_omission_mitigation(
         &self,
         request_metric: &GooseRequestMetric,
     ) -> Result<u64, GooseTaskError> {
@@ -1810,7 +2176,16 @@ impl GooseUser {
         }
     }
 
-    fn send_request_metric_to_parent(&self, request_metric: GooseRequestMetric) -> GooseTaskResult {
+    pub(crate) fn send_request_metric_to_parent(
+        &self,
+        request_metric: GooseRequestMetric,
+    ) -> GooseTaskResult {
+        // Count failed requests so open transactions can tell if any of their requests
+        // failed, ignoring requests generated by Coordinated Omission Mitigation.
+        if request_metric.coordinated_omission_elapsed == 0 {
+            self.count_request_failure(&request_metric);
+        }
+
         // If requests-file is enabled, send a copy of the raw request to the logger thread.
         if !self.config.request_log.is_empty() {
             if let Some(logger) = self.logger.as_ref() {
@@ -1830,7 +2205,11 @@ impl GooseUser {
 
     /// If `request_name` is set, unwrap and use this. Otherwise, if the GooseTask has a name
     /// set use it. Otherwise use the path.
-    fn get_request_name<'a>(&'a self, path: &'a str, request_name: Option<&'a str>) -> &'a str {
+    pub(crate) fn get_request_name<'a>(
+        &'a self,
+        path: &'a str,
+        request_name: Option<&'a str>,
+    ) -> &'a str {
         match request_name {
             // If a request_name was passed in, unwrap and return a copy of it.
             Some(rn) => rn,
@@ -1852,7 +2231,11 @@ impl GooseUser {
     /// By default, Goose will consider any response with a 2xx status code as a success.
     /// It may be valid in your test for a non-2xx HTTP status code to be returned. A copy
     /// of your original request is returned with the response, and a mutable copy must be
-    /// included when setting a request as a success.
+    /// included when setting a request as a success. The original error is no longer
+    /// counted in the error summary.
+    ///
+    /// To check status codes and responses declaratively, see the
+    /// [`validation`](../validation/index.html) module.
     ///
     /// # Example
     /// ```rust
@@ -2286,6 +2669,34 @@ impl GooseUser {
     }
 }
 
+/// Record the status code and final URL of a response, or the error if the request
+/// failed. Responses without a 2xx status code are failures.
+pub(crate) fn record_response(
+    request_metric: &mut GooseRequestMetric,
+    response: &Result<Response, reqwest::Error>,
+) {
+    match response {
+        Ok(r) => {
+            let status_code = r.status();
+            debug!("{:?}: status_code {}", &request_metric.raw.url, status_code);
+            // @TODO: match/handle all is_foo() https://docs.rs/http/0.2.1/http/status/struct.StatusCode.html
+            if !status_code.is_success() {
+                request_metric.success = false;
+                request_metric.error = format!("{}: {}", status_code, request_metric.name);
+            }
+            request_metric.set_status_code(Some(status_code));
+            request_metric.set_final_url(r.url().as_str());
+        }
+        Err(e) => {
+            // @TODO: what can we learn from a reqwest error?
+            warn!("{:?}: {}", &request_metric.raw.url, e);
+            request_metric.success = false;
+            request_metric.set_status_code(None);
+            request_metric.error = clean_reqwest_error(e, &request_metric.name);
+        }
+    };
+}
+
 /// Remove path from Reqwest error to avoid having a lot of distincts error
 /// when path parameters are used.
 fn clean_reqwest_error(e: &reqwest::Error, request_name: &str) -> String {
@@ -3030,6 +3441,56 @@ mod tests {
         assert_eq!(session, &session_data);
     }
 
+    #[test]
+    fn test_set_global_data() {
+        #[derive(Debug, PartialEq)]
+        struct CustomGlobalData(String);
+
+        let configuration = GooseConfiguration::parse_args_default(&EMPTY_ARGS).unwrap();
+        let user =
+            GooseUser::single("http://localhost:8080".parse().unwrap(), &configuration).unwrap();
+        let mut other_user =
+            GooseUser::single("http://localhost:8080".parse().unwrap(), &configuration).unwrap();
+        other_user.global_data = user.global_data.clone();
+
+        assert!(other_user.get_global_data::<CustomGlobalData>().is_none());
+        user.set_global_data(CustomGlobalData("foo".to_string()));
+        user.set_global_data(7_usize);
+
+        // Global data is shared by users, and stored by type.
+        let global_data = other_user.get_global_data::<CustomGlobalData>();
+        assert_eq!(global_data.unwrap().0, "foo");
+        assert_eq!(*other_user.get_global_data::<usize>().unwrap(), 7);
+        assert!(other_user.get_global_data::<String>().is_none());
+
+        // Setting global data of the same type replaces it.
+        other_user.set_global_data(CustomGlobalData("bar".to_string()));
+        let global_data = user.get_global_data::<CustomGlobalData>();
+        assert_eq!(global_data.unwrap().0, "bar");
+    }
+
+    #[test]
+    fn test_variables() {
+        let configuration = GooseConfiguration::parse_args_default(&EMPTY_ARGS).unwrap();
+        let mut user =
+            GooseUser::single("http://localhost:8080".parse().unwrap(), &configuration).unwrap();
+
+        assert!(user.get_variable("nid").is_none());
+        user.set_variable("nid", "1");
+        user.set_variable("nid", "42");
+        user.set_variable("op", "edit");
+        assert_eq!(user.get_variable("nid"), Some("42"));
+
+        assert_eq!(
+            user.expand_variables("/node/{{nid}}/{{ op }}"),
+            "/node/42/edit"
+        );
+        // Unknown and unterminated references are left unchanged.
+        assert_eq!(user.expand_variables("/{{uid}}/{{nid}}"), "/{{uid}}/42");
+        assert_eq!(user.expand_variables("/node/{{nid"), "/node/{{nid");
+        assert_eq!(user.expand_variables("/node"), "/node");
+    }
+
     #[test]
     fn test_get_mut_session_data() {
         #[derive(Debug)]
@@ -0,0 +1,359 @@
+//! Optional gRPC requests, enabled with the `grpc` feature.
+//!
+//! A [`GooseUser`] can make unary and server-streaming gRPC calls with
+//! [`GooseUser::grpc_unary`] and [`GooseUser::grpc_server_streaming`], sending and
+//! receiving [`prost`](https://docs.rs/prost) messages over HTTP/2. Each user has its own
+//! [`Channel`], connected to the host being load tested when the first call is made, or
+//! configured with [`GooseUser::set_grpc_channel`].
+//!
+//! Calls are recorded next to HTTP requests in the request metrics, with the `GRPC`
+//! method and the path of the gRPC method as their name, for example
+//! `GRPC /helloworld.Greeter/SayHello`. A call is a failure unless it returns the `Ok`
+//! status. Calls have no HTTP status code, instead the gRPC status code of each call is
+//! recorded in [`GooseRequestMetric::grpc_status_code`], and tracked with `--status-codes`
+//! in [`GooseRequestMetricAggregate::grpc_status_code_counts`](../metrics/struct.GooseRequestMetricAggregate.html#structfield.grpc_status_code_counts).
+//! gRPC status codes are displayed next to HTTP status codes, labelled as `gRPC`: `0` is
+//! `Ok`, `5` is `NotFound`, and so on.
+//!
+//! Enable the feature in `Cargo.toml`:
+//!
+//! ```toml
+//! [dependencies]
+//! goose = { version = "^0.14", features = ["grpc"] }
+//! ```
+//!
+//! ## Example
+//!
+//! ```rust
+//! use goose::prelude::*;
+//!
+//! #[derive(Clone, PartialEq, prost::Message)]
+//! pub struct HelloRequest {
+//!     #[prost(string, tag = "1")]
+//!     pub name: String,
+//! }
+//!
+//! #[derive(Clone, PartialEq, prost::Message)]
+//! pub struct HelloReply {
+//!     #[prost(string, tag = "1")]
+//!     pub message: String,
+//! }
+//!
+//! let mut task = task!(say_hello);
+//!
+//! async fn say_hello(user: &mut GooseUser) -> GooseTaskResult {
+//!     let goose = user
+//!         .grpc_unary::<_, HelloReply>(
+//!             "/helloworld.Greeter/SayHello",
+//!             HelloRequest {
+//!                 name: "Goose".to_string(),
+//!             },
+//!         )
+//!         .await?;
+//!     if let Ok(reply) = goose.response {
+//!         println!("{}", reply.into_inner().message);
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use http::uri::PathAndQuery;
+use std::convert::TryFrom;
+use std::time::Instant;
+use tonic::client::Grpc;
+use tonic::codec::ProstCodec;
+use tonic::transport::{Channel, Endpoint};
+use tonic::{Code, IntoRequest, Request, Response, Status};
+
+use crate::goose::{GooseMethod, GooseTaskError, GooseUser};
+use crate::metrics::{GooseRawRequest, GooseRequestMetric};
+
+/// The method gRPC calls are recorded with.
+const GRPC_METHOD: &str = "GRPC";
+
+/// The response to a gRPC call.
+#[derive(Debug)]
+pub struct GooseGrpcResponse<T> {
+    /// The request that this is a response to.
+    pub request: GooseRequestMetric,
+    /// The response.
+    pub response: Result<Response<T>, Status>,
+}
+
+impl GooseUser {
+    /// Set the [`Channel`] gRPC calls made by this user are sent over.
+    ///
+    /// By default, each user lazily connects to the host being load tested. Set a
+    /// channel to connect to another host, or to configure the connection, for example
+    /// with timeouts.
+    ///
+    /// # Example
+    /// ```rust
+    /// use goose::prelude::*;
+    /// use std::time::Duration;
+    /// use tonic::transport::Endpoint;
+    ///
+    /// let mut task = task!(setup_channel).set_on_start();
+    ///
+    /// async fn setup_channel(user: &mut GooseUser) -> GooseTaskResult {
+    ///     let channel = Endpoint::from_static("http://127.0.0.1:50051")
+    ///         .timeout(Duration::from_secs(5))
+    ///         .connect_lazy();
+    ///     user.set_grpc_channel(channel);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn set_grpc_channel(&mut self, channel: Channel) {
+        self.grpc_channel = Some(channel);
+    }
+
+    /// Make a unary gRPC call and collect relevant metrics.
+    ///
+    /// The request is a [`prost::Message`](https://docs.rs/prost/*/prost/trait.Message.html),
+    /// or a [`Request`] to also set metadata. Like [`GooseUser::goose_send`], a call that
+    /// fails is not an error: the [`Status`] is in the response, and the call can be
+    /// marked a success with [`GooseUser::set_success`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use goose::prelude::*;
+    ///
+    /// #[derive(Clone, PartialEq, prost::Message)]
+    /// pub struct GetItemRequest {
+    ///     #[prost(uint64, tag = "1")]
+    ///     pub id: u64,
+    /// }
+    ///
+    /// #[derive(Clone, PartialEq, prost::Message)]
+    /// pub struct Item {
+    ///     #[prost(string, tag = "1")]
+    ///     pub name: String,
+    /// }
+    ///
+    /// let mut task = task!(get_item);
+    ///
+    /// async fn get_item(user: &mut GooseUser) -> GooseTaskResult {
+    ///     let mut request = tonic::Request::new(GetItemRequest { id: 7 });
+    ///     request
+    ///         .metadata_mut()
+    ///         .insert("authorization", "Bearer token".parse().unwrap());
+    ///     let _goose = user
+    ///         .grpc_unary::<GetItemRequest, Item>("/shop.Items/GetItem", request)
+    ///         .await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn grpc_unary<Req, Resp>(
+        &mut self,
+        path: &str,
+        request: impl IntoRequest<Req>,
+    ) -> Result<GooseGrpcResponse<Resp>, GooseTaskError>
+    where
+        Req: prost::Message + Send + Sync + 'static,
+        Resp: prost::Message + Default + Send + Sync + 'static,
+    {
+        self.throttle_request().await?;
+
+        let mut request_metric = self.new_grpc_metric(path)?;
+
+        let started = Instant::now();
+        let response = match self.grpc_client() {
+            Ok(grpc) => unary(grpc, path, request.into_request()).await,
+            Err(status) => Err(status),
+        };
+        request_metric.set_response_time(started.elapsed());
+
+        self.record_grpc_call(request_metric, response).await
+    }
+
+    /// Make a server-streaming gRPC call and collect relevant metrics.
+    ///
+    /// All messages streamed by the server are received and returned together, and
+    /// the call is timed until the stream ends. The call fails if the stream ends with
+    /// an error.
+    ///
+    /// # Example
+    /// ```rust
+    /// use goose::prelude::*;
+    ///
+    /// #[derive(Clone, PartialEq, prost::Message)]
+    /// pub struct ListItemsRequest {
+    ///     #[prost(uint32, tag = "1")]
+    ///     pub page_size: u32,
+    /// }
+    ///
+    /// #[derive(Clone, PartialEq, prost::Message)]
+    /// pub struct Item {
+    ///     #[prost(string, tag = "1")]
+    ///     pub name: String,
+    /// }
+    ///
+    /// let mut task = task!(list_items);
+    ///
+    /// async fn list_items(user: &mut GooseUser) -> GooseTaskResult {
+    ///     let goose = user
+    ///         .grpc_server_streaming::<_, Item>(
+    ///             "/shop.Items/ListItems",
+    ///             ListItemsRequest { page_size: 20 },
+    ///         )
+    ///         .await?;
+    ///     if let Ok(items) = goose.response {
+    ///         println!("{} items", items.get_ref().len());
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn grpc_server_streaming<Req, Resp>(
+        &mut self,
+        path: &str,
+        request: impl IntoRequest<Req>,
+    ) -> Result<GooseGrpcResponse<Vec<Resp>>, GooseTaskError>
+    where
+        Req: prost::Message + Send + Sync + 'static,
+        Resp: prost::Message + Default + Send + Sync + 'static,
+    {
+        self.throttle_request().await?;
+
+        let mut request_metric = self.new_grpc_metric(path)?;
+
+        let started = Instant::now();
+        let response = match self.grpc_client() {
+            Ok(grpc) => server_streaming(grpc, path, request.into_request()).await,
+            Err(status) => Err(status),
+        };
+        request_metric.set_response_time(started.elapsed());
+
+        self.record_grpc_call(request_metric, response).await
+    }
+
+    /// Returns a gRPC client using the channel of the user.
+    fn grpc_client(&mut self) -> Result<Grpc<Channel>, Status> {
+        let channel = match self.grpc_channel.as_ref() {
+            Some(channel) => channel.clone(),
+            // Lazily connect to the host being load tested.
+            None => {
+                let channel = Endpoint::from_shared(self.base_url.to_string())
+                    .map_err(|e| Status::invalid_argument(format!("invalid host: {}", e)))?
+                    .connect_lazy();
+                self.grpc_channel = Some(channel.clone());
+                channel
+            }
+        };
+
+        Ok(Grpc::new(channel))
+    }
+
+    /// Record information about a gRPC call before it is made.
+    fn new_grpc_metric(&self, path: &str) -> Result<GooseRequestMetric, GooseTaskError> {
+        let url = self.build_url(path)?;
+        let raw_request = GooseRawRequest::new(
+            GooseMethod::Protocol(GRPC_METHOD.to_string()),
+            &url,
+            Vec::new(),
+            "",
+        );
+        let mut request_metric = GooseRequestMetric::new(
+            raw_request,
+            path,
+            self.started.elapsed().as_millis(),
+            self.weighted_users_index,
+        );
+        request_metric.set_final_url(&url);
+
+        Ok(request_metric)
+    }
+
+    /// Record the status of a gRPC call, and send it to the parent process.
+    async fn record_grpc_call<T>(
+        &self,
+        mut request_metric: GooseRequestMetric,
+        response: Result<Response<T>, Status>,
+    ) -> Result<GooseGrpcResponse<T>, GooseTaskError> {
+        let code = match &response {
+            Ok(_) => Code::Ok,
+            Err(status) => status.code(),
+        };
+        request_metric.grpc_status_code = Some(code as u16);
+        if code != Code::Ok {
+            request_metric.success = false;
+            request_metric.error = format!("{}: {}", code, request_metric.name);
+        }
+
+        // If enabled, track the cadence between each time the same request is made, to
+        // backfill requests blocked by the upstream server.
+        request_metric.user_cadence = self
+            .coordinated_omission_mitigation(&request_metric)
+            .await?;
+
+        // Send a copy of the raw request object to the parent process if
+        // we're tracking metrics.
+        if !self.config.no_metrics {
+            self.send_request_metric_to_parent(request_metric.clone())?;
+        }
+
+        Ok(GooseGrpcResponse {
+            request: request_metric,
+            response,
+        })
+    }
+}
+
+/// Make a unary gRPC call.
+async fn unary<Req, Resp>(
+    mut grpc: Grpc<Channel>,
+    path: &str,
+    request: Request<Req>,
+) -> Result<Response<Resp>, Status>
+where
+    Req: prost::Message + Send + Sync + 'static,
+    Resp: prost::Message + Default + Send + Sync + 'static,
+{
+    let path = grpc_path(path)?;
+    grpc_ready(&mut grpc).await?;
+    grpc.unary(request, path, ProstCodec::default()).await
+}
+
+/// Make a server-streaming gRPC call, receiving all messages until the stream ends.
+async fn server_streaming<Req, Resp>(
+    mut grpc: Grpc<Channel>,
+    path: &str,
+    request: Request<Req>,
+) -> Result<Response<Vec<Resp>>, Status>
+where
+    Req: prost::Message + Send + Sync + 'static,
+    Resp: prost::Message + Default + Send + Sync + 'static,
+{
+    let path = grpc_path(path)?;
+    grpc_ready(&mut grpc).await?;
+    let streaming = grpc
+        .server_streaming(request, path, ProstCodec::default())
+        .await?;
+
+    let metadata = streaming.metadata().clone();
+    let mut stream = streaming.into_inner();
+    let mut messages = Vec::new();
+    while let Some(message) = stream.message().await? {
+        messages.push(message);
+    }
+
+    let mut response = Response::new(messages);
+    *response.metadata_mut() = metadata;
+    Ok(response)
+}
+
+/// Parse the path of a gRPC method, ie `/helloworld.Greeter/SayHello`.
+fn grpc_path(path: &str) -> Result<PathAndQuery, Status> {
+    PathAndQuery::try_from(path)
+        .map_err(|e| Status::invalid_argument(format!("invalid gRPC method path: {}", e)))
+}
+
+/// Wait until the channel is ready to make a call.
+async fn grpc_ready(grpc: &mut Grpc<Channel>) -> Result<(), Status> {
+    grpc.ready()
+        .await
+        .map_err(|e| Status::unavailable(format!("service was not ready: {}", e)))
+}
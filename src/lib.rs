@@ -439,18 +439,37 @@
 #[macro_use]
 extern crate log;
 
+mod arrival_rate;
+pub mod baseline;
 pub mod config;
+pub mod config_env;
+pub mod config_file;
 pub mod controller;
+pub mod custom_metrics;
+pub mod extraction;
+pub mod feeder;
 pub mod goose;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod load_profile;
 pub mod logger;
 #[cfg(feature = "gaggle")]
 mod manager;
 pub mod metrics;
 pub mod prelude;
+mod prometheus;
 mod report;
+pub mod sink;
+pub mod static_assets;
+pub mod summary;
+pub mod threshold;
 mod throttle;
+pub mod transaction;
+pub mod transport;
 mod user;
 pub mod util;
+pub mod validation;
+pub mod websocket;
 #[cfg(feature = "gaggle")]
 mod worker;
 
@@ -472,11 +491,19 @@ use std::time::Duration;
 use std::{fmt, io, time};
 use tokio::fs::File;
 
+use crate::arrival_rate::GooseIterationReceiver;
 use crate::config::{GooseConfiguration, GooseDefaults};
 use crate::controller::{GooseControllerProtocol, GooseControllerRequest};
-use crate::goose::{GaggleUser, GooseTask, GooseTaskSet, GooseUser, GooseUserCommand};
+use crate::feeder::GooseFeeder;
+use crate::goose::{
+    GaggleUser, GooseGlobalData, GooseTask, GooseTaskSet, GooseUser, GooseUserCommand,
+    GooseUserData,
+};
 use crate::logger::{GooseLoggerJoinHandle, GooseLoggerTx};
 use crate::metrics::{GooseMetric, GooseMetrics};
+use crate::sink::GooseMetricsSink;
+use crate::summary::GooseSummary;
+use crate::threshold::GooseThreshold;
 #[cfg(feature = "gaggle")]
 use crate::worker::{register_shutdown_pipe_handler, GaggleMetrics};
 
@@ -486,6 +513,9 @@ const DEFAULT_TELNET_PORT: &str = "5116";
 /// Constant defining Goose's default WebSocket Controller port.
 const DEFAULT_WEBSOCKET_PORT: &str = "5117";
 
+/// Constant defining Goose's default Prometheus metrics endpoint port.
+const DEFAULT_PROMETHEUS_PORT: &str = "5118";
+
 // WORKER_ID is only used when running a gaggle (a distributed load test).
 lazy_static! {
     static ref WORKER_ID: AtomicUsize = AtomicUsize::new(0);
@@ -530,6 +560,13 @@ pub enum GooseError {
         /// An optional explanation of the error.
         detail: String,
     },
+    /// Failed to load a [`GooseFeeder`](./feeder/struct.GooseFeeder.html).
+    InvalidFeeder {
+        /// The name of the feeder that failed to load.
+        name: String,
+        /// An explanation of the error.
+        detail: String,
+    },
     /// Failed to parse a hostname.
     InvalidHost {
         /// The invalid hostname that caused this error.
@@ -539,6 +576,13 @@ pub enum GooseError {
         /// Wraps a [`url::ParseError`](https://docs.rs/url/*/url/enum.ParseError.html).
         parse_error: url::ParseError,
     },
+    /// Failed to compile a regular expression, CSS selector or JSON path.
+    InvalidPattern {
+        /// The invalid regular expression, CSS selector or JSON path.
+        pattern: String,
+        /// An explanation of the error.
+        detail: String,
+    },
     /// Invalid option or value specified, may only be invalid in context.
     InvalidOption {
         /// The invalid option that caused this error, may be only invalid in context.
@@ -569,6 +613,11 @@ pub enum GooseError {
         /// An optional explanation of the error.
         detail: String,
     },
+    /// One or more [thresholds](./threshold/index.html) were not met by the final metrics.
+    ThresholdsFailed {
+        /// Which thresholds failed, and the measured values.
+        detail: String,
+    },
 }
 /// Implement a helper to provide a text description of all possible types of errors.
 impl GooseError {
@@ -578,11 +627,14 @@ impl GooseError {
             GooseError::Reqwest(_) => "reqwest::Error",
             GooseError::TokioJoin(_) => "tokio::task::JoinError",
             GooseError::FeatureNotEnabled { .. } => "required compile-time feature not enabled",
+            GooseError::InvalidFeeder { .. } => "failed to load feeder",
             GooseError::InvalidHost { .. } => "failed to parse hostname",
+            GooseError::InvalidPattern { .. } => "invalid pattern",
             GooseError::InvalidOption { .. } => "invalid option or value specified",
             GooseError::InvalidWaitTime { .. } => "invalid wait_time specified",
             GooseError::InvalidWeight { .. } => "invalid weight specified",
             GooseError::NoTaskSets { .. } => "no task sets defined",
+            GooseError::ThresholdsFailed { .. } => "one or more thresholds failed",
         }
     }
 }
@@ -602,6 +654,23 @@ impl fmt::Display for GooseError {
             GooseError::InvalidHost {
                 ref parse_error, ..
             } => write!(f, "GooseError: {} ({})", self.describe(), parse_error),
+            GooseError::InvalidFeeder {
+                ref name,
+                ref detail,
+            } => write!(f, "GooseError: {} {} ({})", self.describe(), name, detail),
+            GooseError::InvalidPattern {
+                ref pattern,
+                ref detail,
+            } => write!(
+                f,
+                "GooseError: {} {} ({})",
+                self.describe(),
+                pattern,
+                detail
+            ),
+            GooseError::ThresholdsFailed { ref detail } => {
+                write!(f, "GooseError: {} ({})", self.describe(), detail)
+            }
             _ => write!(f, "GooseError: {}", self.describe()),
         }
     }
@@ -668,6 +737,18 @@ pub enum AttackPhase {
     Starting,
     /// All [`GooseUser`](./goose/struct.GooseUser.html)s have launched and are generating load.
     Running,
+    /// Additional [`GooseUser`](./goose/struct.GooseUser.html)s are launching to reach the
+    /// target of the current [`GooseLoadProfile`](./load_profile/struct.GooseLoadProfile.html)
+    /// stage, or the number of users set by a Controller.
+    Increase,
+    /// Some [`GooseUser`](./goose/struct.GooseUser.html)s are gracefully stopping to reach
+    /// the target of the current
+    /// [`GooseLoadProfile`](./load_profile/struct.GooseLoadProfile.html) stage, or the
+    /// number of users set by a Controller.
+    Decrease,
+    /// A Controller has paused the load test, all
+    /// [`GooseUser`](./goose/struct.GooseUser.html)s are waiting to resume.
+    Paused,
     /// [`GooseUser`](./goose/struct.GooseUser.html)s are stopping.
     Stopping,
     /// Exiting the load test.
@@ -742,6 +823,10 @@ struct GooseAttackRunState {
     running_metrics_timer: std::time::Instant,
     /// Boolean flag indicating if running metrics should be displayed.
     display_running_metrics: bool,
+    /// Timer tracking when to record the next metrics snapshot, if enabled.
+    snapshot_timer: std::time::Instant,
+    /// Timer tracking when to flush metrics sinks, if any are registered.
+    sink_flush_timer: std::time::Instant,
     /// Boolean flag indicating if all [`GooseUser`](./goose/struct.GooseUser.html)s
     /// have been spawned.
     all_users_spawned: bool,
@@ -752,6 +837,21 @@ struct GooseAttackRunState {
     canceled: Arc<AtomicBool>,
     /// Optional socket used to coordinate a distributed Gaggle.
     socket: Option<Socket>,
+    /// Index of the currently running
+    /// [`GooseLoadProfile`](./load_profile/struct.GooseLoadProfile.html) stage, if enabled.
+    load_profile_stage: usize,
+    /// A timestamp tracking when the current load profile stage started.
+    load_profile_timer: std::time::Instant,
+    /// How many milliseconds to wait between launching or stopping each
+    /// [`GooseUser`](./goose/struct.GooseUser.html) in the Increase and Decrease phases.
+    ramp_interval_ms: usize,
+    /// Optional sender used to start iterations at a fixed arrival rate, if enabled.
+    iterations_tx: Option<flume::Sender<()>>,
+    /// Optional receiver shared by all [`GooseUser`](./goose/struct.GooseUser.html)s to
+    /// run iterations at a fixed arrival rate, if enabled.
+    iterations_rx: Option<GooseIterationReceiver>,
+    /// The phase to return to when a paused load test is resumed.
+    resume_phase: AttackPhase,
 }
 
 /// Global internal state for the load test.
@@ -781,8 +881,20 @@ pub struct GooseAttack {
     scheduler: GooseScheduler,
     /// When the load test started.
     started: Option<time::Instant>,
+    /// When the load test was paused, if currently paused.
+    paused: Option<time::Instant>,
     /// All metrics merged together.
     metrics: GooseMetrics,
+    /// Sinks that receive all metrics while the load test runs.
+    metrics_sinks: Vec<Box<dyn GooseMetricsSink>>,
+    /// Pass/fail thresholds evaluated against the final metrics.
+    thresholds: Vec<GooseThreshold>,
+    /// Summary of an earlier load test to compare the final metrics against.
+    baseline: Option<GooseSummary>,
+    /// Test data feeders available to all [`GooseUser`](./goose/struct.GooseUser.html)s.
+    feeders: Vec<Arc<GooseFeeder>>,
+    /// Data shared by the load test and all [`GooseUser`](./goose/struct.GooseUser.html)s.
+    global_data: GooseGlobalData,
 }
 /// Goose's internal global state.
 impl GooseAttack {
@@ -808,7 +920,13 @@ impl GooseAttack {
             attack_phase: AttackPhase::Idle,
             scheduler: GooseScheduler::RoundRobin,
             started: None,
+            paused: None,
             metrics: GooseMetrics::default(),
+            metrics_sinks: Vec::new(),
+            thresholds: Vec::new(),
+            baseline: None,
+            feeders: Vec::new(),
+            global_data: GooseGlobalData::default(),
         })
     }
 
@@ -842,7 +960,13 @@ impl GooseAttack {
             attack_phase: AttackPhase::Idle,
             scheduler: GooseScheduler::RoundRobin,
             started: None,
+            paused: None,
             metrics: GooseMetrics::default(),
+            metrics_sinks: Vec::new(),
+            thresholds: Vec::new(),
+            baseline: None,
+            feeders: Vec::new(),
+            global_data: GooseGlobalData::default(),
         })
     }
 
@@ -948,6 +1072,82 @@ impl GooseAttack {
         self
     }
 
+    /// Register a [`GooseFeeder`](./feeder/struct.GooseFeeder.html), making its records
+    /// available to all [`GooseUser`](./goose/struct.GooseUser.html)s with
+    /// [`GooseUser::get_record`](./goose/struct.GooseUser.html#method.get_record). A feeder
+    /// registered on a [`GooseTaskSet`](./goose/struct.GooseTaskSet.html) with the same
+    /// name takes precedence for users running that task set.
+    ///
+    /// # Example
+    /// ```rust
+    /// use goose::prelude::*;
+    /// use goose::feeder::GooseFeeder;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), GooseError> {
+    ///     GooseAttack::initialize()?
+    ///         .register_feeder(GooseFeeder::new("products", Vec::new()))
+    ///         .register_taskset(taskset!("ExampleTasks")
+    ///             .register_task(task!(example_task))
+    ///         );
+    ///
+    ///     Ok(())
+    /// }
+    ///
+    /// async fn example_task(user: &mut GooseUser) -> GooseTaskResult {
+    ///     let product = user.get_record("products")?;
+    ///     let id = product.get_str("id").unwrap_or_default();
+    ///     let _goose = user.get(&format!("/product/{}", id)).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn register_feeder(mut self, feeder: GooseFeeder) -> Self {
+        self.feeders.push(Arc::new(feeder));
+        self
+    }
+
+    /// Set global data shared by all [`GooseUser`](./goose/struct.GooseUser.html)s, and
+    /// by the [`test_start`](./struct.GooseAttack.html#method.test_start) and
+    /// [`test_stop`](./struct.GooseAttack.html#method.test_stop) tasks. Users read it with
+    /// [`GooseUser::get_global_data`](./goose/struct.GooseUser.html#method.get_global_data).
+    ///
+    /// One value of each type can be stored, and setting a value of a type that's already
+    /// set replaces it. Global data can also be set while the load test runs with
+    /// [`GooseUser::set_global_data`](./goose/struct.GooseUser.html#method.set_global_data),
+    /// for example to share an auth token or the IDs of fixtures created by `test_start`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use goose::prelude::*;
+    ///
+    /// struct Products(Vec<usize>);
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), GooseError> {
+    ///     GooseAttack::initialize()?
+    ///         .set_global_data(Products(vec![1, 2, 3]))
+    ///         .register_taskset(taskset!("ExampleTasks")
+    ///             .register_task(task!(example_task))
+    ///         );
+    ///
+    ///     Ok(())
+    /// }
+    ///
+    /// async fn example_task(user: &mut GooseUser) -> GooseTaskResult {
+    ///     let products = user.get_global_data::<Products>().expect("Missing products!");
+    ///     for id in &products.0 {
+    ///         let _goose = user.get(&format!("/product/{}", id)).await?;
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn set_global_data<T: GooseUserData>(self, data: T) -> Self {
+        self.global_data.set(data);
+        self
+    }
+
     /// Optionally define a task to run before users are started and all task sets
     /// start running. This is would generally be used to set up anything required
     /// for the load test.
@@ -1282,6 +1482,11 @@ impl GooseAttack {
             std::process::exit(0);
         }
 
+        // Load options from environment variables, then from the configuration file, if
+        // configured.
+        self.configuration.load_environment()?;
+        self.configuration.load_config_file()?;
+
         // Configure GooseConfiguration.
         self.configuration.configure(&self.defaults);
 
@@ -1291,6 +1496,12 @@ impl GooseAttack {
         // Configure the validated run time.
         self.set_run_time()?;
 
+        // Add thresholds configured with `--threshold`.
+        self.configure_thresholds()?;
+
+        // Load the baseline, if configured.
+        self.configure_baseline()?;
+
         // With a validated GooseConfiguration, enter a run mode.
         self.attack_mode = if self.configuration.manager {
             AttackMode::Manager
@@ -1316,6 +1527,7 @@ impl GooseAttack {
         // Calculate a unique hash for the current load test.
         let mut s = DefaultHasher::new();
         self.task_sets.hash(&mut s);
+        self.feeders.hash(&mut s);
         self.metrics.hash = s.finish();
         debug!("hash: {}", self.metrics.hash);
 
@@ -1353,6 +1565,15 @@ impl GooseAttack {
             self = self.start_attack(None).await?;
         }
 
+        // Evaluate thresholds against the final metrics and write summaries, Workers don't
+        // have final metrics.
+        if self.attack_mode != AttackMode::Worker {
+            let thresholds = self.evaluate_thresholds();
+            // Write summaries even if thresholds failed, they include the results.
+            self.write_summaries().await?;
+            thresholds?;
+        }
+
         Ok(self.metrics)
     }
 
@@ -1416,6 +1637,7 @@ impl GooseAttack {
             // Stand-alone and Manager processes can display metrics.
             if !self.configuration.no_metrics {
                 self.metrics.display_metrics = true;
+                self.metrics.percentiles = self.get_percentiles();
             }
 
             if self.attack_mode == AttackMode::StandAlone {
@@ -1488,13 +1710,16 @@ impl GooseAttack {
         (Some(all_threads_throttle), Some(parent_to_throttle_tx))
     }
 
-    // Helper to optionally spawn a telnet and/or WebSocket Controller thread. The Controller
-    // threads share a control channel, allowing it to send requests to the parent process. When
-    // a response is required, the Controller will also send a one-shot channel allowing a direct
-    // reply.
+    // Helper to optionally spawn a telnet and/or WebSocket Controller thread, and the
+    // Prometheus metrics endpoint. These threads share a control channel, allowing them to
+    // send requests to the parent process. When a response is required, they also send a
+    // one-shot channel allowing a direct reply.
     async fn setup_controllers(&mut self) -> Option<flume::Receiver<GooseControllerRequest>> {
-        // If the telnet controller is disabled, return immediately.
-        if self.configuration.no_telnet && self.configuration.no_websocket {
+        // If all controllers are disabled, return immediately.
+        if self.configuration.no_telnet
+            && self.configuration.no_websocket
+            && !self.configuration.prometheus
+        {
             return None;
         }
 
@@ -1561,11 +1786,41 @@ impl GooseAttack {
             // There is no need to rejoin this thread when the load test ends.
             let _ = Some(tokio::spawn(controller::controller_main(
                 self.configuration.clone(),
-                all_threads_controller_request_tx,
+                all_threads_controller_request_tx.clone(),
                 GooseControllerProtocol::WebSocket,
             )));
         }
 
+        // Configure Prometheus metrics endpoint if enabled.
+        if self.configuration.prometheus {
+            // Configure prometheus_host, using default if run-time option is not set.
+            if self.configuration.prometheus_host.is_empty() {
+                self.configuration.prometheus_host =
+                    if let Some(host) = self.defaults.prometheus_host.clone() {
+                        host
+                    } else {
+                        "0.0.0.0".to_string()
+                    }
+            }
+
+            // Then configure prometheus_port, using default if run-time option is not set.
+            if self.configuration.prometheus_port == 0 {
+                self.configuration.prometheus_port =
+                    if let Some(port) = self.defaults.prometheus_port {
+                        port
+                    } else {
+                        DEFAULT_PROMETHEUS_PORT.to_string().parse().unwrap()
+                    };
+            }
+
+            // Spawn the metrics endpoint thread. There is no need to rejoin this thread when
+            // the load test ends.
+            let _ = Some(tokio::spawn(prometheus::prometheus_main(
+                self.configuration.clone(),
+                all_threads_controller_request_tx,
+            )));
+        }
+
         // Return the parent end of the Controller channel.
         Some(controller_request_rx)
     }
@@ -1594,6 +1849,8 @@ impl GooseAttack {
                         self.defaults.host.clone(),
                     )?;
                     let mut user = GooseUser::single(base_url, &self.configuration)?;
+                    user.feeders = self.user_feeders(None);
+                    user.global_data = self.global_data.clone();
                     let function = &t.function;
                     let _ = function(&mut user).await;
                 }
@@ -1620,6 +1877,8 @@ impl GooseAttack {
                         self.defaults.host.clone(),
                     )?;
                     let mut user = GooseUser::single(base_url, &self.configuration)?;
+                    user.feeders = self.user_feeders(None);
+                    user.global_data = self.global_data.clone();
                     let function = &t.function;
                     let _ = function(&mut user).await;
                 }
@@ -1649,6 +1908,9 @@ impl GooseAttack {
         // Optionally spawn a telnet and/or Websocket Controller thread.
         let controller_channel_rx = self.setup_controllers().await;
 
+        // Optionally send metrics to StatsD and/or InfluxDB.
+        self.setup_metrics_sinks()?;
+
         // Grab now() once from the standard library, used by multiple timers in
         // the run state.
         let std_now = std::time::Instant::now();
@@ -1672,10 +1934,18 @@ impl GooseAttack {
             user_channels: Vec::new(),
             running_metrics_timer: std_now,
             display_running_metrics: false,
+            snapshot_timer: std_now,
+            sink_flush_timer: std_now,
             all_users_spawned: false,
             shutdown_after_stop: !self.configuration.no_autostart,
             canceled: Arc::new(AtomicBool::new(false)),
             socket,
+            load_profile_stage: 0,
+            load_profile_timer: std_now,
+            ramp_interval_ms: 0,
+            iterations_tx: None,
+            iterations_rx: None,
+            resume_phase: AttackPhase::Running,
         };
 
         // Access socket to avoid errors.
@@ -1687,6 +1957,134 @@ impl GooseAttack {
         Ok(goose_attack_run_state)
     }
 
+    // Launch a single [`GooseUser`](./goose/struct.GooseUser.html) thread.
+    fn launch_user(
+        &mut self,
+        goose_attack_run_state: &mut GooseAttackRunState,
+        mut thread_user: GooseUser,
+    ) {
+        // Remember which task group this user is using.
+        thread_user.weighted_users_index = goose_attack_run_state.spawn_user_counter;
+        goose_attack_run_state.spawn_user_counter += 1;
+
+        // Create a per-thread channel allowing parent thread to control child threads.
+        let (parent_sender, thread_receiver): (
+            flume::Sender<GooseUserCommand>,
+            flume::Receiver<GooseUserCommand>,
+        ) = flume::unbounded();
+        goose_attack_run_state.user_channels.push(parent_sender);
+
+        // Clone the logger_tx if enabled, otherwise is None.
+        thread_user.logger = goose_attack_run_state.all_threads_logger_tx.clone();
+
+        // Copy the GooseUser-throttle receiver channel, used by all threads.
+        thread_user.throttle = if self.configuration.throttle_requests > 0 {
+            Some(goose_attack_run_state.throttle_threads_tx.clone().unwrap())
+        } else {
+            None
+        };
+
+        // Copy the GooseUser-to-parent sender channel, used by all threads.
+        thread_user.channel_to_parent = Some(goose_attack_run_state.all_threads_metrics_tx.clone());
+
+        // Copy the appropriate task_set into the thread.
+        let thread_task_set = self.task_sets[thread_user.task_sets_index].clone();
+
+        // Give the user access to feeders registered on the load test and its task set.
+        thread_user.feeders = self.user_feeders(Some(thread_user.task_sets_index));
+
+        // Share global data with the user.
+        thread_user.global_data = self.global_data.clone();
+
+        // We number threads from 1 as they're human-visible (in the logs),
+        // whereas weighted_users_index starts at 0.
+        let thread_number = goose_attack_run_state.spawn_user_counter;
+
+        let is_worker = self.attack_mode == AttackMode::Worker;
+
+        // If running on Worker, use Worker configuration in GooseUser.
+        if is_worker {
+            thread_user.config = self.configuration.clone();
+        }
+
+        // Launch a new user.
+        let user = tokio::spawn(user::user_main(
+            thread_number,
+            thread_task_set,
+            thread_user,
+            thread_receiver,
+            goose_attack_run_state.iterations_rx.clone(),
+            is_worker,
+        ));
+
+        goose_attack_run_state.users.push(user);
+        self.metrics.users += 1;
+    }
+
+    // Get the next weighted [`GooseUser`](./goose/struct.GooseUser.html) to launch,
+    // allocating more if all have already been launched.
+    fn next_weighted_user(&mut self) -> Result<GooseUser, GooseError> {
+        if self.weighted_users.is_empty() {
+            self.weighted_users = self.weight_task_set_users()?;
+        }
+        // The weighted_users vector was just filled, so unwrap() is safe.
+        Ok(self.weighted_users.pop().unwrap())
+    }
+
+    // Gracefully stop the most recently launched [`GooseUser`](./goose/struct.GooseUser.html),
+    // allowing it to run its `on_stop` tasks. The thread is joined when the load test stops.
+    fn stop_user(&mut self, goose_attack_run_state: &mut GooseAttackRunState) {
+        if let Some(send_to_user) = goose_attack_run_state.user_channels.pop() {
+            self.metrics.users -= 1;
+            let index = goose_attack_run_state.user_channels.len();
+            match send_to_user.send(GooseUserCommand::Exit) {
+                Ok(_) => {
+                    debug!("telling user {} to exit", index);
+                }
+                Err(e) => {
+                    info!("failed to tell user {} to exit: {}", index, e);
+                }
+            }
+        }
+    }
+
+    // Pause the [`GooseAttack`](./struct.GooseAttack.html), telling all
+    // [`GooseUser`](./goose/struct.GooseUser.html)s to wait after their current task.
+    fn pause_attack(&mut self, goose_attack_run_state: &mut GooseAttackRunState) {
+        self.paused = Some(time::Instant::now());
+        goose_attack_run_state.resume_phase = self.attack_phase.clone();
+        for (index, send_to_user) in goose_attack_run_state.user_channels.iter().enumerate() {
+            if let Err(e) = send_to_user.send(GooseUserCommand::Wait) {
+                info!("failed to tell user {} to pause: {}", index, e);
+            }
+        }
+        self.set_attack_phase(goose_attack_run_state, AttackPhase::Paused);
+    }
+
+    // Resume a paused [`GooseAttack`](./struct.GooseAttack.html). Timers are moved forward
+    // by the time spent paused, so the pause doesn't count towards `run_time`, load profile
+    // stages, or the duration of the load test.
+    fn resume_attack(&mut self, goose_attack_run_state: &mut GooseAttackRunState) {
+        if let Some(paused) = self.paused.take() {
+            let paused_for = paused.elapsed();
+            info!("resuming load test after pausing for {:?}", paused_for);
+            if let Some(started) = self.started.as_mut() {
+                *started += paused_for;
+            }
+            goose_attack_run_state.load_profile_timer += paused_for;
+            goose_attack_run_state.spawn_user_timer += paused_for;
+            goose_attack_run_state.running_metrics_timer += paused_for;
+            goose_attack_run_state.snapshot_timer += paused_for;
+        }
+        for (index, send_to_user) in goose_attack_run_state.user_channels.iter().enumerate() {
+            if let Err(e) = send_to_user.send(GooseUserCommand::Run) {
+                info!("failed to tell user {} to resume: {}", index, e);
+            }
+        }
+        let phase = goose_attack_run_state.resume_phase.clone();
+        self.set_attack_phase(goose_attack_run_state, phase);
+    }
+
     // Spawn [`GooseUser`](./goose/struct.GooseUser.html) threads to generate a
     // [`GooseAttack`](./struct.GooseAttack.html).
     async fn spawn_attack(
@@ -1716,7 +2114,7 @@ impl GooseAttack {
                 goose_attack_run_state.spawn_user_in_ms,
             )
         {
-            if let Some(mut thread_user) = self.weighted_users.pop() {
+            if let Some(thread_user) = self.weighted_users.pop() {
                 // Reset the spawn timer.
                 goose_attack_run_state.spawn_user_timer = std::time::Instant::now();
 
@@ -1730,57 +2128,7 @@ impl GooseAttack {
                     goose_attack_run_state.spawn_user_in_ms *=
                         self.configuration.expect_workers.unwrap() as usize;
                 };
-                goose_attack_run_state.spawn_user_counter += 1;
-
-                // Remember which task group this user is using.
-                thread_user.weighted_users_index = self.metrics.users;
-
-                // Create a per-thread channel allowing parent thread to control child threads.
-                let (parent_sender, thread_receiver): (
-                    flume::Sender<GooseUserCommand>,
-                    flume::Receiver<GooseUserCommand>,
-                ) = flume::unbounded();
-                goose_attack_run_state.user_channels.push(parent_sender);
-
-                // Clone the logger_tx if enabled, otherwise is None.
-                thread_user.logger = goose_attack_run_state.all_threads_logger_tx.clone();
-
-                // Copy the GooseUser-throttle receiver channel, used by all threads.
-                thread_user.throttle = if self.configuration.throttle_requests > 0 {
-                    Some(goose_attack_run_state.throttle_threads_tx.clone().unwrap())
-                } else {
-                    None
-                };
-
-                // Copy the GooseUser-to-parent sender channel, used by all threads.
-                thread_user.channel_to_parent =
-                    Some(goose_attack_run_state.all_threads_metrics_tx.clone());
-
-                // Copy the appropriate task_set into the thread.
-                let thread_task_set = self.task_sets[thread_user.task_sets_index].clone();
-
-                // We number threads from 1 as they're human-visible (in the logs),
-                // whereas metrics.users starts at 0.
-                let thread_number = self.metrics.users + 1;
-
-                let is_worker = self.attack_mode == AttackMode::Worker;
-
-                // If running on Worker, use Worker configuration in GooseUser.
-                if is_worker {
-                    thread_user.config = self.configuration.clone();
-                }
-
-                // Launch a new user.
-                let user = tokio::spawn(user::user_main(
-                    thread_number,
-                    thread_task_set,
-                    thread_user,
-                    thread_receiver,
-                    is_worker,
-                ));
-
-                goose_attack_run_state.users.push(user);
-                self.metrics.users += 1;
+                self.launch_user(goose_attack_run_state, thread_user);
 
                 if let Some(running_metrics) = self.configuration.running_metrics {
                     if self.attack_mode != AttackMode::Worker
@@ -1942,6 +2290,8 @@ impl GooseAttack {
                         GaggleMetrics::Requests(self.metrics.requests.clone()),
                         GaggleMetrics::Errors(self.metrics.errors.clone()),
                         GaggleMetrics::Tasks(self.metrics.tasks.clone()),
+                        GaggleMetrics::Transactions(self.metrics.transactions.clone()),
+                        GaggleMetrics::Custom(self.metrics.custom.clone()),
                     ],
                     true,
                 );
@@ -1983,6 +2333,7 @@ impl GooseAttack {
             self.metrics.display_metrics = true;
             // Only display status codes if enabled.
             self.metrics.display_status_codes = self.configuration.status_codes;
+            self.metrics.percentiles = self.get_percentiles();
         }
 
         // Reset the run state.
@@ -1997,8 +2348,19 @@ impl GooseAttack {
         goose_attack_run_state.user_channels = Vec::new();
         goose_attack_run_state.running_metrics_timer = std_now;
         goose_attack_run_state.display_running_metrics = false;
+        goose_attack_run_state.snapshot_timer = std_now;
+        goose_attack_run_state.sink_flush_timer = std_now;
         goose_attack_run_state.shutdown_after_stop = !self.configuration.no_autostart;
         goose_attack_run_state.all_users_spawned = false;
+        goose_attack_run_state.load_profile_stage = 0;
+        goose_attack_run_state.load_profile_timer = std_now;
+        goose_attack_run_state.ramp_interval_ms = 0;
+
+        goose_attack_run_state.resume_phase = AttackPhase::Running;
+        self.paused = None;
+
+        // If enabled, prepare to start iterations at a fixed arrival rate.
+        self.initialize_iterations(goose_attack_run_state);
 
         // If enabled, spawn a logger thread.
         let (logger_handle, all_threads_logger_tx) =
@@ -2072,14 +2434,50 @@ impl GooseAttack {
                 // In the Start phase, Goose launches GooseUser threads and starts a GooseAttack.
                 AttackPhase::Starting => {
                     self.update_duration();
-                    self.spawn_attack(&mut goose_attack_run_state)
-                        .await
-                        .expect("failed to start GooseAttack");
+                    // If a load profile is configured, its first stage launches GooseUsers.
+                    if self.configuration.test_plan.is_some() {
+                        self.start_load_profile_stage(&mut goose_attack_run_state, 0)
+                            .await?;
+                    } else {
+                        self.spawn_attack(&mut goose_attack_run_state)
+                            .await
+                            .expect("failed to start GooseAttack");
+                    }
                 }
                 // In the Running phase, Goose maintains the configured GooseAttack.
                 AttackPhase::Running => {
                     self.update_duration();
-                    self.monitor_attack(&mut goose_attack_run_state).await?;
+                    if self.configuration.test_plan.is_some() {
+                        self.monitor_load_profile(&mut goose_attack_run_state)
+                            .await?;
+                    } else if self.configuration.arrival_rate.is_some() {
+                        self.monitor_arrival_rate(&mut goose_attack_run_state)
+                            .await?;
+                    } else {
+                        self.monitor_attack(&mut goose_attack_run_state).await?;
+                    }
+                }
+                // In the Increase phase, Goose launches more GooseUser threads to reach the
+                // target number of users.
+                AttackPhase::Increase => {
+                    self.update_duration();
+                    self.increase_attack(&mut goose_attack_run_state).await?;
+                }
+                // In the Decrease phase, Goose gracefully stops GooseUser threads to reach the
+                // target number of users.
+                AttackPhase::Decrease => {
+                    self.update_duration();
+                    self.decrease_attack(&mut goose_attack_run_state).await?;
+                }
+                // In the Paused phase, GooseUser threads wait for a Controller to resume the
+                // load test.
+                AttackPhase::Paused => {
+                    // Wake up twice per second to check for Controller requests.
+                    goose_attack_run_state.drift_timer = util::sleep_minus_drift(
+                        time::Duration::from_millis(500),
+                        goose_attack_run_state.drift_timer,
+                    )
+                    .await;
                 }
                 // In the Stopping phase, Goose stops all GooseUser threads and optionally reports
                 // any collected metrics.
@@ -2093,8 +2491,14 @@ impl GooseAttack {
                     self.stop_attack().await?;
                     // Collect all metrics sent by GooseUser threads.
                     self.sync_metrics(&mut goose_attack_run_state, true).await?;
+                    // Record a final snapshot of requests made since the previous snapshot.
+                    if !self.metrics.snapshot_requests.is_empty() {
+                        self.record_snapshot(&mut goose_attack_run_state);
+                    }
                     // The load test is fully stopped at this point.
                     self.metrics.stopped = Some(Local::now());
+                    // Compare the final metrics against the baseline, if enabled.
+                    self.compare_baseline();
                     // Write an html report, if enabled.
                     self.write_html_report(&mut goose_attack_run_state).await?;
                     // Shutdown Goose or go into an idle waiting state.
@@ -2119,6 +2523,37 @@ impl GooseAttack {
             self.handle_controller_requests(&mut goose_attack_run_state)
                 .await?;
 
+            // Optionally stop the load test once a threshold can no longer be met. Metrics
+            // collected before all users started may still be reset, so wait until then.
+            if self.configuration.threshold_abort
+                && goose_attack_run_state.all_users_spawned
+                && matches!(
+                    self.attack_phase,
+                    AttackPhase::Running | AttackPhase::Increase | AttackPhase::Decrease
+                )
+            {
+                if let Some(threshold) = self.unmeetable_threshold() {
+                    info!(
+                        "stopping load test, threshold can no longer be met: {}",
+                        threshold
+                    );
+                    self.set_attack_phase(&mut goose_attack_run_state, AttackPhase::Stopping);
+                    self.metrics.stopping = Some(Local::now());
+                }
+            }
+
+            // Stop the load test once a feeder configured to stop runs out of records.
+            if matches!(
+                self.attack_phase,
+                AttackPhase::Increase | AttackPhase::Running | AttackPhase::Decrease
+            ) {
+                if let Some(feeder) = self.exhausted_feeder() {
+                    info!("stopping load test, feeder {} is out of records", feeder);
+                    self.set_attack_phase(&mut goose_attack_run_state, AttackPhase::Stopping);
+                    self.metrics.stopping = Some(Local::now());
+                }
+            }
+
             // Gracefully exit loop if ctrl-c is caught.
             if self.attack_phase != AttackPhase::Shutdown
                 && goose_attack_run_state.canceled.load(Ordering::SeqCst)
@@ -0,0 +1,495 @@
+//! Optional multi-stage load profiles.
+//!
+//! By default, Goose launches [`GooseUser`](../goose/struct.GooseUser.html)s at the
+//! configured `--hatch-rate` (or within the configured `--startup-time`) until `--users`
+//! are running, and then generates load for `--run-time`. A [`GooseLoadProfile`] instead
+//! describes a load test as a series of stages, each of which increases or decreases the
+//! number of running [`GooseUser`](../goose/struct.GooseUser.html)s to a target count and
+//! then holds that count until the stage ends.
+//!
+//! Load profiles are configured with the `--test-plan` run-time option, or with
+//! [`GooseDefault::TestPlan`](../config/enum.GooseDefault.html#variant.TestPlan).
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::util;
+use crate::{AttackPhase, GooseAttack, GooseAttackRunState, GooseError};
+
+/// A single stage of a [`GooseLoadProfile`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GooseLoadProfileStage {
+    /// How many [`GooseUser`](../goose/struct.GooseUser.html)s should be running when
+    /// the stage finishes ramping.
+    pub users: usize,
+    /// How long (in seconds) the stage lasts, including the time spent ramping.
+    pub duration: usize,
+    /// An optional number of [`GooseUser`](../goose/struct.GooseUser.html)s to launch
+    /// (or stop) per second. If not set, the ramp is spread evenly across the entire
+    /// `duration` of the stage.
+    pub ramp_rate: Option<f32>,
+}
+impl GooseLoadProfileStage {
+    /// Returns how many milliseconds to wait between launching (or stopping) each
+    /// [`GooseUser`](../goose/struct.GooseUser.html) when changing from `current_users`
+    /// to the target number of users for this stage. Returns 0 if all users should be
+    /// launched (or stopped) at once.
+    pub(crate) fn ramp_interval_ms(&self, current_users: usize) -> usize {
+        let difference = self.users.abs_diff(current_users);
+
+        let ramp_rate = match self.ramp_rate {
+            Some(r) => r,
+            // A stage with no duration is a spike, all users change at once.
+            None if self.duration == 0 || difference == 0 => return 0,
+            None => difference as f32 / self.duration as f32,
+        };
+
+        (1_000.0 / ramp_rate) as usize
+    }
+}
+
+/// A load test defined as a series of [`GooseLoadProfileStage`]s.
+///
+/// A load profile is expressed as a semicolon-separated list of stages, where each
+/// stage is defined as `users,duration` or `users,duration,ramp_rate`. For example,
+/// `10,30s;100,5m;0,1m` ramps up to 10 users over 30 seconds, then up to 100 users
+/// over 5 minutes, and finally back down to 0 users over 1 minute. A stage that
+/// doesn't change the number of users holds the current load, so `100,1m;100,1h;0,1m`
+/// ramps to 100 users, holds for an hour, then ramps back down. A `ramp_rate` instead
+/// launches (or stops) the given number of users per second and then holds the target
+/// until the stage ends, so `1000,2m,1000` simulates a spike of 1,000 users for two
+/// minutes.
+///
+/// When ramping down, [`GooseUser`](../goose/struct.GooseUser.html)s are stopped one
+/// at a time, allowing each to run its `on_stop` tasks.
+///
+/// # Example
+/// ```rust
+/// use goose::load_profile::GooseLoadProfile;
+/// use std::str::FromStr;
+///
+/// let load_profile = GooseLoadProfile::from_str("10,30s;100,5m;0,1m").unwrap();
+/// assert_eq!(load_profile.stages.len(), 3);
+/// assert_eq!(load_profile.max_users(), 100);
+/// assert_eq!(load_profile.total_duration(), 390);
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GooseLoadProfile {
+    /// All stages of the load profile, in the order they run.
+    pub stages: Vec<GooseLoadProfileStage>,
+}
+impl GooseLoadProfile {
+    /// The largest number of [`GooseUser`](../goose/struct.GooseUser.html)s running at
+    /// once during the load profile.
+    pub fn max_users(&self) -> usize {
+        self.stages.iter().map(|s| s.users).max().unwrap_or(0)
+    }
+
+    /// How long (in seconds) it takes to run all stages of the load profile.
+    pub fn total_duration(&self) -> usize {
+        self.stages.iter().map(|s| s.duration).sum()
+    }
+}
+/// Allow `--test-plan` from the command line by implementing [`FromStr`].
+impl FromStr for GooseLoadProfile {
+    type Err = GooseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Validate the duration with the same time span format as `--run-time`.
+        let timespan = Regex::new(r"^(\d+|((\d+?)h)?((\d+?)m)?((\d+?)s)?)$")
+            .expect("failed to compile timespan Regex");
+
+        let invalid = |detail: String| GooseError::InvalidOption {
+            option: "--test-plan".to_string(),
+            value: s.to_string(),
+            detail,
+        };
+
+        let mut stages = Vec::new();
+        for stage in s.split(';').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+            let values: Vec<&str> = stage.split(',').map(|v| v.trim()).collect();
+            if values.len() < 2 || values.len() > 3 {
+                return Err(invalid(format!(
+                    "Invalid stage \"{}\", expected: users,duration[,ramp_rate] (ie \"100,5m\").",
+                    stage
+                )));
+            }
+
+            let users = usize::from_str(values[0]).map_err(|_| {
+                invalid(format!(
+                    "Invalid users \"{}\" in stage \"{}\", expected an integer.",
+                    values[0], stage
+                ))
+            })?;
+
+            if values[1].is_empty() || !timespan.is_match(values[1]) {
+                return Err(invalid(format!(
+                    "Invalid duration \"{}\" in stage \"{}\", expected a time span (ie 30s, 20m, 3h, 1h30m).",
+                    values[1], stage
+                )));
+            }
+            let duration = util::parse_timespan(values[1]);
+
+            let ramp_rate = match values.get(2) {
+                Some(rate) => match f32::from_str(rate) {
+                    Ok(r) if r > 0.0 && r.is_finite() => Some(r),
+                    _ => {
+                        return Err(invalid(format!(
+                            "Invalid ramp_rate \"{}\" in stage \"{}\", expected users per second greater than 0.",
+                            rate, stage
+                        )))
+                    }
+                },
+                None => None,
+            };
+
+            stages.push(GooseLoadProfileStage {
+                users,
+                duration,
+                ramp_rate,
+            });
+        }
+
+        if stages.is_empty() {
+            return Err(invalid(
+                "A load profile requires at least one stage (ie \"10,30s;100,5m;0,1m\")."
+                    .to_string(),
+            ));
+        }
+
+        Ok(GooseLoadProfile { stages })
+    }
+}
+
+/// The parent process side of running a [`GooseLoadProfile`], and of ramping the number of
+/// running [`GooseUser`](../goose/struct.GooseUser.html)s up or down.
+impl GooseAttack {
+    // Start the specified stage of the configured load profile, entering the Increase,
+    // Decrease or Running phase depending on how many GooseUsers are already running.
+    pub(crate) async fn start_load_profile_stage(
+        &mut self,
+        goose_attack_run_state: &mut GooseAttackRunState,
+        stage_index: usize,
+    ) -> Result<(), GooseError> {
+        // Leaving the first stage, optionally flush and reset the metrics collected so far.
+        if stage_index > 0 && !goose_attack_run_state.all_users_spawned {
+            self.reset_metrics(goose_attack_run_state).await?;
+            self.metrics.started = Some(chrono::Local::now());
+        }
+
+        let stage = match self.configuration.test_plan.as_ref() {
+            Some(load_profile) => match load_profile.stages.get(stage_index) {
+                Some(s) => s.clone(),
+                None => {
+                    // All stages have completed, stop the load test.
+                    info!("load profile complete");
+                    self.set_attack_phase(goose_attack_run_state, AttackPhase::Stopping);
+                    self.metrics.stopping = Some(chrono::Local::now());
+                    return Ok(());
+                }
+            },
+            // A load profile must be configured to get here.
+            None => unreachable!(),
+        };
+
+        let running_users = goose_attack_run_state.user_channels.len();
+        info!(
+            "starting load profile stage {}: {} -> {} users over {} seconds",
+            stage_index + 1,
+            running_users,
+            stage.users,
+            stage.duration
+        );
+
+        goose_attack_run_state.load_profile_stage = stage_index;
+        goose_attack_run_state.load_profile_timer = std::time::Instant::now();
+        self.ramp_users(
+            goose_attack_run_state,
+            stage.users,
+            stage.ramp_interval_ms(running_users),
+        );
+
+        Ok(())
+    }
+
+    // Enter the Increase, Decrease or Running phase to ramp to the specified number of
+    // GooseUsers, launching or stopping one every `ramp_interval_ms` milliseconds.
+    pub(crate) fn ramp_users(
+        &mut self,
+        goose_attack_run_state: &mut GooseAttackRunState,
+        users: usize,
+        ramp_interval_ms: usize,
+    ) {
+        goose_attack_run_state.ramp_interval_ms = ramp_interval_ms;
+        // Launch (or stop) the first user immediately.
+        goose_attack_run_state.spawn_user_timer = std::time::Instant::now();
+        goose_attack_run_state.spawn_user_in_ms = 0;
+
+        let running_users = goose_attack_run_state.user_channels.len();
+        let phase = match users {
+            u if u > running_users => AttackPhase::Increase,
+            u if u < running_users => AttackPhase::Decrease,
+            _ => AttackPhase::Running,
+        };
+        self.set_attack_phase(goose_attack_run_state, phase);
+    }
+
+    // Returns true and starts the next load profile stage if the current stage has ended.
+    async fn load_profile_stage_expired(
+        &mut self,
+        goose_attack_run_state: &mut GooseAttackRunState,
+    ) -> Result<bool, GooseError> {
+        let stage_index = goose_attack_run_state.load_profile_stage;
+        // A load profile must be configured to get here, so unwrap() is safe.
+        let duration = self.configuration.test_plan.as_ref().unwrap().stages[stage_index].duration;
+        if goose_attack_run_state
+            .load_profile_timer
+            .elapsed()
+            .as_millis()
+            >= (duration * 1_000) as u128
+        {
+            self.start_load_profile_stage(goose_attack_run_state, stage_index + 1)
+                .await?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    // Returns true if the Increase or Decrease phase has ended early, because either the
+    // current load profile stage ended, or the run_time timer expired.
+    async fn ramp_expired(
+        &mut self,
+        goose_attack_run_state: &mut GooseAttackRunState,
+    ) -> Result<bool, GooseError> {
+        if self.configuration.test_plan.is_some() {
+            // Users are launched or stopped all at once if there's no ramp interval, even
+            // if the stage has no duration.
+            if goose_attack_run_state.ramp_interval_ms > 0 {
+                return self
+                    .load_profile_stage_expired(goose_attack_run_state)
+                    .await;
+            }
+        } else if util::timer_expired(self.started.unwrap(), self.run_time) {
+            self.set_attack_phase(goose_attack_run_state, AttackPhase::Stopping);
+            self.metrics.stopping = Some(chrono::Local::now());
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    // Returns the number of users being ramped to: the target of the current load profile
+    // stage if enabled, otherwise the configured number of users.
+    pub(crate) fn target_users(&self, goose_attack_run_state: &GooseAttackRunState) -> usize {
+        match self.configuration.test_plan.as_ref() {
+            Some(load_profile) => {
+                load_profile.stages[goose_attack_run_state.load_profile_stage].users
+            }
+            // Users are always configured without a load profile, so unwrap() is safe.
+            None => self.configuration.users.unwrap(),
+        }
+    }
+
+    // Sleep until it's time to launch or stop the next user, waking up at least twice
+    // a second to check on the load test and controller requests.
+    async fn load_profile_sleep(&self, goose_attack_run_state: &mut GooseAttackRunState) {
+        let elapsed = goose_attack_run_state
+            .spawn_user_timer
+            .elapsed()
+            .as_millis() as usize;
+        let remaining = goose_attack_run_state
+            .spawn_user_in_ms
+            .saturating_sub(elapsed)
+            .min(500);
+        if remaining > 0 {
+            debug!("sleeping {} milliseconds...", remaining);
+            tokio::time::sleep(tokio::time::Duration::from_millis(remaining as u64)).await;
+        }
+        goose_attack_run_state.drift_timer = tokio::time::Instant::now();
+    }
+
+    // In the Increase phase, launch GooseUsers at the configured rate until the target
+    // number of users is reached.
+    pub(crate) async fn increase_attack(
+        &mut self,
+        goose_attack_run_state: &mut GooseAttackRunState,
+    ) -> Result<(), GooseError> {
+        if self.ramp_expired(goose_attack_run_state).await? {
+            return Ok(());
+        }
+
+        if goose_attack_run_state.spawn_user_in_ms == 0
+            || util::ms_timer_expired(
+                goose_attack_run_state.spawn_user_timer,
+                goose_attack_run_state.spawn_user_in_ms,
+            )
+        {
+            goose_attack_run_state.spawn_user_timer = std::time::Instant::now();
+            goose_attack_run_state.spawn_user_in_ms = goose_attack_run_state.ramp_interval_ms;
+
+            let thread_user = self.next_weighted_user()?;
+            self.launch_user(goose_attack_run_state, thread_user);
+        } else {
+            self.load_profile_sleep(goose_attack_run_state).await;
+        }
+
+        // Hold the load once the target is reached.
+        if goose_attack_run_state.user_channels.len() >= self.target_users(goose_attack_run_state) {
+            // Pause a tenth of a second waiting for the final user to fully start up.
+            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+            info!(
+                "launched {} users...",
+                goose_attack_run_state.user_channels.len()
+            );
+
+            // The first time the load test is fully ramped up, optionally reset metrics.
+            if !goose_attack_run_state.all_users_spawned {
+                self.reset_metrics(goose_attack_run_state).await?;
+                self.metrics.started = Some(chrono::Local::now());
+            }
+            self.set_attack_phase(goose_attack_run_state, AttackPhase::Running);
+        }
+
+        Ok(())
+    }
+
+    // In the Decrease phase, gracefully stop GooseUsers one at a time at the configured
+    // rate until the target number of users is reached.
+    pub(crate) async fn decrease_attack(
+        &mut self,
+        goose_attack_run_state: &mut GooseAttackRunState,
+    ) -> Result<(), GooseError> {
+        if self.ramp_expired(goose_attack_run_state).await? {
+            return Ok(());
+        }
+
+        if goose_attack_run_state.spawn_user_in_ms == 0
+            || util::ms_timer_expired(
+                goose_attack_run_state.spawn_user_timer,
+                goose_attack_run_state.spawn_user_in_ms,
+            )
+        {
+            goose_attack_run_state.spawn_user_timer = std::time::Instant::now();
+            goose_attack_run_state.spawn_user_in_ms = goose_attack_run_state.ramp_interval_ms;
+
+            self.stop_user(goose_attack_run_state);
+        } else {
+            self.load_profile_sleep(goose_attack_run_state).await;
+        }
+
+        // Hold the load once the target is reached.
+        if goose_attack_run_state.user_channels.len() <= self.target_users(goose_attack_run_state) {
+            info!(
+                "decreased to {} users...",
+                goose_attack_run_state.user_channels.len()
+            );
+            self.set_attack_phase(goose_attack_run_state, AttackPhase::Running);
+        }
+
+        Ok(())
+    }
+
+    // In the Running phase, hold the current load until the load profile stage ends.
+    pub(crate) async fn monitor_load_profile(
+        &mut self,
+        goose_attack_run_state: &mut GooseAttackRunState,
+    ) -> Result<(), GooseError> {
+        if !self
+            .load_profile_stage_expired(goose_attack_run_state)
+            .await?
+        {
+            // Subtract the time spent doing other things, running the main parent loop twice
+            // per second.
+            goose_attack_run_state.drift_timer = util::sleep_minus_drift(
+                std::time::Duration::from_millis(500),
+                goose_attack_run_state.drift_timer,
+            )
+            .await;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_load_profile() {
+        let load_profile = GooseLoadProfile::from_str("10,30s;100,5m;0,1m").unwrap();
+        assert_eq!(
+            load_profile.stages,
+            vec![
+                GooseLoadProfileStage {
+                    users: 10,
+                    duration: 30,
+                    ramp_rate: None,
+                },
+                GooseLoadProfileStage {
+                    users: 100,
+                    duration: 300,
+                    ramp_rate: None,
+                },
+                GooseLoadProfileStage {
+                    users: 0,
+                    duration: 60,
+                    ramp_rate: None,
+                },
+            ]
+        );
+        assert_eq!(load_profile.max_users(), 100);
+        assert_eq!(load_profile.total_duration(), 390);
+
+        // Whitespace, a trailing semicolon, integer durations and ramp rates are allowed.
+        let load_profile = GooseLoadProfile::from_str(" 5, 10 ; 50,1h2m3s,2.5; ").unwrap();
+        assert_eq!(load_profile.stages.len(), 2);
+        assert_eq!(load_profile.stages[0].duration, 10);
+        assert_eq!(load_profile.stages[1].duration, 3_723);
+        assert_eq!(load_profile.stages[1].ramp_rate, Some(2.5));
+
+        // Invalid load profiles.
+        assert!(GooseLoadProfile::from_str("").is_err());
+        assert!(GooseLoadProfile::from_str("10").is_err());
+        assert!(GooseLoadProfile::from_str("10,30s,1,1").is_err());
+        assert!(GooseLoadProfile::from_str("ten,30s").is_err());
+        assert!(GooseLoadProfile::from_str("-10,30s").is_err());
+        assert!(GooseLoadProfile::from_str("10,30d").is_err());
+        assert!(GooseLoadProfile::from_str("10,").is_err());
+        assert!(GooseLoadProfile::from_str("10,30s,0").is_err());
+        assert!(GooseLoadProfile::from_str("10,30s,fast").is_err());
+    }
+
+    #[test]
+    fn ramp_interval() {
+        // Ramp 10 users over 30 seconds: one user every 3 seconds.
+        let stage = GooseLoadProfileStage {
+            users: 10,
+            duration: 30,
+            ramp_rate: None,
+        };
+        assert_eq!(stage.ramp_interval_ms(0), 3_000);
+        // Ramping down from 20 users takes the same amount of time.
+        assert_eq!(stage.ramp_interval_ms(20), 3_000);
+        // Holding the load doesn't launch or stop users.
+        assert_eq!(stage.ramp_interval_ms(10), 0);
+
+        // An explicit ramp rate is used as is: 4 users per second.
+        let stage = GooseLoadProfileStage {
+            users: 10,
+            duration: 30,
+            ramp_rate: Some(4.0),
+        };
+        assert_eq!(stage.ramp_interval_ms(0), 250);
+
+        // A stage without a duration launches all users at once.
+        let stage = GooseLoadProfileStage {
+            users: 100,
+            duration: 0,
+            ramp_rate: None,
+        };
+        assert_eq!(stage.ramp_interval_ms(0), 0);
+    }
+}
@@ -228,7 +228,7 @@ fn error_csv_header() -> String {
 fn requests_csv_header() -> String {
     // No quotes needed in header.
     format!(
-        "{},{},{},{},{},{},{},{},{},{},{},{},{}",
+        "{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
         "elapsed",
         "raw",
         "name",
@@ -236,6 +236,7 @@ fn requests_csv_header() -> String {
         "redirected",
         "response_time",
         "status_code",
+        "grpc_status_code",
         "success",
         "update",
         "user",
@@ -337,6 +338,23 @@ impl GooseLogger<GooseErrorMetric> for GooseConfiguration {
         )
     }
 }
+/// Debug output of a GooseRequestMetric structure, leaving out the id that only identifies
+/// the request within this process. The id is the first field.
+fn debug_request(request: &GooseRequestMetric, pretty: bool) -> String {
+    if pretty {
+        format!("{:#?}", request).replacen(
+            &format!("GooseRequestMetric {{\n    id: {},\n", request.id),
+            "GooseRequestMetric {\n",
+            1,
+        )
+    } else {
+        format!("{:?}", request).replacen(
+            &format!("GooseRequestMetric {{ id: {}, ", request.id),
+            "GooseRequestMetric { ",
+            1,
+        )
+    }
+}
 /// Traits for GooseRequestMetric logs.
 impl GooseLogger<GooseRequestMetric> for GooseConfiguration {
     /// Converts a GooseRequestMetric structure to a formatted string.
@@ -346,9 +364,9 @@ impl GooseLogger<GooseRequestMetric> for GooseConfiguration {
                 // Use serde_json to create JSON.
                 GooseLogFormat::Json => json!(message).to_string(),
                 // Raw format is Debug output for GooseRequestMetric structure.
-                GooseLogFormat::Raw => format!("{:?}", message),
+                GooseLogFormat::Raw => debug_request(&message, false),
                 // Pretty format is Debug Pretty output for GooseRequestMetric structure.
-                GooseLogFormat::Pretty => format!("{:#?}", message),
+                GooseLogFormat::Pretty => debug_request(&message, true),
                 // Not yet implemented.
                 GooseLogFormat::Csv => self.prepare_csv(&message),
             }
@@ -362,7 +380,7 @@ impl GooseLogger<GooseRequestMetric> for GooseConfiguration {
     fn prepare_csv(&self, request: &GooseRequestMetric) -> String {
         format!(
             // Put quotes around name, url and final_url as they are strings.
-            "{},\"{:?}\",\"{}\",\"{}\",{},{},{},{},{},{},{},{},{}",
+            "{},\"{:?}\",\"{}\",\"{}\",{},{},{},{},{},{},{},{},{},{}",
             request.elapsed,
             request.raw,
             request.name,
@@ -370,6 +388,9 @@ impl GooseLogger<GooseRequestMetric> for GooseConfiguration {
             request.redirected,
             request.response_time,
             request.status_code,
+            request
+                .grpc_status_code
+                .map_or_else(String::new, |code| code.to_string()),
             request.success,
             request.update,
             request.user,
@@ -812,3 +833,24 @@ impl GooseConfiguration {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::goose::GooseMethod;
+    use crate::metrics::GooseRawRequest;
+
+    #[test]
+    fn request_id_not_logged() {
+        let raw_request = GooseRawRequest::new(GooseMethod::Get, "/", vec![], "");
+        let request = GooseRequestMetric::new(raw_request, "/", 0, 0);
+        assert!(format!("{:?}", request).contains("id: "));
+
+        let raw = debug_request(&request, false);
+        assert!(raw.starts_with("GooseRequestMetric { elapsed: 0, "));
+        assert!(!raw.contains(" id: "));
+        let pretty = debug_request(&request, true);
+        assert!(pretty.starts_with("GooseRequestMetric {\n    elapsed: 0,\n"));
+        assert!(!pretty.contains(" id: "));
+    }
+}
@@ -8,8 +8,9 @@ use std::sync::Arc;
 use std::time;
 
 use crate::metrics::{
-    self, GooseErrorMetricAggregate, GooseErrorMetrics, GooseRequestMetricAggregate,
-    GooseRequestMetrics, GooseTaskMetricAggregate, GooseTaskMetrics,
+    self, GooseCustomMetricAggregate, GooseCustomMetrics, GooseErrorMetricAggregate,
+    GooseErrorMetrics, GooseRequestMetricAggregate, GooseRequestMetrics, GooseTaskMetricAggregate,
+    GooseTaskMetrics, GooseTransactionMetrics,
 };
 use crate::util;
 use crate::worker::GaggleMetrics;
@@ -31,6 +32,10 @@ pub struct GooseUserInitializer {
     pub run_time: usize,
     /// Numerical identifier for worker.
     pub worker_id: usize,
+    /// Which partition of feeder records the Worker hands out, numbered from 0.
+    pub feeder_partition: usize,
+    /// How many partitions feeder records are split into, one per Worker.
+    pub feeder_partitions: usize,
 }
 
 // Mutable singleton globally tracking how many workers are currently being managed.
@@ -76,8 +81,8 @@ fn merge_tasks_from_worker(
 ) -> GooseTaskMetricAggregate {
     // Make a mutable copy where we can merge things
     let mut merged_task = parent_task.clone();
-    // Iterate over user times, and merge into global time
-    merged_task.times = metrics::merge_times(merged_task.times, user_task.times.clone());
+    // Merge user times into global times.
+    merged_task.times.add(&user_task.times);
     // Increment total task time counter.
     merged_task.total_time += &user_task.total_time;
     // Increment count of how many task counters we've seen.
@@ -93,6 +98,32 @@ fn merge_tasks_from_worker(
     merged_task
 }
 
+/// Merge custom metrics from a Worker into global parent metrics.
+fn merge_custom_from_worker(
+    parent_custom: &GooseCustomMetricAggregate,
+    worker_custom: &GooseCustomMetricAggregate,
+) -> GooseCustomMetricAggregate {
+    // Make a mutable copy where we can merge things
+    let mut merged_custom = parent_custom.clone();
+    // Values of another kind are ignored.
+    if worker_custom.kind != merged_custom.kind || worker_custom.samples == 0 {
+        return merged_custom;
+    }
+    // If the Worker saw a new smallest or largest value, update them.
+    if merged_custom.samples == 0 || worker_custom.minimum < merged_custom.minimum {
+        merged_custom.minimum = worker_custom.minimum;
+    }
+    if merged_custom.samples == 0 || worker_custom.maximum > merged_custom.maximum {
+        merged_custom.maximum = worker_custom.maximum;
+    }
+    // Merge Worker times into global times.
+    merged_custom.times.add(&worker_custom.times);
+    merged_custom.total += worker_custom.total;
+    merged_custom.last = worker_custom.last;
+    merged_custom.samples += worker_custom.samples;
+    merged_custom
+}
+
 /// Merge per-user request metrics from user thread into global parent metrics
 fn merge_requests_from_worker(
     parent_request: &GooseRequestMetricAggregate,
@@ -101,11 +132,11 @@ fn merge_requests_from_worker(
 ) -> GooseRequestMetricAggregate {
     // Make a mutable copy where we can merge things
     let mut merged_request = parent_request.clone();
-    // Iterate over user response times, and merge into global response time
-    merged_request.raw_data.times = metrics::merge_times(
-        merged_request.raw_data.times,
-        user_request.raw_data.times.clone(),
-    );
+    // Merge user response times into global response times.
+    merged_request
+        .raw_data
+        .times
+        .add(&user_request.raw_data.times);
     // Increment total response time counter.
     merged_request.raw_data.total_time += &user_request.raw_data.total_time;
     // Increment count of how many response counters we've seen.
@@ -142,6 +173,12 @@ fn merge_requests_from_worker(
                 .status_code_counts
                 .insert(*status_code, new_count);
         }
+        for (grpc_status_code, count) in &user_request.grpc_status_code_counts {
+            *merged_request
+                .grpc_status_code_counts
+                .entry(*grpc_status_code)
+                .or_insert(0) += count;
+        }
     }
     merged_request
 }
@@ -193,6 +230,23 @@ fn merge_request_metrics(goose_attack: &mut GooseAttack, requests: GooseRequestM
         debug!("requests metrics received: {:?}", requests.len());
         for (request_key, request) in requests {
             trace!("request_key: {}", request_key);
+            // If recording snapshots, also merge into requests made since the previous
+            // snapshot.
+            if goose_attack.configuration.snapshot_interval.is_some() {
+                let merged_request = match goose_attack.metrics.snapshot_requests.get(&request_key)
+                {
+                    Some(parent_request) => merge_requests_from_worker(
+                        parent_request,
+                        &request,
+                        goose_attack.configuration.status_codes,
+                    ),
+                    None => request.clone(),
+                };
+                goose_attack
+                    .metrics
+                    .snapshot_requests
+                    .insert(request_key.to_string(), merged_request);
+            }
             let merged_request;
             if let Some(parent_request) = goose_attack.metrics.requests.get(&request_key) {
                 merged_request = merge_requests_from_worker(
@@ -225,6 +279,39 @@ fn merge_task_metrics(goose_attack: &mut GooseAttack, tasks: GooseTaskMetrics) {
     }
 }
 
+/// Helper to merge in transaction metrics from Worker.
+fn merge_transaction_metrics(
+    goose_attack: &mut GooseAttack,
+    transactions: GooseTransactionMetrics,
+) {
+    for (name, transaction) in transactions {
+        match goose_attack.metrics.transactions.get_mut(&name) {
+            Some(parent_transaction) => parent_transaction.merge(&transaction),
+            // First time seeing this transaction, simply insert it.
+            None => {
+                goose_attack.metrics.transactions.insert(name, transaction);
+            }
+        }
+    }
+}
+
+/// Helper to merge in custom metrics from Worker.
+fn merge_custom_metrics(goose_attack: &mut GooseAttack, custom: GooseCustomMetrics) {
+    for (name, custom_metric) in custom {
+        let merged_custom_metric;
+        if let Some(parent_custom_metric) = goose_attack.metrics.custom.get(&name) {
+            merged_custom_metric = merge_custom_from_worker(parent_custom_metric, &custom_metric);
+        } else {
+            // First time seeing this custom metric, simply insert it.
+            merged_custom_metric = custom_metric;
+        }
+        goose_attack
+            .metrics
+            .custom
+            .insert(name, merged_custom_metric);
+    }
+}
+
 /// Helper to merge in errors from the Worker.
 fn merge_error_metrics(goose_attack: &mut GooseAttack, errors: GooseErrorMetrics) {
     if !errors.is_empty() {
@@ -292,6 +379,7 @@ pub(crate) async fn manager_main(mut goose_attack: GooseAttack) -> GooseAttack {
     let mut started = time::Instant::now();
     goose_attack.started = Some(started);
     let mut running_metrics_timer = time::Instant::now();
+    let mut snapshot_timer = time::Instant::now();
     let mut exit_timer = time::Instant::now();
     let mut load_test_running = false;
     let mut load_test_finished = false;
@@ -372,6 +460,15 @@ pub(crate) async fn manager_main(mut goose_attack: GooseAttack) -> GooseAttack {
                     goose_attack.metrics.print_running();
                 }
             }
+
+            // Record a snapshot of metrics merged from Workers, if enabled.
+            if let Some(snapshot_interval) = goose_attack.configuration.snapshot_interval {
+                if !load_test_finished && util::timer_expired(snapshot_timer, snapshot_interval) {
+                    let interval = snapshot_timer.elapsed().as_secs_f32();
+                    snapshot_timer = time::Instant::now();
+                    goose_attack.metrics.record_snapshot(interval);
+                }
+            }
         } else if canceled.load(Ordering::SeqCst) {
             info!("load test canceled, exiting");
             std::process::exit(1);
@@ -473,6 +570,13 @@ pub(crate) async fn manager_main(mut goose_attack: GooseAttack) -> GooseAttack {
                                 config: user.config.clone(),
                                 run_time: goose_attack.run_time,
                                 worker_id: workers.len(),
+                                // Each Worker hands out a different partition of feeder
+                                // records.
+                                feeder_partition: workers.len() - 1,
+                                feeder_partitions: goose_attack
+                                    .configuration
+                                    .expect_workers
+                                    .unwrap(),
                             });
                         }
 
@@ -505,6 +609,7 @@ pub(crate) async fn manager_main(mut goose_attack: GooseAttack) -> GooseAttack {
                             started = time::Instant::now();
                             goose_attack.started = Some(started);
                             running_metrics_timer = time::Instant::now();
+                            snapshot_timer = time::Instant::now();
                             load_test_running = true;
 
                             // Run any configured test_start() functions.
@@ -545,6 +650,14 @@ pub(crate) async fn manager_main(mut goose_attack: GooseAttack) -> GooseAttack {
                             GaggleMetrics::Errors(errors) => {
                                 merge_error_metrics(&mut goose_attack, errors)
                             }
+                            // Merge in transaction metrics from Worker.
+                            GaggleMetrics::Transactions(transactions) => {
+                                merge_transaction_metrics(&mut goose_attack, transactions)
+                            }
+                            // Merge in custom metrics from Worker.
+                            GaggleMetrics::Custom(custom) => {
+                                merge_custom_metrics(&mut goose_attack, custom)
+                            }
                             // Ignore Worker heartbeats.
                             GaggleMetrics::WorkerInit(_) => (),
                         }
@@ -585,6 +698,13 @@ pub(crate) async fn manager_main(mut goose_attack: GooseAttack) -> GooseAttack {
             }
         }
     }
+    // Record a final snapshot of requests made since the previous snapshot.
+    if !goose_attack.metrics.snapshot_requests.is_empty() {
+        goose_attack
+            .metrics
+            .record_snapshot(snapshot_timer.elapsed().as_secs_f32());
+    }
+
     // Run any configured test_stop() functions.
     goose_attack.run_test_stop().await.unwrap();
 
@@ -9,26 +9,33 @@
 //! [`GooseErrorMetrics`] are displayed in tables.
 
 use chrono::prelude::*;
+use hdrhistogram::Histogram;
 use http::StatusCode;
 use itertools::Itertools;
 use num_format::{Locale, ToFormattedString};
 use regex::RegexSet;
 use serde::ser::SerializeStruct;
-use serde::{Deserialize, Serialize, Serializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::cmp::Ordering;
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+use std::time::Duration;
 use std::{f32, fmt};
 use tokio::io::AsyncWriteExt;
 
+use crate::baseline::{GooseBaselineComparison, GooseBaselineDelta};
 use crate::config::GooseDefaults;
 use crate::goose::{get_base_url, GooseMethod, GooseTaskSet};
 use crate::logger::GooseLog;
 use crate::report;
+use crate::threshold::GooseThresholdResult;
 use crate::util;
 #[cfg(feature = "gaggle")]
 use crate::worker::{self, GaggleMetrics};
-use crate::{AttackMode, GooseAttack, GooseAttackRunState, GooseConfiguration, GooseError};
+use crate::{
+    AttackMode, AttackPhase, GooseAttack, GooseAttackRunState, GooseConfiguration, GooseError,
+};
 
 /// Used to send metrics from [`GooseUser`](../goose/struct.GooseUser.html) threads
 /// to the parent Goose process.
@@ -40,13 +47,17 @@ use crate::{AttackMode, GooseAttack, GooseAttackRunState, GooseConfiguration, Go
 /// The parent process will spend up to 80% of its time receiving and aggregating
 /// these metrics. The parent process aggregates [`GooseRequestMetric`]s into
 /// [`GooseRequestMetricAggregate`], [`GooseTaskMetric`]s into [`GooseTaskMetricAggregate`],
-/// and [`GooseErrorMetric`]s into [`GooseErrorMetricAggregate`]. Aggregation happens in the
+/// [`GooseTransactionMetric`]s into [`GooseTransactionMetricAggregate`],
+/// [`GooseCustomMetric`]s into [`GooseCustomMetricAggregate`], and
+/// [`GooseErrorMetric`]s into [`GooseErrorMetricAggregate`]. Aggregation happens in the
 /// parent process so the individual [`GooseUser`](../goose/struct.GooseUser.html) threads
 /// can spend all their time generating and validating load.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum GooseMetric {
     Request(GooseRequestMetric),
     Task(GooseTaskMetric),
+    Transaction(GooseTransactionMetric),
+    Custom(GooseCustomMetric),
 }
 
 /// THIS IS AN EXPERIMENTAL FEATURE, DISABLED BY DEFAULT. Optionally mitigate the loss of data
@@ -226,6 +237,67 @@ pub type GooseRequestMetrics = HashMap<String, GooseRequestMetricAggregate>;
 /// ```
 pub type GooseTaskMetrics = Vec<Vec<GooseTaskMetricAggregate>>;
 
+/// All transactions completed during a load test.
+///
+/// Goose tracks metrics about each transaction ended by a
+/// [`GooseUser`](../goose/struct.GooseUser.html), unless metrics are disabled with the
+/// `--no-metrics` run-time option, or with
+/// [`GooseDefault::NoMetrics`](../config/enum.GooseDefault.html#variant.NoMetrics).
+///
+/// Aggregated transactions ([`GooseTransactionMetricAggregate`]) are stored in a
+/// `BTreeMap` keyed by the name of the transaction.
+///
+/// # Example
+/// When viewed with [`std::fmt::Display`], [`GooseTransactionMetrics`] are displayed in
+/// a table:
+/// ```text
+///  === PER TRANSACTION METRICS ===
+/// ------------------------------------------------------------------------------
+/// Name                     |   # times run |        # fails |  trans/s |  fail/s
+/// ------------------------------------------------------------------------------
+/// checkout                 |            93 |         2 (2%) |     9.30 |    0.20
+/// login                    |           100 |         0 (0%) |    10.00 |    0.00
+/// -------------------------+---------------+----------------+----------+--------
+/// Aggregated               |           193 |         2 (1%) |    19.30 |    0.20
+/// ------------------------------------------------------------------------------
+/// Name                     |    Avg (ms) |        Min |         Max |     Median
+/// ------------------------------------------------------------------------------
+/// checkout                 |      412.86 |        301 |         845 |        400
+/// login                    |      120.31 |         87 |         219 |        120
+/// -------------------------+-------------+------------+-------------+-----------
+/// Aggregated               |      261.06 |         87 |         845 |        210
+/// ```
+pub type GooseTransactionMetrics = BTreeMap<String, GooseTransactionMetricAggregate>;
+
+/// All custom metrics recorded during a load test.
+///
+/// Tasks can record their own counters, gauges and timings, for example how many items
+/// are in a cart or how long a job took to complete. Each is stored as a
+/// [`GooseCustomMetricAggregate`], and they are all stored together within a `BTreeMap`
+/// keyed by name. Custom metrics are recorded unless metrics are disabled with the
+/// `--no-metrics` run-time option, or with
+/// [`GooseDefault::NoMetrics`](../config/enum.GooseDefault.html#variant.NoMetrics).
+///
+/// # Example
+/// When viewed with [`std::fmt::Display`], [`GooseCustomMetrics`] are displayed in
+/// a table for each kind:
+/// ```text
+///  === CUSTOM METRICS ===
+/// ------------------------------------------------------------------------------
+/// Counter                  |         Total |        per second
+/// ------------------------------------------------------------------------------
+/// items added              |           412 |             41.20
+/// ------------------------------------------------------------------------------
+/// Gauge                    |        Last |        Min |     Average |        Max
+/// ------------------------------------------------------------------------------
+/// queue depth              |       12.00 |       0.00 |        6.25 |      18.00
+/// ------------------------------------------------------------------------------
+/// Timing                   |    Avg (ms) |        Min |         Max |     Median
+/// ------------------------------------------------------------------------------
+/// job completed            |      812.40 |        350 |       1,902 |        790
+/// ```
+pub type GooseCustomMetrics = BTreeMap<String, GooseCustomMetricAggregate>;
+
 /// All errors detected during a load test.
 ///
 /// By default Goose tracks all errors detected during the load test. Each error is stored
@@ -291,6 +363,11 @@ impl GooseRawRequest {
 /// knows which request is being updated.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GooseRequestMetric {
+    /// Identifies the request within this process, so an update can be matched with the
+    /// request it updates. Left out of the request log, and not sent to the Manager of a
+    /// Gaggle.
+    #[serde(skip)]
+    pub(crate) id: u64,
     /// How many milliseconds the load test has been running.
     pub elapsed: u64,
     /// The raw request that the GooseClient made.
@@ -303,8 +380,12 @@ pub struct GooseRequestMetric {
     pub redirected: bool,
     /// How many milliseconds the request took.
     pub response_time: u64,
+    /// How many microseconds the request took, used to calculate response time percentiles.
+    pub response_time_micros: u64,
     /// The HTTP response code (optional).
     pub status_code: u16,
+    /// The status code returned by a gRPC call, which has no HTTP response code.
+    pub grpc_status_code: Option<u16>,
     /// Whether or not the request was successful.
     pub success: bool,
     /// Whether or not we're updating a previous request, modifies how the parent thread records it.
@@ -321,16 +402,25 @@ pub struct GooseRequestMetric {
     /// [`GooseUser`](../goose/struct.GooseUser.html) thread.
     pub user_cadence: u64,
 }
+/// The id of the next request, unique within this process.
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(0);
+/// The id the next request will have. Requests made earlier have lower ids.
+pub(crate) fn next_request_id() -> u64 {
+    NEXT_REQUEST_ID.load(AtomicOrdering::SeqCst)
+}
 impl GooseRequestMetric {
     pub(crate) fn new(raw: GooseRawRequest, name: &str, elapsed: u128, user: usize) -> Self {
         GooseRequestMetric {
+            id: NEXT_REQUEST_ID.fetch_add(1, AtomicOrdering::SeqCst),
             elapsed: elapsed as u64,
             raw,
             name: name.to_string(),
             final_url: "".to_string(),
             redirected: false,
             response_time: 0,
+            response_time_micros: 0,
             status_code: 0,
+            grpc_status_code: None,
             success: true,
             update: false,
             user,
@@ -349,8 +439,9 @@ impl GooseRequestMetric {
     }
 
     // Record how long the `response_time` took.
-    pub(crate) fn set_response_time(&mut self, response_time: u128) {
-        self.response_time = response_time as u64;
+    pub(crate) fn set_response_time(&mut self, response_time: Duration) {
+        self.response_time = response_time.as_millis() as u64;
+        self.response_time_micros = response_time.as_micros() as u64;
     }
 
     // Record the returned `status_code`.
@@ -383,6 +474,9 @@ pub struct GooseRequestMetricAggregate {
     pub coordinated_omission_data: Option<GooseRequestMetricTimingData>,
     /// Per-status-code counters, tracking how often each response code was returned for this request.
     pub status_code_counts: HashMap<u16, usize>,
+    /// Per-status-code counters, tracking how often each gRPC status code was returned for
+    /// this gRPC call.
+    pub grpc_status_code_counts: HashMap<u16, usize>,
     /// Total number of times this path-method request resulted in a successful (2xx) status code.
     ///
     /// A count of how many requests resulted in a 2xx status code.
@@ -407,12 +501,14 @@ impl GooseRequestMetricAggregate {
             raw_data: GooseRequestMetricTimingData::new(None),
             coordinated_omission_data: None,
             status_code_counts: HashMap::new(),
+            grpc_status_code_counts: HashMap::new(),
             success_count: 0,
             fail_count: 0,
             load_test_hash,
         }
     }
 
+    /// Record a new response time in microseconds.
     pub(crate) fn record_time(&mut self, time_elapsed: u64, coordinated_omission_mitigation: bool) {
         // Only add time_elapsed to raw_data if the time wasn't generated by Coordinated
         // Omission Mitigation.
@@ -434,6 +530,43 @@ impl GooseRequestMetricAggregate {
         }
     }
 
+    /// Record a [`GooseRequestMetric`] sent by a [`GooseUser`](../goose/struct.GooseUser.html).
+    pub(crate) fn record_request(
+        &mut self,
+        request_metric: &GooseRequestMetric,
+        status_codes: bool,
+    ) {
+        // Handle a metrics update.
+        if request_metric.update {
+            // The original request may have been recorded before metrics were reset.
+            if request_metric.success {
+                self.success_count += 1;
+                self.fail_count = self.fail_count.saturating_sub(1);
+            } else {
+                self.success_count = self.success_count.saturating_sub(1);
+                self.fail_count += 1;
+            }
+        }
+        // Store a new metric.
+        else {
+            self.record_time(
+                request_metric.response_time_micros,
+                request_metric.coordinated_omission_elapsed > 0,
+            );
+            if status_codes {
+                match request_metric.grpc_status_code {
+                    Some(grpc_status_code) => self.set_grpc_status_code(grpc_status_code),
+                    None => self.set_status_code(request_metric.status_code),
+                }
+            }
+            if request_metric.success {
+                self.success_count += 1;
+            } else {
+                self.fail_count += 1;
+            }
+        }
+    }
+
     /// Increment counter for status code, creating new counter if first time seeing status code.
     pub(crate) fn set_status_code(&mut self, status_code: u16) {
         let counter = match self.status_code_counts.get(&status_code) {
@@ -451,6 +584,14 @@ impl GooseRequestMetricAggregate {
         self.status_code_counts.insert(status_code, counter);
         debug!("incremented {} counter: {}", status_code, counter);
     }
+
+    /// Increment counter for gRPC status code.
+    pub(crate) fn set_grpc_status_code(&mut self, grpc_status_code: u16) {
+        *self
+            .grpc_status_code_counts
+            .entry(grpc_status_code)
+            .or_insert(0) += 1;
+    }
 }
 /// Implement ordering for GooseRequestMetricAggregate.
 impl Ord for GooseRequestMetricAggregate {
@@ -464,25 +605,117 @@ impl PartialOrd for GooseRequestMetricAggregate {
         Some(self.cmp(other))
     }
 }
+/// A bounded-error histogram of times, recorded in microseconds.
+///
+/// Times are stored in an [HDR histogram](http://hdrhistogram.org/) with 3 significant
+/// digits, so every percentile is accurate to within 0.1% no matter how slow the
+/// response, while memory use stays small and fixed. Histograms from each
+/// [`GooseUser`](../goose/struct.GooseUser.html), and in a Gaggle from each Worker, are
+/// merged without any further loss of precision.
+///
+/// When serialized, or displayed with `{:?}`, only the recorded times and how many times
+/// each was recorded are included.
+#[derive(Clone)]
+pub struct GooseHistogram(Histogram<u64>);
+impl GooseHistogram {
+    /// Create a new, empty histogram.
+    pub fn new() -> Self {
+        // An auto-resizing histogram with 3 significant digits is always valid.
+        GooseHistogram(Histogram::new(3).unwrap())
+    }
+
+    /// Record a time in microseconds.
+    pub(crate) fn record(&mut self, micros: u64) {
+        // The histogram grows as needed, only saturating if a time is too large to track.
+        if self.0.record(micros).is_err() {
+            self.0.saturating_record(micros);
+        }
+    }
+
+    /// Merge all times recorded in another histogram into this one.
+    pub fn add(&mut self, other: &GooseHistogram) {
+        // Auto-resizing histograms can always be added together.
+        self.0.add(&other.0).unwrap();
+    }
+
+    /// How many times have been recorded.
+    pub fn len(&self) -> u64 {
+        self.0.len()
+    }
+
+    /// Returns true if no times have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The time in microseconds that the given percentile (from 0 to 100) of all
+    /// recorded times completed within.
+    pub fn value_at_percentile(&self, percentile: f64) -> u64 {
+        self.0.value_at_percentile(percentile)
+    }
+
+    /// Iterate over each distinct recorded time in microseconds, along with how many
+    /// times it was recorded.
+    pub fn iter(&self) -> impl Iterator<Item = (u64, u64)> + '_ {
+        self.0
+            .iter_recorded()
+            .map(|v| (v.value_iterated_to(), v.count_at_value()))
+    }
+}
+impl Default for GooseHistogram {
+    fn default() -> Self {
+        GooseHistogram::new()
+    }
+}
+impl PartialEq for GooseHistogram {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+impl Eq for GooseHistogram {}
+impl fmt::Debug for GooseHistogram {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_map().entries(self.iter()).finish()
+    }
+}
+impl Serialize for GooseHistogram {
+    // Serialize as a compact list of [time, count] pairs instead of the full histogram.
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.iter())
+    }
+}
+impl<'de> Deserialize<'de> for GooseHistogram {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut histogram = GooseHistogram::new();
+        for (micros, count) in Vec::<(u64, u64)>::deserialize(deserializer)? {
+            if histogram.0.record_n(micros, count).is_err() {
+                histogram.0.saturating_record_n(micros, count);
+            }
+        }
+        Ok(histogram)
+    }
+}
+
 /// Collects per-request timing metrics.
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct GooseRequestMetricTimingData {
-    /// Per-response-time counters, tracking how often pages are returned with this response time.
-    ///
-    /// All response times between 1 and 100ms are stored without any rounding. Response times between
-    /// 100 and 500ms are rounded to the nearest 10ms and then stored. Response times betwee 500 and
-    /// 1000ms are rounded to the nearest 100ms. Response times larger than 1000ms are rounded to the
-    /// nearest 1000ms.
-    pub times: BTreeMap<usize, usize>,
+    /// All response times seen so far, in microseconds.
+    pub times: GooseHistogram,
     /// The shortest response time seen so far.
     ///
     /// For example a `min_response_time` of `3` means the quickest response for this method-path
-    /// pair returned in 3 milliseconds. This value is not rounded.
+    /// pair returned in 3 milliseconds. This value is rounded to the nearest millisecond.
     pub minimum_time: usize,
     /// The longest response time seen so far.
     ///
     /// For example a `max_response_time` of `2013` means the slowest response for this method-path
-    /// pair returned in 2013 milliseconds. This value is not rounded.
+    /// pair returned in 2013 milliseconds. This value is rounded to the nearest millisecond.
     pub maximum_time: usize,
     /// Total combined response times seen so far.
     ///
@@ -503,7 +736,7 @@ impl GooseRequestMetricTimingData {
         // Create a new empty metric_data.
         } else {
             GooseRequestMetricTimingData {
-                times: BTreeMap::new(),
+                times: GooseHistogram::new(),
                 minimum_time: 0,
                 maximum_time: 0,
                 total_time: 0,
@@ -512,10 +745,12 @@ impl GooseRequestMetricTimingData {
         }
     }
 
-    /// Record a new time.
+    /// Record a new time in microseconds.
     pub(crate) fn record_time(&mut self, time_elapsed: u64) {
-        // Perform this conversin only once, then re-use throughout this funciton.
-        let time = time_elapsed as usize;
+        // The histogram tracks microseconds, all other times are tracked in milliseconds
+        // rounded to the nearest millisecond.
+        self.times.record(time_elapsed);
+        let time = ((time_elapsed + 500) / 1_000) as usize;
 
         // Update minimum if this one is fastest yet.
         if time > 0 && (self.minimum_time == 0 || time < self.minimum_time) {
@@ -532,40 +767,6 @@ impl GooseRequestMetricTimingData {
 
         // Each time we store a new time, increment counter by one.
         self.counter += 1;
-
-        // Round the time so we can combine similar times together and
-        // minimize required memory to store and push upstream to the parent.
-        // No rounding for 1-100ms times.
-        let rounded_time = if time_elapsed < 100 {
-            time
-        }
-        // Round to nearest 10 for 100-500ms times.
-        else if time_elapsed < 500 {
-            ((time_elapsed as f64 / 10.0).round() * 10.0) as usize
-        }
-        // Round to nearest 100 for 500-1000ms times.
-        else if time_elapsed < 1000 {
-            ((time_elapsed as f64 / 100.0).round() * 100.0) as usize
-        }
-        // Round to nearest 1000 for all larger times.
-        else {
-            ((time_elapsed as f64 / 1000.0).round() * 1000.0) as usize
-        };
-
-        let counter = match self.times.get(&rounded_time) {
-            // We've seen this elapsed time before, increment counter.
-            Some(c) => {
-                debug!("got {:?} counter: {}", rounded_time, c);
-                *c + 1
-            }
-            // First time we've seen this elapsed time, initialize counter.
-            None => {
-                debug!("no match for counter: {}", rounded_time);
-                1
-            }
-        };
-        debug!("incremented {} counter: {}", rounded_time, counter);
-        self.times.insert(rounded_time, counter);
     }
 }
 
@@ -580,8 +781,10 @@ pub struct GooseTaskMetric {
     pub task_index: usize,
     /// The optional name of the task.
     pub name: String,
-    /// How long task ran.
+    /// How many milliseconds the task ran.
     pub run_time: u64,
+    /// How many microseconds the task ran, used to calculate run time percentiles.
+    pub run_time_micros: u64,
     /// Whether or not the request was successful.
     pub success: bool,
     /// Which GooseUser thread processed the request.
@@ -602,14 +805,16 @@ impl GooseTaskMetric {
             task_index,
             name,
             run_time: 0,
+            run_time_micros: 0,
             success: true,
             user,
         }
     }
 
     /// Update a GooseTaskMetric metric.
-    pub(crate) fn set_time(&mut self, time: u128, success: bool) {
-        self.run_time = time as u64;
+    pub(crate) fn set_time(&mut self, time: Duration, success: bool) {
+        self.run_time = time.as_millis() as u64;
+        self.run_time_micros = time.as_micros() as u64;
         self.success = success;
     }
 }
@@ -631,8 +836,8 @@ pub struct GooseTaskMetricAggregate {
     pub task_index: usize,
     /// An optional name for the task.
     pub task_name: String,
-    /// Per-run-time counters, tracking how often tasks take a given time to complete.
-    pub times: BTreeMap<usize, usize>,
+    /// All run-times for this task, in microseconds.
+    pub times: GooseHistogram,
     /// The shortest run-time for this task.
     pub min_time: usize,
     /// The longest run-time for this task.
@@ -659,7 +864,7 @@ impl GooseTaskMetricAggregate {
             taskset_name: taskset_name.to_string(),
             task_index,
             task_name: task_name.to_string(),
-            times: BTreeMap::new(),
+            times: GooseHistogram::new(),
             min_time: 0,
             max_time: 0,
             total_time: 0,
@@ -669,10 +874,12 @@ impl GooseTaskMetricAggregate {
         }
     }
 
-    /// Track task function elapsed time in milliseconds.
+    /// Track task function elapsed time in microseconds.
     pub(crate) fn set_time(&mut self, time: u64, success: bool) {
-        // Perform this conversion only once, then re-use throughout this function.
-        let time_usize = time as usize;
+        // The histogram tracks microseconds, all other times are tracked in milliseconds
+        // rounded to the nearest millisecond.
+        self.times.record(time);
+        let time_usize = ((time + 500) / 1_000) as usize;
 
         // Update minimum if this one is fastest yet.
         if self.min_time == 0 || time_usize < self.min_time {
@@ -695,28 +902,280 @@ impl GooseTaskMetricAggregate {
         } else {
             self.fail_count += 1;
         }
+    }
+}
 
-        // Round the time so we can combine similar times together and
-        // minimize required memory to store and push upstream to the parent.
-        let rounded_time = match time {
-            // No rounding for times 0-100 ms.
-            0..=100 => time_usize,
-            // Round to nearest 10 for times 100-500 ms.
-            101..=500 => ((time as f64 / 10.0).round() * 10.0) as usize,
-            // Round to nearest 100 for times 500-1000 ms.
-            501..=1000 => ((time as f64 / 100.0).round() * 10.0) as usize,
-            // Round to nearest 1000 for larger times.
-            _ => ((time as f64 / 1000.0).round() * 10.0) as usize,
-        };
+/// The metrics collected each time a transaction ends.
+///
+/// A transaction times an arbitrary group of requests, see
+/// [`GooseUser::begin_transaction`](../goose/struct.GooseUser.html#method.begin_transaction).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GooseTransactionMetric {
+    /// How many milliseconds the load test had been running when the transaction began.
+    pub elapsed: u64,
+    /// The name of the transaction.
+    pub name: String,
+    /// How many milliseconds the transaction ran.
+    pub run_time: u64,
+    /// How many microseconds the transaction ran, used to calculate run time percentiles.
+    pub run_time_micros: u64,
+    /// Whether or not all requests in the transaction were successful.
+    pub success: bool,
+    /// Which GooseUser thread ran the transaction.
+    pub user: usize,
+}
+impl GooseTransactionMetric {
+    /// Create a new GooseTransactionMetric metric.
+    pub(crate) fn new(elapsed: u128, name: &str, user: usize) -> Self {
+        GooseTransactionMetric {
+            elapsed: elapsed as u64,
+            name: name.to_string(),
+            run_time: 0,
+            run_time_micros: 0,
+            success: true,
+            user,
+        }
+    }
+
+    /// Update a GooseTransactionMetric metric.
+    pub(crate) fn set_time(&mut self, time: Duration, success: bool) {
+        self.run_time = time.as_millis() as u64;
+        self.run_time_micros = time.as_micros() as u64;
+        self.success = success;
+    }
+}
+
+/// Aggregated per-transaction metrics updated each time a transaction ends.
+///
+/// [`GooseTransactionMetric`]s are sent by [`GooseUser`](../goose/struct.GooseUser.html)
+/// threads to the Goose parent process where they are aggregated together into this
+/// structure, and stored in [`GooseMetrics::transactions`].
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct GooseTransactionMetricAggregate {
+    /// The name of the transaction.
+    pub name: String,
+    /// All run-times for this transaction, in microseconds.
+    pub times: GooseHistogram,
+    /// The shortest run-time for this transaction.
+    pub min_time: usize,
+    /// The longest run-time for this transaction.
+    pub max_time: usize,
+    /// Total combined run-times for this transaction.
+    pub total_time: usize,
+    /// Total number of times transaction has run.
+    pub counter: usize,
+    /// Total number of times transaction has run successfully.
+    pub success_count: usize,
+    /// Total number of times transaction has failed.
+    pub fail_count: usize,
+}
+impl GooseTransactionMetricAggregate {
+    /// Create a new GooseTransactionMetricAggregate.
+    pub(crate) fn new(name: &str) -> Self {
+        GooseTransactionMetricAggregate {
+            name: name.to_string(),
+            times: GooseHistogram::new(),
+            min_time: 0,
+            max_time: 0,
+            total_time: 0,
+            counter: 0,
+            success_count: 0,
+            fail_count: 0,
+        }
+    }
+
+    /// Track transaction elapsed time in microseconds.
+    pub(crate) fn set_time(&mut self, time: u64, success: bool) {
+        // The histogram tracks microseconds, all other times are tracked in milliseconds
+        // rounded to the nearest millisecond.
+        self.times.record(time);
+        let time_usize = ((time + 500) / 1_000) as usize;
+
+        // Update minimum if this one is fastest yet.
+        if self.min_time == 0 || time_usize < self.min_time {
+            self.min_time = time_usize;
+        }
+
+        // Update maximum if this one is slowest yet.
+        if time_usize > self.max_time {
+            self.max_time = time_usize;
+        }
+
+        // Update total_time, adding in this one.
+        self.total_time += time_usize;
+
+        // Each time we store a new time, increment counter by one.
+        self.counter += 1;
+
+        if success {
+            self.success_count += 1;
+        } else {
+            self.fail_count += 1;
+        }
+    }
+
+    /// Merge another aggregate of the same transaction into this one.
+    pub(crate) fn merge(&mut self, other: &GooseTransactionMetricAggregate) {
+        self.times.add(&other.times);
+        self.min_time = update_min_time(self.min_time, other.min_time);
+        self.max_time = update_max_time(self.max_time, other.max_time);
+        self.total_time += other.total_time;
+        self.counter += other.counter;
+        self.success_count += other.success_count;
+        self.fail_count += other.fail_count;
+    }
+}
+
+/// The kind of a custom metric.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum GooseCustomMetricKind {
+    /// A count that only goes up, such as items added to carts.
+    Counter,
+    /// A value that can go up and down, such as an observed queue depth.
+    Gauge,
+    /// A duration, such as how long a job took to complete.
+    Timing,
+}
+/// Implement format trait to allow displaying the kind of custom metrics.
+impl fmt::Display for GooseCustomMetricKind {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GooseCustomMetricKind::Counter => write!(fmt, "counter"),
+            GooseCustomMetricKind::Gauge => write!(fmt, "gauge"),
+            GooseCustomMetricKind::Timing => write!(fmt, "timing"),
+        }
+    }
+}
+
+/// A single value of a custom metric.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum GooseCustomMetricValue {
+    /// Increment a counter by this amount.
+    Counter(u64),
+    /// Set a gauge to this value.
+    Gauge(f64),
+    /// Record a duration of this many microseconds.
+    Timing(u64),
+}
+impl GooseCustomMetricValue {
+    /// The kind of custom metric this value is recorded in.
+    pub fn kind(&self) -> GooseCustomMetricKind {
+        match self {
+            GooseCustomMetricValue::Counter(_) => GooseCustomMetricKind::Counter,
+            GooseCustomMetricValue::Gauge(_) => GooseCustomMetricKind::Gauge,
+            GooseCustomMetricValue::Timing(_) => GooseCustomMetricKind::Timing,
+        }
+    }
+}
 
-        let counter = match self.times.get(&rounded_time) {
-            // We've seen this time before, increment counter.
-            Some(c) => *c + 1,
-            // First time we've seen this time, initialize counter.
-            None => 1,
+/// The metrics collected each time a custom metric is recorded.
+///
+/// Custom metrics are recorded by tasks, see
+/// [`GooseUser::increment_counter`](../goose/struct.GooseUser.html#method.increment_counter),
+/// [`GooseUser::set_gauge`](../goose/struct.GooseUser.html#method.set_gauge) and
+/// [`GooseUser::record_timing`](../goose/struct.GooseUser.html#method.record_timing).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GooseCustomMetric {
+    /// How many milliseconds the load test has been running.
+    pub elapsed: u64,
+    /// The name of the custom metric.
+    pub name: String,
+    /// The value recorded.
+    pub value: GooseCustomMetricValue,
+    /// Which GooseUser thread recorded the value.
+    pub user: usize,
+}
+impl GooseCustomMetric {
+    /// Create a new GooseCustomMetric metric.
+    pub(crate) fn new(
+        elapsed: u128,
+        name: &str,
+        value: GooseCustomMetricValue,
+        user: usize,
+    ) -> Self {
+        GooseCustomMetric {
+            elapsed: elapsed as u64,
+            name: name.to_string(),
+            value,
+            user,
+        }
+    }
+}
+
+/// Aggregated values of a custom metric.
+///
+/// [`GooseCustomMetric`]s are sent by [`GooseUser`](../goose/struct.GooseUser.html)
+/// threads to the Goose parent process where they are aggregated together into this
+/// structure, and stored in [`GooseMetrics::custom`]. Gauge and timing values are in the
+/// unit they were recorded in, timings in milliseconds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GooseCustomMetricAggregate {
+    /// The name of the custom metric.
+    pub name: String,
+    /// The kind of the custom metric, values of other kinds recorded with the same name
+    /// are ignored.
+    pub kind: GooseCustomMetricKind,
+    /// How many values were recorded.
+    pub samples: usize,
+    /// The sum of all values recorded, the current count of a counter.
+    pub total: f64,
+    /// The smallest value recorded.
+    pub minimum: f64,
+    /// The largest value recorded.
+    pub maximum: f64,
+    /// The most recently recorded value, the current value of a gauge.
+    pub last: f64,
+    /// All timings, in microseconds.
+    pub times: GooseHistogram,
+}
+impl GooseCustomMetricAggregate {
+    /// Create a new GooseCustomMetricAggregate.
+    pub(crate) fn new(name: &str, kind: GooseCustomMetricKind) -> Self {
+        GooseCustomMetricAggregate {
+            name: name.to_string(),
+            kind,
+            samples: 0,
+            total: 0.0,
+            minimum: 0.0,
+            maximum: 0.0,
+            last: 0.0,
+            times: GooseHistogram::new(),
+        }
+    }
+
+    /// The average of all values recorded.
+    pub fn average(&self) -> f64 {
+        match self.samples {
+            0 => 0.0,
+            _ => self.total / self.samples as f64,
+        }
+    }
+
+    /// Record a value, returning false if it's of another kind.
+    pub(crate) fn record(&mut self, value: &GooseCustomMetricValue) -> bool {
+        if value.kind() != self.kind {
+            return false;
+        }
+        let value = match *value {
+            GooseCustomMetricValue::Counter(increment) => increment as f64,
+            GooseCustomMetricValue::Gauge(value) => value,
+            GooseCustomMetricValue::Timing(micros) => {
+                // The histogram tracks microseconds, all other times are tracked in
+                // milliseconds.
+                self.times.record(micros);
+                micros as f64 / 1_000.0
+            }
         };
-        self.times.insert(rounded_time, counter);
-        debug!("incremented {} counter: {}", rounded_time, counter);
+        if self.samples == 0 || value < self.minimum {
+            self.minimum = value;
+        }
+        if self.samples == 0 || value > self.maximum {
+            self.maximum = value;
+        }
+        self.total += value;
+        self.last = value;
+        self.samples += 1;
+        true
     }
 }
 
@@ -839,10 +1298,12 @@ pub struct GooseMetrics {
     pub stopped: Option<DateTime<Local>>,
     /// Total number of seconds the load test ran.
     pub duration: usize,
-    /// Total number of users simulated during this load test.
+    /// Number of users currently simulated by this load test.
     ///
     /// This value may be smaller than what was configured at start time if the test
-    /// didn't run long enough for all configured users to start.
+    /// didn't run long enough for all configured users to start. It increases and
+    /// decreases as users are launched and gracefully stopped, for example by a load
+    /// profile or by the `users` Controller command.
     pub users: usize,
     /// Tracks details about each request made during the load test.
     ///
@@ -856,6 +1317,16 @@ pub struct GooseMetrics {
     /// [GooseDefault::NoTaskMetrics](../config/enum.GooseDefault.html#variant.NoTaskMetrics) or
     /// [GooseDefault::NoMetrics](../config/enum.GooseDefault.html#variant.NoMetrics).
     pub tasks: GooseTaskMetrics,
+    /// Tracks details about each transaction ended during the load test.
+    ///
+    /// Can be disabled with the `--no-metrics` run-time option, or with
+    /// [GooseDefault::NoMetrics](../config/enum.GooseDefault.html#variant.NoMetrics).
+    pub transactions: GooseTransactionMetrics,
+    /// Tracks the counters, gauges and timings recorded by tasks during the load test.
+    ///
+    /// Can be disabled with the `--no-metrics` run-time option, or with
+    /// [GooseDefault::NoMetrics](../config/enum.GooseDefault.html#variant.NoMetrics).
+    pub custom: GooseCustomMetrics,
     /// Tracks and counts each time an error is detected during the load test.
     ///
     /// Can be disabled with either the `--no-error-summary` or `--no-metrics` run-time options,
@@ -865,6 +1336,32 @@ pub struct GooseMetrics {
     pub errors: GooseErrorMetrics,
     /// Tracks all hosts that the load test is run against.
     pub hosts: HashSet<String>,
+    /// Tracks iterations launched at a fixed arrival rate.
+    ///
+    /// Only set when the load test is configured with the `--arrival-rate` run-time
+    /// option, or with
+    /// [GooseDefault::ArrivalRate](../config/enum.GooseDefault.html#variant.ArrivalRate).
+    pub iterations: Option<GooseIterationMetrics>,
+    /// Snapshots of request metrics, each covering one interval of the load test.
+    ///
+    /// Only recorded when the load test is configured with the `--snapshot-interval`
+    /// run-time option, or with
+    /// [GooseDefault::SnapshotInterval](../config/enum.GooseDefault.html#variant.SnapshotInterval).
+    pub snapshots: Vec<GooseMetricsSnapshot>,
+    /// The result of each threshold, evaluated against the final metrics.
+    ///
+    /// Only set when the load test is configured with the `--threshold` run-time option,
+    /// or with
+    /// [GooseAttack::set_threshold](../struct.GooseAttack.html#method.set_threshold).
+    pub thresholds: Vec<GooseThresholdResult>,
+    /// The final metrics compared against a baseline.
+    ///
+    /// Only set when the load test is configured with the `--baseline` run-time option,
+    /// or with
+    /// [GooseDefault::Baseline](../config/enum.GooseDefault.html#variant.Baseline).
+    pub baseline: Option<GooseBaselineComparison>,
+    /// Requests made since the previous snapshot, if recording snapshots.
+    pub(crate) snapshot_requests: GooseRequestMetrics,
     /// Flag indicating whether or not these are the final metrics, used to determine
     /// which metrics should be displayed. Defaults to false.
     pub(crate) final_metrics: bool,
@@ -873,6 +1370,8 @@ pub struct GooseMetrics {
     /// Flag indicating whether or not to display metrics. This defaults to false on
     /// Workers, otherwise true.
     pub(crate) display_metrics: bool,
+    /// Response time percentiles to display, configured with `--percentiles`.
+    pub(crate) percentiles: Vec<f64>,
 }
 impl GooseMetrics {
     /// Initialize the task_metrics vector, and determine which hosts are being
@@ -981,6 +1480,40 @@ impl GooseMetrics {
         }
     }
 
+    /// Record a snapshot of the requests made since the previous snapshot, which was
+    /// taken `interval` seconds ago, and start collecting the next snapshot.
+    pub(crate) fn record_snapshot(&mut self, interval: f32) {
+        let percentiles = &self.percentiles;
+        let snapshot = |times: &GooseHistogram, total: usize, fails: usize| GooseRequestSnapshot {
+            requests_per_second: total as f32 / interval,
+            failures_per_second: fails as f32 / interval,
+            response_time_percentiles: percentiles_in_millis(times, percentiles),
+        };
+
+        let mut requests = BTreeMap::new();
+        let mut aggregated_times = GooseHistogram::new();
+        let (mut aggregated_total, mut aggregated_fails) = (0, 0);
+        for (request_key, request) in std::mem::take(&mut self.snapshot_requests) {
+            let total = request.success_count + request.fail_count;
+            aggregated_times.add(&request.raw_data.times);
+            aggregated_total += total;
+            aggregated_fails += request.fail_count;
+            requests.insert(
+                request_key,
+                snapshot(&request.raw_data.times, total, request.fail_count),
+            );
+        }
+        let aggregated = snapshot(&aggregated_times, aggregated_total, aggregated_fails);
+
+        self.snapshots.push(GooseMetricsSnapshot {
+            timestamp: Local::now().timestamp(),
+            interval,
+            users: self.users,
+            requests,
+            aggregated,
+        });
+    }
+
     /// Optionally prepares a table of requests and fails.
     ///
     /// This function is invoked by `GooseMetrics::print()` and
@@ -1270,7 +1803,7 @@ impl GooseMetrics {
             return Ok(());
         }
 
-        let mut aggregate_task_times: BTreeMap<usize, usize> = BTreeMap::new();
+        let mut aggregate_task_times = GooseHistogram::new();
         let mut aggregate_total_task_time: usize = 0;
         let mut aggregate_task_time_counter: usize = 0;
         let mut aggregate_min_task_time: usize = 0;
@@ -1306,8 +1839,8 @@ impl GooseMetrics {
                     displayed_task_set = true;
                 }
 
-                // Iterate over user task times, and merge into global task times.
-                aggregate_task_times = merge_times(aggregate_task_times, task.times.clone());
+                // Merge user task times into global task times.
+                aggregate_task_times.add(&task.times);
 
                 // Increment total task time counter.
                 aggregate_total_task_time += &task.total_time;
@@ -1337,12 +1870,7 @@ impl GooseMetrics {
                     average,
                     format_number(task.min_time),
                     format_number(task.max_time),
-                    format_number(util::median(
-                        &task.times,
-                        task.counter,
-                        task.min_time,
-                        task.max_time
-                    )),
+                    calculate_response_time_percentile(&task.times, 50.0),
                     avg_precision = average_precision,
                 )?;
             }
@@ -1365,12 +1893,7 @@ impl GooseMetrics {
                 average,
                 format_number(aggregate_min_task_time),
                 format_number(aggregate_max_task_time),
-                format_number(util::median(
-                    &aggregate_task_times,
-                    aggregate_task_time_counter,
-                    aggregate_min_task_time,
-                    aggregate_max_task_time
-                )),
+                calculate_response_time_percentile(&aggregate_task_times, 50.0),
                 avg_precision = average_precision,
             )?;
         }
@@ -1378,6 +1901,304 @@ impl GooseMetrics {
         Ok(())
     }
 
+    /// Optionally prepares a table of transactions.
+    ///
+    /// This function is invoked by `GooseMetrics::print()` and
+    /// `GooseMetrics::print_running()`.
+    pub(crate) fn fmt_transactions(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // If there's nothing to display, exit immediately.
+        if self.transactions.is_empty() || !self.display_metrics {
+            return Ok(());
+        }
+
+        writeln!(
+            fmt,
+            "\n === PER TRANSACTION METRICS ===\n ------------------------------------------------------------------------------"
+        )?;
+        writeln!(
+            fmt,
+            " {:<24} | {:>13} | {:>14} | {:>8} | {:>7}",
+            "Name", "# times run", "# fails", "trans/s", "fail/s"
+        )?;
+        writeln!(
+            fmt,
+            " ------------------------------------------------------------------------------"
+        )?;
+        let mut aggregate_fail_count = 0;
+        let mut aggregate_total_count = 0;
+        for transaction in self.transactions.values() {
+            let total_count = transaction.success_count + transaction.fail_count;
+            self.fmt_transaction_counts_row(
+                fmt,
+                &transaction.name,
+                total_count,
+                transaction.fail_count,
+            )?;
+            aggregate_total_count += total_count;
+            aggregate_fail_count += transaction.fail_count;
+        }
+        if self.transactions.len() > 1 {
+            writeln!(
+                fmt,
+                " -------------------------+---------------+----------------+----------+--------"
+            )?;
+            self.fmt_transaction_counts_row(
+                fmt,
+                "Aggregated",
+                aggregate_total_count,
+                aggregate_fail_count,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    // Display a single row of the transactions table.
+    fn fmt_transaction_counts_row(
+        &self,
+        fmt: &mut fmt::Formatter<'_>,
+        name: &str,
+        total_count: usize,
+        fail_count: usize,
+    ) -> fmt::Result {
+        let fail_percent = if fail_count > 0 {
+            fail_count as f32 / total_count as f32 * 100.0
+        } else {
+            0.0
+        };
+        // Compress 100.0 and 0.0 to 100 and 0 respectively to save width.
+        let fails = if fail_percent as usize == 100 || fail_percent as usize == 0 {
+            format!("{} ({}%)", format_number(fail_count), fail_percent as usize)
+        } else {
+            format!("{} ({:.1}%)", format_number(fail_count), fail_percent)
+        };
+        let (runs_per_second, fails_per_second) =
+            per_second_calculations(self.duration, total_count, fail_count);
+        writeln!(
+            fmt,
+            " {:<24} | {:>13} | {:>14} | {:>8.runs_p$} | {:>7.fails_p$}",
+            util::truncate_string(name, 24),
+            format_number(total_count),
+            fails,
+            runs_per_second,
+            fails_per_second,
+            runs_p = determine_precision(runs_per_second),
+            fails_p = determine_precision(fails_per_second),
+        )
+    }
+
+    /// Optionally prepares a table of transaction times, and when displaying the final
+    /// metrics report the slowest transactions within several percentiles.
+    ///
+    /// This function is invoked by `GooseMetrics::print()` and
+    /// `GooseMetrics::print_running()`.
+    pub(crate) fn fmt_transaction_times(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // If there's nothing to display, exit immediately.
+        if self.transactions.is_empty() || !self.display_metrics {
+            return Ok(());
+        }
+
+        let mut aggregate = GooseTransactionMetricAggregate::new("Aggregated");
+        writeln!(
+            fmt,
+            " ------------------------------------------------------------------------------"
+        )?;
+        writeln!(
+            fmt,
+            " {:<24} | {:>11} | {:>10} | {:>11} | {:>10}",
+            "Name", "Avg (ms)", "Min", "Max", "Median"
+        )?;
+        writeln!(
+            fmt,
+            " ------------------------------------------------------------------------------"
+        )?;
+        for transaction in self.transactions.values() {
+            aggregate.merge(transaction);
+            fmt_transaction_times_row(fmt, transaction)?;
+        }
+        if self.transactions.len() > 1 {
+            writeln!(
+                fmt,
+                " -------------------------+-------------+------------+-------------+-----------"
+            )?;
+            fmt_transaction_times_row(fmt, &aggregate)?;
+        }
+
+        // Only include percentiles when displaying the final metrics report.
+        if !self.final_metrics {
+            return Ok(());
+        }
+
+        let mut header = format!(" {:<24}", "Name");
+        let mut separator = " -------------------------".to_string();
+        for percentile in &self.percentiles {
+            header.push_str(&format!(" | {:>6}", format!("{}%", percentile)));
+            separator.push_str("+--------");
+        }
+        separator.truncate(header.len());
+
+        writeln!(
+            fmt,
+            " ------------------------------------------------------------------------------"
+        )?;
+        writeln!(
+            fmt,
+            " Slowest transaction within specified percentile of transactions (in ms):"
+        )?;
+        writeln!(
+            fmt,
+            " ------------------------------------------------------------------------------"
+        )?;
+        writeln!(fmt, "{}", header)?;
+        writeln!(
+            fmt,
+            " ------------------------------------------------------------------------------"
+        )?;
+        for transaction in self.transactions.values() {
+            self.fmt_percentiles_row(fmt, &transaction.name, Some(&transaction.times))?;
+        }
+        if self.transactions.len() > 1 {
+            writeln!(fmt, "{}", separator)?;
+            self.fmt_percentiles_row(fmt, "Aggregated", Some(&aggregate.times))?;
+        }
+
+        Ok(())
+    }
+
+    /// Optionally prepares a table of each kind of custom metric.
+    ///
+    /// This function is invoked by `GooseMetrics::print()` and
+    /// `GooseMetrics::print_running()`.
+    pub(crate) fn fmt_custom(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // If there's nothing to display, exit immediately.
+        if self.custom.is_empty() || !self.display_metrics {
+            return Ok(());
+        }
+
+        writeln!(fmt, "\n === CUSTOM METRICS ===")?;
+        let of_kind = |kind: GooseCustomMetricKind| {
+            self.custom
+                .values()
+                .filter(move |custom| custom.kind == kind)
+                .peekable()
+        };
+
+        let mut counters = of_kind(GooseCustomMetricKind::Counter);
+        if counters.peek().is_some() {
+            writeln!(
+                fmt,
+                " ------------------------------------------------------------------------------"
+            )?;
+            writeln!(
+                fmt,
+                " {:<24} | {:>13} | {:>17}",
+                "Counter", "Total", "per second"
+            )?;
+            writeln!(
+                fmt,
+                " ------------------------------------------------------------------------------"
+            )?;
+            for counter in counters {
+                let (per_second, _) =
+                    per_second_calculations(self.duration, counter.total as usize, 0);
+                writeln!(
+                    fmt,
+                    " {:<24} | {:>13} | {:>17.p$}",
+                    util::truncate_string(&counter.name, 24),
+                    format_number(counter.total as usize),
+                    per_second,
+                    p = determine_precision(per_second),
+                )?;
+            }
+        }
+
+        let mut gauges = of_kind(GooseCustomMetricKind::Gauge);
+        if gauges.peek().is_some() {
+            writeln!(
+                fmt,
+                " ------------------------------------------------------------------------------"
+            )?;
+            writeln!(
+                fmt,
+                " {:<24} | {:>11} | {:>10} | {:>11} | {:>10}",
+                "Gauge", "Last", "Min", "Average", "Max"
+            )?;
+            writeln!(
+                fmt,
+                " ------------------------------------------------------------------------------"
+            )?;
+            for gauge in gauges {
+                writeln!(
+                    fmt,
+                    " {:<24} | {:>11.2} | {:>10.2} | {:>11.2} | {:>10.2}",
+                    util::truncate_string(&gauge.name, 24),
+                    gauge.last,
+                    gauge.minimum,
+                    gauge.average(),
+                    gauge.maximum,
+                )?;
+            }
+        }
+
+        let mut timings = of_kind(GooseCustomMetricKind::Timing);
+        if timings.peek().is_some() {
+            writeln!(
+                fmt,
+                " ------------------------------------------------------------------------------"
+            )?;
+            writeln!(
+                fmt,
+                " {:<24} | {:>11} | {:>10} | {:>11} | {:>10}",
+                "Timing", "Avg (ms)", "Min", "Max", "Median"
+            )?;
+            writeln!(
+                fmt,
+                " ------------------------------------------------------------------------------"
+            )?;
+            for timing in timings {
+                writeln!(
+                    fmt,
+                    " {:<24} | {:>11.2} | {:>10} | {:>11} | {:>10}",
+                    util::truncate_string(&timing.name, 24),
+                    timing.average(),
+                    format_number(timing.minimum.round() as usize),
+                    format_number(timing.maximum.round() as usize),
+                    calculate_response_time_percentile(&timing.times, 50.0),
+                )?;
+            }
+
+            // Only include percentiles when displaying the final metrics report.
+            if self.final_metrics {
+                let mut header = format!(" {:<24}", "Timing");
+                for percentile in &self.percentiles {
+                    header.push_str(&format!(" | {:>6}", format!("{}%", percentile)));
+                }
+                writeln!(
+                    fmt,
+                    " ------------------------------------------------------------------------------"
+                )?;
+                writeln!(
+                    fmt,
+                    " Slowest timing within specified percentile of timings (in ms):"
+                )?;
+                writeln!(
+                    fmt,
+                    " ------------------------------------------------------------------------------"
+                )?;
+                writeln!(fmt, "{}", header)?;
+                writeln!(
+                    fmt,
+                    " ------------------------------------------------------------------------------"
+                )?;
+                for timing in of_kind(GooseCustomMetricKind::Timing) {
+                    self.fmt_percentiles_row(fmt, &timing.name, Some(&timing.times))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Optionally prepares a table of response times.
     ///
     /// This function is invoked by `GooseMetrics::print()` and
@@ -1388,7 +2209,7 @@ impl GooseMetrics {
             return Ok(());
         }
 
-        let mut aggregate_raw_times: BTreeMap<usize, usize> = BTreeMap::new();
+        let mut aggregate_raw_times = GooseHistogram::new();
         let mut aggregate_raw_total_time: usize = 0;
         let mut aggregate_raw_counter: usize = 0;
         let mut aggregate_raw_min_time: usize = 0;
@@ -1421,7 +2242,7 @@ impl GooseMetrics {
             let raw_average_precision = determine_precision(raw_average);
 
             // Merge in all times from this request into an aggregate.
-            aggregate_raw_times = merge_times(aggregate_raw_times, request.raw_data.times.clone());
+            aggregate_raw_times.add(&request.raw_data.times);
             // Increment total response time counter.
             aggregate_raw_total_time += &request.raw_data.total_time;
             // Increment counter tracking individual response times seen.
@@ -1440,12 +2261,7 @@ impl GooseMetrics {
                 raw_average,
                 format_number(request.raw_data.minimum_time),
                 format_number(request.raw_data.maximum_time),
-                format_number(util::median(
-                    &request.raw_data.times,
-                    request.raw_data.counter,
-                    request.raw_data.minimum_time,
-                    request.raw_data.maximum_time,
-                )),
+                calculate_response_time_percentile(&request.raw_data.times, 50.0),
                 raw_avg_precision = raw_average_precision,
             )?;
         }
@@ -1469,12 +2285,7 @@ impl GooseMetrics {
                 raw_average,
                 format_number(aggregate_raw_min_time),
                 format_number(aggregate_raw_max_time),
-                format_number(util::median(
-                    &aggregate_raw_times,
-                    aggregate_raw_counter,
-                    aggregate_raw_min_time,
-                    aggregate_raw_max_time
-                )),
+                calculate_response_time_percentile(&aggregate_raw_times, 50.0),
                 avg_precision = raw_average_precision,
             )?;
         }
@@ -1490,7 +2301,7 @@ impl GooseMetrics {
         )?;
         writeln!(fmt, " Adjusted for Coordinated Omission:")?;
 
-        let mut aggregate_co_times: BTreeMap<usize, usize> = BTreeMap::new();
+        let mut aggregate_co_times = GooseHistogram::new();
         let mut aggregate_co_total_time: usize = 0;
         let mut aggregate_co_counter: usize = 0;
         let mut aggregate_co_min_time: usize = 0;
@@ -1513,7 +2324,6 @@ impl GooseMetrics {
         for (request_key, request) in self.requests.iter().sorted() {
             let co_average;
             let standard_deviation;
-            let co_minimum;
             let co_maximum;
             if let Some(co_data) = request.coordinated_omission_data.as_ref() {
                 let raw_average = match request.raw_data.counter {
@@ -1525,7 +2335,7 @@ impl GooseMetrics {
                     _ => co_data.total_time as f32 / co_data.counter as f32,
                 };
                 standard_deviation = util::standard_deviation(raw_average, co_average);
-                aggregate_co_times = merge_times(aggregate_co_times, co_data.times.clone());
+                aggregate_co_times.add(&co_data.times);
                 aggregate_co_counter += co_data.counter;
                 // If user had new fastest response time, update global fastest response time.
                 aggregate_co_min_time =
@@ -1534,12 +2344,10 @@ impl GooseMetrics {
                 aggregate_co_max_time =
                     update_max_time(aggregate_raw_max_time, co_data.maximum_time);
                 aggregate_co_total_time += co_data.total_time;
-                co_minimum = co_data.minimum_time;
                 co_maximum = co_data.maximum_time;
             } else {
                 co_average = 0.0;
                 standard_deviation = 0.0;
-                co_minimum = 0;
                 co_maximum = 0;
             }
             let co_average_precision = determine_precision(co_average);
@@ -1554,12 +2362,7 @@ impl GooseMetrics {
                     co_average,
                     standard_deviation,
                     format_number(co_maximum),
-                    format_number(util::median(
-                        &co_data.times,
-                        co_data.counter,
-                        co_minimum,
-                        co_maximum,
-                    )),
+                    calculate_response_time_percentile(&co_data.times, 50.0),
                     co_avg_precision = co_average_precision,
                     sd_precision = standard_deviation_precision,
                 )?;
@@ -1598,12 +2401,7 @@ impl GooseMetrics {
                 co_average,
                 standard_deviation,
                 format_number(aggregate_co_max_time),
-                format_number(util::median(
-                    &aggregate_co_times,
-                    aggregate_co_counter,
-                    aggregate_co_min_time,
-                    aggregate_co_max_time
-                )),
+                calculate_response_time_percentile(&aggregate_co_times, 50.0),
                 avg_precision = co_average_precision,
                 sd_precision = standard_deviation_precision,
             )?;
@@ -1622,11 +2420,15 @@ impl GooseMetrics {
             return Ok(());
         }
 
-        let mut raw_aggregate_response_times: BTreeMap<usize, usize> = BTreeMap::new();
-        let mut raw_aggregate_total_response_time: usize = 0;
-        let mut raw_aggregate_response_time_counter: usize = 0;
-        let mut raw_aggregate_min_response_time: usize = 0;
-        let mut raw_aggregate_max_response_time: usize = 0;
+        // Build a header and separator with a column for each configured percentile.
+        let mut header = format!(" {:<24}", "Name");
+        let mut separator = " -------------------------".to_string();
+        for percentile in &self.percentiles {
+            header.push_str(&format!(" | {:>6}", format!("{}%", percentile)));
+            separator.push_str("+--------");
+        }
+        separator.truncate(header.len());
+
         writeln!(
             fmt,
             " ------------------------------------------------------------------------------"
@@ -1639,144 +2441,27 @@ impl GooseMetrics {
             fmt,
             " ------------------------------------------------------------------------------"
         )?;
-        writeln!(
-            fmt,
-            " {:<24} | {:>6} | {:>6} | {:>6} | {:>6} | {:>6} | {:>6}",
-            "Name", "50%", "75%", "98%", "99%", "99.9%", "99.99%"
-        )?;
+        writeln!(fmt, "{}", header)?;
         writeln!(
             fmt,
             " ------------------------------------------------------------------------------"
         )?;
         // Track whether or not Coordinated Omission Mitigation kicked in.
         let mut co_data = false;
+        let mut raw_aggregate_response_times = GooseHistogram::new();
         for (request_key, request) in self.requests.iter().sorted() {
             if !co_data && request.coordinated_omission_data.is_some() {
                 co_data = true;
             }
 
-            // Iterate over user response times, and merge into global response times.
-            raw_aggregate_response_times =
-                merge_times(raw_aggregate_response_times, request.raw_data.times.clone());
-
-            // Increment total response time counter.
-            raw_aggregate_total_response_time += &request.raw_data.total_time;
-
-            // Increment counter tracking individual response times seen.
-            raw_aggregate_response_time_counter += &request.raw_data.counter;
-
-            // If user had new fastest response time, update global fastest response time.
-            raw_aggregate_min_response_time = update_min_time(
-                raw_aggregate_min_response_time,
-                request.raw_data.minimum_time,
-            );
+            // Merge user response times into global response times.
+            raw_aggregate_response_times.add(&request.raw_data.times);
 
-            // If user had new slowest response time, update global slowest response time.
-            raw_aggregate_max_response_time = update_max_time(
-                raw_aggregate_max_response_time,
-                request.raw_data.maximum_time,
-            );
-            // Sort response times so we can calculate a mean.
-            writeln!(
-                fmt,
-                " {:<24} | {:>6} | {:>6} | {:>6} | {:>6} | {:>6} | {:>6}",
-                util::truncate_string(request_key, 24),
-                calculate_response_time_percentile(
-                    &request.raw_data.times,
-                    request.raw_data.counter,
-                    request.raw_data.minimum_time,
-                    request.raw_data.maximum_time,
-                    0.5
-                ),
-                calculate_response_time_percentile(
-                    &request.raw_data.times,
-                    request.raw_data.counter,
-                    request.raw_data.minimum_time,
-                    request.raw_data.maximum_time,
-                    0.75
-                ),
-                calculate_response_time_percentile(
-                    &request.raw_data.times,
-                    request.raw_data.counter,
-                    request.raw_data.minimum_time,
-                    request.raw_data.maximum_time,
-                    0.98
-                ),
-                calculate_response_time_percentile(
-                    &request.raw_data.times,
-                    request.raw_data.counter,
-                    request.raw_data.minimum_time,
-                    request.raw_data.maximum_time,
-                    0.99
-                ),
-                calculate_response_time_percentile(
-                    &request.raw_data.times,
-                    request.raw_data.counter,
-                    request.raw_data.minimum_time,
-                    request.raw_data.maximum_time,
-                    0.999
-                ),
-                calculate_response_time_percentile(
-                    &request.raw_data.times,
-                    request.raw_data.counter,
-                    request.raw_data.minimum_time,
-                    request.raw_data.maximum_time,
-                    0.9999
-                ),
-            )?;
+            self.fmt_percentiles_row(fmt, request_key, Some(&request.raw_data.times))?;
         }
         if self.requests.len() > 1 {
-            writeln!(
-                fmt,
-                " -------------------------+--------+--------+--------+--------+--------+-------"
-            )?;
-            writeln!(
-                fmt,
-                " {:<24} | {:>6} | {:>6} | {:>6} | {:>6} | {:>6} | {:>6}",
-                "Aggregated",
-                calculate_response_time_percentile(
-                    &raw_aggregate_response_times,
-                    raw_aggregate_response_time_counter,
-                    raw_aggregate_min_response_time,
-                    raw_aggregate_max_response_time,
-                    0.5
-                ),
-                calculate_response_time_percentile(
-                    &raw_aggregate_response_times,
-                    raw_aggregate_response_time_counter,
-                    raw_aggregate_min_response_time,
-                    raw_aggregate_max_response_time,
-                    0.75
-                ),
-                calculate_response_time_percentile(
-                    &raw_aggregate_response_times,
-                    raw_aggregate_response_time_counter,
-                    raw_aggregate_min_response_time,
-                    raw_aggregate_max_response_time,
-                    0.98
-                ),
-                calculate_response_time_percentile(
-                    &raw_aggregate_response_times,
-                    raw_aggregate_response_time_counter,
-                    raw_aggregate_min_response_time,
-                    raw_aggregate_max_response_time,
-                    0.99
-                ),
-                calculate_response_time_percentile(
-                    &raw_aggregate_response_times,
-                    raw_aggregate_response_time_counter,
-                    raw_aggregate_min_response_time,
-                    raw_aggregate_max_response_time,
-                    0.999
-                ),
-                calculate_response_time_percentile(
-                    &raw_aggregate_response_times,
-                    raw_aggregate_response_time_counter,
-                    raw_aggregate_min_response_time,
-                    raw_aggregate_max_response_time,
-                    0.9999
-                ),
-            )?;
+            writeln!(fmt, "{}", separator)?;
+            self.fmt_percentiles_row(fmt, "Aggregated", Some(&raw_aggregate_response_times))?;
         }
 
         // If there's no Coordinated Omission Mitigation data to display, exit.
@@ -1784,12 +2469,6 @@ impl GooseMetrics {
             return Ok(());
         }
 
-        let mut co_aggregate_response_times: BTreeMap<usize, usize> = BTreeMap::new();
-        let mut co_aggregate_total_response_time: usize = 0;
-        let mut co_aggregate_response_time_counter: usize = 0;
-        let mut co_aggregate_min_response_time: usize = 0;
-        let mut co_aggregate_max_response_time: usize = 0;
-
         writeln!(
             fmt,
             " ------------------------------------------------------------------------------"
@@ -1797,162 +2476,58 @@ impl GooseMetrics {
         writeln!(fmt, " Adjusted for Coordinated Omission:")?;
         writeln!(
             fmt,
-            " ------------------------------------------------------------------------------"
-        )?;
-        writeln!(
-            fmt,
-            " {:<24} | {:>6} | {:>6} | {:>6} | {:>6} | {:>6} | {:>6}",
-            "Name", "50%", "75%", "98%", "99%", "99.9%", "99.99%"
-        )?;
-        writeln!(
-            fmt,
-            " ------------------------------------------------------------------------------"
-        )?;
-        for (request_key, request) in self.requests.iter().sorted() {
-            if let Some(coordinated_omission_data) = request.coordinated_omission_data.as_ref() {
-                // Iterate over user response times, and merge into global response times.
-                co_aggregate_response_times = merge_times(
-                    co_aggregate_response_times,
-                    coordinated_omission_data.times.clone(),
-                );
-
-                // Increment total response time counter.
-                co_aggregate_total_response_time += &coordinated_omission_data.total_time;
-
-                // Increment counter tracking individual response times seen.
-                co_aggregate_response_time_counter += &coordinated_omission_data.counter;
-
-                // If user had new fastest response time, update global fastest response time.
-                co_aggregate_min_response_time = update_min_time(
-                    co_aggregate_min_response_time,
-                    coordinated_omission_data.minimum_time,
-                );
-
-                // If user had new slowest response time, update global slowest response time.
-                co_aggregate_max_response_time = update_max_time(
-                    co_aggregate_max_response_time,
-                    coordinated_omission_data.maximum_time,
-                );
-
-                // Sort response times so we can calculate a mean.
-                writeln!(
-                    fmt,
-                    " {:<24} | {:>6} | {:>6} | {:>6} | {:>6} | {:>6} | {:>6}",
-                    util::truncate_string(request_key, 24),
-                    calculate_response_time_percentile(
-                        &coordinated_omission_data.times,
-                        coordinated_omission_data.counter,
-                        coordinated_omission_data.minimum_time,
-                        coordinated_omission_data.maximum_time,
-                        0.5
-                    ),
-                    calculate_response_time_percentile(
-                        &coordinated_omission_data.times,
-                        coordinated_omission_data.counter,
-                        coordinated_omission_data.minimum_time,
-                        coordinated_omission_data.maximum_time,
-                        0.75
-                    ),
-                    calculate_response_time_percentile(
-                        &coordinated_omission_data.times,
-                        coordinated_omission_data.counter,
-                        coordinated_omission_data.minimum_time,
-                        coordinated_omission_data.maximum_time,
-                        0.98
-                    ),
-                    calculate_response_time_percentile(
-                        &coordinated_omission_data.times,
-                        coordinated_omission_data.counter,
-                        coordinated_omission_data.minimum_time,
-                        coordinated_omission_data.maximum_time,
-                        0.99
-                    ),
-                    calculate_response_time_percentile(
-                        &coordinated_omission_data.times,
-                        coordinated_omission_data.counter,
-                        coordinated_omission_data.minimum_time,
-                        coordinated_omission_data.maximum_time,
-                        0.999
-                    ),
-                    calculate_response_time_percentile(
-                        &coordinated_omission_data.times,
-                        coordinated_omission_data.counter,
-                        coordinated_omission_data.minimum_time,
-                        coordinated_omission_data.maximum_time,
-                        0.9999
-                    ),
-                )?;
-            } else {
-                writeln!(
-                    fmt,
-                    " {:<24} | {:>6} | {:>6} | {:>6} | {:>6} | {:>6} | {:>6}",
-                    util::truncate_string(request_key, 24),
-                    "-",
-                    "-",
-                    "-",
-                    "-",
-                    "-",
-                    "-"
-                )?;
+            " ------------------------------------------------------------------------------"
+        )?;
+        writeln!(fmt, "{}", header)?;
+        writeln!(
+            fmt,
+            " ------------------------------------------------------------------------------"
+        )?;
+        let mut co_aggregate_response_times = GooseHistogram::new();
+        for (request_key, request) in self.requests.iter().sorted() {
+            let co_response_times = request
+                .coordinated_omission_data
+                .as_ref()
+                .map(|coordinated_omission_data| &coordinated_omission_data.times);
+
+            // Merge user response times into global response times.
+            if let Some(co_response_times) = co_response_times {
+                co_aggregate_response_times.add(co_response_times);
             }
+
+            self.fmt_percentiles_row(fmt, request_key, co_response_times)?;
         }
         if self.requests.len() > 1 {
-            writeln!(
-                fmt,
-                " -------------------------+--------+--------+--------+--------+--------+-------"
-            )?;
-            writeln!(
-                fmt,
-                " {:<24} | {:>6} | {:>6} | {:>6} | {:>6} | {:>6} | {:>6}",
-                "Aggregated",
-                calculate_response_time_percentile(
-                    &co_aggregate_response_times,
-                    co_aggregate_response_time_counter,
-                    co_aggregate_min_response_time,
-                    co_aggregate_max_response_time,
-                    0.5
-                ),
-                calculate_response_time_percentile(
-                    &co_aggregate_response_times,
-                    co_aggregate_response_time_counter,
-                    co_aggregate_min_response_time,
-                    co_aggregate_max_response_time,
-                    0.75
-                ),
-                calculate_response_time_percentile(
-                    &co_aggregate_response_times,
-                    co_aggregate_response_time_counter,
-                    co_aggregate_min_response_time,
-                    co_aggregate_max_response_time,
-                    0.98
-                ),
-                calculate_response_time_percentile(
-                    &co_aggregate_response_times,
-                    co_aggregate_response_time_counter,
-                    co_aggregate_min_response_time,
-                    co_aggregate_max_response_time,
-                    0.99
-                ),
-                calculate_response_time_percentile(
-                    &co_aggregate_response_times,
-                    co_aggregate_response_time_counter,
-                    co_aggregate_min_response_time,
-                    co_aggregate_max_response_time,
-                    0.999
-                ),
-                calculate_response_time_percentile(
-                    &co_aggregate_response_times,
-                    co_aggregate_response_time_counter,
-                    co_aggregate_min_response_time,
-                    co_aggregate_max_response_time,
-                    0.9999
-                ),
-            )?;
+            writeln!(fmt, "{}", separator)?;
+            self.fmt_percentiles_row(fmt, "Aggregated", Some(&co_aggregate_response_times))?;
         }
 
         Ok(())
     }
 
+    /// Writes a single row of the percentiles table, or a row of dashes if no times were
+    /// recorded.
+    fn fmt_percentiles_row(
+        &self,
+        fmt: &mut fmt::Formatter<'_>,
+        name: &str,
+        response_times: Option<&GooseHistogram>,
+    ) -> fmt::Result {
+        write!(fmt, " {:<24}", util::truncate_string(name, 24))?;
+        for percentile in &self.percentiles {
+            if let Some(response_times) = response_times {
+                write!(
+                    fmt,
+                    " | {:>6}",
+                    calculate_response_time_percentile(response_times, *percentile)
+                )?;
+            } else {
+                write!(fmt, " | {:>6}", "-")?;
+            }
+        }
+        writeln!(fmt)
+    }
+
     /// Optionally prepares a table of response status codes.
     ///
     /// This function is invoked by `GooseMetrics::print()` and
@@ -1973,10 +2548,17 @@ impl GooseMetrics {
             " ------------------------------------------------------------------------------"
         )?;
         let mut aggregated_status_code_counts: HashMap<u16, usize> = HashMap::new();
+        let mut aggregated_grpc_status_code_counts: HashMap<u16, usize> = HashMap::new();
         for (request_key, request) in self.requests.iter().sorted() {
-            let codes = prepare_status_codes(
-                &request.status_code_counts,
-                &mut Some(&mut aggregated_status_code_counts),
+            let codes = join_status_codes(
+                prepare_status_codes(
+                    &request.status_code_counts,
+                    &mut Some(&mut aggregated_status_code_counts),
+                ),
+                prepare_grpc_status_codes(
+                    &request.grpc_status_code_counts,
+                    &mut Some(&mut aggregated_grpc_status_code_counts),
+                ),
             );
 
             writeln!(
@@ -1990,7 +2572,10 @@ impl GooseMetrics {
             fmt,
             " -------------------------+----------------------------------------------------"
         )?;
-        let codes = prepare_status_codes(&aggregated_status_code_counts, &mut None);
+        let codes = join_status_codes(
+            prepare_status_codes(&aggregated_status_code_counts, &mut None),
+            prepare_grpc_status_codes(&aggregated_grpc_status_code_counts, &mut None),
+        );
         writeln!(fmt, " {:<24} | {:>51} ", "Aggregated", codes)?;
 
         Ok(())
@@ -2039,6 +2624,144 @@ impl GooseMetrics {
         Ok(())
     }
 
+    /// Optionally prepares a table of threshold results.
+    ///
+    /// This function is invoked by `GooseMetrics::print()`.
+    pub(crate) fn fmt_thresholds(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Thresholds are only evaluated against the final metrics.
+        if !self.final_metrics || self.thresholds.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(
+            fmt,
+            "\n === THRESHOLDS ===\n ------------------------------------------------------------------------------"
+        )?;
+        writeln!(
+            fmt,
+            " {:<50} | {:>14} | {:>6}",
+            "Threshold", "Value", "Result"
+        )?;
+        writeln!(
+            fmt,
+            " ------------------------------------------------------------------------------"
+        )?;
+        for result in &self.thresholds {
+            writeln!(
+                fmt,
+                " {:<50} | {:>14} | {:>6}",
+                util::truncate_string(&result.threshold, 50),
+                result.to_string(),
+                if result.passed { "PASS" } else { "FAIL" },
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Optionally prepares a table comparing the final metrics against a baseline.
+    ///
+    /// This function is invoked by `GooseMetrics::print()`.
+    pub(crate) fn fmt_baseline(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The baseline is only compared against the final metrics.
+        let comparison = match self.baseline.as_ref() {
+            Some(comparison) if self.final_metrics => comparison,
+            _ => return Ok(()),
+        };
+
+        writeln!(
+            fmt,
+            "\n === BASELINE ===\n ------------------------------------------------------------------------------"
+        )?;
+        // Regressions are marked with a `*`.
+        let delta = |delta: &GooseBaselineDelta| {
+            if delta.regression {
+                format!("{}*", delta)
+            } else {
+                delta.to_string()
+            }
+        };
+        for (header, rows) in &[
+            ("Request", &comparison.requests),
+            ("Task", &comparison.tasks),
+        ] {
+            if rows.is_empty() {
+                continue;
+            }
+            writeln!(
+                fmt,
+                " {:<18} | {:>9} | {:>9} | {:>9} | {:>9} | {:>9}",
+                header, "req/s", "avg", "p95", "p99", "fail %"
+            )?;
+            writeln!(
+                fmt,
+                " ------------------------------------------------------------------------------"
+            )?;
+            for row in rows.iter() {
+                writeln!(
+                    fmt,
+                    " {:<18} | {:>9} | {:>9} | {:>9} | {:>9} | {:>9}",
+                    util::truncate_string(&row.name, 18),
+                    delta(&row.requests_per_second),
+                    delta(&row.average),
+                    delta(&row.p95),
+                    delta(&row.p99),
+                    delta(&row.failure_rate),
+                )?;
+            }
+            writeln!(
+                fmt,
+                " ------------------------------------------------------------------------------"
+            )?;
+        }
+        writeln!(
+            fmt,
+            " Compared to {}, * marks regressions beyond {}% ({} found)",
+            comparison.baseline,
+            comparison.tolerance,
+            comparison.regressions(),
+        )?;
+
+        Ok(())
+    }
+
+    /// Optionally prepares a table of iterations launched at a fixed arrival rate.
+    ///
+    /// This function is invoked by `GooseMetrics::print()` and
+    /// `GooseMetrics::print_running()`.
+    pub(crate) fn fmt_iterations(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // If there's nothing to display, exit immediately.
+        let iterations = match self.iterations.as_ref() {
+            Some(i) => i,
+            None => return Ok(()),
+        };
+
+        writeln!(
+            fmt,
+            " ------------------------------------------------------------------------------"
+        )?;
+        writeln!(
+            fmt,
+            " {:<24} | {:>11} | {:>11} | {:>11} | {:>10}",
+            "Iterations", "Scheduled", "Delayed", "Dropped", "Peak users"
+        )?;
+        writeln!(
+            fmt,
+            " ------------------------------------------------------------------------------"
+        )?;
+        writeln!(
+            fmt,
+            " {:<24} | {:>11} | {:>11} | {:>11} | {:>10}",
+            format!("{}/s", iterations.rate),
+            format_number(iterations.scheduled),
+            format_number(iterations.delayed),
+            format_number(iterations.dropped),
+            format_number(iterations.peak_users),
+        )?;
+
+        Ok(())
+    }
+
     // Determine the seconds, minutes and hours between two chrono:DateTimes.
     fn get_seconds_minutes_hours(
         &self,
@@ -2144,7 +2867,7 @@ impl Serialize for GooseMetrics {
     where
         S: Serializer,
     {
-        let mut s = serializer.serialize_struct("GooseMetrics", 10)?;
+        let mut s = serializer.serialize_struct("GooseMetrics", 18)?;
         s.serialize_field("hash", &self.hash)?;
         // Convert started field to a unix timestamp.
         let timestamp;
@@ -2158,10 +2881,30 @@ impl Serialize for GooseMetrics {
         s.serialize_field("users", &self.users)?;
         s.serialize_field("requests", &self.requests)?;
         s.serialize_field("tasks", &self.tasks)?;
+        s.serialize_field("transactions", &self.transactions)?;
+        s.serialize_field("custom", &self.custom)?;
         s.serialize_field("errors", &self.errors)?;
+        s.serialize_field("iterations", &self.iterations)?;
         s.serialize_field("final_metrics", &self.final_metrics)?;
         s.serialize_field("display_status_codes", &self.display_status_codes)?;
         s.serialize_field("display_metrics", &self.display_metrics)?;
+        s.serialize_field("percentiles", &self.percentiles)?;
+        // Include the configured percentiles of each request's response times, in the
+        // same order as the percentiles field.
+        let response_time_percentiles: BTreeMap<&str, Vec<f64>> = self
+            .requests
+            .iter()
+            .map(|(request_key, request)| {
+                (
+                    request_key.as_str(),
+                    percentiles_in_millis(&request.raw_data.times, &self.percentiles),
+                )
+            })
+            .collect();
+        s.serialize_field("response_time_percentiles", &response_time_percentiles)?;
+        s.serialize_field("snapshots", &self.snapshots)?;
+        s.serialize_field("thresholds", &self.thresholds)?;
+        s.serialize_field("baseline", &self.baseline)?;
         s.end()
     }
 }
@@ -2174,15 +2917,84 @@ impl fmt::Display for GooseMetrics {
         // flags are set.
         self.fmt_tasks(fmt)?;
         self.fmt_task_times(fmt)?;
+        self.fmt_transactions(fmt)?;
+        self.fmt_transaction_times(fmt)?;
+        self.fmt_custom(fmt)?;
         self.fmt_requests(fmt)?;
         self.fmt_response_times(fmt)?;
         self.fmt_percentiles(fmt)?;
         self.fmt_status_codes(fmt)?;
         self.fmt_errors(fmt)?;
+        self.fmt_iterations(fmt)?;
+        self.fmt_thresholds(fmt)?;
+        self.fmt_baseline(fmt)?;
         self.fmt_overview(fmt)
     }
 }
 
+/// Metrics collected when launching iterations at a fixed arrival rate.
+///
+/// By default each [`GooseUser`](../goose/struct.GooseUser.html) starts its next
+/// [`GooseTask`](../goose/struct.GooseTask.html) only after the previous one completes,
+/// so the load generated drops when the server under test slows down. When configured
+/// with `--arrival-rate`, Goose instead starts a new iteration through all of a
+/// [`GooseTaskSet`](../goose/struct.GooseTaskSet.html)'s tasks at a fixed rate, using an
+/// idle [`GooseUser`](../goose/struct.GooseUser.html) from a pool that grows as needed up
+/// to `--max-users`. Iterations that could not start on time are counted here instead of
+/// silently reducing the load.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct GooseIterationMetrics {
+    /// The configured number of iterations to start per second.
+    pub rate: f32,
+    /// How many iterations were due to start.
+    pub scheduled: usize,
+    /// How many iterations were delayed because no
+    /// [`GooseUser`](../goose/struct.GooseUser.html) was idle, and a new one had to be
+    /// launched to run it.
+    pub delayed: usize,
+    /// How many iterations were dropped because no
+    /// [`GooseUser`](../goose/struct.GooseUser.html) was idle and `--max-users` were
+    /// already running.
+    pub dropped: usize,
+    /// The most [`GooseUser`](../goose/struct.GooseUser.html)s in the pool at once.
+    pub peak_users: usize,
+}
+
+/// Request metrics collected during one interval of a running load test.
+///
+/// [`GooseMetrics`] only contain totals for the entire load test. When configured with
+/// `--snapshot-interval`, Goose also records a snapshot every interval, making it
+/// possible to see how the load and response times changed over the course of the load
+/// test. Snapshots are stored in [`GooseMetrics::snapshots`], and are included when
+/// serializing [`GooseMetrics`], for example with the `metricsjson` Controller command.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct GooseMetricsSnapshot {
+    /// Unix timestamp when the snapshot was recorded.
+    pub timestamp: i64,
+    /// How many seconds of the load test the snapshot covers.
+    pub interval: f32,
+    /// How many [`GooseUser`](../goose/struct.GooseUser.html)s were running when the
+    /// snapshot was recorded.
+    pub users: usize,
+    /// Metrics for each request made during the interval, keyed by method and name (for
+    /// example `GET /`).
+    pub requests: BTreeMap<String, GooseRequestSnapshot>,
+    /// Metrics for all requests made during the interval.
+    pub aggregated: GooseRequestSnapshot,
+}
+
+/// Metrics for one request during one [`GooseMetricsSnapshot`] interval.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct GooseRequestSnapshot {
+    /// Requests made per second during the interval.
+    pub requests_per_second: f32,
+    /// Failed requests per second during the interval.
+    pub failures_per_second: f32,
+    /// Response times in milliseconds at each of the configured percentiles, in the same
+    /// order as the `--percentiles` run-time option.
+    pub response_time_percentiles: Vec<f64>,
+}
+
 /// For tracking and counting requests made during a load test.
 ///
 /// The request that Goose is making. User threads send this data to the parent thread
@@ -2262,6 +3074,15 @@ impl GooseErrorMetricAggregate {
 }
 
 impl GooseAttack {
+    // Returns the configured response time percentiles, or the default percentiles.
+    pub(crate) fn get_percentiles(&self) -> Vec<f64> {
+        match self.configuration.percentiles.as_ref() {
+            // Percentiles are validated in GooseConfiguration::validate(), so unwrap() is safe.
+            Some(percentiles) => parse_percentiles(percentiles).unwrap(),
+            None => DEFAULT_PERCENTILES.to_vec(),
+        }
+    }
+
     // If metrics are enabled, synchronize metrics from child threads to the parent. If
     // flush is true all metrics will be received regardless of how long it takes. If
     // flush is false, metrics will only be received for up to 400 ms before exiting to
@@ -2288,6 +3109,27 @@ impl GooseAttack {
             // Load messages from user threads until the receiver queue is empty.
             let received_message = self.receive_metrics(goose_attack_run_state, flush).await?;
 
+            // Check if we're recording a snapshot, which is never enabled on a Worker.
+            if let Some(snapshot_interval) = self.configuration.snapshot_interval {
+                if !matches!(self.attack_phase, AttackPhase::Idle | AttackPhase::Paused)
+                    && util::timer_expired(goose_attack_run_state.snapshot_timer, snapshot_interval)
+                {
+                    self.record_snapshot(goose_attack_run_state);
+                }
+            }
+
+            // Flush metrics sinks, if any are registered.
+            if !self.metrics_sinks.is_empty()
+                && (flush
+                    || util::timer_expired(
+                        goose_attack_run_state.sink_flush_timer,
+                        self.configuration.sink_flush_interval.unwrap_or(1),
+                    ))
+            {
+                goose_attack_run_state.sink_flush_timer = std::time::Instant::now();
+                self.flush_metrics_sinks().await;
+            }
+
             // As worker, push metrics up to manager.
             if self.attack_mode == AttackMode::Worker && received_message {
                 #[cfg(feature = "gaggle")]
@@ -2298,6 +3140,8 @@ impl GooseAttack {
                         vec![
                             GaggleMetrics::Requests(self.metrics.requests.clone()),
                             GaggleMetrics::Tasks(self.metrics.tasks.clone()),
+                            GaggleMetrics::Transactions(self.metrics.transactions.clone()),
+                            GaggleMetrics::Custom(self.metrics.custom.clone()),
                         ],
                         true,
                     ) {
@@ -2308,6 +3152,8 @@ impl GooseAttack {
                     }
                     // The manager has all our metrics, reset locally.
                     self.metrics.requests = HashMap::new();
+                    self.metrics.transactions = BTreeMap::new();
+                    self.metrics.custom = BTreeMap::new();
                     self.metrics.initialize_task_metrics(
                         &self.task_sets,
                         &self.configuration,
@@ -2327,6 +3173,16 @@ impl GooseAttack {
         Ok(())
     }
 
+    // Record a snapshot of the requests made since the previous snapshot.
+    pub(crate) fn record_snapshot(&mut self, goose_attack_run_state: &mut GooseAttackRunState) {
+        let interval = goose_attack_run_state
+            .snapshot_timer
+            .elapsed()
+            .as_secs_f32();
+        goose_attack_run_state.snapshot_timer = std::time::Instant::now();
+        self.metrics.record_snapshot(interval);
+    }
+
     // When the [`GooseAttack`](./struct.GooseAttack.html) goes from the `Starting`
     // phase to the `Running` phase, optionally flush metrics.
     pub(crate) async fn reset_metrics(
@@ -2339,7 +3195,8 @@ impl GooseAttack {
             self.sync_metrics(goose_attack_run_state, true).await?;
 
             goose_attack_run_state.all_users_spawned = true;
-            let users = self.configuration.users.unwrap();
+            // If running a load profile, the first stage defines how many users are hatched.
+            let users = self.target_users(goose_attack_run_state);
             if !self.configuration.no_reset_metrics {
                 // Display the running metrics collected so far, before resetting them.
                 self.update_duration();
@@ -2361,6 +3218,8 @@ impl GooseAttack {
                 }
 
                 self.metrics.requests = HashMap::new();
+                self.metrics.transactions = BTreeMap::new();
+                self.metrics.custom = BTreeMap::new();
                 self.metrics.initialize_task_metrics(
                     &self.task_sets,
                     &self.configuration,
@@ -2387,42 +3246,31 @@ impl GooseAttack {
     // Also writes it to the request_file if enabled.
     async fn record_request_metric(&mut self, request_metric: &GooseRequestMetric) {
         let key = format!("{} {}", request_metric.raw.method, request_metric.name);
-        let mut merge_request = match self.metrics.requests.get(&key) {
-            Some(m) => m.clone(),
-            None => GooseRequestMetricAggregate::new(
+        // Status codes are always tracked when exported to Prometheus.
+        let status_codes = self.configuration.status_codes || self.configuration.prometheus;
+        // Update in place, as cloning response time histograms is expensive.
+        let new_request = || {
+            GooseRequestMetricAggregate::new(
                 &request_metric.name,
                 request_metric.raw.method.clone(),
                 0,
-            ),
+            )
         };
 
-        // Handle a metrics update.
-        if request_metric.update {
-            if request_metric.success {
-                merge_request.success_count += 1;
-                merge_request.fail_count -= 1;
-            } else {
-                merge_request.success_count -= 1;
-                merge_request.fail_count += 1;
-            }
-        }
-        // Store a new metric.
-        else {
-            merge_request.record_time(
-                request_metric.response_time,
-                request_metric.coordinated_omission_elapsed > 0,
-            );
-            if self.configuration.status_codes {
-                merge_request.set_status_code(request_metric.status_code);
-            }
-            if request_metric.success {
-                merge_request.success_count += 1;
-            } else {
-                merge_request.fail_count += 1;
-            }
+        // If recording snapshots, also track requests made since the previous snapshot.
+        if self.configuration.snapshot_interval.is_some() {
+            self.metrics
+                .snapshot_requests
+                .entry(key.clone())
+                .or_insert_with(new_request)
+                .record_request(request_metric, status_codes);
         }
 
-        self.metrics.requests.insert(key, merge_request);
+        self.metrics
+            .requests
+            .entry(key)
+            .or_insert_with(new_request)
+            .record_request(request_metric, status_codes);
     }
 
     // Receive metrics from [`GooseUser`](./goose/struct.GooseUser.html) threads. If flush
@@ -2445,8 +3293,14 @@ impl GooseAttack {
             received_message = true;
             match message.unwrap() {
                 GooseMetric::Request(request_metric) => {
-                    // If there was an error, store it.
-                    if !request_metric.error.is_empty() {
+                    // Send the request to all metrics sinks.
+                    self.sink_request_metric(&request_metric);
+
+                    // If there was an error, store it, unless a failed request was
+                    // since marked a success.
+                    if request_metric.update && request_metric.success {
+                        self.forget_error(&request_metric);
+                    } else if !request_metric.error.is_empty() {
                         self.record_error(&request_metric, goose_attack_run_state);
                     }
 
@@ -2469,6 +3323,7 @@ impl GooseAttack {
                             // Backfill until reaching the expected request cadence.
                             if response_time > request_metric.response_time as i64 {
                                 co_metric.response_time = response_time as u64;
+                                co_metric.response_time_micros = response_time as u64 * 1_000;
                                 self.record_request_metric(&co_metric).await;
                                 response_time -= request_metric.user_cadence as i64;
                             } else {
@@ -2483,9 +3338,36 @@ impl GooseAttack {
                     }
                 }
                 GooseMetric::Task(raw_task) => {
+                    // Send the task to all metrics sinks.
+                    self.sink_task_metric(&raw_task);
+
                     // Store a new metric.
                     self.metrics.tasks[raw_task.taskset_index][raw_task.task_index]
-                        .set_time(raw_task.run_time, raw_task.success);
+                        .set_time(raw_task.run_time_micros, raw_task.success);
+                }
+                GooseMetric::Transaction(raw_transaction) => {
+                    self.metrics
+                        .transactions
+                        .entry(raw_transaction.name.clone())
+                        .or_insert_with(|| {
+                            GooseTransactionMetricAggregate::new(&raw_transaction.name)
+                        })
+                        .set_time(raw_transaction.run_time_micros, raw_transaction.success);
+                }
+                GooseMetric::Custom(raw_custom) => {
+                    let kind = raw_custom.value.kind();
+                    if !self
+                        .metrics
+                        .custom
+                        .entry(raw_custom.name.clone())
+                        .or_insert_with(|| GooseCustomMetricAggregate::new(&raw_custom.name, kind))
+                        .record(&raw_custom.value)
+                    {
+                        warn!(
+                            "ignoring {} value of {} custom metric {}",
+                            kind, self.metrics.custom[&raw_custom.name].kind, raw_custom.name
+                        );
+                    }
                 }
             }
             // Unless flushing all metrics, break out of receive loop after timeout.
@@ -2549,10 +3431,30 @@ impl GooseAttack {
         self.metrics.errors.insert(error_string, error_metrics);
     }
 
+    /// Remove one occurrence of the error of a request that was marked a success after
+    /// failing, as it's no longer an error.
+    pub(crate) fn forget_error(&mut self, raw_request: &GooseRequestMetric) {
+        let error_string = format!(
+            "{}.{}.{}",
+            raw_request.error, raw_request.raw.method, raw_request.name
+        );
+        if let Some(error_metrics) = self.metrics.errors.get_mut(&error_string) {
+            error_metrics.occurrences -= 1;
+            if error_metrics.occurrences == 0 {
+                self.metrics.errors.remove(&error_string);
+            }
+        }
+    }
+
     // Update metrics showing how long the load test has been running.
     pub(crate) fn update_duration(&mut self) {
         if let Some(started) = self.started {
-            self.metrics.duration = started.elapsed().as_secs() as usize;
+            // Time spent paused doesn't count towards the duration of the load test.
+            let elapsed = match self.paused {
+                Some(paused) => paused.saturating_duration_since(started),
+                None => started.elapsed(),
+            };
+            self.metrics.duration = elapsed.as_secs() as usize;
         } else {
             self.metrics.duration = 0;
         }
@@ -2626,10 +3528,10 @@ impl GooseAttack {
             let mut raw_aggregate_response_time_counter: usize = 0;
             let mut raw_aggregate_response_time_minimum: usize = 0;
             let mut raw_aggregate_response_time_maximum: usize = 0;
-            let mut raw_aggregate_response_times: BTreeMap<usize, usize> = BTreeMap::new();
+            let mut raw_aggregate_response_times = GooseHistogram::new();
             let mut co_aggregate_response_time_counter: usize = 0;
             let mut co_aggregate_response_time_maximum: usize = 0;
-            let mut co_aggregate_response_times: BTreeMap<usize, usize> = BTreeMap::new();
+            let mut co_aggregate_response_times = GooseHistogram::new();
             let mut co_data = false;
             for (request_key, request) in self.metrics.requests.iter().sorted() {
                 // Determine whether or not to include Coordinated Omission data.
@@ -2670,9 +3572,7 @@ impl GooseAttack {
                     &method,
                     &name,
                     &request.raw_data.times,
-                    request.raw_data.counter,
-                    request.raw_data.minimum_time,
-                    request.raw_data.maximum_time,
+                    &self.metrics.percentiles,
                 ));
 
                 // Collect aggregated request and response metrics.
@@ -2687,8 +3587,7 @@ impl GooseAttack {
                     raw_aggregate_response_time_maximum,
                     request.raw_data.maximum_time,
                 );
-                raw_aggregate_response_times =
-                    merge_times(raw_aggregate_response_times, request.raw_data.times.clone());
+                raw_aggregate_response_times.add(&request.raw_data.times);
             }
 
             // Prepare aggregate per-request metrics.
@@ -2718,9 +3617,7 @@ impl GooseAttack {
                 "",
                 "Aggregated",
                 &raw_aggregate_response_times,
-                raw_aggregate_total_count,
-                raw_aggregate_response_time_minimum,
-                raw_aggregate_response_time_maximum,
+                &self.metrics.percentiles,
             ));
 
             // Compile the request metrics template.
@@ -2735,6 +3632,9 @@ impl GooseAttack {
                 raw_responses_rows.push(report::response_metrics_row(metric));
             }
 
+            // Build the response metrics table headers.
+            let percentiles_header = report::percentiles_header(&self.metrics.percentiles);
+
             let co_requests_template: String;
             let co_responses_template: String;
             if co_data {
@@ -2770,9 +3670,7 @@ impl GooseAttack {
                             &method,
                             &name,
                             &coordinated_omission_data.times,
-                            coordinated_omission_data.counter,
-                            coordinated_omission_data.minimum_time,
-                            coordinated_omission_data.maximum_time,
+                            &self.metrics.percentiles,
                         ));
 
                         // Collect aggregated request and response metrics.
@@ -2781,10 +3679,7 @@ impl GooseAttack {
                             co_aggregate_response_time_maximum,
                             coordinated_omission_data.maximum_time,
                         );
-                        co_aggregate_response_times = merge_times(
-                            co_aggregate_response_times,
-                            coordinated_omission_data.times.clone(),
-                        );
+                        co_aggregate_response_times.add(&coordinated_omission_data.times);
                     }
                     let total_request_count = request.success_count + request.fail_count;
                     co_aggregate_total_count += total_request_count;
@@ -2812,9 +3707,7 @@ impl GooseAttack {
                     "",
                     "Aggregated",
                     &co_aggregate_response_times,
-                    co_aggregate_total_count,
-                    raw_aggregate_response_time_minimum,
-                    co_aggregate_response_time_maximum,
+                    &self.metrics.percentiles,
                 ));
 
                 // Compile the co_request metrics rows.
@@ -2838,6 +3731,7 @@ impl GooseAttack {
                 // Compile the status_code metrics template.
                 co_responses_template = report::coordinated_omission_response_metrics_template(
                     &co_response_rows.join("\n"),
+                    &percentiles_header,
                 );
             } else {
                 // If --status-codes is not enabled, return an empty template.
@@ -2854,7 +3748,7 @@ impl GooseAttack {
                 let mut aggregate_task_time_counter: usize = 0;
                 let mut aggregate_task_time_minimum: usize = 0;
                 let mut aggregate_task_time_maximum: usize = 0;
-                let mut aggregate_task_times: BTreeMap<usize, usize> = BTreeMap::new();
+                let mut aggregate_task_times = GooseHistogram::new();
                 for (task_set_counter, task_set) in self.metrics.tasks.iter().enumerate() {
                     for (task_counter, task) in task_set.iter().enumerate() {
                         if task_counter == 0 {
@@ -2897,8 +3791,7 @@ impl GooseAttack {
 
                         aggregate_total_count += total_run_count;
                         aggregate_fail_count += task.fail_count;
-                        aggregate_task_times =
-                            merge_times(aggregate_task_times, task.times.clone());
+                        aggregate_task_times.add(&task.times);
                         aggregate_task_time_counter += &task.counter;
                         aggregate_task_time_minimum =
                             update_min_time(aggregate_task_time_minimum, task.min_time);
@@ -2939,6 +3832,124 @@ impl GooseAttack {
                 tasks_template = "".to_string();
             }
 
+            // Only build the transactions template if any transactions ran.
+            let transactions_template = if !self.metrics.transactions.is_empty() {
+                let mut transaction_rows = Vec::new();
+                let mut transaction_times_rows = Vec::new();
+                let mut aggregate = GooseTransactionMetricAggregate::new("Aggregated");
+                let mut transactions: Vec<&GooseTransactionMetricAggregate> =
+                    self.metrics.transactions.values().collect();
+                for transaction in self.metrics.transactions.values() {
+                    aggregate.merge(transaction);
+                }
+                if transactions.len() > 1 {
+                    transactions.push(&aggregate);
+                }
+                for transaction in transactions {
+                    let total_run_count = transaction.success_count + transaction.fail_count;
+                    let (runs_per_second, failures_per_second) = per_second_calculations(
+                        self.metrics.duration,
+                        total_run_count,
+                        transaction.fail_count,
+                    );
+                    let average = match transaction.counter {
+                        0 => 0.00,
+                        _ => transaction.total_time as f32 / transaction.counter as f32,
+                    };
+                    transaction_rows.push(report::transaction_metrics_row(
+                        report::TransactionMetric {
+                            name: transaction.name.to_string(),
+                            number_of_runs: total_run_count,
+                            number_of_failures: transaction.fail_count,
+                            run_time_average: format!("{:.2}", average),
+                            run_time_minimum: transaction.min_time,
+                            run_time_maximum: transaction.max_time,
+                            runs_per_second: format!("{:.2}", runs_per_second),
+                            failures_per_second: format!("{:.2}", failures_per_second),
+                        },
+                    ));
+                    transaction_times_rows.push(report::transaction_times_row(
+                        report::get_response_metric(
+                            "",
+                            &transaction.name,
+                            &transaction.times,
+                            &self.metrics.percentiles,
+                        ),
+                    ));
+                }
+                report::transaction_metrics_template(
+                    &transaction_rows.join("\n"),
+                    &transaction_times_rows.join("\n"),
+                    &percentiles_header,
+                )
+            } else {
+                "".to_string()
+            };
+
+            // Only build the custom metrics template if any custom metrics were recorded.
+            let custom_template = if !self.metrics.custom.is_empty() {
+                let mut counter_rows = Vec::new();
+                let mut gauge_rows = Vec::new();
+                let mut timing_rows = Vec::new();
+                for custom in self.metrics.custom.values() {
+                    match custom.kind {
+                        GooseCustomMetricKind::Counter => {
+                            let (per_second, _) = per_second_calculations(
+                                self.metrics.duration,
+                                custom.total as usize,
+                                0,
+                            );
+                            counter_rows.push(report::custom_metrics_row(report::CustomMetric {
+                                name: custom.name.to_string(),
+                                values: vec![
+                                    format_number(custom.total as usize),
+                                    format!("{:.2}", per_second),
+                                ],
+                            }));
+                        }
+                        GooseCustomMetricKind::Gauge => {
+                            gauge_rows.push(report::custom_metrics_row(report::CustomMetric {
+                                name: custom.name.to_string(),
+                                values: vec![
+                                    format!("{:.2}", custom.last),
+                                    format!("{:.2}", custom.minimum),
+                                    format!("{:.2}", custom.average()),
+                                    format!("{:.2}", custom.maximum),
+                                ],
+                            }));
+                        }
+                        GooseCustomMetricKind::Timing => {
+                            let mut values = vec![
+                                format!("{:.2}", custom.average()),
+                                format!("{:.0}", custom.minimum),
+                                format!("{:.0}", custom.maximum),
+                            ];
+                            values.extend(
+                                report::get_response_metric(
+                                    "",
+                                    &custom.name,
+                                    &custom.times,
+                                    &self.metrics.percentiles,
+                                )
+                                .percentiles,
+                            );
+                            timing_rows.push(report::custom_metrics_row(report::CustomMetric {
+                                name: custom.name.to_string(),
+                                values,
+                            }));
+                        }
+                    }
+                }
+                report::custom_metrics_template(
+                    &counter_rows.join("\n"),
+                    &gauge_rows.join("\n"),
+                    &timing_rows.join("\n"),
+                    &percentiles_header,
+                )
+            } else {
+                "".to_string()
+            };
+
             // Only build the tasks template if --no-task-metrics isn't enabled.
             let errors_template: String;
             if !self.metrics.errors.is_empty() {
@@ -2956,6 +3967,7 @@ impl GooseAttack {
             if self.configuration.status_codes {
                 let mut status_code_metrics = Vec::new();
                 let mut aggregated_status_code_counts: HashMap<u16, usize> = HashMap::new();
+                let mut aggregated_grpc_status_code_counts: HashMap<u16, usize> = HashMap::new();
                 for (request_key, request) in self.metrics.requests.iter().sorted() {
                     let method = format!("{}", request.method);
                     // The request_key is "{method} {name}", so by stripping the "{method} "
@@ -2966,9 +3978,15 @@ impl GooseAttack {
                         .to_string();
 
                     // Build a list of status codes, and update the aggregate record.
-                    let codes = prepare_status_codes(
-                        &request.status_code_counts,
-                        &mut Some(&mut aggregated_status_code_counts),
+                    let codes = join_status_codes(
+                        prepare_status_codes(
+                            &request.status_code_counts,
+                            &mut Some(&mut aggregated_status_code_counts),
+                        ),
+                        prepare_grpc_status_codes(
+                            &request.grpc_status_code_counts,
+                            &mut Some(&mut aggregated_grpc_status_code_counts),
+                        ),
                     );
 
                     // Add a row of data for the status code table.
@@ -2980,8 +3998,10 @@ impl GooseAttack {
                 }
 
                 // Build a list of aggregate status codes.
-                let aggregated_codes =
-                    prepare_status_codes(&aggregated_status_code_counts, &mut None);
+                let aggregated_codes = join_status_codes(
+                    prepare_status_codes(&aggregated_status_code_counts, &mut None),
+                    prepare_grpc_status_codes(&aggregated_grpc_status_code_counts, &mut None),
+                );
 
                 // Add a final row of aggregate data for the status code table.
                 status_code_metrics.push(report::StatusCodeMetric {
@@ -3014,9 +4034,20 @@ impl GooseAttack {
                     raw_responses_template: &raw_responses_rows.join("\n"),
                     co_requests_template: &co_requests_template,
                     co_responses_template: &co_responses_template,
+                    percentiles_header: &percentiles_header,
                     tasks_template: &tasks_template,
+                    transactions_template: &transactions_template,
+                    custom_template: &custom_template,
                     status_codes_template: &status_code_template,
                     errors_template: &errors_template,
+                    charts_template: &report::charts_template(
+                        &self.metrics.snapshots,
+                        &self.metrics.percentiles,
+                    ),
+                    baseline_template: &match self.metrics.baseline.as_ref() {
+                        Some(comparison) => report::baseline_template(comparison),
+                        None => "".to_string(),
+                    },
                 },
             );
 
@@ -3068,25 +4099,25 @@ pub(crate) fn format_number(number: usize) -> String {
     (number).to_formatted_string(&Locale::en)
 }
 
-/// A helper function that merges together times.
-///
-/// Used in `lib.rs` to merge together per-thread times, and in `metrics.rs` to
-/// aggregate all times.
-pub(crate) fn merge_times(
-    mut global_response_times: BTreeMap<usize, usize>,
-    local_response_times: BTreeMap<usize, usize>,
-) -> BTreeMap<usize, usize> {
-    // Iterate over user response times, and merge into global response times.
-    for (response_time, count) in &local_response_times {
-        let counter = match global_response_times.get(response_time) {
-            // We've seen this response_time before, increment counter.
-            Some(c) => *c + count,
-            // First time we've seen this response time, initialize counter.
-            None => *count,
-        };
-        global_response_times.insert(*response_time, counter);
-    }
-    global_response_times
+/// Display a single row of the transaction times table.
+fn fmt_transaction_times_row(
+    fmt: &mut fmt::Formatter<'_>,
+    transaction: &GooseTransactionMetricAggregate,
+) -> fmt::Result {
+    let average = match transaction.counter {
+        0 => 0.00,
+        _ => transaction.total_time as f32 / transaction.counter as f32,
+    };
+    writeln!(
+        fmt,
+        " {:<24} | {:>11.avg_precision$} | {:>10} | {:>11} | {:>10}",
+        util::truncate_string(&transaction.name, 24),
+        average,
+        format_number(transaction.min_time),
+        format_number(transaction.max_time),
+        calculate_response_time_percentile(&transaction.times, 50.0),
+        avg_precision = determine_precision(average),
+    )
 }
 
 /// A helper function to update the global minimum time based on local time.
@@ -3105,55 +4136,107 @@ pub(crate) fn update_max_time(mut global_max: usize, max: usize) -> usize {
     global_max
 }
 
-/// Get the response time that a certain number of percent of the requests finished within.
+/// Response time percentiles displayed if `--percentiles` is not configured.
+pub(crate) const DEFAULT_PERCENTILES: [f64; 6] = [50.0, 75.0, 98.0, 99.0, 99.9, 99.99];
+
+/// Parse a comma separated list of percentiles, such as "50,90,99,99.9".
+pub(crate) fn parse_percentiles(percentiles: &str) -> Result<Vec<f64>, String> {
+    let mut parsed = Vec::new();
+    for percentile in percentiles.split(',') {
+        match percentile.trim().parse::<f64>() {
+            Ok(p) if p > 0.0 && p <= 100.0 => parsed.push(p),
+            _ => {
+                return Err(format!(
+                    "invalid percentile \"{}\", percentiles must be numbers greater than 0 and no larger than 100.",
+                    percentile
+                ))
+            }
+        }
+    }
+    Ok(parsed)
+}
+
+/// Returns response times in milliseconds at each of the requested percentiles.
+fn percentiles_in_millis(times: &GooseHistogram, percentiles: &[f64]) -> Vec<f64> {
+    percentiles
+        .iter()
+        .map(|percentile| times.value_at_percentile(*percentile) as f64 / 1_000.0)
+        .collect()
+}
+
+/// Format a time in microseconds as milliseconds, with fractions for times under 1 second.
+pub(crate) fn format_micros(micros: u64) -> String {
+    let milliseconds = micros as f32 / 1_000.0;
+    let precision = determine_precision(milliseconds);
+    if precision == 0 {
+        format_number(milliseconds.round() as usize)
+    } else {
+        format!("{:.*}", precision, milliseconds)
+    }
+}
+
+/// Get the response time that a certain percent of the requests finished within.
 pub(crate) fn calculate_response_time_percentile(
-    response_times: &BTreeMap<usize, usize>,
-    total_requests: usize,
-    min: usize,
-    max: usize,
-    percent: f32,
+    response_times: &GooseHistogram,
+    percentile: f64,
 ) -> String {
-    let percentile_request = (total_requests as f32 * percent).round() as usize;
     debug!(
-        "percentile: {}, request {} of total {}",
-        percent, percentile_request, total_requests
+        "percentile: {}, of {} response times",
+        percentile,
+        response_times.len()
     );
+    format_micros(response_times.value_at_percentile(percentile))
+}
 
-    let mut total_count: usize = 0;
+/// Helper to count and aggregate seen status codes.
+pub(crate) fn prepare_status_codes(
+    status_code_counts: &HashMap<u16, usize>,
+    aggregate_counts: &mut Option<&mut HashMap<u16, usize>>,
+) -> String {
+    prepare_labelled_status_codes(status_code_counts, aggregate_counts, "")
+}
 
-    for (value, counter) in response_times {
-        total_count += counter;
-        if total_count >= percentile_request {
-            if *value < min {
-                return format_number(min);
-            } else if *value > max {
-                return format_number(max);
-            } else {
-                return format_number(*value);
-            }
-        }
+/// Helper to count and aggregate seen gRPC status codes, labelled so they can't be mistaken
+/// for HTTP status codes.
+pub(crate) fn prepare_grpc_status_codes(
+    grpc_status_code_counts: &HashMap<u16, usize>,
+    aggregate_counts: &mut Option<&mut HashMap<u16, usize>>,
+) -> String {
+    prepare_labelled_status_codes(grpc_status_code_counts, aggregate_counts, "gRPC ")
+}
+
+/// Helper to list HTTP status codes followed by gRPC status codes.
+pub(crate) fn join_status_codes(status_codes: String, grpc_status_codes: String) -> String {
+    if status_codes.is_empty() {
+        grpc_status_codes
+    } else if grpc_status_codes.is_empty() {
+        status_codes
+    } else {
+        format!("{}, {}", status_codes, grpc_status_codes)
     }
-    format_number(0)
 }
 
-/// Helper to count and aggregate seen status codes.
-pub(crate) fn prepare_status_codes(
+/// Helper to count and aggregate seen status codes, labelling each of them.
+fn prepare_labelled_status_codes(
     status_code_counts: &HashMap<u16, usize>,
     aggregate_counts: &mut Option<&mut HashMap<u16, usize>>,
+    label: &str,
 ) -> String {
     let mut codes: String = "".to_string();
     for (status_code, count) in status_code_counts {
         if codes.is_empty() {
             codes = format!(
-                "{} [{}]",
+                "{} [{}{}]",
                 count.to_formatted_string(&Locale::en),
+                label,
                 status_code
             );
         } else {
             codes = format!(
-                "{}, {} [{}]",
+                "{}, {} [{}{}]",
                 codes.clone(),
                 count.to_formatted_string(&Locale::en),
+                label,
                 status_code
             );
         }
@@ -3202,45 +4285,68 @@ mod test {
 
     #[test]
     fn response_time_merge() {
-        let mut global_response_times: BTreeMap<usize, usize> = BTreeMap::new();
-        let local_response_times: BTreeMap<usize, usize> = BTreeMap::new();
-        global_response_times = merge_times(global_response_times, local_response_times.clone());
-        // @TODO: how can we do useful testing of private method and objects?
-        assert_eq!(&global_response_times, &local_response_times);
+        let mut global_response_times = GooseHistogram::new();
+        let mut local_response_times = GooseHistogram::new();
+        global_response_times.add(&local_response_times);
+        assert!(global_response_times.is_empty());
+
+        global_response_times.record(1_000);
+        local_response_times.record(1_000);
+        local_response_times.record(2_000);
+        global_response_times.add(&local_response_times);
+        assert_eq!(global_response_times.len(), 3);
+        assert_eq!(
+            global_response_times.iter().collect::<Vec<(u64, u64)>>(),
+            vec![(1_000, 2), (2_000, 1)]
+        );
+
+        // Histograms are unchanged after being sent from a Worker to the Manager.
+        let serialized = serde_cbor::to_vec(&global_response_times).unwrap();
+        let deserialized: GooseHistogram = serde_cbor::from_slice(&serialized).unwrap();
+        assert_eq!(deserialized, global_response_times);
     }
 
     #[test]
     fn max_response_time_percentile() {
-        let mut response_times: BTreeMap<usize, usize> = BTreeMap::new();
-        response_times.insert(1, 1);
-        response_times.insert(2, 1);
-        response_times.insert(3, 1);
-        // 3 * .5 = 1.5, rounds to 2.
-        assert!(calculate_response_time_percentile(&response_times, 3, 1, 3, 0.5) == "2");
-        response_times.insert(3, 2);
-        // 4 * .5 = 2
-        assert!(calculate_response_time_percentile(&response_times, 4, 1, 3, 0.5) == "2");
-        // 4 * .25 = 1
-        assert!(calculate_response_time_percentile(&response_times, 4, 1, 3, 0.25) == "1");
-        // 4 * .75 = 3
-        assert!(calculate_response_time_percentile(&response_times, 4, 1, 3, 0.75) == "3");
-        // 4 * 1 = 4 (and the 4th response time is also 3)
-        assert!(calculate_response_time_percentile(&response_times, 4, 1, 3, 1.0) == "3");
-
-        // 4 * .5 = 2, but uses specified minimum of 2
-        assert!(calculate_response_time_percentile(&response_times, 4, 2, 3, 0.25) == "2");
-        // 4 * .75 = 3, but uses specified maximum of 2
-        assert!(calculate_response_time_percentile(&response_times, 4, 1, 2, 0.75) == "2");
-
-        response_times.insert(10, 25);
-        response_times.insert(20, 25);
-        response_times.insert(30, 25);
-        response_times.insert(50, 25);
-        response_times.insert(100, 10);
-        response_times.insert(200, 1);
-        assert!(calculate_response_time_percentile(&response_times, 115, 1, 200, 0.9) == "50");
-        assert!(calculate_response_time_percentile(&response_times, 115, 1, 200, 0.99) == "100");
-        assert!(calculate_response_time_percentile(&response_times, 115, 1, 200, 0.999) == "200");
+        let mut response_times = GooseHistogram::new();
+        response_times.record(1_000);
+        response_times.record(2_000);
+        response_times.record(3_000);
+        assert!(calculate_response_time_percentile(&response_times, 50.0) == "2.00");
+        response_times.record(3_000);
+        assert!(calculate_response_time_percentile(&response_times, 50.0) == "2.00");
+        assert!(calculate_response_time_percentile(&response_times, 25.0) == "1.00");
+        assert!(calculate_response_time_percentile(&response_times, 75.0) == "3.00");
+        assert!(calculate_response_time_percentile(&response_times, 100.0) == "3.00");
+
+        // Microsecond resolution is preserved.
+        response_times.record(1_234);
+        assert!(calculate_response_time_percentile(&response_times, 40.0) == "1.23");
+
+        for (milliseconds, count) in [(10, 25), (20, 25), (30, 25), (50, 25), (100, 10), (200, 1)] {
+            for _ in 0..count {
+                response_times.record(milliseconds * 1_000);
+            }
+        }
+        // Percentiles of larger times are accurate to within 0.1%.
+        for (percentile, milliseconds) in [(90.0, 50), (99.0, 100), (99.9, 200)] {
+            let micros = response_times.value_at_percentile(percentile);
+            assert!(micros >= milliseconds * 1_000);
+            assert!(micros <= milliseconds * 1_001);
+        }
+    }
+
+    #[test]
+    fn parse_response_time_percentiles() {
+        assert_eq!(
+            parse_percentiles("50,90, 99,99.9,99.99").unwrap(),
+            vec![50.0, 90.0, 99.0, 99.9, 99.99]
+        );
+        assert_eq!(parse_percentiles("100").unwrap(), vec![100.0]);
+        assert!(parse_percentiles("0").is_err());
+        assert!(parse_percentiles("101").is_err());
+        assert!(parse_percentiles("50,,99").is_err());
+        assert!(parse_percentiles("p99").is_err());
     }
 
     #[test]
@@ -3282,11 +4388,12 @@ mod test {
         assert!(!request_metric.update);
 
         let response_time = 123;
-        request_metric.set_response_time(response_time);
+        request_metric.set_response_time(Duration::from_micros(123_456));
         assert_eq!(request_metric.raw.method, GooseMethod::Get);
         assert_eq!(request_metric.name, "/".to_string());
         assert_eq!(request_metric.raw.url, PATH.to_string());
         assert_eq!(request_metric.response_time, response_time as u64);
+        assert_eq!(request_metric.response_time_micros, 123_456);
         assert_eq!(request_metric.status_code, 0);
         assert!(request_metric.success);
         assert!(!request_metric.update);
@@ -3307,7 +4414,7 @@ mod test {
         let mut request = GooseRequestMetricAggregate::new("/", GooseMethod::Get, 0);
         assert_eq!(request.path, "/".to_string());
         assert_eq!(request.method, GooseMethod::Get);
-        assert_eq!(request.raw_data.times.len(), 0);
+        assert!(request.raw_data.times.is_empty());
         assert_eq!(request.raw_data.minimum_time, 0);
         assert_eq!(request.raw_data.maximum_time, 0);
         assert_eq!(request.raw_data.total_time, 0);
@@ -3317,11 +4424,11 @@ mod test {
         assert_eq!(request.fail_count, 0);
 
         // Tracking a response time updates several fields.
-        request.record_time(1, false);
+        request.record_time(1_000, false);
         // We've seen only one response time so far.
-        assert_eq!(request.raw_data.times.len(), 1);
-        // We've seen one response time of length 1.
-        assert_eq!(request.raw_data.times[&1], 1);
+        assert_eq!(request.raw_data.times.iter().count(), 1);
+        // We've seen one response time of length 1 ms.
+        assert_eq!(request.raw_data.times.0.count_at(1_000), 1);
         // The minimum response time seen so far is 1.
         assert_eq!(request.raw_data.minimum_time, 1);
         // The maximum response time seen so far is 1.
@@ -3338,11 +4445,11 @@ mod test {
         assert_eq!(request.fail_count, 0);
 
         // Tracking another response time updates all related fields.
-        request.record_time(10, false);
+        request.record_time(10_000, false);
         // We've added a new unique response time.
-        assert_eq!(request.raw_data.times.len(), 2);
+        assert_eq!(request.raw_data.times.iter().count(), 2);
         // We've seen the 10 ms response time 1 time.
-        assert_eq!(request.raw_data.times[&10], 1);
+        assert_eq!(request.raw_data.times.0.count_at(10_000), 1);
         // Minimum doesn't change.
         assert_eq!(request.raw_data.minimum_time, 1);
         // Maximum is new response time.
@@ -3359,11 +4466,11 @@ mod test {
         assert_eq!(request.fail_count, 0);
 
         // Tracking another response time updates all related fields.
-        request.record_time(10, false);
+        request.record_time(10_000, false);
         // We've incremented the counter of an existing response time.
-        assert_eq!(request.raw_data.times.len(), 2);
+        assert_eq!(request.raw_data.times.iter().count(), 2);
         // We've seen the 10 ms response time 2 times.
-        assert_eq!(request.raw_data.times[&10], 2);
+        assert_eq!(request.raw_data.times.0.count_at(10_000), 2);
         // Minimum doesn't change.
         assert_eq!(request.raw_data.minimum_time, 1);
         // Maximum doesn't change.
@@ -3374,14 +4481,14 @@ mod test {
         assert_eq!(request.raw_data.counter, 3);
 
         // Tracking another response time updates all related fields.
-        request.record_time(101, false);
+        request.record_time(101_000, false);
         // We've added a new response time for the first time.
-        assert_eq!(request.raw_data.times.len(), 3);
-        // The response time was internally rounded to 100, which we've seen once.
-        assert_eq!(request.raw_data.times[&100], 1);
+        assert_eq!(request.raw_data.times.iter().count(), 3);
+        // We've seen the 101 ms response time once.
+        assert_eq!(request.raw_data.times.0.count_at(101_000), 1);
         // Minimum doesn't change.
         assert_eq!(request.raw_data.minimum_time, 1);
-        // Maximum increases to actual maximum, not rounded maximum.
+        // Maximum is new response time.
         assert_eq!(request.raw_data.maximum_time, 101);
         // Total combined response times is now 122 ms.
         assert_eq!(request.raw_data.total_time, 122);
@@ -3389,62 +4496,62 @@ mod test {
         assert_eq!(request.raw_data.counter, 4);
 
         // Tracking another response time updates all related fields.
-        request.record_time(102, false);
-        // Due to rounding, this increments the existing 100 ms response time.
-        assert_eq!(request.raw_data.times.len(), 3);
-        // The response time was internally rounded to 100, which we've now seen twice.
-        assert_eq!(request.raw_data.times[&100], 2);
+        request.record_time(101_050, false);
+        // Response times within 0.1% of each other are counted together.
+        assert_eq!(request.raw_data.times.iter().count(), 3);
+        // The response time is counted with the 101 ms response time, now seen twice.
+        assert_eq!(request.raw_data.times.0.count_at(101_000), 2);
         // Minimum doesn't change.
         assert_eq!(request.raw_data.minimum_time, 1);
-        // Maximum increases to actual maximum, not rounded maximum.
-        assert_eq!(request.raw_data.maximum_time, 102);
-        // Add 102 to the total response time so far.
-        assert_eq!(request.raw_data.total_time, 224);
+        // Maximum doesn't change, as it's tracked in milliseconds.
+        assert_eq!(request.raw_data.maximum_time, 101);
+        // Add 101 to the total response time so far.
+        assert_eq!(request.raw_data.total_time, 223);
         // We've seen five response times so far.
         assert_eq!(request.raw_data.counter, 5);
 
         // Tracking another response time updates all related fields.
-        request.record_time(155, false);
+        request.record_time(155_000, false);
         // Adds a new response time.
-        assert_eq!(request.raw_data.times.len(), 4);
-        // The response time was internally rounded to 160, seen for the first time.
-        assert_eq!(request.raw_data.times[&160], 1);
+        assert_eq!(request.raw_data.times.iter().count(), 4);
+        // We've seen the 155 ms response time once.
+        assert_eq!(request.raw_data.times.0.count_at(155_000), 1);
         // Minimum doesn't change.
         assert_eq!(request.raw_data.minimum_time, 1);
-        // Maximum increases to actual maximum, not rounded maximum.
+        // Maximum is new response time.
         assert_eq!(request.raw_data.maximum_time, 155);
         // Add 155 to the total response time so far.
-        assert_eq!(request.raw_data.total_time, 379);
+        assert_eq!(request.raw_data.total_time, 378);
         // We've seen six response times so far.
         assert_eq!(request.raw_data.counter, 6);
 
         // Tracking another response time updates all related fields.
-        request.record_time(2345, false);
+        request.record_time(2_345_000, false);
         // Adds a new response time.
-        assert_eq!(request.raw_data.times.len(), 5);
-        // The response time was internally rounded to 2000, seen for the first time.
-        assert_eq!(request.raw_data.times[&2000], 1);
+        assert_eq!(request.raw_data.times.iter().count(), 5);
+        // We've seen the 2,345 ms response time once.
+        assert_eq!(request.raw_data.times.0.count_at(2_345_000), 1);
         // Minimum doesn't change.
         assert_eq!(request.raw_data.minimum_time, 1);
-        // Maximum increases to actual maximum, not rounded maximum.
+        // Maximum is new response time.
         assert_eq!(request.raw_data.maximum_time, 2345);
         // Add 2345 to the total response time so far.
-        assert_eq!(request.raw_data.total_time, 2724);
+        assert_eq!(request.raw_data.total_time, 2723);
         // We've seen seven response times so far.
         assert_eq!(request.raw_data.counter, 7);
 
         // Tracking another response time updates all related fields.
-        request.record_time(987654321, false);
+        request.record_time(987_654_321_000, false);
         // Adds a new response time.
-        assert_eq!(request.raw_data.times.len(), 6);
-        // The response time was internally rounded to 987654000, seen for the first time.
-        assert_eq!(request.raw_data.times[&987654000], 1);
+        assert_eq!(request.raw_data.times.iter().count(), 6);
+        // We've seen the 987,654,321 ms response time once.
+        assert_eq!(request.raw_data.times.0.count_at(987_654_321_000), 1);
         // Minimum doesn't change.
         assert_eq!(request.raw_data.minimum_time, 1);
-        // Maximum increases to actual maximum, not rounded maximum.
+        // Maximum is new response time.
         assert_eq!(request.raw_data.maximum_time, 987654321);
         // Add 987654321 to the total response time so far.
-        assert_eq!(request.raw_data.total_time, 987657045);
+        assert_eq!(request.raw_data.total_time, 987657044);
         // We've seen eight response times so far.
         assert_eq!(request.raw_data.counter, 8);
 
@@ -3458,10 +4565,10 @@ mod test {
         assert_eq!(request.success_count, 0);
         assert_eq!(request.fail_count, 0);
         // Nothing else changes.
-        assert_eq!(request.raw_data.times.len(), 6);
+        assert_eq!(request.raw_data.times.iter().count(), 6);
         assert_eq!(request.raw_data.minimum_time, 1);
         assert_eq!(request.raw_data.maximum_time, 987654321);
-        assert_eq!(request.raw_data.total_time, 987657045);
+        assert_eq!(request.raw_data.total_time, 987657044);
         assert_eq!(request.raw_data.counter, 8);
 
         // Tracking status code updates all related fields.
@@ -3501,10 +4608,77 @@ mod test {
         // Nothing else changes.
         assert_eq!(request.success_count, 0);
         assert_eq!(request.fail_count, 0);
-        assert_eq!(request.raw_data.times.len(), 6);
+        assert_eq!(request.raw_data.times.iter().count(), 6);
         assert_eq!(request.raw_data.minimum_time, 1);
         assert_eq!(request.raw_data.maximum_time, 987654321);
-        assert_eq!(request.raw_data.total_time, 987657045);
+        assert_eq!(request.raw_data.total_time, 987657044);
         assert_eq!(request.raw_data.counter, 8);
     }
+
+    #[test]
+    fn goose_snapshot() {
+        let mut metrics = GooseMetrics {
+            users: 2,
+            percentiles: vec![50.0, 100.0],
+            ..Default::default()
+        };
+
+        // Record 3 successful and 1 failed request in the current interval.
+        let raw_request = GooseRawRequest::new(GooseMethod::Get, "/", vec![], "");
+        let mut request_metric = GooseRequestMetric::new(raw_request, "/", 0, 0);
+        let mut request = GooseRequestMetricAggregate::new("/", GooseMethod::Get, 0);
+        for (micros, success) in [(500, true), (1_000, true), (1_500, true), (2_000, false)] {
+            request_metric.response_time_micros = micros;
+            request_metric.success = success;
+            request.record_request(&request_metric, false);
+        }
+        metrics
+            .snapshot_requests
+            .insert("GET /".to_string(), request);
+
+        // Rates are calculated from the length of the interval.
+        metrics.record_snapshot(2.0);
+        assert_eq!(metrics.snapshots.len(), 1);
+        assert_eq!(metrics.snapshots[0].users, 2);
+        let snapshot = &metrics.snapshots[0].requests["GET /"];
+        assert!((snapshot.requests_per_second - 2.0).abs() < f32::EPSILON);
+        assert!((snapshot.failures_per_second - 0.5).abs() < f32::EPSILON);
+        assert_eq!(snapshot.response_time_percentiles, vec![1.0, 2.0]);
+
+        // With only one request, the aggregated metrics are the same.
+        assert_eq!(&metrics.snapshots[0].aggregated, snapshot);
+
+        // The next snapshot starts empty.
+        assert!(metrics.snapshot_requests.is_empty());
+        metrics.record_snapshot(1.0);
+        assert_eq!(metrics.snapshots.len(), 2);
+        assert!(metrics.snapshots[1].requests.is_empty());
+    }
+
+    #[test]
+    fn custom_metrics() {
+        let mut gauge = GooseCustomMetricAggregate::new("depth", GooseCustomMetricKind::Gauge);
+        for value in &[4.0, -2.0, 10.0] {
+            assert!(gauge.record(&GooseCustomMetricValue::Gauge(*value)));
+        }
+        assert_eq!(gauge.samples, 3);
+        assert!((gauge.minimum + 2.0).abs() < f64::EPSILON);
+        assert!((gauge.maximum - 10.0).abs() < f64::EPSILON);
+        assert!((gauge.last - 10.0).abs() < f64::EPSILON);
+        assert!((gauge.average() - 4.0).abs() < f64::EPSILON);
+
+        // Values of another kind are ignored.
+        assert!(!gauge.record(&GooseCustomMetricValue::Counter(1)));
+        assert_eq!(gauge.samples, 3);
+
+        // Timings are tracked in milliseconds, and in a histogram of microseconds.
+        let mut timing = GooseCustomMetricAggregate::new("job", GooseCustomMetricKind::Timing);
+        assert!(timing.record(&GooseCustomMetricValue::Timing(1_500)));
+        assert!(timing.record(&GooseCustomMetricValue::Timing(500)));
+        assert_eq!(timing.samples, 2);
+        assert_eq!(timing.times.len(), 2);
+        assert!((timing.minimum - 0.5).abs() < f64::EPSILON);
+        assert!((timing.maximum - 1.5).abs() < f64::EPSILON);
+        assert!((timing.total - 2.0).abs() < f64::EPSILON);
+    }
 }
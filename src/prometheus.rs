@@ -0,0 +1,577 @@
+//! Optional Prometheus metrics endpoint.
+//!
+//! When enabled with `--prometheus`, Goose launches a minimal HTTP listener next to the
+//! telnet and WebSocket Controllers. It serves the running metrics at `/metrics` in the
+//! [Prometheus text format](https://prometheus.io/docs/instrumenting/exposition_formats/),
+//! so a load test can be scraped and graphed alongside the system it's testing. The same
+//! output is available from the Controllers with the `prometheus` command.
+
+use std::fmt::{Display, Write};
+use std::io;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::config::GooseConfiguration;
+use crate::controller::{
+    GooseControllerCommand, GooseControllerRequest, GooseControllerRequestMessage,
+    GooseControllerResponse, GooseControllerResponseMessage,
+};
+use crate::metrics::{GooseCustomMetricKind, GooseHistogram, GooseMetrics};
+use crate::{AttackPhase, GooseAttack};
+
+/// Upper bounds of the response time histogram buckets in seconds, the same default
+/// buckets used by the official Prometheus client libraries.
+const BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// All phases of a load test, each exported with a value of 1 if active and 0 if not.
+const ATTACK_PHASES: [AttackPhase; 8] = [
+    AttackPhase::Idle,
+    AttackPhase::Starting,
+    AttackPhase::Running,
+    AttackPhase::Increase,
+    AttackPhase::Decrease,
+    AttackPhase::Paused,
+    AttackPhase::Stopping,
+    AttackPhase::Shutdown,
+];
+
+/// The largest HTTP request header accepted from a client.
+const MAX_REQUEST_SIZE: usize = 8192;
+
+/// The content type of the Prometheus text format.
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// The parent process side of the Prometheus metrics endpoint.
+impl GooseAttack {
+    // Render the current metrics in the Prometheus text format.
+    pub(crate) fn prometheus_metrics(&self) -> String {
+        render_metrics(&self.metrics, &self.attack_phase)
+    }
+}
+
+/// Render metrics and the current phase of the load test in the Prometheus text format.
+fn render_metrics(metrics: &GooseMetrics, attack_phase: &AttackPhase) -> String {
+    let mut output = String::new();
+
+    write_header(
+        &mut output,
+        "goose_attack_phase",
+        "gauge",
+        "Current phase of the load test.",
+    );
+    for phase in &ATTACK_PHASES {
+        let name = format!("{:?}", phase).to_lowercase();
+        write_sample(
+            &mut output,
+            "goose_attack_phase",
+            &[("phase", &name)],
+            (phase == attack_phase) as u8,
+        );
+    }
+
+    write_header(
+        &mut output,
+        "goose_users",
+        "gauge",
+        "Number of GooseUsers running.",
+    );
+    write_sample(&mut output, "goose_users", &[], metrics.users);
+
+    // Sort requests so the output is stable from one scrape to the next.
+    let mut requests: Vec<_> = metrics.requests.values().collect();
+    requests.sort_by(|a, b| (&a.path, a.method.to_string()).cmp(&(&b.path, b.method.to_string())));
+    let requests: Vec<_> = requests
+        .into_iter()
+        .map(|request| (request.method.to_string(), request))
+        .collect();
+
+    write_header(
+        &mut output,
+        "goose_requests_total",
+        "counter",
+        "Requests made, by method and name.",
+    );
+    for (method, request) in &requests {
+        write_sample(
+            &mut output,
+            "goose_requests_total",
+            &[("method", method), ("name", &request.path)],
+            request.success_count + request.fail_count,
+        );
+    }
+
+    write_header(
+        &mut output,
+        "goose_request_failures_total",
+        "counter",
+        "Failed requests, by method and name.",
+    );
+    for (method, request) in &requests {
+        write_sample(
+            &mut output,
+            "goose_request_failures_total",
+            &[("method", method), ("name", &request.path)],
+            request.fail_count,
+        );
+    }
+
+    write_header(
+        &mut output,
+        "goose_request_status_codes_total",
+        "counter",
+        "Responses received, by method, name and status code.",
+    );
+    for (method, request) in &requests {
+        let mut status_codes: Vec<_> = request.status_code_counts.iter().collect();
+        status_codes.sort();
+        for (status_code, count) in status_codes {
+            write_sample(
+                &mut output,
+                "goose_request_status_codes_total",
+                &[
+                    ("method", method),
+                    ("name", &request.path),
+                    ("status_code", &status_code.to_string()),
+                ],
+                count,
+            );
+        }
+    }
+
+    write_header(
+        &mut output,
+        "goose_request_grpc_status_codes_total",
+        "counter",
+        "gRPC calls completed, by method, name and gRPC status code.",
+    );
+    for (method, request) in &requests {
+        let mut grpc_status_codes: Vec<_> = request.grpc_status_code_counts.iter().collect();
+        grpc_status_codes.sort();
+        for (grpc_status_code, count) in grpc_status_codes {
+            write_sample(
+                &mut output,
+                "goose_request_grpc_status_codes_total",
+                &[
+                    ("method", method),
+                    ("name", &request.path),
+                    ("grpc_status_code", &grpc_status_code.to_string()),
+                ],
+                count,
+            );
+        }
+    }
+
+    write_header(
+        &mut output,
+        "goose_response_time_seconds",
+        "histogram",
+        "Response times, by method and name.",
+    );
+    for (method, request) in &requests {
+        write_histogram(
+            &mut output,
+            "goose_response_time_seconds",
+            &[("method", method), ("name", &request.path)],
+            &request.raw_data.times,
+        );
+    }
+
+    let tasks: Vec<_> = metrics.tasks.iter().flatten().collect();
+
+    write_header(
+        &mut output,
+        "goose_tasks_total",
+        "counter",
+        "Tasks run, by task set and task.",
+    );
+    for task in &tasks {
+        let index = format!("{}.{}", task.taskset_index + 1, task.task_index + 1);
+        write_sample(
+            &mut output,
+            "goose_tasks_total",
+            &[
+                ("index", &index),
+                ("taskset", &task.taskset_name),
+                ("task", &task.task_name),
+            ],
+            task.success_count + task.fail_count,
+        );
+    }
+
+    write_header(
+        &mut output,
+        "goose_task_failures_total",
+        "counter",
+        "Failed tasks, by task set and task.",
+    );
+    for task in &tasks {
+        let index = format!("{}.{}", task.taskset_index + 1, task.task_index + 1);
+        write_sample(
+            &mut output,
+            "goose_task_failures_total",
+            &[
+                ("index", &index),
+                ("taskset", &task.taskset_name),
+                ("task", &task.task_name),
+            ],
+            task.fail_count,
+        );
+    }
+
+    let custom_of_kind = |kind: GooseCustomMetricKind| {
+        metrics
+            .custom
+            .values()
+            .filter(move |custom| custom.kind == kind)
+    };
+
+    write_header(
+        &mut output,
+        "goose_custom_counter_total",
+        "counter",
+        "Custom counters recorded by tasks, by name.",
+    );
+    for counter in custom_of_kind(GooseCustomMetricKind::Counter) {
+        write_sample(
+            &mut output,
+            "goose_custom_counter_total",
+            &[("name", &counter.name)],
+            counter.total,
+        );
+    }
+
+    write_header(
+        &mut output,
+        "goose_custom_gauge",
+        "gauge",
+        "Last value of custom gauges recorded by tasks, by name.",
+    );
+    for gauge in custom_of_kind(GooseCustomMetricKind::Gauge) {
+        write_sample(
+            &mut output,
+            "goose_custom_gauge",
+            &[("name", &gauge.name)],
+            gauge.last,
+        );
+    }
+
+    write_header(
+        &mut output,
+        "goose_custom_timing_seconds",
+        "histogram",
+        "Custom timings recorded by tasks, by name.",
+    );
+    for timing in custom_of_kind(GooseCustomMetricKind::Timing) {
+        write_histogram(
+            &mut output,
+            "goose_custom_timing_seconds",
+            &[("name", &timing.name)],
+            &timing.times,
+        );
+    }
+
+    output
+}
+
+/// Write the HELP and TYPE lines that precede the samples of a metric.
+fn write_header(output: &mut String, name: &str, metric_type: &str, help: &str) {
+    let _ = writeln!(output, "# HELP {} {}", name, help);
+    let _ = writeln!(output, "# TYPE {} {}", name, metric_type);
+}
+
+/// Write a single sample, with optional labels.
+fn write_sample<T: Display>(output: &mut String, name: &str, labels: &[(&str, &str)], value: T) {
+    let labels = labels
+        .iter()
+        .map(|(label, value)| format!("{}=\"{}\"", label, escape_label_value(value)))
+        .collect::<Vec<_>>();
+    if labels.is_empty() {
+        let _ = writeln!(output, "{} {}", name, value);
+    } else {
+        let _ = writeln!(output, "{}{{{}}} {}", name, labels.join(","), value);
+    }
+}
+
+/// Write the cumulative buckets, sum and count of a histogram of times in microseconds.
+fn write_histogram(
+    output: &mut String,
+    name: &str,
+    labels: &[(&str, &str)],
+    times: &GooseHistogram,
+) {
+    let bucket_name = format!("{}_bucket", name);
+    for bucket in &BUCKETS {
+        let limit = (bucket * 1_000_000.0) as u64;
+        let count: u64 = times
+            .iter()
+            .filter(|(micros, _)| *micros <= limit)
+            .map(|(_, count)| count)
+            .sum();
+        let le = bucket.to_string();
+        let mut bucket_labels = labels.to_vec();
+        bucket_labels.push(("le", &le));
+        write_sample(output, &bucket_name, &bucket_labels, count);
+    }
+    let mut bucket_labels = labels.to_vec();
+    bucket_labels.push(("le", "+Inf"));
+    write_sample(output, &bucket_name, &bucket_labels, times.len());
+
+    let sum: u64 = times.iter().map(|(micros, count)| micros * count).sum();
+    write_sample(
+        output,
+        &format!("{}_sum", name),
+        labels,
+        sum as f64 / 1_000_000.0,
+    );
+    write_sample(output, &format!("{}_count", name), labels, times.len());
+}
+
+/// Escape a label value as required by the Prometheus text format.
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('"', r#"\""#)
+        .replace('\n', r"\n")
+}
+
+/// Launch the Prometheus metrics endpoint, serving each scrape in its own thread.
+pub(crate) async fn prometheus_main(
+    // Expose load test configuration to the endpoint thread.
+    configuration: GooseConfiguration,
+    // For sending requests to the parent process.
+    channel_tx: flume::Sender<GooseControllerRequest>,
+) -> io::Result<()> {
+    let address = format!(
+        "{}:{}",
+        configuration.prometheus_host, configuration.prometheus_port
+    );
+
+    debug!(
+        "preparing to bind Prometheus metrics endpoint to: {}",
+        address
+    );
+    let listener = TcpListener::bind(&address).await?;
+    info!("Prometheus metrics endpoint listening on: {}", address);
+
+    // Counter increments each time a client connects, identifying it to the parent.
+    let mut client_id: u32 = 0;
+
+    // Wait for a connection.
+    while let Ok((stream, _)) = listener.accept().await {
+        client_id += 1;
+
+        // The returned JoinHandle is ignored as the thread exits after one response.
+        tokio::spawn(serve_client(stream, client_id, channel_tx.clone()));
+    }
+
+    Ok(())
+}
+
+/// Read a single HTTP request from the client, and write the response.
+async fn serve_client(
+    mut stream: TcpStream,
+    client_id: u32,
+    channel_tx: flume::Sender<GooseControllerRequest>,
+) {
+    let response = match read_request(&mut stream).await {
+        Some((method, path)) if method == "GET" && path == "/metrics" => {
+            match request_metrics(client_id, &channel_tx).await {
+                Ok(metrics) => http_response("200 OK", CONTENT_TYPE, &metrics),
+                Err(e) => http_response("503 Service Unavailable", "text/plain", &e),
+            }
+        }
+        Some((_, path)) if path == "/metrics" => http_response(
+            "405 Method Not Allowed",
+            "text/plain",
+            "method not allowed\n",
+        ),
+        Some(_) => http_response("404 Not Found", "text/plain", "not found\n"),
+        None => http_response("400 Bad Request", "text/plain", "bad request\n"),
+    };
+
+    if stream.write_all(response.as_bytes()).await.is_err() {
+        warn!("failed to write Prometheus metrics to client {}", client_id);
+    }
+    let _ = stream.shutdown().await;
+}
+
+/// Read the request headers, returning the method and path from the request line.
+async fn read_request(stream: &mut TcpStream) -> Option<(String, String)> {
+    let mut request = Vec::new();
+    let mut buf = [0; 1024];
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+        match stream.read(&mut buf).await {
+            Ok(0) | Err(_) => return None,
+            Ok(n) => request.extend_from_slice(&buf[..n]),
+        }
+        if request.len() > MAX_REQUEST_SIZE {
+            return None;
+        }
+    }
+
+    let request = String::from_utf8_lossy(&request);
+    let mut request_line = request.lines().next()?.split_whitespace();
+    let method = request_line.next()?.to_string();
+    // Ignore any query string.
+    let path = request_line.next()?.split('?').next()?.to_string();
+    Some((method, path))
+}
+
+/// Ask the parent process for the current metrics in the Prometheus text format.
+async fn request_metrics(
+    client_id: u32,
+    channel_tx: &flume::Sender<GooseControllerRequest>,
+) -> Result<String, String> {
+    let (response_tx, response_rx): (
+        tokio::sync::oneshot::Sender<GooseControllerResponse>,
+        tokio::sync::oneshot::Receiver<GooseControllerResponse>,
+    ) = tokio::sync::oneshot::channel();
+
+    if channel_tx
+        .try_send(GooseControllerRequest {
+            response_channel: Some(response_tx),
+            client_id,
+            request: GooseControllerRequestMessage {
+                command: GooseControllerCommand::Prometheus,
+                value: None,
+            },
+        })
+        .is_err()
+    {
+        return Err("parent process has closed the controller channel\n".to_string());
+    }
+
+    match response_rx.await {
+        Ok(GooseControllerResponse {
+            response: GooseControllerResponseMessage::Prometheus(metrics),
+            ..
+        }) => Ok(metrics),
+        Ok(_) => Err("error loading metrics\n".to_string()),
+        Err(e) => Err(format!("one-shot channel dropped without reply: {}\n", e)),
+    }
+}
+
+/// Build a complete HTTP response, closing the connection after it's sent.
+fn http_response(status: &str, content_type: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::goose::GooseMethod;
+    use crate::metrics::{
+        GooseCustomMetricAggregate, GooseCustomMetricValue, GooseRequestMetricAggregate,
+        GooseTaskMetricAggregate,
+    };
+
+    #[test]
+    fn prometheus_format() {
+        let mut metrics = GooseMetrics {
+            users: 3,
+            ..Default::default()
+        };
+
+        let mut request = GooseRequestMetricAggregate::new("/", GooseMethod::Get, 0);
+        // Times are recorded in microseconds.
+        for micros in &[1_000, 2_000, 20_000, 20_000_000] {
+            request.record_time(*micros, false);
+        }
+        request.set_status_code(200);
+        request.set_status_code(200);
+        request.set_status_code(200);
+        request.set_status_code(500);
+        request.success_count = 3;
+        request.fail_count = 1;
+        metrics.requests.insert("GET /".to_string(), request);
+
+        let mut request = GooseRequestMetricAggregate::new(
+            "/test.Echo/Say",
+            GooseMethod::Protocol("GRPC".to_string()),
+            0,
+        );
+        request.set_grpc_status_code(0);
+        request.set_grpc_status_code(5);
+        metrics
+            .requests
+            .insert("GRPC /test.Echo/Say".to_string(), request);
+
+        let mut task = GooseTaskMetricAggregate::new(0, "LoadTest", 1, "get \"index\"");
+        task.success_count = 5;
+        task.fail_count = 2;
+        metrics.tasks.push(vec![task]);
+
+        for (name, value) in &[
+            ("items", GooseCustomMetricValue::Counter(3)),
+            ("items", GooseCustomMetricValue::Counter(4)),
+            ("depth", GooseCustomMetricValue::Gauge(2.5)),
+            ("job", GooseCustomMetricValue::Timing(20_000)),
+        ] {
+            metrics
+                .custom
+                .entry(name.to_string())
+                .or_insert_with(|| GooseCustomMetricAggregate::new(name, value.kind()))
+                .record(value);
+        }
+
+        let output = render_metrics(&metrics, &AttackPhase::Running);
+        let lines: Vec<&str> = output.lines().collect();
+
+        // Only the current phase is active.
+        assert!(lines.contains(&r#"goose_attack_phase{phase="running"} 1"#));
+        assert!(lines.contains(&r#"goose_attack_phase{phase="idle"} 0"#));
+        assert!(lines.contains(&"goose_users 3"));
+
+        assert!(lines.contains(&r#"goose_requests_total{method="GET",name="/"} 4"#));
+        assert!(lines.contains(&r#"goose_request_failures_total{method="GET",name="/"} 1"#));
+        assert!(lines.contains(
+            &r#"goose_request_status_codes_total{method="GET",name="/",status_code="200"} 3"#
+        ));
+        assert!(lines.contains(
+            &r#"goose_request_status_codes_total{method="GET",name="/",status_code="500"} 1"#
+        ));
+        assert!(lines.contains(
+            &r#"goose_request_grpc_status_codes_total{method="GRPC",name="/test.Echo/Say",grpc_status_code="5"} 1"#
+        ));
+        assert!(!lines
+            .iter()
+            .any(|line| line.contains(r#"name="/test.Echo/Say",status_code="#)));
+
+        // Buckets are cumulative, and the slowest request only fits in +Inf.
+        assert!(lines.contains(
+            &r#"goose_response_time_seconds_bucket{method="GET",name="/",le="0.005"} 2"#
+        ));
+        assert!(lines.contains(
+            &r#"goose_response_time_seconds_bucket{method="GET",name="/",le="0.025"} 3"#
+        ));
+        assert!(lines
+            .contains(&r#"goose_response_time_seconds_bucket{method="GET",name="/",le="10"} 3"#));
+        assert!(lines
+            .contains(&r#"goose_response_time_seconds_bucket{method="GET",name="/",le="+Inf"} 4"#));
+        assert!(lines.contains(&r#"goose_response_time_seconds_count{method="GET",name="/"} 4"#));
+
+        // Label values are escaped.
+        assert!(lines.contains(
+            &r#"goose_tasks_total{index="1.2",taskset="LoadTest",task="get \"index\""} 7"#
+        ));
+        assert!(lines.contains(
+            &r#"goose_task_failures_total{index="1.2",taskset="LoadTest",task="get \"index\""} 2"#
+        ));
+
+        assert!(lines.contains(&r#"goose_custom_counter_total{name="items"} 7"#));
+        assert!(lines.contains(&r#"goose_custom_gauge{name="depth"} 2.5"#));
+        assert!(lines.contains(&r#"goose_custom_timing_seconds_bucket{name="job",le="0.025"} 1"#));
+
+        // Every metric is preceded by its type.
+        assert!(lines.contains(&"# TYPE goose_response_time_seconds histogram"));
+        assert!(lines.contains(&"# TYPE goose_requests_total counter"));
+    }
+}
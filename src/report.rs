@@ -1,9 +1,7 @@
 //! Optionally writes an html-formatted summary report after running a load test.
 
-use crate::metrics;
-
-use std::collections::BTreeMap;
-use std::mem;
+use crate::baseline::{GooseBaselineComparison, GooseBaselineDelta, GooseBaselineRow};
+use crate::metrics::{self, GooseHistogram, GooseMetricsSnapshot};
 
 use serde::Serialize;
 
@@ -14,9 +12,14 @@ pub struct GooseReportTemplates<'a> {
     pub raw_responses_template: &'a str,
     pub co_requests_template: &'a str,
     pub co_responses_template: &'a str,
+    pub percentiles_header: &'a str,
     pub tasks_template: &'a str,
+    pub transactions_template: &'a str,
+    pub custom_template: &'a str,
     pub status_codes_template: &'a str,
     pub errors_template: &'a str,
+    pub charts_template: &'a str,
+    pub baseline_template: &'a str,
 }
 
 /// Defines the metrics reported about requests.
@@ -48,14 +51,7 @@ pub struct CORequestMetric {
 pub struct ResponseMetric {
     pub method: String,
     pub name: String,
-    pub percentile_50: String,
-    pub percentile_60: String,
-    pub percentile_70: String,
-    pub percentile_80: String,
-    pub percentile_90: String,
-    pub percentile_95: String,
-    pub percentile_99: String,
-    pub percentile_100: String,
+    pub percentiles: Vec<String>,
 }
 
 /// Defines the metrics reported about tasks.
@@ -73,6 +69,26 @@ pub struct TaskMetric {
     pub failures_per_second: String,
 }
 
+/// Defines the metrics reported about transactions.
+#[derive(Debug, Clone, Serialize)]
+pub struct TransactionMetric {
+    pub name: String,
+    pub number_of_runs: usize,
+    pub number_of_failures: usize,
+    pub run_time_average: String,
+    pub run_time_minimum: usize,
+    pub run_time_maximum: usize,
+    pub runs_per_second: String,
+    pub failures_per_second: String,
+}
+
+/// Defines the metrics reported about a custom counter, gauge or timing.
+#[derive(Debug, Clone, Serialize)]
+pub struct CustomMetric {
+    pub name: String,
+    pub values: Vec<String>,
+}
+
 /// Defines the metrics reported about status codes.
 pub struct StatusCodeMetric {
     pub method: String,
@@ -84,38 +100,40 @@ pub struct StatusCodeMetric {
 pub fn get_response_metric(
     method: &str,
     name: &str,
-    response_times: &BTreeMap<usize, usize>,
-    total_request_count: usize,
-    response_time_minimum: usize,
-    response_time_maximum: usize,
+    response_times: &GooseHistogram,
+    percentiles: &[f64],
 ) -> ResponseMetric {
-    // Calculate percentiles in a loop.
-    let mut percentiles = Vec::new();
-    for percent in &[0.5, 0.6, 0.7, 0.8, 0.9, 0.95, 0.99, 1.0] {
-        percentiles.push(metrics::calculate_response_time_percentile(
-            response_times,
-            total_request_count,
-            response_time_minimum,
-            response_time_maximum,
-            *percent,
-        ));
-    }
-
-    // Now take the Strings out of the Vector and build a ResponseMetric object.
     ResponseMetric {
         method: method.to_string(),
         name: name.to_string(),
-        percentile_50: mem::take(&mut percentiles[0]),
-        percentile_60: mem::take(&mut percentiles[1]),
-        percentile_70: mem::take(&mut percentiles[2]),
-        percentile_80: mem::take(&mut percentiles[3]),
-        percentile_90: mem::take(&mut percentiles[4]),
-        percentile_95: mem::take(&mut percentiles[5]),
-        percentile_99: mem::take(&mut percentiles[6]),
-        percentile_100: mem::take(&mut percentiles[7]),
+        percentiles: percentiles
+            .iter()
+            .map(|percentile| {
+                metrics::calculate_response_time_percentile(response_times, *percentile)
+            })
+            .collect(),
     }
 }
 
+/// Build the response metrics table headers, one for each configured percentile.
+pub fn percentiles_header(percentiles: &[f64]) -> String {
+    percentiles
+        .iter()
+        .map(|percentile| format!("<th>{}%ile (ms)</th>", percentile))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Build the cells of an individual row of response metrics in the html report.
+fn percentiles_cells(metric: &ResponseMetric) -> String {
+    metric
+        .percentiles
+        .iter()
+        .map(|percentile| format!("<td>{}</td>", percentile))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
 /// Build an individual row of raw request metrics in the html report.
 pub fn raw_request_metrics_row(metric: RequestMetric) -> String {
     format!(
@@ -148,25 +166,11 @@ pub fn response_metrics_row(metric: ResponseMetric) -> String {
         r#"<tr>
             <td>{method}</td>
             <td>{name}</td>
-            <td>{percentile_50}</td>
-            <td>{percentile_60}</td>
-            <td>{percentile_70}</td>
-            <td>{percentile_80}</td>
-            <td>{percentile_90}</td>
-            <td>{percentile_95}</td>
-            <td>{percentile_99}</td>
-            <td>{percentile_100}</td>
+            {percentiles}
         </tr>"#,
         method = metric.method,
         name = metric.name,
-        percentile_50 = metric.percentile_50,
-        percentile_60 = metric.percentile_60,
-        percentile_70 = metric.percentile_70,
-        percentile_80 = metric.percentile_80,
-        percentile_90 = metric.percentile_90,
-        percentile_95 = metric.percentile_95,
-        percentile_99 = metric.percentile_99,
-        percentile_100 = metric.percentile_100,
+        percentiles = percentiles_cells(&metric),
     )
 }
 
@@ -216,7 +220,10 @@ pub fn coordinated_omission_request_metrics_row(metric: CORequestMetric) -> Stri
 
 /// If Coordinated Omission Mitigation is triggered, add a relevant response table to the
 /// html report.
-pub fn coordinated_omission_response_metrics_template(co_responses_rows: &str) -> String {
+pub fn coordinated_omission_response_metrics_template(
+    co_responses_rows: &str,
+    percentiles_header: &str,
+) -> String {
     format!(
         r#"<div class="responses">
         <h2>Response Time Metrics With Coordinated Omission Mitigation</h2>
@@ -225,14 +232,7 @@ pub fn coordinated_omission_response_metrics_template(co_responses_rows: &str) -
                 <tr>
                     <th>Method</th>
                     <th>Name</th>
-                    <th>50%ile (ms)</th>
-                    <th>60%ile (ms)</th>
-                    <th>70%ile (ms)</th>
-                    <th>80%ile (ms)</th>
-                    <th>90%ile (ms)</th>
-                    <th>95%ile (ms)</th>
-                    <th>99%ile (ms)</th>
-                    <th>100%ile (ms)</th>
+                    {percentiles_header}
                 </tr>
             </thead>
             <tbody>
@@ -241,6 +241,7 @@ pub fn coordinated_omission_response_metrics_template(co_responses_rows: &str) -
         </table>
     </div>"#,
         co_responses_rows = co_responses_rows,
+        percentiles_header = percentiles_header,
     )
 }
 
@@ -251,25 +252,11 @@ pub fn coordinated_omission_response_metrics_row(metric: ResponseMetric) -> Stri
         r#"<tr>
             <td>{method}</td>
             <td>{name}</td>
-            <td>{percentile_50}</td>
-            <td>{percentile_60}</td>
-            <td>{percentile_70}</td>
-            <td>{percentile_80}</td>
-            <td>{percentile_90}</td>
-            <td>{percentile_95}</td>
-            <td>{percentile_99}</td>
-            <td>{percentile_100}</td>
+            {percentiles}
         </tr>"#,
         method = metric.method,
         name = metric.name,
-        percentile_50 = metric.percentile_50,
-        percentile_60 = metric.percentile_60,
-        percentile_70 = metric.percentile_70,
-        percentile_80 = metric.percentile_80,
-        percentile_90 = metric.percentile_90,
-        percentile_95 = metric.percentile_95,
-        percentile_99 = metric.percentile_99,
-        percentile_100 = metric.percentile_100,
+        percentiles = percentiles_cells(&metric),
     )
 }
 
@@ -310,6 +297,85 @@ pub fn status_code_metrics_row(metric: StatusCodeMetric) -> String {
     )
 }
 
+/// If a baseline is configured, add tables comparing requests and tasks against the
+/// baseline to the html report.
+pub fn baseline_template(comparison: &GooseBaselineComparison) -> String {
+    let mut tables = String::new();
+    for (header, rows) in &[
+        ("Request", &comparison.requests),
+        ("Task", &comparison.tasks),
+    ] {
+        if rows.is_empty() {
+            continue;
+        }
+        let rows: Vec<String> = rows.iter().map(baseline_row).collect();
+        tables.push_str(&format!(
+            r#"<table>
+            <thead>
+                <tr>
+                    <th colspan="2">{header}</th>
+                    <th>RPS</th>
+                    <th>Average (ms)</th>
+                    <th>95% (ms)</th>
+                    <th>99% (ms)</th>
+                    <th>Failures</th>
+                </tr>
+            </thead>
+            <tbody>
+                {rows}
+            </tbody>
+        </table>
+        "#,
+            header = header,
+            rows = rows.join("\n"),
+        ));
+    }
+
+    format!(
+        r#"<div class="baseline">
+        <h2>Baseline Comparison</h2>
+        <p>Compared to <span>{baseline}</span>, regressions beyond {tolerance}% are highlighted.</p>
+        {tables}
+    </div>"#,
+        baseline = comparison.baseline,
+        tolerance = comparison.tolerance,
+        tables = tables,
+    )
+}
+
+/// Build an individual row of the baseline comparison in the html report.
+fn baseline_row(row: &GooseBaselineRow) -> String {
+    let cell = |delta: &GooseBaselineDelta, unit: &str| {
+        format!(
+            r#"<td{class}>{current:.2}{unit} ({delta})</td>"#,
+            class = if delta.regression {
+                r#" class="regression""#
+            } else {
+                ""
+            },
+            current = delta.current,
+            unit = unit,
+            delta = delta,
+        )
+    };
+    format!(
+        r#"<tr>
+        <td colspan="2">{name}</td>
+        {requests_per_second}
+        {average}
+        {p95}
+        {p99}
+        {failure_rate}
+    </tr>"#,
+        name = row.name,
+        requests_per_second = cell(&row.requests_per_second, ""),
+        average = cell(&row.average, ""),
+        p95 = cell(&row.p95, ""),
+        p99 = cell(&row.p99, ""),
+        failure_rate = cell(&row.failure_rate, "%"),
+    )
+}
+
 /// If task metrics are enabled, add a task metrics table to the html report.
 pub fn task_metrics_template(task_rows: &str) -> String {
     format!(
@@ -371,6 +437,181 @@ pub fn task_metrics_row(metric: TaskMetric) -> String {
     }
 }
 
+/// If there are transactions, add transaction metrics tables to the html report.
+pub fn transaction_metrics_template(
+    transaction_rows: &str,
+    transaction_times_rows: &str,
+    percentiles_header: &str,
+) -> String {
+    format!(
+        r#"<div class="transactions">
+        <h2>Transaction Metrics</h2>
+        <table>
+            <thead>
+                <tr>
+                    <th>Name</th>
+                    <th># Times Run</th>
+                    <th># Fails</th>
+                    <th>Average (ms)</th>
+                    <th>Min (ms)</th>
+                    <th>Max (ms)</th>
+                    <th>TPS</th>
+                    <th>Failures/s</th>
+                </tr>
+            </thead>
+            <tbody>
+                {transaction_rows}
+            </tbody>
+        </table>
+    </div>
+
+    <div class="transactions">
+        <h2>Transaction Time Metrics</h2>
+        <table>
+            <thead>
+                <tr>
+                    <th>Name</th>
+                    {percentiles_header}
+                </tr>
+            </thead>
+            <tbody>
+                {transaction_times_rows}
+            </tbody>
+        </table>
+    </div>"#,
+        transaction_rows = transaction_rows,
+        transaction_times_rows = transaction_times_rows,
+        percentiles_header = percentiles_header,
+    )
+}
+
+/// Build an individual row of transaction metrics in the html report.
+pub fn transaction_metrics_row(metric: TransactionMetric) -> String {
+    format!(
+        r#"<tr>
+            <td>{name}</td>
+            <td>{number_of_runs}</td>
+            <td>{number_of_failures}</td>
+            <td>{run_time_average}</td>
+            <td>{run_time_minimum}</td>
+            <td>{run_time_maximum}</td>
+            <td>{runs_per_second}</td>
+            <td>{failures_per_second}</td>
+        </tr>"#,
+        name = metric.name,
+        number_of_runs = metrics::format_number(metric.number_of_runs),
+        number_of_failures = metrics::format_number(metric.number_of_failures),
+        run_time_average = metric.run_time_average,
+        run_time_minimum = metric.run_time_minimum,
+        run_time_maximum = metric.run_time_maximum,
+        runs_per_second = metric.runs_per_second,
+        failures_per_second = metric.failures_per_second,
+    )
+}
+
+/// Build an individual row of transaction time percentiles in the html report.
+pub fn transaction_times_row(metric: ResponseMetric) -> String {
+    format!(
+        r#"<tr>
+            <td>{name}</td>
+            {percentiles}
+        </tr>"#,
+        name = metric.name,
+        percentiles = percentiles_cells(&metric),
+    )
+}
+
+/// If there are custom metrics, add a table for each kind of custom metric to the html
+/// report.
+pub fn custom_metrics_template(
+    counter_rows: &str,
+    gauge_rows: &str,
+    timing_rows: &str,
+    percentiles_header: &str,
+) -> String {
+    let mut tables = Vec::new();
+    if !counter_rows.is_empty() {
+        tables.push(format!(
+            r#"<table>
+            <thead>
+                <tr>
+                    <th>Counter</th>
+                    <th>Total</th>
+                    <th>Per second</th>
+                </tr>
+            </thead>
+            <tbody>
+                {counter_rows}
+            </tbody>
+        </table>"#,
+            counter_rows = counter_rows,
+        ));
+    }
+    if !gauge_rows.is_empty() {
+        tables.push(format!(
+            r#"<table>
+            <thead>
+                <tr>
+                    <th>Gauge</th>
+                    <th>Last</th>
+                    <th>Min</th>
+                    <th>Average</th>
+                    <th>Max</th>
+                </tr>
+            </thead>
+            <tbody>
+                {gauge_rows}
+            </tbody>
+        </table>"#,
+            gauge_rows = gauge_rows,
+        ));
+    }
+    if !timing_rows.is_empty() {
+        tables.push(format!(
+            r#"<table>
+            <thead>
+                <tr>
+                    <th>Timing</th>
+                    <th>Average (ms)</th>
+                    <th>Min (ms)</th>
+                    <th>Max (ms)</th>
+                    {percentiles_header}
+                </tr>
+            </thead>
+            <tbody>
+                {timing_rows}
+            </tbody>
+        </table>"#,
+            timing_rows = timing_rows,
+            percentiles_header = percentiles_header,
+        ));
+    }
+    format!(
+        r#"<div class="custom">
+        <h2>Custom Metrics</h2>
+        {tables}
+    </div>"#,
+        tables = tables.join("\n"),
+    )
+}
+
+/// Build an individual row of custom metrics in the html report.
+pub fn custom_metrics_row(metric: CustomMetric) -> String {
+    format!(
+        r#"<tr>
+            <td>{name}</td>
+            {values}
+        </tr>"#,
+        name = metric.name,
+        values = metric
+            .values
+            .iter()
+            .map(|value| format!("<td>{}</td>", value))
+            .collect::<Vec<String>>()
+            .join("\n"),
+    )
+}
+
 /// If there are errors, add an errors table to the html report.
 pub fn errors_template(error_rows: &str) -> String {
     format!(
@@ -404,6 +645,243 @@ pub fn error_row(error: &metrics::GooseErrorMetricAggregate) -> String {
     )
 }
 
+/// Width of each chart in the html report, matching the width of the report.
+const CHART_WIDTH: f32 = 1000.0;
+/// Height of each chart in the html report.
+const CHART_HEIGHT: f32 = 350.0;
+/// Space around the plotted area for the title, axis labels and legend: top, right,
+/// bottom, left.
+const CHART_MARGIN: (f32, f32, f32, f32) = (40.0, 20.0, 60.0, 60.0);
+/// Colors used for each line in a chart.
+const CHART_COLORS: [&str; 6] = [
+    "#00ca5a", "#2c7fb8", "#f03b20", "#fdae61", "#7b3294", "#636363",
+];
+
+/// A line drawn on a chart, with one value for each snapshot.
+struct ChartSeries {
+    name: String,
+    values: Vec<f32>,
+}
+
+/// If snapshots were recorded, add charts of how the load test changed over time to the
+/// html report.
+///
+/// Charts are drawn as inline SVG so the report doesn't load anything from the network.
+pub fn charts_template(snapshots: &[GooseMetricsSnapshot], percentiles: &[f64]) -> String {
+    if snapshots.is_empty() {
+        return "".to_string();
+    }
+
+    // Each snapshot is plotted at the end of the interval it covers.
+    let mut elapsed = 0.0;
+    let times: Vec<f32> = snapshots
+        .iter()
+        .map(|snapshot| {
+            elapsed += snapshot.interval;
+            elapsed
+        })
+        .collect();
+
+    let series = |name: &str, value: &dyn Fn(&GooseMetricsSnapshot) -> f32| ChartSeries {
+        name: name.to_string(),
+        values: snapshots.iter().map(value).collect(),
+    };
+
+    let charts = [
+        line_chart(
+            "Requests per second",
+            &times,
+            &[series("Aggregated", &|s| s.aggregated.requests_per_second)],
+        ),
+        line_chart(
+            "Response times (ms)",
+            &times,
+            &percentiles
+                .iter()
+                .enumerate()
+                .map(|(index, percentile)| {
+                    series(&format!("{}%", percentile), &|s| {
+                        s.aggregated
+                            .response_time_percentiles
+                            .get(index)
+                            .copied()
+                            .unwrap_or(0.0) as f32
+                    })
+                })
+                .collect::<Vec<ChartSeries>>(),
+        ),
+        line_chart(
+            "Errors per second",
+            &times,
+            &[series("Aggregated", &|s| s.aggregated.failures_per_second)],
+        ),
+        line_chart("Users", &times, &[series("Users", &|s| s.users as f32)]),
+    ];
+
+    format!(
+        r#"<div class="charts-container">
+        <h2>Charts</h2>
+        {charts}
+    </div>"#,
+        charts = charts.join("\n"),
+    )
+}
+
+/// Draw an SVG line chart, plotting each series against the elapsed seconds in `times`.
+fn line_chart(title: &str, times: &[f32], series: &[ChartSeries]) -> String {
+    let (top, right, bottom, left) = CHART_MARGIN;
+    let plot_width = CHART_WIDTH - left - right;
+    let plot_height = CHART_HEIGHT - top - bottom;
+
+    let max_time = times.last().copied().unwrap_or(0.0).max(1.0);
+    let max_value = chart_maximum(
+        series
+            .iter()
+            .flat_map(|s| s.values.iter().copied())
+            .fold(0.0, f32::max),
+    );
+    let x = |time: f32| left + time / max_time * plot_width;
+    let y = |value: f32| top + plot_height - value / max_value * plot_height;
+
+    let mut svg = format!(
+        r##"<svg class="chart" viewBox="0 0 {width} {height}" xmlns="http://www.w3.org/2000/svg" font-family="Arial, Helvetica, sans-serif" font-size="12">
+            <rect width="{width}" height="{height}" fill="#fff"/>
+            <text x="{center}" y="24" text-anchor="middle" font-size="16" fill="#333">{title}</text>"##,
+        width = CHART_WIDTH,
+        height = CHART_HEIGHT,
+        center = CHART_WIDTH / 2.0,
+        title = title,
+    );
+
+    // Horizontal grid lines, labeled with values.
+    for tick in 0..=5 {
+        let value = max_value * tick as f32 / 5.0;
+        svg.push_str(&format!(
+            r##"
+            <line x1="{x1}" y1="{y:.1}" x2="{x2}" y2="{y:.1}" stroke="#e5e5e5"/>
+            <text x="{label}" y="{y:.1}" dy="4" text-anchor="end" fill="#666">{value}</text>"##,
+            x1 = left,
+            x2 = left + plot_width,
+            y = y(value),
+            label = left - 6.0,
+            value = format_chart_value(value),
+        ));
+    }
+
+    // Labels showing how long the load test had been running.
+    for tick in 0..=5 {
+        let time = max_time * tick as f32 / 5.0;
+        svg.push_str(&format!(
+            r##"
+            <text x="{x:.1}" y="{y}" text-anchor="middle" fill="#666">{time}</text>"##,
+            x = x(time),
+            y = top + plot_height + 18.0,
+            time = format_chart_time(time),
+        ));
+    }
+
+    // Axes.
+    svg.push_str(&format!(
+        r##"
+            <polyline points="{left},{top} {left},{bottom} {right},{bottom}" fill="none" stroke="#999"/>"##,
+        left = left,
+        top = top,
+        bottom = top + plot_height,
+        right = left + plot_width,
+    ));
+
+    // A line and a legend entry for each series.
+    let mut legend_x = left;
+    for (index, s) in series.iter().enumerate() {
+        let color = CHART_COLORS[index % CHART_COLORS.len()];
+        let points: Vec<(f32, f32)> = times
+            .iter()
+            .zip(&s.values)
+            .map(|(time, value)| (x(*time), y(*value)))
+            .collect();
+        svg.push_str(&format!(
+            r##"
+            <polyline points="{points}" fill="none" stroke="{color}" stroke-width="2"/>"##,
+            points = points
+                .iter()
+                .map(|(x, y)| format!("{:.1},{:.1}", x, y))
+                .collect::<Vec<String>>()
+                .join(" "),
+            color = color,
+        ));
+        // Mark each point if there are few enough to tell apart, with the value shown
+        // when hovering.
+        if points.len() <= 60 {
+            for ((x, y), value) in points.iter().zip(&s.values) {
+                svg.push_str(&format!(
+                    r##"
+            <circle cx="{x:.1}" cy="{y:.1}" r="3" fill="{color}"><title>{name}: {value}</title></circle>"##,
+                    x = x,
+                    y = y,
+                    color = color,
+                    name = s.name,
+                    value = format_chart_value(*value),
+                ));
+            }
+        }
+        svg.push_str(&format!(
+            r##"
+            <rect x="{x}" y="{y}" width="12" height="12" fill="{color}"/>
+            <text x="{text_x}" y="{text_y}" fill="#333">{name}</text>"##,
+            x = legend_x,
+            y = CHART_HEIGHT - 22.0,
+            color = color,
+            text_x = legend_x + 16.0,
+            text_y = CHART_HEIGHT - 12.0,
+            name = s.name,
+        ));
+        legend_x += 40.0 + 7.0 * s.name.len() as f32;
+    }
+
+    svg.push_str("\n        </svg>");
+    svg
+}
+
+/// Round the largest value on a chart up to 1, 2 or 5 times a power of 10, so the grid
+/// lines are labeled with round numbers.
+fn chart_maximum(value: f32) -> f32 {
+    if value <= 0.0 {
+        return 1.0;
+    }
+    let magnitude = 10_f32.powf(value.log10().floor());
+    for step in [1.0, 2.0, 5.0, 10.0] {
+        if value <= step * magnitude {
+            return step * magnitude;
+        }
+    }
+    10.0 * magnitude
+}
+
+/// Format a value on a chart, only showing fractions for small values.
+fn format_chart_value(value: f32) -> String {
+    if value >= 100.0 || value.fract() == 0.0 {
+        format!("{:.0}", value)
+    } else {
+        format!("{:.2}", value)
+    }
+}
+
+/// Format seconds elapsed since the load test started as `MM:SS`, or as `HH:MM:SS` once
+/// the load test has run for an hour.
+fn format_chart_time(seconds: f32) -> String {
+    let seconds = seconds.round() as usize;
+    if seconds >= 3600 {
+        format!(
+            "{:02}:{:02}:{:02}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        )
+    } else {
+        format!("{:02}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
 /// Build the html report.
 pub fn build_report(
     users: &str,
@@ -471,6 +949,11 @@ pub fn build_report(
         .download a {{
             color: #00ca5a;
         }}
+
+        td.regression {{
+            color: #f03b20;
+            font-weight: bold;
+        }}
     </style>
 </head>
 <body>
@@ -484,6 +967,8 @@ pub fn build_report(
             <p><span><small><em>{pkg_name} v{pkg_version}</em></small></span></pr>
         </div>
 
+        {baseline_template}
+
         <div class="requests">
             <h2>Request Metrics</h2>
             <table>
@@ -515,14 +1000,7 @@ pub fn build_report(
                     <tr>
                         <th>Method</th>
                         <th>Name</th>
-                        <th>50%ile (ms)</th>
-                        <th>60%ile (ms)</th>
-                        <th>70%ile (ms)</th>
-                        <th>80%ile (ms)</th>
-                        <th>90%ile (ms)</th>
-                        <th>95%ile (ms)</th>
-                        <th>99%ile (ms)</th>
-                        <th>100%ile (ms)</th>
+                        {percentiles_header}
                     </tr>
                 </thead>
                 <tbody>
@@ -537,8 +1015,14 @@ pub fn build_report(
 
         {tasks_template}
 
+        {transactions_template}
+
+        {custom_template}
+
         {errors_template}
 
+        {charts_template}
+
     </div>
 </body>
 </html>"#,
@@ -551,8 +1035,71 @@ pub fn build_report(
         raw_responses_template = templates.raw_responses_template,
         co_requests_template = templates.co_requests_template,
         co_responses_template = templates.co_responses_template,
+        percentiles_header = templates.percentiles_header,
         tasks_template = templates.tasks_template,
+        transactions_template = templates.transactions_template,
+        custom_template = templates.custom_template,
         status_codes_template = templates.status_codes_template,
         errors_template = templates.errors_template,
+        charts_template = templates.charts_template,
+        baseline_template = templates.baseline_template,
     )
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::metrics::GooseRequestSnapshot;
+
+    #[test]
+    fn chart_scale() {
+        // Chart maximums are rounded up to a round number.
+        assert!((chart_maximum(0.0) - 1.0).abs() < f32::EPSILON);
+        assert!((chart_maximum(0.3) - 0.5).abs() < f32::EPSILON);
+        assert!((chart_maximum(1.0) - 1.0).abs() < f32::EPSILON);
+        assert!((chart_maximum(13.0) - 20.0).abs() < f32::EPSILON);
+        assert!((chart_maximum(480.0) - 500.0).abs() < f32::EPSILON);
+        assert!((chart_maximum(501.0) - 1000.0).abs() < f32::EPSILON);
+
+        // Values only include fractions when they're small.
+        assert_eq!(format_chart_value(2.0), "2");
+        assert_eq!(format_chart_value(2.5), "2.50");
+        assert_eq!(format_chart_value(123.4), "123");
+
+        // Elapsed times only include hours after running for an hour.
+        assert_eq!(format_chart_time(0.0), "00:00");
+        assert_eq!(format_chart_time(75.4), "01:15");
+        assert_eq!(format_chart_time(3_725.0), "01:02:05");
+    }
+
+    #[test]
+    fn charts() {
+        // Without snapshots, no charts are added to the report.
+        assert_eq!(charts_template(&[], &[50.0]), "");
+
+        let snapshot = GooseMetricsSnapshot {
+            timestamp: 0,
+            interval: 5.0,
+            users: 2,
+            aggregated: GooseRequestSnapshot {
+                requests_per_second: 10.0,
+                failures_per_second: 1.0,
+                response_time_percentiles: vec![3.0, 9.0],
+            },
+            ..Default::default()
+        };
+        let charts = charts_template(&[snapshot.clone(), snapshot], &[50.0, 99.0]);
+
+        // Requests, response times, errors and users are charted.
+        assert_eq!(charts.matches("<svg").count(), 4);
+        // A line is drawn for each percentile.
+        assert!(charts.contains(">50%</text>"));
+        assert!(charts.contains(">99%</text>"));
+        // Points are plotted at the end of each interval.
+        assert!(charts.contains(">00:10</text>"));
+        // Charts are self-contained.
+        assert!(!charts.contains("<script"));
+        assert!(!charts.contains("src="));
+        assert!(!charts.contains("href="));
+    }
+}
@@ -0,0 +1,709 @@
+//! Optional metrics sinks, streaming metrics to external time-series stores.
+//!
+//! A [`GooseMetricsSink`] receives each [`GooseRequestMetric`] and [`GooseTaskMetric`]
+//! as the parent thread receives it from the [`GooseUser`](../goose/struct.GooseUser.html)
+//! threads, and is regularly flushed while the load test runs. This allows results to be
+//! graphed as the load test runs, without waiting for the end-of-test report.
+//!
+//! Goose includes two sinks which are enabled with run-time options:
+//!  - `--statsd HOST:PORT` sends metrics to [StatsD](https://github.com/statsd/statsd)
+//!    over UDP, with tags in the DogStatsD format.
+//!  - `--influxdb URL` sends metrics to [InfluxDB](https://www.influxdata.com/) in the
+//!    line protocol, over UDP (ie `udp://127.0.0.1:8089`) or HTTP (ie
+//!    `http://127.0.0.1:8086/write?db=goose`).
+//!
+//! Both optionally add the tags set with `--sink-tags` to all metrics, and are flushed
+//! every `--sink-flush-interval` seconds. Custom sinks can be registered with
+//! [`GooseAttack::register_metrics_sink`](../struct.GooseAttack.html#method.register_metrics_sink).
+
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::net::UdpSocket;
+
+use crate::metrics::{GooseRequestMetric, GooseTaskMetric};
+use crate::{GooseAttack, GooseError};
+
+/// The largest UDP datagram sent, small enough to avoid fragmentation on most networks.
+const MAX_DATAGRAM_SIZE: usize = 1432;
+
+/// A destination for metrics as they are received by the parent thread.
+///
+/// Sinks are called from the parent thread, so `record_request` and `record_task`
+/// should only buffer the metric. Buffered metrics are sent in `flush`, which is
+/// called every `--sink-flush-interval` seconds and when the load test stops.
+///
+/// # Example
+/// ```rust
+/// use async_trait::async_trait;
+/// use goose::prelude::*;
+/// use goose::metrics::{GooseRequestMetric, GooseTaskMetric};
+/// use goose::sink::GooseMetricsSink;
+///
+/// #[derive(Default)]
+/// struct CountingSink {
+///     requests: usize,
+///     tasks: usize,
+/// }
+///
+/// #[async_trait]
+/// impl GooseMetricsSink for CountingSink {
+///     fn record_request(&mut self, _request: &GooseRequestMetric) {
+///         self.requests += 1;
+///     }
+///
+///     fn record_task(&mut self, _task: &GooseTaskMetric, _taskset: &str) {
+///         self.tasks += 1;
+///     }
+///
+///     async fn flush(&mut self) -> Result<(), GooseError> {
+///         println!("{} requests and {} tasks", self.requests, self.tasks);
+///         Ok(())
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait GooseMetricsSink: Send + Sync {
+    /// Buffer a request metric. A metric with `update` set changes whether a previously
+    /// recorded request was a success or a failure.
+    fn record_request(&mut self, request: &GooseRequestMetric);
+
+    /// Buffer a task metric, along with the name of the task set it belongs to.
+    fn record_task(&mut self, task: &GooseTaskMetric, taskset: &str);
+
+    /// Send all buffered metrics.
+    async fn flush(&mut self) -> Result<(), GooseError>;
+}
+
+impl GooseAttack {
+    /// Register a custom [`GooseMetricsSink`], which receives all request and task
+    /// metrics while the load test runs. Any number of sinks can be registered, and
+    /// they're used alongside the sinks enabled by `--statsd` and `--influxdb`.
+    ///
+    /// Sinks receive metrics in the process that collects them, so they're not
+    /// supported in Gaggle mode.
+    ///
+    /// # Example
+    /// ```rust
+    /// use async_trait::async_trait;
+    /// use goose::prelude::*;
+    /// use goose::metrics::{GooseRequestMetric, GooseTaskMetric};
+    /// use goose::sink::GooseMetricsSink;
+    ///
+    /// struct LogSink;
+    ///
+    /// #[async_trait]
+    /// impl GooseMetricsSink for LogSink {
+    ///     fn record_request(&mut self, request: &GooseRequestMetric) {
+    ///         println!("{} {}ms", request.name, request.response_time);
+    ///     }
+    ///
+    ///     fn record_task(&mut self, _task: &GooseTaskMetric, _taskset: &str) {}
+    ///
+    ///     async fn flush(&mut self) -> Result<(), GooseError> {
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), GooseError> {
+    ///     GooseAttack::initialize()?
+    ///         .register_metrics_sink(LogSink);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn register_metrics_sink(mut self, sink: impl GooseMetricsSink + 'static) -> Self {
+        self.metrics_sinks.push(Box::new(sink));
+        self
+    }
+
+    // Add the built-in sinks enabled by run-time options. The options were already
+    // validated, but errors are still returned rather than ignored.
+    pub(crate) fn setup_metrics_sinks(&mut self) -> Result<(), GooseError> {
+        let tags = match self.configuration.sink_tags.as_ref() {
+            Some(sink_tags) => {
+                parse_tags(sink_tags).map_err(|detail| GooseError::InvalidOption {
+                    option: "`configuration.sink_tags`".to_string(),
+                    value: sink_tags.to_string(),
+                    detail,
+                })?
+            }
+            None => Vec::new(),
+        };
+
+        if let Some(statsd) = self.configuration.statsd.as_ref() {
+            let address =
+                parse_statsd_address(statsd).map_err(|detail| GooseError::InvalidOption {
+                    option: "`configuration.statsd`".to_string(),
+                    value: statsd.to_string(),
+                    detail,
+                })?;
+            info!("sending metrics to StatsD at {}", address);
+            self.metrics_sinks
+                .push(Box::new(StatsdSink::new(address, &tags)));
+        }
+
+        if let Some(influxdb) = self.configuration.influxdb.as_ref() {
+            let destination =
+                parse_influxdb_url(influxdb).map_err(|detail| GooseError::InvalidOption {
+                    option: "`configuration.influxdb`".to_string(),
+                    value: influxdb.to_string(),
+                    detail,
+                })?;
+            info!("sending metrics to InfluxDB at {}", influxdb);
+            self.metrics_sinks
+                .push(Box::new(InfluxDbSink::new(destination, &tags)));
+        }
+
+        Ok(())
+    }
+
+    // Send all request metrics received from a GooseUser to the registered sinks.
+    pub(crate) fn sink_request_metric(&mut self, request: &GooseRequestMetric) {
+        for sink in self.metrics_sinks.iter_mut() {
+            sink.record_request(request);
+        }
+    }
+
+    // Send all task metrics received from a GooseUser to the registered sinks.
+    pub(crate) fn sink_task_metric(&mut self, task: &GooseTaskMetric) {
+        if self.metrics_sinks.is_empty() {
+            return;
+        }
+        let taskset = &self.task_sets[task.taskset_index].name;
+        for sink in self.metrics_sinks.iter_mut() {
+            sink.record_task(task, taskset);
+        }
+    }
+
+    // Flush all registered sinks. A sink that fails to flush is logged, but doesn't
+    // stop the load test.
+    pub(crate) async fn flush_metrics_sinks(&mut self) {
+        for sink in self.metrics_sinks.iter_mut() {
+            if let Err(e) = sink.flush().await {
+                warn!("failed to flush metrics sink: {}", e);
+            }
+        }
+    }
+}
+
+/// Where the InfluxDB sink sends metrics.
+#[derive(Debug, PartialEq)]
+pub(crate) enum InfluxDbDestination {
+    /// Send line protocol datagrams to this address.
+    Udp(SocketAddr),
+    /// POST line protocol to this URL.
+    Http(String),
+}
+
+/// Resolve the `HOST:PORT` of a StatsD server.
+pub(crate) fn parse_statsd_address(statsd: &str) -> Result<SocketAddr, String> {
+    resolve(statsd)
+}
+
+/// Parse an InfluxDB URL, which must use the `udp`, `http` or `https` scheme.
+pub(crate) fn parse_influxdb_url(influxdb: &str) -> Result<InfluxDbDestination, String> {
+    let url = url::Url::parse(influxdb)
+        .map_err(|e| format!("`configuration.influxdb` must be a valid URL: {}", e))?;
+    match url.scheme() {
+        "udp" => match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => {
+                Ok(InfluxDbDestination::Udp(resolve(&format!("{}:{}", host, port))?))
+            }
+            _ => Err("`configuration.influxdb` must include a host and port when using UDP (ie udp://127.0.0.1:8089).".to_string()),
+        },
+        "http" | "https" => Ok(InfluxDbDestination::Http(influxdb.to_string())),
+        scheme => Err(format!(
+            "`configuration.influxdb` scheme must be udp, http or https, not {}.",
+            scheme
+        )),
+    }
+}
+
+/// Parse a comma separated list of `key=value` tags.
+pub(crate) fn parse_tags(sink_tags: &str) -> Result<Vec<(String, String)>, String> {
+    let mut tags = Vec::new();
+    for tag in sink_tags.split(',') {
+        let tag = tag.trim();
+        if tag.is_empty() {
+            continue;
+        }
+        match tag.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => {
+                tags.push((key.trim().to_string(), value.trim().to_string()))
+            }
+            _ => {
+                return Err(format!(
+                    "`configuration.sink_tags` must be a comma separated list of key=value pairs, invalid tag: {}",
+                    tag
+                ))
+            }
+        }
+    }
+    Ok(tags)
+}
+
+// Resolve a HOST:PORT string to the first matching socket address.
+fn resolve(address: &str) -> Result<SocketAddr, String> {
+    match address.to_socket_addrs() {
+        Ok(mut addresses) => addresses
+            .next()
+            .ok_or_else(|| format!("failed to resolve {}", address)),
+        Err(e) => Err(format!(
+            "failed to resolve {} (expected HOST:PORT): {}",
+            address, e
+        )),
+    }
+}
+
+// Bind a local UDP socket able to send to the given address.
+async fn bind_udp(address: &SocketAddr) -> Result<UdpSocket, GooseError> {
+    let local = if address.is_ipv4() {
+        "0.0.0.0:0"
+    } else {
+        "[::]:0"
+    };
+    Ok(UdpSocket::bind(local).await?)
+}
+
+// Send newline separated lines in as few datagrams as possible.
+async fn send_lines(
+    socket: &UdpSocket,
+    address: &SocketAddr,
+    lines: &[String],
+) -> Result<(), GooseError> {
+    let mut datagram = String::new();
+    for line in lines {
+        if !datagram.is_empty() && datagram.len() + line.len() + 1 > MAX_DATAGRAM_SIZE {
+            socket.send_to(datagram.as_bytes(), address).await?;
+            datagram.clear();
+        }
+        if !datagram.is_empty() {
+            datagram.push('\n');
+        }
+        datagram.push_str(line);
+    }
+    if !datagram.is_empty() {
+        socket.send_to(datagram.as_bytes(), address).await?;
+    }
+    Ok(())
+}
+
+// The tag and value of the status code of a request, the gRPC status code of gRPC calls.
+fn status_code_tag(request: &GooseRequestMetric) -> (&'static str, String) {
+    match request.grpc_status_code {
+        Some(grpc_status_code) => ("grpc_status_code", grpc_status_code.to_string()),
+        None => ("status_code", request.status_code.to_string()),
+    }
+}
+
+// The task index displayed in metrics, ie 1.1 for the first task of the first task set.
+fn task_index(task: &GooseTaskMetric) -> String {
+    format!("{}.{}", task.taskset_index + 1, task.task_index + 1)
+}
+
+/// Sends metrics to StatsD over UDP.
+///
+/// Counters are aggregated between flushes, while every response time is sent as a
+/// timing so StatsD can calculate percentiles. Tags use the DogStatsD `|#key:value`
+/// format.
+pub(crate) struct StatsdSink {
+    /// The StatsD server.
+    address: SocketAddr,
+    /// Local socket, bound on the first flush.
+    socket: Option<UdpSocket>,
+    /// Tags added to all metrics, already formatted.
+    tags: String,
+    /// Counters aggregated since the last flush, keyed by name and tags.
+    counters: BTreeMap<(&'static str, String), i64>,
+    /// Timings recorded since the last flush.
+    timings: Vec<String>,
+}
+impl StatsdSink {
+    pub(crate) fn new(address: SocketAddr, tags: &[(String, String)]) -> Self {
+        let mut formatted = String::new();
+        for (key, value) in tags {
+            let _ = write!(
+                formatted,
+                ",{}:{}",
+                Self::sanitize(key),
+                Self::sanitize(value)
+            );
+        }
+        StatsdSink {
+            address,
+            socket: None,
+            tags: formatted,
+            counters: BTreeMap::new(),
+            timings: Vec::new(),
+        }
+    }
+
+    // Characters that are part of the DogStatsD format can't be used in tags.
+    fn sanitize(value: &str) -> String {
+        value.replace(&[',', '|', '#', '\n'][..], "_")
+    }
+
+    // Format a list of tags, including the global tags. Empty tag values are left out.
+    fn format_tags(&self, tags: &[(&str, &str)]) -> String {
+        let mut formatted = String::new();
+        for (key, value) in tags {
+            if !value.is_empty() {
+                let _ = write!(formatted, ",{}:{}", key, Self::sanitize(value));
+            }
+        }
+        formatted.push_str(&self.tags);
+        formatted.replacen(',', "|#", 1)
+    }
+
+    fn increment(&mut self, name: &'static str, tags: &str, value: i64) {
+        *self.counters.entry((name, tags.to_string())).or_insert(0) += value;
+    }
+}
+
+#[async_trait]
+impl GooseMetricsSink for StatsdSink {
+    fn record_request(&mut self, request: &GooseRequestMetric) {
+        let method = request.raw.method.to_string();
+        let (status_code_tag, status_code) = status_code_tag(request);
+        let tags = self.format_tags(&[
+            ("method", &method),
+            ("name", &request.name),
+            (status_code_tag, &status_code),
+        ]);
+
+        // An update only changes whether the request was a failure.
+        if request.update {
+            let delta = if request.success { -1 } else { 1 };
+            self.increment("goose.request_failures", &tags, delta);
+            return;
+        }
+
+        self.increment("goose.requests", &tags, 1);
+        if !request.success {
+            self.increment("goose.request_failures", &tags, 1);
+        }
+        self.timings.push(format!(
+            "goose.response_time:{}|ms{}",
+            request.response_time_micros as f64 / 1_000.0,
+            tags
+        ));
+    }
+
+    fn record_task(&mut self, task: &GooseTaskMetric, taskset: &str) {
+        let index = task_index(task);
+        let tags = self.format_tags(&[
+            ("index", &index),
+            ("taskset", taskset),
+            ("task", &task.name),
+        ]);
+
+        self.increment("goose.tasks", &tags, 1);
+        if !task.success {
+            self.increment("goose.task_failures", &tags, 1);
+        }
+        self.timings.push(format!(
+            "goose.task_time:{}|ms{}",
+            task.run_time_micros as f64 / 1_000.0,
+            tags
+        ));
+    }
+
+    async fn flush(&mut self) -> Result<(), GooseError> {
+        if self.counters.is_empty() && self.timings.is_empty() {
+            return Ok(());
+        }
+
+        let mut lines: Vec<String> = self
+            .counters
+            .iter()
+            .filter(|(_, value)| **value != 0)
+            .map(|((name, tags), value)| format!("{}:{}|c{}", name, value, tags))
+            .collect();
+        lines.append(&mut self.timings);
+        self.counters.clear();
+
+        if self.socket.is_none() {
+            self.socket = Some(bind_udp(&self.address).await?);
+        }
+        send_lines(self.socket.as_ref().unwrap(), &self.address, &lines).await
+    }
+}
+
+/// Summary of the requests or tasks with the same tags since the last flush.
+#[derive(Debug, Default)]
+struct InfluxDbAggregate {
+    /// How many times the request or task ran.
+    count: i64,
+    /// How many times the request or task failed.
+    failures: i64,
+    /// Total time in microseconds.
+    total_time: u64,
+    /// Shortest time in microseconds.
+    min_time: u64,
+    /// Longest time in microseconds.
+    max_time: u64,
+}
+impl InfluxDbAggregate {
+    fn record_time(&mut self, time: u64, success: bool) {
+        if self.count == 0 || time < self.min_time {
+            self.min_time = time;
+        }
+        if time > self.max_time {
+            self.max_time = time;
+        }
+        self.count += 1;
+        self.total_time += time;
+        if !success {
+            self.failures += 1;
+        }
+    }
+
+    // Format the fields of a line, times are in milliseconds.
+    fn fields(&self, prefix: &str) -> String {
+        let mut fields = format!("count={}i,failures={}i", self.count, self.failures);
+        if self.count > 0 {
+            let _ = write!(
+                fields,
+                ",{prefix}_min={},{prefix}_mean={},{prefix}_max={}",
+                self.min_time as f64 / 1_000.0,
+                self.total_time as f64 / self.count as f64 / 1_000.0,
+                self.max_time as f64 / 1_000.0,
+                prefix = prefix
+            );
+        }
+        fields
+    }
+}
+
+/// Sends metrics to InfluxDB in the line protocol, over UDP or HTTP.
+///
+/// Requests and tasks are aggregated between flushes, and each flush writes one
+/// `goose_requests` point per method, name and status code, and one `goose_tasks`
+/// point per task.
+pub(crate) struct InfluxDbSink {
+    /// Where metrics are sent.
+    destination: InfluxDbDestination,
+    /// Local socket, bound on the first flush when sending over UDP.
+    socket: Option<UdpSocket>,
+    /// Client used when sending over HTTP.
+    client: reqwest::Client,
+    /// Tags added to all metrics, already formatted.
+    tags: String,
+    /// Requests aggregated since the last flush, keyed by tags.
+    requests: BTreeMap<String, InfluxDbAggregate>,
+    /// Tasks aggregated since the last flush, keyed by tags.
+    tasks: BTreeMap<String, InfluxDbAggregate>,
+}
+impl InfluxDbSink {
+    pub(crate) fn new(destination: InfluxDbDestination, tags: &[(String, String)]) -> Self {
+        let mut formatted = String::new();
+        for (key, value) in tags {
+            if !value.is_empty() {
+                let _ = write!(formatted, ",{}={}", Self::escape(key), Self::escape(value));
+            }
+        }
+        InfluxDbSink {
+            destination,
+            socket: None,
+            client: reqwest::Client::new(),
+            tags: formatted,
+            requests: BTreeMap::new(),
+            tasks: BTreeMap::new(),
+        }
+    }
+
+    // Escape commas, equal signs and spaces in tag keys and values.
+    fn escape(value: &str) -> String {
+        let mut escaped = String::with_capacity(value.len());
+        for c in value.chars() {
+            match c {
+                ',' | '=' | ' ' => {
+                    escaped.push('\\');
+                    escaped.push(c);
+                }
+                '\n' => escaped.push(' '),
+                _ => escaped.push(c),
+            }
+        }
+        escaped
+    }
+
+    // Format a list of tags, including the global tags. Empty tag values are invalid in
+    // the line protocol, so they're left out.
+    fn format_tags(&self, tags: &[(&str, &str)]) -> String {
+        let mut formatted = String::new();
+        for (key, value) in tags {
+            if !value.is_empty() {
+                let _ = write!(formatted, ",{}={}", key, Self::escape(value));
+            }
+        }
+        formatted.push_str(&self.tags);
+        formatted
+    }
+}
+
+#[async_trait]
+impl GooseMetricsSink for InfluxDbSink {
+    fn record_request(&mut self, request: &GooseRequestMetric) {
+        let method = request.raw.method.to_string();
+        let (status_code_tag, status_code) = status_code_tag(request);
+        let tags = self.format_tags(&[
+            ("method", &method),
+            ("name", &request.name),
+            (status_code_tag, &status_code),
+        ]);
+        let aggregate = self.requests.entry(tags).or_default();
+
+        // An update only changes whether the request was a failure.
+        if request.update {
+            aggregate.failures += if request.success { -1 } else { 1 };
+        } else {
+            aggregate.record_time(request.response_time_micros, request.success);
+        }
+    }
+
+    fn record_task(&mut self, task: &GooseTaskMetric, taskset: &str) {
+        let index = task_index(task);
+        let tags = self.format_tags(&[
+            ("index", &index),
+            ("taskset", taskset),
+            ("task", &task.name),
+        ]);
+        self.tasks
+            .entry(tags)
+            .or_default()
+            .record_time(task.run_time_micros, task.success);
+    }
+
+    async fn flush(&mut self) -> Result<(), GooseError> {
+        if self.requests.is_empty() && self.tasks.is_empty() {
+            return Ok(());
+        }
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|since| since.as_nanos())
+            .unwrap_or(0);
+        let mut lines = Vec::new();
+        for (tags, aggregate) in &self.requests {
+            lines.push(format!(
+                "goose_requests{} {} {}",
+                tags,
+                aggregate.fields("response_time"),
+                timestamp
+            ));
+        }
+        for (tags, aggregate) in &self.tasks {
+            lines.push(format!(
+                "goose_tasks{} {} {}",
+                tags,
+                aggregate.fields("run_time"),
+                timestamp
+            ));
+        }
+        self.requests.clear();
+        self.tasks.clear();
+
+        match &self.destination {
+            InfluxDbDestination::Udp(address) => {
+                if self.socket.is_none() {
+                    self.socket = Some(bind_udp(address).await?);
+                }
+                send_lines(self.socket.as_ref().unwrap(), address, &lines).await
+            }
+            InfluxDbDestination::Http(url) => {
+                let response = self.client.post(url).body(lines.join("\n")).send().await?;
+                if response.status().is_success() {
+                    Ok(())
+                } else {
+                    Err(GooseError::Io(io::Error::other(format!(
+                        "InfluxDB returned {}",
+                        response.status()
+                    ))))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::goose::GooseMethod;
+    use crate::metrics::GooseRawRequest;
+
+    #[test]
+    fn parse_options() {
+        assert!(parse_statsd_address("127.0.0.1:8125").is_ok());
+        assert!(parse_statsd_address("127.0.0.1").is_err());
+
+        assert_eq!(
+            parse_influxdb_url("udp://127.0.0.1:8089"),
+            Ok(InfluxDbDestination::Udp("127.0.0.1:8089".parse().unwrap()))
+        );
+        assert_eq!(
+            parse_influxdb_url("http://localhost:8086/write?db=goose"),
+            Ok(InfluxDbDestination::Http(
+                "http://localhost:8086/write?db=goose".to_string()
+            ))
+        );
+        assert!(parse_influxdb_url("udp://127.0.0.1").is_err());
+        assert!(parse_influxdb_url("tcp://127.0.0.1:8089").is_err());
+        assert!(parse_influxdb_url("127.0.0.1:8089").is_err());
+
+        assert_eq!(
+            parse_tags("env=test, region = eu,"),
+            Ok(vec![
+                ("env".to_string(), "test".to_string()),
+                ("region".to_string(), "eu".to_string()),
+            ])
+        );
+        assert!(parse_tags("env").is_err());
+        assert!(parse_tags("=test").is_err());
+    }
+
+    #[test]
+    fn line_formats() {
+        let tags = vec![("env".to_string(), "load test".to_string())];
+        let raw = GooseRawRequest::new(GooseMethod::Get, "http://127.0.0.1/", vec![], "");
+        let mut request = GooseRequestMetric::new(raw, "/", 0, 0);
+        request.status_code = 200;
+        request.response_time_micros = 1_500;
+
+        let mut statsd = StatsdSink::new("127.0.0.1:8125".parse().unwrap(), &tags);
+        statsd.record_request(&request);
+        assert_eq!(
+            statsd.timings,
+            vec!["goose.response_time:1.5|ms|#method:GET,name:/,status_code:200,env:load test"]
+        );
+        // A failure update is counted without recording another response time.
+        request.update = true;
+        request.success = false;
+        statsd.record_request(&request);
+        assert_eq!(statsd.timings.len(), 1);
+        let tags_key = "|#method:GET,name:/,status_code:200,env:load test".to_string();
+        assert_eq!(statsd.counters[&("goose.requests", tags_key.clone())], 1);
+        assert_eq!(statsd.counters[&("goose.request_failures", tags_key)], 1);
+
+        let mut influxdb = InfluxDbSink::new(
+            InfluxDbDestination::Udp("127.0.0.1:8089".parse().unwrap()),
+            &tags,
+        );
+        request.update = false;
+        request.success = true;
+        influxdb.record_request(&request);
+        request.response_time_micros = 2_500;
+        influxdb.record_request(&request);
+        let (key, aggregate) = influxdb.requests.iter().next().unwrap();
+        assert_eq!(key, ",method=GET,name=/,status_code=200,env=load\\ test");
+        assert_eq!(
+            aggregate.fields("response_time"),
+            "count=2i,failures=0i,response_time_min=1.5,response_time_mean=2,response_time_max=2.5"
+        );
+    }
+}
@@ -0,0 +1,390 @@
+//! Optional loading of the static assets of HTML pages, like a browser.
+//!
+//! When a browser loads a page it also loads the images, scripts and stylesheets that the
+//! page references, several at a time, and revalidates assets it already has in its cache.
+//! Once [`GooseUser::set_static_assets`](../goose/struct.GooseUser.html#method.set_static_assets)
+//! is enabled, each successful response with a `text/html` content type is parsed for
+//! `<img src>`, `<script src>` and `<link rel="stylesheet" href>` assets, which are then
+//! loaded before the response is returned to the task.
+//!
+//! A [`GooseStaticAssets`] configures how assets are loaded:
+//!  - only assets on the same host as the page are loaded, unless
+//!    [`GooseStaticAssets::all_hosts`] is set;
+//!  - up to 6 assets are loaded at a time, the connection limit of most browsers, which
+//!    can be changed with [`GooseStaticAssets::parallelism`];
+//!  - all assets are recorded under the request name `static asset`, which can be changed
+//!    with [`GooseStaticAssets::request_name`].
+//!
+//! Each user keeps an in-memory cache of the `ETag` and `Last-Modified` headers of the
+//! assets it loaded. Assets are requested again each time a page references them, but
+//! with `If-None-Match` and `If-Modified-Since` headers, so the server can respond with a
+//! `304 Not Modified`, which is counted as a success.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use goose::prelude::*;
+//! use goose::static_assets::GooseStaticAssets;
+//!
+//! let mut setup = task!(enable_static_assets).set_on_start();
+//! let mut task = task!(loadtest_index_page);
+//!
+//! async fn enable_static_assets(user: &mut GooseUser) -> GooseTaskResult {
+//!     user.set_static_assets(Some(GooseStaticAssets::new().parallelism(4)));
+//!
+//!     Ok(())
+//! }
+//!
+//! async fn loadtest_index_page(user: &mut GooseUser) -> GooseTaskResult {
+//!     // Also loads the images, scripts and stylesheets of the page.
+//!     let _goose = user.get("/").await?;
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use futures::stream::{self, StreamExt};
+use lazy_static::lazy_static;
+use reqwest::{header, Response, ResponseBuilderExt, StatusCode};
+use scraper::{Html, Selector};
+use std::collections::HashSet;
+use std::time::Instant;
+use url::Url;
+
+use crate::goose::{record_response, GooseTaskError, GooseTaskResult, GooseUser};
+
+lazy_static! {
+    /// Matches the base URL of relative URLs in the page.
+    static ref BASE: Selector = Selector::parse("base[href]").unwrap();
+    /// Matches images and scripts.
+    static ref SRC: Selector = Selector::parse("img[src], script[src]").unwrap();
+    /// Matches stylesheets and other linked resources.
+    static ref LINK: Selector = Selector::parse("link[rel][href]").unwrap();
+}
+
+/// Configures how a [`GooseUser`](../goose/struct.GooseUser.html) loads static assets, see
+/// the [module documentation](index.html).
+#[derive(Clone, Debug)]
+pub struct GooseStaticAssets {
+    /// Whether to load assets from other hosts than the page.
+    all_hosts: bool,
+    /// How many assets to load at a time.
+    parallelism: usize,
+    /// The request name that assets are recorded under.
+    request_name: String,
+}
+impl Default for GooseStaticAssets {
+    fn default() -> Self {
+        GooseStaticAssets {
+            all_hosts: false,
+            parallelism: 6,
+            request_name: "static asset".to_string(),
+        }
+    }
+}
+impl GooseStaticAssets {
+    /// Load up to 6 assets at a time from the same host as the page, recorded as
+    /// `static asset`.
+    pub fn new() -> Self {
+        GooseStaticAssets::default()
+    }
+
+    /// Also load assets from other hosts than the page, such as a CDN.
+    pub fn all_hosts(mut self) -> Self {
+        self.all_hosts = true;
+        self
+    }
+
+    /// Load up to this many assets at a time. Values below 1 load one asset at a time.
+    pub fn parallelism(mut self, parallelism: usize) -> Self {
+        self.parallelism = parallelism.max(1);
+        self
+    }
+
+    /// Record assets under this request name.
+    pub fn request_name(mut self, request_name: &str) -> Self {
+        self.request_name = request_name.to_string();
+        self
+    }
+
+    /// Returns the unique URLs of the assets referenced by the page that should be loaded,
+    /// in the order they appear.
+    fn asset_urls(&self, page_url: &Url, html: &str) -> Vec<Url> {
+        let document = Html::parse_document(html);
+
+        // Relative URLs are relative to the `<base>` of the page, if set.
+        let base_url = document
+            .select(&BASE)
+            .next()
+            .and_then(|base| base.value().attr("href"))
+            .and_then(|href| page_url.join(href).ok())
+            .unwrap_or_else(|| page_url.clone());
+
+        let sources = document
+            .select(&SRC)
+            .filter_map(|element| element.value().attr("src"));
+        let stylesheets = document
+            .select(&LINK)
+            .filter(|element| {
+                element
+                    .value()
+                    .attr("rel")
+                    .unwrap_or_default()
+                    .split_whitespace()
+                    .any(|rel| rel.eq_ignore_ascii_case("stylesheet"))
+            })
+            .filter_map(|element| element.value().attr("href"));
+
+        let mut seen = HashSet::new();
+        sources
+            .chain(stylesheets)
+            .filter_map(|href| base_url.join(href.trim()).ok())
+            .filter(|url| url.scheme() == "http" || url.scheme() == "https")
+            .filter(|url| {
+                self.all_hosts
+                    || (url.host_str() == page_url.host_str()
+                        && url.port_or_known_default() == page_url.port_or_known_default())
+            })
+            .filter(|url| seen.insert(url.to_string()))
+            .collect()
+    }
+}
+
+/// The validators of an asset in a user's cache.
+#[derive(Clone, Debug)]
+pub(crate) struct GooseCachedAsset {
+    /// The `ETag` header of the asset.
+    etag: Option<header::HeaderValue>,
+    /// The `Last-Modified` header of the asset.
+    last_modified: Option<header::HeaderValue>,
+}
+
+impl GooseUser {
+    /// Enable or disable loading the static assets of HTML pages like a browser, see the
+    /// [`static_assets`](../static_assets/index.html) module.
+    ///
+    /// Disabled by default. Typically enabled in an
+    /// [`on_start`](struct.GooseTask.html#method.set_on_start) task.
+    pub fn set_static_assets(&mut self, static_assets: Option<GooseStaticAssets>) {
+        self.static_assets = static_assets;
+    }
+
+    /// Load the static assets referenced by an HTML page, as if it was loaded while
+    /// [`GooseUser::set_static_assets`] is enabled. Relative URLs are relative to
+    /// `page_url`. Uses the default [`GooseStaticAssets`] if loading static assets isn't
+    /// enabled.
+    ///
+    /// Failed asset requests are recorded as failures, but don't return an error.
+    ///
+    /// # Example
+    /// ```rust
+    /// use goose::prelude::*;
+    ///
+    /// let mut task = task!(load_cached_page);
+    ///
+    /// /// Load the assets of a page that was read from disk.
+    /// async fn load_cached_page(user: &mut GooseUser) -> GooseTaskResult {
+    ///     let page_url = user.base_url.clone();
+    ///     let html = r#"<img src="/logo.png"><script src="/app.js"></script>"#;
+    ///     user.load_static_assets(&page_url, html).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn load_static_assets(&mut self, page_url: &Url, html: &str) -> GooseTaskResult {
+        let static_assets = self.static_assets.clone().unwrap_or_default();
+        let asset_urls = static_assets.asset_urls(page_url, html);
+
+        // Prepare all requests, revalidating cached assets.
+        let mut requests = Vec::with_capacity(asset_urls.len());
+        for asset_url in asset_urls {
+            let mut request_builder = self.client.get(asset_url.as_str());
+            if let Some(cached_asset) = self.static_asset_cache.get(asset_url.as_str()) {
+                if let Some(etag) = &cached_asset.etag {
+                    request_builder = request_builder.header(header::IF_NONE_MATCH, etag);
+                }
+                if let Some(last_modified) = &cached_asset.last_modified {
+                    request_builder =
+                        request_builder.header(header::IF_MODIFIED_SINCE, last_modified);
+                }
+            }
+            let request = request_builder.build()?;
+            let request_metric =
+                self.new_request_metric(&request, Some(&static_assets.request_name))?;
+            requests.push((asset_url, request, request_metric));
+        }
+
+        let throttle = if self.is_throttled {
+            self.throttle.clone()
+        } else {
+            None
+        };
+        let client = self.client.clone();
+        let mut loaded = stream::iter(requests.into_iter().map(
+            |(asset_url, request, request_metric)| {
+                let throttle = throttle.clone();
+                let client = client.clone();
+                async move {
+                    // Each asset is a request, and is throttled.
+                    if let Some(throttle) = throttle {
+                        throttle.send_async(true).await?;
+                    }
+                    let started = Instant::now();
+                    let response = client.execute(request).await;
+                    let response_time = started.elapsed();
+                    // Read the body, like a browser.
+                    let response = match response {
+                        Ok(response) => {
+                            let status_code = response.status();
+                            let headers = response.headers().clone();
+                            response
+                                .bytes()
+                                .await
+                                .map(|_| (response_time, status_code, headers))
+                        }
+                        Err(e) => Err(e),
+                    };
+                    Ok::<_, GooseTaskError>((asset_url, request_metric, response))
+                }
+            },
+        ))
+        .buffer_unordered(static_assets.parallelism);
+
+        while let Some(asset) = loaded.next().await {
+            let (asset_url, mut request_metric, response) = asset?;
+            match response {
+                Ok((response_time, status_code, headers)) => {
+                    request_metric.set_response_time(response_time);
+                    request_metric.set_status_code(Some(status_code));
+                    request_metric.set_final_url(asset_url.as_str());
+                    if status_code.is_success() {
+                        // Remember the validators of the asset, if any.
+                        let etag = headers.get(header::ETAG).cloned();
+                        let last_modified = headers.get(header::LAST_MODIFIED).cloned();
+                        if etag.is_some() || last_modified.is_some() {
+                            self.static_asset_cache.insert(
+                                asset_url.to_string(),
+                                GooseCachedAsset {
+                                    etag,
+                                    last_modified,
+                                },
+                            );
+                        }
+                    } else if status_code != StatusCode::NOT_MODIFIED {
+                        request_metric.success = false;
+                        request_metric.error = format!("{}: {}", status_code, request_metric.name);
+                    }
+                }
+                Err(e) => record_response(&mut request_metric, &Err(e)),
+            }
+            request_metric.user_cadence = self
+                .coordinated_omission_mitigation(&request_metric)
+                .await?;
+            if !self.config.no_metrics {
+                self.send_request_metric_to_parent(request_metric)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Load the static assets of an HTML page, returning a copy of the response that can
+    /// still be read by the task.
+    pub(crate) async fn load_static_assets_of(
+        &mut self,
+        response: Response,
+    ) -> Result<Result<Response, reqwest::Error>, GooseTaskError> {
+        let is_html = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .unwrap_or_default()
+            .trim_start()
+            .starts_with("text/html");
+        if !is_html {
+            return Ok(Ok(response));
+        }
+
+        let status = response.status();
+        let version = response.version();
+        let headers = response.headers().clone();
+        let page_url = response.url().clone();
+        let body = match response.bytes().await {
+            Ok(body) => body,
+            Err(e) => return Ok(Err(e)),
+        };
+
+        self.load_static_assets(&page_url, &String::from_utf8_lossy(&body))
+            .await?;
+
+        // The body was read, so return a new response with the same body.
+        let mut page = http::Response::builder()
+            .url(page_url)
+            .body(body)
+            // A builder without invalid parts always builds.
+            .unwrap();
+        *page.status_mut() = status;
+        *page.version_mut() = version;
+        *page.headers_mut() = headers;
+
+        Ok(Ok(Response::from(page)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn asset_urls() {
+        let page_url = Url::parse("http://example.com/blog/post").unwrap();
+        let html = r#"<html><head>
+            <link rel="stylesheet" href="/css/site.css">
+            <link rel="Alternate StyleSheet" href="print.css">
+            <link rel="icon" href="/favicon.ico">
+            <script src="/js/app.js"></script>
+            <script>inline();</script>
+            <script src="https://cdn.example.net/lib.js"></script>
+        </head><body>
+            <img src="logo.png"><img src="/blog/logo.png"><img src="data:image/png;base64,AAAA">
+            <img src="http://example.com:8080/other-port.png">
+        </body></html>"#;
+
+        let urls = |static_assets: &GooseStaticAssets, html: &str| {
+            static_assets
+                .asset_urls(&page_url, html)
+                .iter()
+                .map(|url| url.to_string())
+                .collect::<Vec<String>>()
+        };
+
+        // Duplicates, inline scripts, data URLs and other hosts aren't loaded.
+        assert_eq!(
+            urls(&GooseStaticAssets::new(), html),
+            vec![
+                "http://example.com/js/app.js",
+                "http://example.com/blog/logo.png",
+                "http://example.com/css/site.css",
+                "http://example.com/blog/print.css",
+            ]
+        );
+        assert_eq!(
+            urls(&GooseStaticAssets::new().all_hosts(), html),
+            vec![
+                "http://example.com/js/app.js",
+                "https://cdn.example.net/lib.js",
+                "http://example.com/blog/logo.png",
+                "http://example.com:8080/other-port.png",
+                "http://example.com/css/site.css",
+                "http://example.com/blog/print.css",
+            ]
+        );
+
+        // Relative URLs are relative to the base URL, if set.
+        let html = r#"<head><base href="/static/"></head><body><img src="logo.png"></body>"#;
+        assert_eq!(
+            urls(&GooseStaticAssets::new(), html),
+            vec!["http://example.com/static/logo.png"]
+        );
+    }
+}
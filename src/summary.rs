@@ -0,0 +1,943 @@
+//! Optional JSON, CSV and JUnit XML summaries of a load test, for tracking results over time.
+//!
+//! When a load test finishes, Goose can write a summary of the final metrics to a JSON
+//! file with the `--summary-json` run-time option, and to a CSV file with the
+//! `--summary-csv` run-time option. Unlike the html report, summaries are meant to be read
+//! by other tools, for example to compare results between builds in a CI pipeline. The
+//! `--junit-xml` run-time option writes a JUnit XML report in which each request, task
+//! and transaction is a test case, so CI systems can display load test failures next to
+//! other tests.
+//!
+//! All formats are generated from the same [`GooseSummary`], which has a stable schema:
+//! fields may be added in later releases, but are not renamed or removed without
+//! incrementing [`SUMMARY_SCHEMA_VERSION`]. All times are in milliseconds, all rates are
+//! per second, and all timestamps are RFC 3339.
+//!
+//! The CSV summary has one row per value, with the columns `section`, `key`, `field` and
+//! `value`. For example, the 95th percentile response time of `GET /` is written as:
+//!
+//! ```text
+//! request,GET /,response_times.percentiles.p95,12
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tokio::fs::File;
+use tokio::io::AsyncWriteExt;
+
+use crate::baseline::BASELINE_PERCENTILES;
+use crate::config::GooseConfiguration;
+use crate::goose::GooseMethod;
+use crate::metrics::{
+    per_second_calculations, GooseCustomMetricKind, GooseHistogram, GooseIterationMetrics,
+    GooseMetrics, GooseRequestMetricTimingData, DEFAULT_PERCENTILES,
+};
+use crate::threshold::GooseThresholdResult;
+use crate::{GooseAttack, GooseError};
+
+/// The version of the [`GooseSummary`] schema.
+///
+/// Incremented whenever a field is renamed or removed, or its meaning changes.
+pub const SUMMARY_SCHEMA_VERSION: u32 = 1;
+
+/// A summary of the final metrics of a load test.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GooseSummary {
+    /// The version of this schema, see [`SUMMARY_SCHEMA_VERSION`].
+    pub schema_version: u32,
+    /// The version of Goose that ran the load test.
+    pub goose_version: String,
+    /// When the load test started launching users.
+    pub starting: Option<String>,
+    /// When all users were launched.
+    pub started: Option<String>,
+    /// When the load test started stopping users.
+    pub stopping: Option<String>,
+    /// When the load test stopped.
+    pub stopped: Option<String>,
+    /// How many seconds the load test ran, not including the time spent launching users.
+    pub duration: usize,
+    /// How many users were running when the load test finished.
+    pub users: usize,
+    /// The hosts that were load tested.
+    pub hosts: Vec<String>,
+    /// The response time percentiles included in the summary: the configured percentiles,
+    /// and the 95th and 99th percentiles which are compared against a baseline.
+    pub percentiles: Vec<f64>,
+    /// Metrics for each request, sorted by method and name.
+    pub requests: Vec<GooseSummaryRequest>,
+    /// Metrics for all requests aggregated together.
+    pub aggregated: GooseSummaryRequest,
+    /// Metrics for each task, in the order they were registered.
+    pub tasks: Vec<GooseSummaryTask>,
+    /// Metrics for each transaction, sorted by name.
+    pub transactions: Vec<GooseSummaryTransaction>,
+    /// Each custom counter, gauge and timing, sorted by name.
+    pub custom: Vec<GooseSummaryCustomMetric>,
+    /// Every error seen during the load test, sorted by request.
+    pub errors: Vec<GooseSummaryError>,
+    /// The result of each threshold, if any were configured.
+    pub thresholds: Vec<GooseThresholdResult>,
+    /// Iteration metrics, only set when the load test ran at a fixed arrival rate.
+    pub iterations: Option<GooseIterationMetrics>,
+    /// The configuration of the load test.
+    pub configuration: serde_json::Value,
+}
+
+/// Summarized metrics for one request, or for all requests aggregated together.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GooseSummaryRequest {
+    /// The request method, or None for aggregated metrics.
+    pub method: Option<GooseMethod>,
+    /// The request name, `Aggregated` for aggregated metrics.
+    pub name: String,
+    /// Total requests.
+    pub requests: usize,
+    /// Total failed requests.
+    pub failures: usize,
+    /// Percentage of requests that failed.
+    pub failure_rate: f64,
+    /// Requests per second.
+    pub requests_per_second: f64,
+    /// Failed requests per second.
+    pub failures_per_second: f64,
+    /// Response times in milliseconds.
+    pub response_times: GooseSummaryTimes,
+    /// Response times in milliseconds including those generated by coordinated omission
+    /// mitigation, only set if it's enabled.
+    pub coordinated_omission_response_times: Option<GooseSummaryTimes>,
+    /// How often each status code was returned.
+    pub status_codes: BTreeMap<u16, usize>,
+    /// How often each gRPC status code was returned, by gRPC calls.
+    #[serde(default)]
+    pub grpc_status_codes: BTreeMap<u16, usize>,
+}
+
+/// Summarized response or run times, in milliseconds.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GooseSummaryTimes {
+    /// Average time.
+    pub average: f64,
+    /// Minimum time.
+    pub minimum: usize,
+    /// Maximum time.
+    pub maximum: usize,
+    /// Median time.
+    pub median: f64,
+    /// Time at each configured percentile, keyed as `p50`, `p99.9` and so on.
+    pub percentiles: BTreeMap<String, f64>,
+}
+
+/// Summarized metrics for one task.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GooseSummaryTask {
+    /// The task set and task index as displayed in the metrics, ie `1.2`.
+    pub index: String,
+    /// The name of the task set.
+    pub taskset: String,
+    /// The name of the task, empty if the task isn't named.
+    pub name: String,
+    /// Total times the task ran.
+    pub runs: usize,
+    /// Total times the task failed.
+    pub failures: usize,
+    /// Percentage of task runs that failed.
+    pub failure_rate: f64,
+    /// Task runs per second.
+    pub runs_per_second: f64,
+    /// Failed task runs per second.
+    pub failures_per_second: f64,
+    /// Task run times in milliseconds.
+    pub run_times: GooseSummaryTimes,
+}
+
+/// Summarized metrics for one transaction.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GooseSummaryTransaction {
+    /// The name of the transaction.
+    pub name: String,
+    /// Total times the transaction ran.
+    pub runs: usize,
+    /// Total times the transaction failed.
+    pub failures: usize,
+    /// Percentage of transaction runs that failed.
+    pub failure_rate: f64,
+    /// Transaction runs per second.
+    pub runs_per_second: f64,
+    /// Failed transaction runs per second.
+    pub failures_per_second: f64,
+    /// Transaction run times in milliseconds.
+    pub run_times: GooseSummaryTimes,
+}
+
+/// Summarized values of one custom metric.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GooseSummaryCustomMetric {
+    /// The name of the custom metric.
+    pub name: String,
+    /// The kind of the custom metric.
+    pub kind: GooseCustomMetricKind,
+    /// How many values were recorded.
+    pub samples: usize,
+    /// The sum of all values, the total of a counter.
+    pub total: f64,
+    /// The sum of all values per second.
+    pub per_second: f64,
+    /// The most recently recorded value.
+    pub last: f64,
+    /// The smallest value recorded.
+    pub minimum: f64,
+    /// The average of all values recorded.
+    pub average: f64,
+    /// The largest value recorded.
+    pub maximum: f64,
+    /// Times in milliseconds, only set for timings.
+    pub times: Option<GooseSummaryTimes>,
+}
+
+/// An error seen during the load test.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GooseSummaryError {
+    /// The method of the request that failed.
+    pub method: GooseMethod,
+    /// The name of the request that failed.
+    pub name: String,
+    /// The error.
+    pub error: String,
+    /// How many times the error was seen.
+    pub occurrences: usize,
+}
+
+impl GooseSummary {
+    /// Summarize the final metrics of a load test.
+    pub fn new(metrics: &GooseMetrics, configuration: &GooseConfiguration) -> Self {
+        let mut percentiles = if metrics.percentiles.is_empty() {
+            DEFAULT_PERCENTILES.to_vec()
+        } else {
+            metrics.percentiles.clone()
+        };
+        // Always include the percentiles compared against a baseline.
+        for percentile in &BASELINE_PERCENTILES {
+            if !percentiles.contains(percentile) {
+                percentiles.push(*percentile);
+            }
+        }
+        percentiles.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let mut hosts: Vec<String> = metrics.hosts.iter().cloned().collect();
+        hosts.sort();
+
+        // Sort requests by key, matching the order they're displayed in.
+        let mut request_keys: Vec<&String> = metrics.requests.keys().collect();
+        request_keys.sort();
+
+        let mut requests = Vec::new();
+        let mut aggregated_raw = GooseRequestMetricTimingData::new(None);
+        let mut aggregated_co: Option<GooseRequestMetricTimingData> = None;
+        let mut aggregated_status_codes = BTreeMap::new();
+        let mut aggregated_grpc_status_codes = BTreeMap::new();
+        let (mut aggregated_success, mut aggregated_fail) = (0, 0);
+        for request_key in request_keys {
+            let request = &metrics.requests[request_key];
+            let status_codes: BTreeMap<u16, usize> = request
+                .status_code_counts
+                .iter()
+                .map(|(code, count)| (*code, *count))
+                .collect();
+            let grpc_status_codes: BTreeMap<u16, usize> = request
+                .grpc_status_code_counts
+                .iter()
+                .map(|(code, count)| (*code, *count))
+                .collect();
+            requests.push(summarize_request(
+                Some(request.method.clone()),
+                &request.path,
+                request.success_count,
+                request.fail_count,
+                &request.raw_data,
+                request.coordinated_omission_data.as_ref(),
+                status_codes.clone(),
+                grpc_status_codes.clone(),
+                metrics.duration,
+                &percentiles,
+            ));
+
+            // Aggregate all requests together.
+            aggregated_success += request.success_count;
+            aggregated_fail += request.fail_count;
+            merge_timing_data(&mut aggregated_raw, &request.raw_data);
+            if let Some(co_data) = request.coordinated_omission_data.as_ref() {
+                merge_timing_data(
+                    aggregated_co.get_or_insert_with(|| GooseRequestMetricTimingData::new(None)),
+                    co_data,
+                );
+            }
+            for (code, count) in status_codes {
+                *aggregated_status_codes.entry(code).or_insert(0) += count;
+            }
+            for (code, count) in grpc_status_codes {
+                *aggregated_grpc_status_codes.entry(code).or_insert(0) += count;
+            }
+        }
+        let aggregated = summarize_request(
+            None,
+            "Aggregated",
+            aggregated_success,
+            aggregated_fail,
+            &aggregated_raw,
+            aggregated_co.as_ref(),
+            aggregated_status_codes,
+            aggregated_grpc_status_codes,
+            metrics.duration,
+            &percentiles,
+        );
+
+        let mut tasks = Vec::new();
+        for task_set in &metrics.tasks {
+            for task in task_set {
+                let runs = task.success_count + task.fail_count;
+                let (runs_per_second, failures_per_second) =
+                    per_second_calculations(metrics.duration, runs, task.fail_count);
+                tasks.push(GooseSummaryTask {
+                    index: format!("{}.{}", task.taskset_index + 1, task.task_index + 1),
+                    taskset: task.taskset_name.clone(),
+                    name: task.task_name.clone(),
+                    runs,
+                    failures: task.fail_count,
+                    failure_rate: failure_rate(runs, task.fail_count),
+                    runs_per_second: runs_per_second as f64,
+                    failures_per_second: failures_per_second as f64,
+                    run_times: summarize_times(
+                        &task.times,
+                        task.min_time,
+                        task.max_time,
+                        task.total_time,
+                        task.counter,
+                        &percentiles,
+                    ),
+                });
+            }
+        }
+
+        let transactions = metrics
+            .transactions
+            .values()
+            .map(|transaction| {
+                let runs = transaction.success_count + transaction.fail_count;
+                let (runs_per_second, failures_per_second) =
+                    per_second_calculations(metrics.duration, runs, transaction.fail_count);
+                GooseSummaryTransaction {
+                    name: transaction.name.clone(),
+                    runs,
+                    failures: transaction.fail_count,
+                    failure_rate: failure_rate(runs, transaction.fail_count),
+                    runs_per_second: runs_per_second as f64,
+                    failures_per_second: failures_per_second as f64,
+                    run_times: summarize_times(
+                        &transaction.times,
+                        transaction.min_time,
+                        transaction.max_time,
+                        transaction.total_time,
+                        transaction.counter,
+                        &percentiles,
+                    ),
+                }
+            })
+            .collect();
+
+        let custom = metrics
+            .custom
+            .values()
+            .map(|custom| GooseSummaryCustomMetric {
+                name: custom.name.clone(),
+                kind: custom.kind,
+                samples: custom.samples,
+                total: custom.total,
+                per_second: match metrics.duration {
+                    0 => 0.0,
+                    duration => custom.total / duration as f64,
+                },
+                last: custom.last,
+                minimum: custom.minimum,
+                average: custom.average(),
+                maximum: custom.maximum,
+                times: match custom.kind {
+                    GooseCustomMetricKind::Timing => Some(summarize_times(
+                        &custom.times,
+                        custom.minimum.round() as usize,
+                        custom.maximum.round() as usize,
+                        custom.total.round() as usize,
+                        custom.samples,
+                        &percentiles,
+                    )),
+                    _ => None,
+                },
+            })
+            .collect();
+
+        let errors = metrics
+            .errors
+            .values()
+            .map(|error| GooseSummaryError {
+                method: error.method.clone(),
+                name: error.name.clone(),
+                error: error.error.clone(),
+                occurrences: error.occurrences,
+            })
+            .collect();
+
+        GooseSummary {
+            schema_version: SUMMARY_SCHEMA_VERSION,
+            goose_version: env!("CARGO_PKG_VERSION").to_string(),
+            starting: metrics.starting.map(|time| time.to_rfc3339()),
+            started: metrics.started.map(|time| time.to_rfc3339()),
+            stopping: metrics.stopping.map(|time| time.to_rfc3339()),
+            stopped: metrics.stopped.map(|time| time.to_rfc3339()),
+            duration: metrics.duration,
+            users: metrics.users,
+            hosts,
+            percentiles,
+            requests,
+            aggregated,
+            tasks,
+            transactions,
+            custom,
+            errors,
+            thresholds: metrics.thresholds.clone(),
+            iterations: metrics.iterations.clone(),
+            configuration: serde_json::to_value(configuration).unwrap_or(serde_json::Value::Null),
+        }
+    }
+
+    /// Serialize the summary as pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("unexpected serde failure")
+    }
+
+    /// Serialize the summary as CSV, with one row per value.
+    pub fn to_csv(&self) -> String {
+        let mut rows = vec!["section,key,field,value".to_string()];
+
+        let mut summary = serde_json::to_value(self).expect("unexpected serde failure");
+        // Everything that isn't a scalar about the load test as a whole has its own section.
+        let mut sections = BTreeMap::new();
+        if let Some(object) = summary.as_object_mut() {
+            for section in &[
+                "configuration",
+                "requests",
+                "aggregated",
+                "tasks",
+                "transactions",
+                "custom",
+                "errors",
+                "thresholds",
+            ] {
+                if let Some(value) = object.remove(*section) {
+                    sections.insert(*section, value);
+                }
+            }
+        }
+        push_csv_rows(&mut rows, "summary", "", &summary);
+        push_csv_rows(&mut rows, "configuration", "", &sections["configuration"]);
+        for request in &self.requests {
+            push_csv_rows(
+                &mut rows,
+                "request",
+                &request_key(request.method.as_ref(), &request.name),
+                &serde_json::to_value(request).expect("unexpected serde failure"),
+            );
+        }
+        push_csv_rows(&mut rows, "aggregated", "", &sections["aggregated"]);
+        for task in &self.tasks {
+            push_csv_rows(
+                &mut rows,
+                "task",
+                &task.index,
+                &serde_json::to_value(task).expect("unexpected serde failure"),
+            );
+        }
+        for transaction in &self.transactions {
+            push_csv_rows(
+                &mut rows,
+                "transaction",
+                &transaction.name,
+                &serde_json::to_value(transaction).expect("unexpected serde failure"),
+            );
+        }
+        for custom in &self.custom {
+            push_csv_rows(
+                &mut rows,
+                "custom",
+                &custom.name,
+                &serde_json::to_value(custom).expect("unexpected serde failure"),
+            );
+        }
+        for error in &self.errors {
+            push_csv_rows(
+                &mut rows,
+                "error",
+                &format!("{} {}: {}", error.method, error.name, error.error),
+                &serde_json::to_value(error).expect("unexpected serde failure"),
+            );
+        }
+        for threshold in &self.thresholds {
+            push_csv_rows(
+                &mut rows,
+                "threshold",
+                &threshold.threshold,
+                &serde_json::to_value(threshold).expect("unexpected serde failure"),
+            );
+        }
+
+        rows.push("".to_string());
+        rows.join("\n")
+    }
+
+    /// Serialize the summary as a JUnit XML report.
+    ///
+    /// Each request, task and transaction is a test case, which fails if it had any
+    /// failures or breached a threshold. Thresholds that don't apply to a single request
+    /// are reported in an `Aggregated` test case.
+    pub fn to_junit_xml(&self) -> String {
+        let mut request_cases = Vec::new();
+        let mut attached = vec![false; self.thresholds.len()];
+        for request in &self.requests {
+            let key = request_key(request.method.as_ref(), &request.name);
+            let mut messages = Vec::new();
+            let mut details = Vec::new();
+            if request.failures > 0 {
+                messages.push(format!(
+                    "{} of {} requests failed",
+                    request.failures, request.requests
+                ));
+                for error in &self.errors {
+                    if request.method.as_ref() == Some(&error.method) && request.name == error.name
+                    {
+                        details.push(format!("{} ({} times)", error.error, error.occurrences));
+                    }
+                }
+            }
+            for (index, threshold) in self.thresholds.iter().enumerate() {
+                if threshold.request.as_ref() == Some(&key) {
+                    attached[index] = true;
+                    if !threshold.passed {
+                        messages.push(format!(
+                            "threshold {} failed: {}",
+                            threshold.threshold, threshold
+                        ));
+                    }
+                }
+            }
+            request_cases.push(JunitCase {
+                name: key,
+                classname: "requests".to_string(),
+                time: run_time(&request.response_times, request.requests),
+                messages,
+                details,
+            });
+        }
+        // Thresholds that weren't attached to a request are reported together.
+        if attached.iter().any(|attached| !attached) {
+            let messages = self
+                .thresholds
+                .iter()
+                .zip(attached)
+                .filter(|(threshold, attached)| !attached && !threshold.passed)
+                .map(|(threshold, _)| {
+                    format!("threshold {} failed: {}", threshold.threshold, threshold)
+                })
+                .collect();
+            request_cases.push(JunitCase {
+                name: self.aggregated.name.clone(),
+                classname: "requests".to_string(),
+                time: run_time(&self.aggregated.response_times, self.aggregated.requests),
+                messages,
+                details: Vec::new(),
+            });
+        }
+
+        let task_cases: Vec<JunitCase> = self
+            .tasks
+            .iter()
+            .map(|task| {
+                let mut messages = Vec::new();
+                if task.failures > 0 {
+                    messages.push(format!("{} of {} runs failed", task.failures, task.runs));
+                }
+                JunitCase {
+                    name: if task.name.is_empty() {
+                        task.index.clone()
+                    } else {
+                        format!("{} {}", task.index, task.name)
+                    },
+                    classname: task.taskset.clone(),
+                    time: run_time(&task.run_times, task.runs),
+                    messages,
+                    details: Vec::new(),
+                }
+            })
+            .collect();
+
+        let transaction_cases: Vec<JunitCase> = self
+            .transactions
+            .iter()
+            .map(|transaction| {
+                let mut messages = Vec::new();
+                if transaction.failures > 0 {
+                    messages.push(format!(
+                        "{} of {} runs failed",
+                        transaction.failures, transaction.runs
+                    ));
+                }
+                JunitCase {
+                    name: transaction.name.clone(),
+                    classname: "transactions".to_string(),
+                    time: run_time(&transaction.run_times, transaction.runs),
+                    messages,
+                    details: Vec::new(),
+                }
+            })
+            .collect();
+
+        let failures = |cases: &[JunitCase]| {
+            cases
+                .iter()
+                .filter(|case| !case.messages.is_empty())
+                .count()
+        };
+        let mut xml = vec!["<?xml version=\"1.0\" encoding=\"UTF-8\"?>".to_string()];
+        xml.push(format!(
+            "<testsuites name=\"goose\" tests=\"{}\" failures=\"{}\" errors=\"0\" time=\"{}\">",
+            request_cases.len() + task_cases.len() + transaction_cases.len(),
+            failures(&request_cases) + failures(&task_cases) + failures(&transaction_cases),
+            self.duration
+        ));
+        for (name, cases) in &[
+            ("requests", request_cases),
+            ("tasks", task_cases),
+            ("transactions", transaction_cases),
+        ] {
+            // Task metrics may be disabled, and there may be no transactions.
+            if cases.is_empty() {
+                continue;
+            }
+            let timestamp = match self.starting.as_ref() {
+                Some(starting) => format!(" timestamp=\"{}\"", xml_escape(starting)),
+                None => "".to_string(),
+            };
+            xml.push(format!(
+                "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"0\" time=\"{}\"{}>",
+                name,
+                cases.len(),
+                failures(cases),
+                self.duration,
+                timestamp
+            ));
+            for case in cases {
+                let attributes = format!(
+                    "name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+                    xml_escape(&case.name),
+                    xml_escape(&case.classname),
+                    case.time
+                );
+                if case.messages.is_empty() {
+                    xml.push(format!("    <testcase {}/>", attributes));
+                } else {
+                    xml.push(format!("    <testcase {}>", attributes));
+                    xml.push(format!(
+                        "      <failure message=\"{}\">{}</failure>",
+                        xml_escape(&case.messages.join("; ")),
+                        case.details
+                            .iter()
+                            .map(|detail| xml_escape(detail))
+                            .collect::<Vec<String>>()
+                            .join("\n")
+                    ));
+                    xml.push("    </testcase>".to_string());
+                }
+            }
+            xml.push("  </testsuite>".to_string());
+        }
+        xml.push("</testsuites>".to_string());
+        xml.push("".to_string());
+        xml.join("\n")
+    }
+}
+
+/// A test case in the JUnit XML report.
+struct JunitCase {
+    name: String,
+    classname: String,
+    /// Total time spent in seconds.
+    time: f64,
+    /// Why the test case failed, empty if it passed.
+    messages: Vec<String>,
+    /// Errors seen by the test case.
+    details: Vec<String>,
+}
+
+/// Total time spent in seconds, from the average time in milliseconds.
+fn run_time(times: &GooseSummaryTimes, count: usize) -> f64 {
+    times.average * count as f64 / 1_000.0
+}
+
+/// Escape a string for use in XML text and attributes.
+fn xml_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' => escaped.push_str("&#10;"),
+            // Other control characters aren't allowed in XML.
+            c if c.is_control() && c != '\t' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The key a request is displayed with in the metrics, ie `GET /`.
+fn request_key(method: Option<&GooseMethod>, name: &str) -> String {
+    match method {
+        Some(method) => format!("{} {}", method, name),
+        None => name.to_string(),
+    }
+}
+
+/// Percentage of total that failed.
+fn failure_rate(total: usize, fail: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        fail as f64 * 100.0 / total as f64
+    }
+}
+
+/// Merge the timing data of one request into aggregated timing data.
+fn merge_timing_data(
+    aggregated: &mut GooseRequestMetricTimingData,
+    data: &GooseRequestMetricTimingData,
+) {
+    aggregated.times.add(&data.times);
+    if data.minimum_time > 0
+        && (aggregated.minimum_time == 0 || data.minimum_time < aggregated.minimum_time)
+    {
+        aggregated.minimum_time = data.minimum_time;
+    }
+    aggregated.maximum_time = aggregated.maximum_time.max(data.maximum_time);
+    aggregated.total_time += data.total_time;
+    aggregated.counter += data.counter;
+}
+
+#[allow(clippy::too_many_arguments)]
+fn summarize_request(
+    method: Option<GooseMethod>,
+    name: &str,
+    success: usize,
+    fail: usize,
+    raw_data: &GooseRequestMetricTimingData,
+    coordinated_omission_data: Option<&GooseRequestMetricTimingData>,
+    status_codes: BTreeMap<u16, usize>,
+    grpc_status_codes: BTreeMap<u16, usize>,
+    duration: usize,
+    percentiles: &[f64],
+) -> GooseSummaryRequest {
+    let total = success + fail;
+    let (requests_per_second, failures_per_second) = per_second_calculations(duration, total, fail);
+    let timing_data = |data: &GooseRequestMetricTimingData| {
+        summarize_times(
+            &data.times,
+            data.minimum_time,
+            data.maximum_time,
+            data.total_time,
+            data.counter,
+            percentiles,
+        )
+    };
+    GooseSummaryRequest {
+        method,
+        name: name.to_string(),
+        requests: total,
+        failures: fail,
+        failure_rate: failure_rate(total, fail),
+        requests_per_second: requests_per_second as f64,
+        failures_per_second: failures_per_second as f64,
+        response_times: timing_data(raw_data),
+        coordinated_omission_response_times: coordinated_omission_data.map(timing_data),
+        status_codes,
+        grpc_status_codes,
+    }
+}
+
+fn summarize_times(
+    times: &GooseHistogram,
+    minimum: usize,
+    maximum: usize,
+    total: usize,
+    counter: usize,
+    percentiles: &[f64],
+) -> GooseSummaryTimes {
+    // The histogram tracks microseconds, convert to milliseconds.
+    let percentile = |percentile: f64| {
+        if times.is_empty() {
+            0.0
+        } else {
+            times.value_at_percentile(percentile) as f64 / 1_000.0
+        }
+    };
+    GooseSummaryTimes {
+        average: if counter == 0 {
+            0.0
+        } else {
+            total as f64 / counter as f64
+        },
+        minimum,
+        maximum,
+        median: percentile(50.0),
+        percentiles: percentiles
+            .iter()
+            .map(|p| (format!("p{}", p), percentile(*p)))
+            .collect(),
+    }
+}
+
+/// Flatten a JSON value into CSV rows, joining nested field names with `.`.
+fn push_csv_rows(rows: &mut Vec<String>, section: &str, key: &str, value: &serde_json::Value) {
+    fn flatten(fields: &mut Vec<(String, String)>, field: String, value: &serde_json::Value) {
+        let join = |name: &str| {
+            if field.is_empty() {
+                name.to_string()
+            } else {
+                format!("{}.{}", field, name)
+            }
+        };
+        match value {
+            serde_json::Value::Object(object) => {
+                for (name, value) in object {
+                    flatten(fields, join(name), value);
+                }
+            }
+            serde_json::Value::Array(array) => {
+                for (index, value) in array.iter().enumerate() {
+                    flatten(fields, join(&index.to_string()), value);
+                }
+            }
+            serde_json::Value::Null => fields.push((field, "".to_string())),
+            serde_json::Value::String(string) => fields.push((field, string.to_string())),
+            _ => fields.push((field, value.to_string())),
+        }
+    }
+
+    let mut fields = Vec::new();
+    flatten(&mut fields, "".to_string(), value);
+    for (field, value) in fields {
+        rows.push(format!(
+            "{},{},{},{}",
+            csv_escape(section),
+            csv_escape(key),
+            csv_escape(&field),
+            csv_escape(&value)
+        ));
+    }
+}
+
+/// Quote a CSV value if it contains a separator, quote or newline.
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+impl GooseAttack {
+    /// Write the JSON and CSV summaries of the final metrics, if enabled.
+    pub(crate) async fn write_summaries(&self) -> Result<(), GooseError> {
+        if self.configuration.summary_json.is_empty()
+            && self.configuration.summary_csv.is_empty()
+            && self.configuration.junit_xml.is_empty()
+        {
+            return Ok(());
+        }
+
+        let summary = GooseSummary::new(&self.metrics, &self.configuration);
+        for (option, path, contents) in &[
+            (
+                "--summary-json",
+                &self.configuration.summary_json,
+                summary.to_json(),
+            ),
+            (
+                "--summary-csv",
+                &self.configuration.summary_csv,
+                summary.to_csv(),
+            ),
+            (
+                "--junit-xml",
+                &self.configuration.junit_xml,
+                summary.to_junit_xml(),
+            ),
+        ] {
+            if path.is_empty() {
+                continue;
+            }
+            if let Err(e) = write_file(path, contents).await {
+                return Err(GooseError::InvalidOption {
+                    option: option.to_string(),
+                    value: path.to_string(),
+                    detail: format!("Failed to write summary file: {}", e),
+                });
+            }
+            info!("wrote summary file to: {}", path);
+        }
+
+        Ok(())
+    }
+}
+
+async fn write_file(path: &str, contents: &str) -> Result<(), std::io::Error> {
+    let mut file = File::create(path).await?;
+    file.write_all(contents.as_bytes()).await?;
+    file.flush().await
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn xml_escaping() {
+        assert_eq!(
+            xml_escape("GET /?a=1&b=<2> \"x\" 'y'"),
+            "GET /?a=1&amp;b=&lt;2&gt; &quot;x&quot; &apos;y&apos;"
+        );
+        assert_eq!(xml_escape("a\nb\u{1b}c\td"), "a&#10;bc\td");
+    }
+
+    #[test]
+    fn csv_rows() {
+        let value = serde_json::json!({
+            "name": "GET /, \"home\"",
+            "times": { "average": 1.5, "percentiles": { "p95": 3.0 } },
+            "hosts": ["a", "b"],
+            "iterations": null,
+        });
+        let mut rows = Vec::new();
+        push_csv_rows(&mut rows, "request", "GET /", &value);
+        assert_eq!(
+            rows,
+            vec![
+                "request,GET /,hosts.0,a",
+                "request,GET /,hosts.1,b",
+                "request,GET /,iterations,",
+                "request,GET /,name,\"GET /, \"\"home\"\"\"",
+                "request,GET /,times.average,1.5",
+                "request,GET /,times.percentiles.p95,3.0",
+            ]
+        );
+    }
+}
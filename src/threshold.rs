@@ -0,0 +1,521 @@
+//! Optional pass/fail thresholds, evaluated against the final metrics.
+//!
+//! A threshold is a rule that the final [`GooseMetrics`] must satisfy for a load test to
+//! pass, for example `GET /:p95<300ms` (95% of `GET /` requests completed in under 300
+//! milliseconds) or `errors<1%` (less than 1% of all requests failed). Thresholds are
+//! configured with the `--threshold` run-time option, which can be set multiple times,
+//! or with [`GooseAttack::set_threshold`](../struct.GooseAttack.html#method.set_threshold).
+//!
+//! Each threshold is written `[REQUEST:]METRIC OPERATOR VALUE`:
+//!  - `REQUEST` is optional, and is the method and name of a request as displayed in the
+//!    metrics (ie `GET /` or `POST login`). Without a request, the threshold applies to all
+//!    requests aggregated together.
+//!  - `METRIC` is one of `avg`, `min`, `max`, `median`, a percentile such as `p95` or
+//!    `p99.9`, `rps` (requests per second), `requests` (total requests), or `errors`
+//!    (failed requests, as a percentage if the value ends in `%`, otherwise a count).
+//!  - `OPERATOR` is one of `<`, `<=`, `>` or `>=`.
+//!  - `VALUE` is a number. Times are in milliseconds, or in seconds when followed by `s`.
+//!
+//! When the load test finishes, the result of each threshold is displayed with the
+//! metrics and stored in [`GooseMetrics::thresholds`]. If any threshold fails,
+//! [`GooseAttack::execute`](../struct.GooseAttack.html#method.execute) returns
+//! [`GooseError::ThresholdsFailed`], so a load test that returns the error from `main()`
+//! exits with a non-zero status. With `--threshold-abort`, the load test stops as soon as a
+//! threshold can no longer be met, for example once more requests failed than allowed.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::metrics::{GooseHistogram, GooseMetrics};
+use crate::{GooseAttack, GooseError};
+
+/// The metric a threshold is evaluated against.
+#[derive(Clone, Debug, PartialEq)]
+enum GooseThresholdMetric {
+    /// Average response time in milliseconds.
+    Average,
+    /// Minimum response time in milliseconds.
+    Minimum,
+    /// Maximum response time in milliseconds.
+    Maximum,
+    /// Response time percentile in milliseconds.
+    Percentile(f64),
+    /// Requests per second.
+    RequestsPerSecond,
+    /// Total requests.
+    Requests,
+    /// Total failed requests.
+    Failures,
+    /// Percentage of requests that failed.
+    FailureRate,
+}
+impl GooseThresholdMetric {
+    // The unit values of this metric are displayed in.
+    fn unit(&self) -> &'static str {
+        match self {
+            GooseThresholdMetric::Average
+            | GooseThresholdMetric::Minimum
+            | GooseThresholdMetric::Maximum
+            | GooseThresholdMetric::Percentile(_) => "ms",
+            GooseThresholdMetric::RequestsPerSecond => "/s",
+            GooseThresholdMetric::Requests | GooseThresholdMetric::Failures => "",
+            GooseThresholdMetric::FailureRate => "%",
+        }
+    }
+}
+
+/// How a metric is compared to the threshold value.
+#[derive(Clone, Debug, PartialEq)]
+enum GooseThresholdComparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+/// A pass/fail rule evaluated against the final metrics.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct GooseThreshold {
+    /// The threshold as it was configured, ie `GET /:p95<300ms`.
+    rule: String,
+    /// The optional request the threshold applies to, ie `GET /`.
+    request: Option<String>,
+    /// The metric being compared.
+    metric: GooseThresholdMetric,
+    /// How the metric is compared.
+    comparison: GooseThresholdComparison,
+    /// The value the metric is compared to, times are in milliseconds.
+    value: f64,
+}
+impl FromStr for GooseThreshold {
+    type Err = String;
+
+    fn from_str(threshold: &str) -> Result<Self, Self::Err> {
+        let rule = threshold.trim();
+        let invalid = |detail: &str| {
+            Err(format!(
+                "invalid threshold `{}`: {} (expected [REQUEST:]METRIC<VALUE, ie \"GET /:p95<300ms\" or \"errors<1%\").",
+                rule, detail
+            ))
+        };
+
+        // Find the operator. Request names can contain `<` and `>` but values can't, so
+        // the operator is the last of them.
+        let (position, comparison, operator_length) = match rule.rfind(&['<', '>'][..]) {
+            Some(position) => {
+                let less = rule[position..].starts_with('<');
+                let or_equal = rule[position + 1..].starts_with('=');
+                let comparison = match (less, or_equal) {
+                    (true, false) => GooseThresholdComparison::Less,
+                    (true, true) => GooseThresholdComparison::LessOrEqual,
+                    (false, false) => GooseThresholdComparison::Greater,
+                    (false, true) => GooseThresholdComparison::GreaterOrEqual,
+                };
+                (position, comparison, if or_equal { 2 } else { 1 })
+            }
+            None => return invalid("missing operator, one of <, <=, > or >="),
+        };
+
+        // The request is optional, and can itself contain colons.
+        let (request, metric) = match rule[..position].rsplit_once(':') {
+            Some((request, metric)) => {
+                if request.trim().is_empty() {
+                    return invalid("empty request name");
+                }
+                (Some(request.trim().to_string()), metric.trim())
+            }
+            None => (None, rule[..position].trim()),
+        };
+
+        // Split the value from its unit.
+        let value = rule[position + operator_length..].trim();
+        let unit_position = value
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(value.len());
+        let (number, unit) = value.split_at(unit_position);
+        let number: f64 = match number.parse() {
+            Ok(number) => number,
+            Err(_) => return invalid("value must be a number"),
+        };
+        let unit = unit.trim();
+
+        let metric = match metric.to_lowercase().as_str() {
+            "avg" => GooseThresholdMetric::Average,
+            "min" => GooseThresholdMetric::Minimum,
+            "max" => GooseThresholdMetric::Maximum,
+            "median" => GooseThresholdMetric::Percentile(50.0),
+            "rps" => GooseThresholdMetric::RequestsPerSecond,
+            "requests" => GooseThresholdMetric::Requests,
+            "errors" | "failures" if unit == "%" => GooseThresholdMetric::FailureRate,
+            "errors" | "failures" => GooseThresholdMetric::Failures,
+            percentile if percentile.starts_with('p') => match percentile[1..].parse::<f64>() {
+                Ok(percentile) if percentile > 0.0 && percentile <= 100.0 => {
+                    GooseThresholdMetric::Percentile(percentile)
+                }
+                _ => return invalid("percentile must be greater than 0 and at most 100"),
+            },
+            _ => return invalid("unknown metric"),
+        };
+
+        // Times default to milliseconds, all other metrics don't have a unit.
+        let value = match (metric.unit(), unit) {
+            ("ms", "") | ("ms", "ms") | ("%", "%") | ("", "") | ("/s", "") | ("/s", "/s") => number,
+            ("ms", "s") => number * 1_000.0,
+            _ => return invalid("invalid unit for this metric"),
+        };
+
+        Ok(GooseThreshold {
+            rule: rule.to_string(),
+            request,
+            metric,
+            comparison,
+            value,
+        })
+    }
+}
+impl GooseThreshold {
+    /// Evaluate the threshold against metrics.
+    fn evaluate(&self, metrics: &GooseMetrics) -> GooseThresholdResult {
+        let value = self.measure(metrics);
+        let passed = match value {
+            Some(value) => match self.comparison {
+                GooseThresholdComparison::Less => value < self.value,
+                GooseThresholdComparison::LessOrEqual => value <= self.value,
+                GooseThresholdComparison::Greater => value > self.value,
+                GooseThresholdComparison::GreaterOrEqual => value >= self.value,
+            },
+            // A threshold can't pass without any matching requests.
+            None => false,
+        };
+        GooseThresholdResult {
+            threshold: self.rule.clone(),
+            request: self.request.clone(),
+            value,
+            unit: self.metric.unit().to_string(),
+            passed,
+        }
+    }
+
+    /// Returns true if the threshold is already failing, and will still be failing no
+    /// matter how many more requests are made. Only counters, and the minimum and
+    /// maximum response times, can't recover.
+    fn can_not_be_met(&self, metrics: &GooseMetrics) -> bool {
+        let final_once_failed = matches!(
+            (&self.metric, &self.comparison),
+            (
+                GooseThresholdMetric::Requests
+                    | GooseThresholdMetric::Failures
+                    | GooseThresholdMetric::Maximum,
+                GooseThresholdComparison::Less | GooseThresholdComparison::LessOrEqual
+            ) | (
+                GooseThresholdMetric::Minimum,
+                GooseThresholdComparison::Greater | GooseThresholdComparison::GreaterOrEqual
+            )
+        );
+        if !final_once_failed {
+            return false;
+        }
+        let result = self.evaluate(metrics);
+        result.value.is_some() && !result.passed
+    }
+
+    /// Measure the metric the threshold is compared to, or None if there were no
+    /// matching requests.
+    fn measure(&self, metrics: &GooseMetrics) -> Option<f64> {
+        let mut times = GooseHistogram::new();
+        let (mut success, mut fail, mut total_time, mut counter) = (0, 0, 0, 0);
+        let (mut minimum, mut maximum) = (0, 0);
+        let mut found = false;
+        for (request_key, request) in &metrics.requests {
+            if let Some(only) = self.request.as_ref() {
+                if request_key != only {
+                    continue;
+                }
+            }
+            found = true;
+            times.add(&request.raw_data.times);
+            success += request.success_count;
+            fail += request.fail_count;
+            total_time += request.raw_data.total_time;
+            counter += request.raw_data.counter;
+            if request.raw_data.minimum_time > 0
+                && (minimum == 0 || request.raw_data.minimum_time < minimum)
+            {
+                minimum = request.raw_data.minimum_time;
+            }
+            maximum = maximum.max(request.raw_data.maximum_time);
+        }
+        if !found {
+            return None;
+        }
+
+        let total = success + fail;
+        match self.metric {
+            GooseThresholdMetric::Average if counter > 0 => {
+                Some(total_time as f64 / counter as f64)
+            }
+            GooseThresholdMetric::Minimum if counter > 0 => Some(minimum as f64),
+            GooseThresholdMetric::Maximum if counter > 0 => Some(maximum as f64),
+            GooseThresholdMetric::Percentile(percentile) if !times.is_empty() => {
+                Some(times.value_at_percentile(percentile) as f64 / 1_000.0)
+            }
+            GooseThresholdMetric::RequestsPerSecond => {
+                Some(total as f64 / metrics.duration.max(1) as f64)
+            }
+            GooseThresholdMetric::Requests => Some(total as f64),
+            GooseThresholdMetric::Failures => Some(fail as f64),
+            GooseThresholdMetric::FailureRate if total > 0 => {
+                Some(fail as f64 * 100.0 / total as f64)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The result of evaluating a threshold against the final metrics.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GooseThresholdResult {
+    /// The threshold as it was configured, ie `GET /:p95<300ms`.
+    pub threshold: String,
+    /// The request the threshold applies to, or None if it applies to all requests.
+    pub request: Option<String>,
+    /// The measured value, or None if there were no matching requests.
+    pub value: Option<f64>,
+    /// The unit of the measured value: `ms`, `%`, `/s`, or empty for counts.
+    pub unit: String,
+    /// Whether or not the threshold was met.
+    pub passed: bool,
+}
+impl fmt::Display for GooseThresholdResult {
+    // Display the measured value with its unit.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.value {
+            Some(value) if self.unit.is_empty() => write!(f, "{}", value),
+            Some(value) => write!(f, "{:.2}{}", value, self.unit),
+            None => write!(f, "no requests"),
+        }
+    }
+}
+
+impl GooseAttack {
+    /// Add a pass/fail threshold, evaluated against the final metrics when the load test
+    /// finishes. If any threshold fails,
+    /// [`GooseAttack::execute`](./struct.GooseAttack.html#method.execute) returns
+    /// [`GooseError::ThresholdsFailed`]. Thresholds can also be set with the
+    /// `--threshold` run-time option, and are combined with any set here.
+    ///
+    /// Thresholds are written `[REQUEST:]METRIC OPERATOR VALUE`, refer to the
+    /// [`threshold`](./threshold/index.html) module for all supported metrics.
+    ///
+    /// # Example
+    /// ```rust
+    /// use goose::prelude::*;
+    ///
+    /// fn main() -> Result<(), GooseError> {
+    ///     GooseAttack::initialize()?
+    ///         // 95% of `GET /` requests must complete in under 300 milliseconds.
+    ///         .set_threshold("GET /:p95<300ms")?
+    ///         // Less than 1% of all requests can fail.
+    ///         .set_threshold("errors<1%")?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn set_threshold(mut self, threshold: &str) -> Result<Self, GooseError> {
+        let parsed = threshold
+            .parse()
+            .map_err(|detail| GooseError::InvalidOption {
+                option: "GooseAttack.set_threshold".to_string(),
+                value: threshold.to_string(),
+                detail,
+            })?;
+        self.thresholds.push(parsed);
+        Ok(self)
+    }
+
+    // Add thresholds configured with `--threshold` to those set with `set_threshold`.
+    pub(crate) fn configure_thresholds(&mut self) -> Result<(), GooseError> {
+        for threshold in &self.configuration.threshold {
+            let parsed = threshold
+                .parse()
+                .map_err(|detail| GooseError::InvalidOption {
+                    option: "`configuration.threshold`".to_string(),
+                    value: threshold.to_string(),
+                    detail,
+                })?;
+            self.thresholds.push(parsed);
+        }
+
+        // Thresholds are evaluated against metrics.
+        if !self.thresholds.is_empty() && self.configuration.no_metrics {
+            return Err(GooseError::InvalidOption {
+                option: "`configuration.no_metrics`".to_string(),
+                value: true.to_string(),
+                detail: "`configuration.no_metrics` can not be set with thresholds.".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    // Returns the first threshold that can no longer be met, if any.
+    pub(crate) fn unmeetable_threshold(&self) -> Option<&str> {
+        self.thresholds
+            .iter()
+            .find(|threshold| threshold.can_not_be_met(&self.metrics))
+            .map(|threshold| threshold.rule.as_str())
+    }
+
+    // Evaluate all thresholds against the final metrics, storing the results in the
+    // metrics. If any threshold failed, display the metrics and return an error.
+    pub(crate) fn evaluate_thresholds(&mut self) -> Result<(), GooseError> {
+        if self.thresholds.is_empty() {
+            return Ok(());
+        }
+
+        self.metrics.thresholds = self
+            .thresholds
+            .iter()
+            .map(|threshold| threshold.evaluate(&self.metrics))
+            .collect();
+
+        let failed: Vec<String> = self
+            .metrics
+            .thresholds
+            .iter()
+            .filter(|result| !result.passed)
+            .map(|result| format!("{} ({})", result.threshold, result))
+            .collect();
+        if failed.is_empty() {
+            info!("all {} thresholds passed", self.thresholds.len());
+            Ok(())
+        } else {
+            // The metrics can't be displayed by the load test as an error is returned
+            // instead, so display them here.
+            self.metrics.print();
+            Err(GooseError::ThresholdsFailed {
+                detail: format!("failed thresholds: {}", failed.join(", ")),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::goose::GooseMethod;
+    use crate::metrics::GooseRequestMetricAggregate;
+
+    #[test]
+    fn parse_thresholds() {
+        let threshold: GooseThreshold = "GET /:p95<300ms".parse().unwrap();
+        assert_eq!(threshold.request, Some("GET /".to_string()));
+        assert_eq!(threshold.metric, GooseThresholdMetric::Percentile(95.0));
+        assert_eq!(threshold.comparison, GooseThresholdComparison::Less);
+        assert_eq!(threshold.value, 300.0);
+
+        let threshold: GooseThreshold = "errors<1%".parse().unwrap();
+        assert_eq!(threshold.request, None);
+        assert_eq!(threshold.metric, GooseThresholdMetric::FailureRate);
+
+        let threshold: GooseThreshold = " GET http://example.com:8080/ : avg >= 1.5s "
+            .parse()
+            .unwrap();
+        assert_eq!(
+            threshold.request,
+            Some("GET http://example.com:8080/".to_string())
+        );
+        assert_eq!(threshold.metric, GooseThresholdMetric::Average);
+        assert_eq!(
+            threshold.comparison,
+            GooseThresholdComparison::GreaterOrEqual
+        );
+        assert_eq!(threshold.value, 1_500.0);
+
+        let threshold: GooseThreshold = "POST login:errors<=10".parse().unwrap();
+        assert_eq!(threshold.metric, GooseThresholdMetric::Failures);
+        assert_eq!(threshold.comparison, GooseThresholdComparison::LessOrEqual);
+
+        let threshold: GooseThreshold = "rps>100".parse().unwrap();
+        assert_eq!(threshold.metric, GooseThresholdMetric::RequestsPerSecond);
+        assert_eq!(threshold.comparison, GooseThresholdComparison::Greater);
+
+        // Request names can contain operators.
+        let threshold: GooseThreshold = "GET /search?q=<a>&n>1:p99>=2s".parse().unwrap();
+        assert_eq!(threshold.request, Some("GET /search?q=<a>&n>1".to_string()));
+        assert_eq!(threshold.metric, GooseThresholdMetric::Percentile(99.0));
+        assert_eq!(
+            threshold.comparison,
+            GooseThresholdComparison::GreaterOrEqual
+        );
+        assert_eq!(threshold.value, 2_000.0);
+        let threshold: GooseThreshold = "GET /a<b:avg<300".parse().unwrap();
+        assert_eq!(threshold.request, Some("GET /a<b".to_string()));
+        assert_eq!(threshold.comparison, GooseThresholdComparison::Less);
+
+        // Invalid thresholds.
+        assert!("p95".parse::<GooseThreshold>().is_err());
+        assert!("p95<fast".parse::<GooseThreshold>().is_err());
+        assert!("p0<300".parse::<GooseThreshold>().is_err());
+        assert!("p101<300".parse::<GooseThreshold>().is_err());
+        assert!("latency<300".parse::<GooseThreshold>().is_err());
+        assert!("avg<1%".parse::<GooseThreshold>().is_err());
+        assert!("requests<10ms".parse::<GooseThreshold>().is_err());
+        assert!(":avg<300".parse::<GooseThreshold>().is_err());
+    }
+
+    #[test]
+    fn evaluate_thresholds() {
+        let mut metrics = GooseMetrics {
+            duration: 10,
+            ..Default::default()
+        };
+        let mut index = GooseRequestMetricAggregate::new("/", GooseMethod::Get, 0);
+        for time in &[10_000, 20_000, 30_000, 40_000] {
+            index.record_time(*time, false);
+            index.success_count += 1;
+        }
+        index.fail_count = 1;
+        metrics.requests.insert("GET /".to_string(), index);
+
+        let evaluate = |threshold: &str| {
+            threshold
+                .parse::<GooseThreshold>()
+                .unwrap()
+                .evaluate(&metrics)
+        };
+        assert!(evaluate("GET /:avg<=25ms").passed);
+        assert_eq!(evaluate("GET /:avg<25ms").value, Some(25.0));
+        assert!(!evaluate("GET /:avg<25ms").passed);
+        assert!(evaluate("GET /:p50<21ms").passed);
+        assert!(!evaluate("GET /:p50<20ms").passed);
+        assert!(evaluate("max<50").passed);
+        assert!(evaluate("min>5").passed);
+        assert!(evaluate("requests>=5").passed);
+        assert_eq!(evaluate("rps>0").value, Some(0.5));
+        assert_eq!(evaluate("errors<1%").value, Some(20.0));
+        assert!(!evaluate("errors<1%").passed);
+        assert!(evaluate("errors<2").passed);
+
+        // A threshold for a request that wasn't made fails.
+        let missing = evaluate("GET /missing:avg<100");
+        assert_eq!(missing.value, None);
+        assert!(!missing.passed);
+        assert_eq!(missing.to_string(), "no requests");
+
+        // Only some failing thresholds can no longer be met.
+        let can_not_be_met = |threshold: &str| {
+            threshold
+                .parse::<GooseThreshold>()
+                .unwrap()
+                .can_not_be_met(&metrics)
+        };
+        assert!(can_not_be_met("errors<1"));
+        assert!(can_not_be_met("max<=30"));
+        assert!(can_not_be_met("min>20"));
+        assert!(!can_not_be_met("errors<2"));
+        assert!(!can_not_be_met("errors<1%"));
+        assert!(!can_not_be_met("avg<5"));
+    }
+}
@@ -0,0 +1,182 @@
+//! Optional transactions, timing a group of requests together.
+//!
+//! Request metrics show how long each individual request took, and task metrics how
+//! long each task took, but what matters to a real user is often how long it takes to
+//! complete a series of steps: logging in, adding an item to the cart and checking out.
+//! A transaction times an arbitrary group of requests, made within a single task or
+//! across several tasks, and is reported as its own section of the metrics, with a count
+//! of how often it ran and failed, and its run time percentiles.
+//!
+//! A transaction starts with [`GooseUser::begin_transaction`] and ends with
+//! [`GooseUser::end_transaction`], or wraps a group of requests with
+//! [`GooseUser::transaction`]. A transaction fails if any request made by the
+//! [`GooseUser`] while it was open failed, including requests later marked a failure with
+//! [`GooseUser::set_failure`].
+//!
+//! ## Example
+//!
+//! ```rust
+//! use goose::prelude::*;
+//!
+//! let mut task = task!(checkout);
+//!
+//! async fn checkout(user: &mut GooseUser) -> GooseTaskResult {
+//!     user.transaction("checkout", |user| {
+//!         Box::pin(async move {
+//!             let _goose = user.post("/cart", "item=7").await?;
+//!             let _goose = user.post("/checkout", "confirm=1").await?;
+//!
+//!             Ok(())
+//!         })
+//!     })
+//!     .await
+//! }
+//! ```
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use crate::goose::{GooseTaskError, GooseTaskResult, GooseUser};
+use crate::metrics::{next_request_id, GooseMetric, GooseRequestMetric, GooseTransactionMetric};
+
+/// A transaction that has begun but not yet ended.
+pub(crate) struct GooseOpenTransaction {
+    /// The metric sent to the parent when the transaction ends.
+    metric: GooseTransactionMetric,
+    /// When the transaction began.
+    started: Instant,
+    /// The id of the first request made during the transaction.
+    first_request_id: u64,
+    /// How many requests made during the transaction failed.
+    request_failures: AtomicUsize,
+}
+
+impl GooseUser {
+    /// Begin timing a transaction.
+    ///
+    /// The transaction is timed until [`GooseUser::end_transaction`] is called with the
+    /// same name, which can be in a later task. Any number of transactions with different
+    /// names can be open at the same time. Beginning a transaction that is already open
+    /// restarts it, discarding the earlier one: this happens when a task returns an error
+    /// before ending its transaction, which [`GooseUser::transaction`] avoids.
+    ///
+    /// # Example
+    /// ```rust
+    /// use goose::prelude::*;
+    ///
+    /// let mut task = task!(login);
+    ///
+    /// async fn login(user: &mut GooseUser) -> GooseTaskResult {
+    ///     user.begin_transaction("login");
+    ///     let _goose = user.get("/user/login").await?;
+    ///     let _goose = user.post("/user/login", "name=foo&pass=bar").await?;
+    ///     user.end_transaction("login")
+    /// }
+    /// ```
+    pub fn begin_transaction(&mut self, name: &str) {
+        let transaction = GooseOpenTransaction {
+            metric: GooseTransactionMetric::new(
+                self.started.elapsed().as_millis(),
+                name,
+                self.weighted_users_index,
+            ),
+            started: Instant::now(),
+            first_request_id: next_request_id(),
+            request_failures: AtomicUsize::new(0),
+        };
+        self.transactions.insert(name.to_string(), transaction);
+    }
+
+    /// End a transaction, recording how long it took since it began.
+    ///
+    /// The transaction is a failure if any request made since
+    /// [`GooseUser::begin_transaction`] failed. Returns
+    /// [`GooseTaskError::TransactionNotStarted`] if no transaction with the name is open.
+    pub fn end_transaction(&mut self, name: &str) -> GooseTaskResult {
+        self.finish_transaction(name, true)
+    }
+
+    /// Time all requests made by a function as a single transaction.
+    ///
+    /// The transaction begins before the function is invoked, and ends when it returns.
+    /// It is a failure if any request failed, or if the function returned an error, which
+    /// is then returned.
+    ///
+    /// # Example
+    /// ```rust
+    /// use goose::prelude::*;
+    ///
+    /// let mut task = task!(browse);
+    ///
+    /// async fn browse(user: &mut GooseUser) -> GooseTaskResult {
+    ///     user.transaction("front page", |user| {
+    ///         Box::pin(async move {
+    ///             let _goose = user.get("/").await?;
+    ///             let _goose = user.get("/misc/drupal.js").await?;
+    ///
+    ///             Ok(())
+    ///         })
+    ///     })
+    ///     .await
+    /// }
+    /// ```
+    pub async fn transaction<F>(&mut self, name: &str, function: F) -> GooseTaskResult
+    where
+        F: for<'r> FnOnce(
+                &'r mut GooseUser,
+            )
+                -> Pin<Box<dyn Future<Output = GooseTaskResult> + Send + 'r>>
+            + Send,
+    {
+        self.begin_transaction(name);
+        let result = function(self).await;
+        self.finish_transaction(name, result.is_ok())?;
+        result
+    }
+
+    /// End a transaction, and send its metric to the parent.
+    fn finish_transaction(&mut self, name: &str, success: bool) -> GooseTaskResult {
+        let transaction = self.transactions.remove(name).ok_or_else(|| {
+            GooseTaskError::TransactionNotStarted {
+                name: name.to_string(),
+            }
+        })?;
+        let success = success && transaction.request_failures.load(Ordering::SeqCst) == 0;
+        let mut metric = transaction.metric;
+        metric.set_time(transaction.started.elapsed(), success);
+
+        if !self.config.no_metrics {
+            // Parent is not defined when running
+            // [`test_start`](../struct.GooseAttack.html#method.test_start),
+            // [`test_stop`](../struct.GooseAttack.html#method.test_stop), and during testing.
+            if let Some(parent) = self.channel_to_parent.as_ref() {
+                parent.send(GooseMetric::Transaction(metric))?;
+            }
+        }
+
+        Ok(())
+    }
+    /// Count a request that failed, or a failed request marked a success, in the open
+    /// transactions it was made during.
+    pub(crate) fn count_request_failure(&self, request_metric: &GooseRequestMetric) {
+        if request_metric.success && !request_metric.update {
+            return;
+        }
+        for transaction in self.transactions.values() {
+            if request_metric.id < transaction.first_request_id {
+                continue;
+            }
+            if request_metric.success {
+                let _ = transaction.request_failures.fetch_update(
+                    Ordering::SeqCst,
+                    Ordering::SeqCst,
+                    |failures| failures.checked_sub(1),
+                );
+            } else {
+                transaction.request_failures.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+    }
+}
@@ -0,0 +1,275 @@
+//! Optional transports, load testing protocols other than HTTP.
+//!
+//! Requests made with [`GooseUser::get`] and the other HTTP helpers are sent by the
+//! [`reqwest::Client`](https://docs.rs/reqwest/*/reqwest/struct.Client.html) of each
+//! [`GooseUser`]. A [`GooseTransport`] sends requests of any other protocol, such as a
+//! raw TCP exchange, a Redis command or a custom RPC, while reusing everything else
+//! Goose provides: scheduling, throttling, Gaggles, metrics and reports.
+//!
+//! Each [`GooseUser`] has its own transports, typically set by an `on_start` task with
+//! [`GooseUser::set_transport`]. Requests are then sent with
+//! [`GooseUser::transport_send`], timed, and recorded next to HTTP requests in the
+//! request metrics, with the protocol of the transport as their method. A request is a
+//! failure if the transport returns an error.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use async_trait::async_trait;
+//! use goose::prelude::*;
+//! use goose::transport::GooseTransport;
+//! use tokio::io::{AsyncReadExt, AsyncWriteExt};
+//! use tokio::net::TcpStream;
+//!
+//! /// Sends a line over TCP, and reads the reply.
+//! struct LineTransport {
+//!     stream: TcpStream,
+//! }
+//!
+//! #[async_trait]
+//! impl GooseTransport for LineTransport {
+//!     type Request = String;
+//!     type Response = String;
+//!     type Error = std::io::Error;
+//!
+//!     fn protocol(&self) -> &str {
+//!         "TCP"
+//!     }
+//!
+//!     async fn send(&mut self, request: String) -> Result<String, std::io::Error> {
+//!         self.stream.write_all(format!("{}\n", request).as_bytes()).await?;
+//!         let mut reply = vec![0; 1024];
+//!         let length = self.stream.read(&mut reply).await?;
+//!         Ok(String::from_utf8_lossy(&reply[..length]).to_string())
+//!     }
+//! }
+//!
+//! let mut connect_task = task!(connect).set_on_start();
+//! let mut status_task = task!(status);
+//!
+//! async fn connect(user: &mut GooseUser) -> GooseTaskResult {
+//!     let stream = TcpStream::connect("127.0.0.1:7000")
+//!         .await
+//!         .expect("failed to connect");
+//!     user.set_transport(LineTransport { stream });
+//!     Ok(())
+//! }
+//!
+//! async fn status(user: &mut GooseUser) -> GooseTaskResult {
+//!     let _goose = user
+//!         .transport_send::<LineTransport>("status", "STATUS".to_string())
+//!         .await?;
+//!     Ok(())
+//! }
+//! ```
+
+use async_trait::async_trait;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Instant;
+
+use crate::goose::{GooseMethod, GooseTaskError, GooseUser};
+use crate::metrics::{GooseRawRequest, GooseRequestMetric};
+
+/// A client sending requests of a protocol other than HTTP.
+///
+/// # Example
+/// ```rust
+/// use async_trait::async_trait;
+/// use goose::transport::GooseTransport;
+/// use std::time::Duration;
+///
+/// /// Simulates a remote procedure call.
+/// struct SleepTransport;
+///
+/// #[async_trait]
+/// impl GooseTransport for SleepTransport {
+///     type Request = Duration;
+///     type Response = ();
+///     type Error = String;
+///
+///     fn protocol(&self) -> &str {
+///         "SLEEP"
+///     }
+///
+///     async fn send(&mut self, request: Duration) -> Result<(), String> {
+///         tokio::time::sleep(request).await;
+///         Ok(())
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait GooseTransport: Send + Sync + 'static {
+    /// The requests sent by the transport.
+    type Request: Send;
+    /// The responses returned by the transport.
+    type Response: Send;
+    /// The errors returned by the transport, recorded as the error of failed requests.
+    type Error: fmt::Display + Send;
+
+    /// The protocol of the transport, displayed as the method of its requests, for
+    /// example `TCP`.
+    fn protocol(&self) -> &str;
+
+    /// Where requests are sent, included in the request log. Empty by default.
+    fn url(&self) -> String {
+        "".to_string()
+    }
+
+    /// Send a request, and return its response.
+    async fn send(&mut self, request: Self::Request) -> Result<Self::Response, Self::Error>;
+}
+
+/// The response to a request sent by a [`GooseTransport`].
+pub struct GooseTransportResponse<T: GooseTransport> {
+    /// The request that this is a response to.
+    pub request: GooseRequestMetric,
+    /// The response.
+    pub response: Result<T::Response, T::Error>,
+}
+
+/// The transports of a user, by type.
+pub(crate) type GooseTransports = HashMap<TypeId, Box<dyn Any + Send + Sync>>;
+
+impl GooseUser {
+    /// Set a [`GooseTransport`] used by this user to send requests, replacing any
+    /// transport of the same type already set.
+    ///
+    /// # Example
+    /// ```rust
+    /// use async_trait::async_trait;
+    /// use goose::prelude::*;
+    /// use goose::transport::GooseTransport;
+    ///
+    /// struct EchoTransport;
+    ///
+    /// #[async_trait]
+    /// impl GooseTransport for EchoTransport {
+    ///     type Request = String;
+    ///     type Response = String;
+    ///     type Error = String;
+    ///
+    ///     fn protocol(&self) -> &str {
+    ///         "ECHO"
+    ///     }
+    ///
+    ///     async fn send(&mut self, request: String) -> Result<String, String> {
+    ///         Ok(request)
+    ///     }
+    /// }
+    ///
+    /// let mut task = task!(setup_transport).set_on_start();
+    ///
+    /// async fn setup_transport(user: &mut GooseUser) -> GooseTaskResult {
+    ///     user.set_transport(EchoTransport);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn set_transport<T: GooseTransport>(&mut self, transport: T) {
+        self.transports
+            .insert(TypeId::of::<T>(), Box::new(transport));
+    }
+
+    /// Send a request with the [`GooseTransport`] of type `T` set by
+    /// [`GooseUser::set_transport`], and collect relevant metrics.
+    ///
+    /// The request is recorded with the protocol of the transport as its method, and
+    /// `request_name` as its name. Like [`GooseUser::goose_send`], a request that fails is
+    /// not an error: the error returned by the transport is in the response, and the
+    /// request can be marked a success with [`GooseUser::set_success`]. Returns
+    /// [`GooseTaskError::TransportNotSet`] if no transport of type `T` is set.
+    ///
+    /// # Example
+    /// ```rust
+    /// use async_trait::async_trait;
+    /// use goose::prelude::*;
+    /// use goose::transport::GooseTransport;
+    ///
+    /// struct EchoTransport;
+    ///
+    /// #[async_trait]
+    /// impl GooseTransport for EchoTransport {
+    ///     type Request = String;
+    ///     type Response = String;
+    ///     type Error = String;
+    ///
+    ///     fn protocol(&self) -> &str {
+    ///         "ECHO"
+    ///     }
+    ///
+    ///     async fn send(&mut self, request: String) -> Result<String, String> {
+    ///         Ok(request)
+    ///     }
+    /// }
+    ///
+    /// let mut task = task!(echo);
+    ///
+    /// async fn echo(user: &mut GooseUser) -> GooseTaskResult {
+    ///     let goose = user
+    ///         .transport_send::<EchoTransport>("echo", "hello".to_string())
+    ///         .await?;
+    ///     if let Ok(reply) = goose.response {
+    ///         assert_eq!(reply, "hello");
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn transport_send<T: GooseTransport>(
+        &mut self,
+        request_name: &str,
+        request: T::Request,
+    ) -> Result<GooseTransportResponse<T>, GooseTaskError> {
+        self.throttle_request().await?;
+
+        // Take the transport while sending, as the request is recorded by the user.
+        let mut transport = self
+            .transports
+            .remove(&TypeId::of::<T>())
+            .and_then(|transport| transport.downcast::<T>().ok())
+            .ok_or_else(|| GooseTaskError::TransportNotSet {
+                transport: std::any::type_name::<T>().to_string(),
+            })?;
+
+        let raw_request = GooseRawRequest::new(
+            GooseMethod::Protocol(transport.protocol().to_string()),
+            &transport.url(),
+            Vec::new(),
+            "",
+        );
+        let mut request_metric = GooseRequestMetric::new(
+            raw_request,
+            request_name,
+            self.started.elapsed().as_millis(),
+            self.weighted_users_index,
+        );
+
+        let started = Instant::now();
+        let response = transport.send(request).await;
+        request_metric.set_response_time(started.elapsed());
+        self.transports.insert(TypeId::of::<T>(), transport);
+
+        if let Err(error) = &response {
+            request_metric.success = false;
+            request_metric.error = format!("{}: {}", error, request_name);
+        }
+
+        // If enabled, track the cadence between each time the same request is made, to
+        // backfill requests blocked by the upstream server.
+        request_metric.user_cadence = self
+            .coordinated_omission_mitigation(&request_metric)
+            .await?;
+
+        // Send a copy of the raw request object to the parent process if
+        // we're tracking metrics.
+        if !self.config.no_metrics {
+            self.send_request_metric_to_parent(request_metric.clone())?;
+        }
+
+        Ok(GooseTransportResponse {
+            request: request_metric,
+            response,
+        })
+    }
+}
@@ -1,8 +1,10 @@
 use futures::future::Fuse;
 use futures::{pin_mut, select, FutureExt};
 use rand::Rng;
+use std::sync::atomic::Ordering;
 use std::time::{self, Duration, Instant};
 
+use crate::arrival_rate::GooseIterationReceiver;
 use crate::get_worker_id;
 use crate::goose::{GooseTaskFunction, GooseTaskSet, GooseUser, GooseUserCommand};
 use crate::logger::GooseLog;
@@ -13,6 +15,7 @@ pub(crate) async fn user_main(
     mut thread_task_set: GooseTaskSet,
     mut thread_user: GooseUser,
     thread_receiver: flume::Receiver<GooseUserCommand>,
+    iterations: Option<GooseIterationReceiver>,
     worker: bool,
 ) {
     if worker {
@@ -50,20 +53,27 @@ pub(crate) async fn user_main(
         }
     }
 
-    // If normal tasks are defined, loop launching tasks until parent tells us to stop.
-    if !thread_task_set.weighted_tasks.is_empty() {
+    // If starting iterations at a fixed arrival rate, the parent decides when to launch
+    // normal tasks.
+    if let Some(iterations) = iterations {
+        if !thread_task_set.weighted_tasks.is_empty() {
+            run_iterations(
+                thread_number,
+                &mut thread_task_set,
+                &mut thread_user,
+                &thread_receiver,
+                iterations,
+            )
+            .await;
+        }
+    } else if !thread_task_set.weighted_tasks.is_empty() {
+        // If normal tasks are defined, loop launching tasks until parent tells us to stop.
+        let task_wait = get_task_wait(&mut thread_task_set);
+
         let mut task_iter = thread_task_set.weighted_tasks.iter().cycle();
         let next_task_delay = Fuse::terminated();
         pin_mut!(next_task_delay);
 
-        let task_wait = match thread_task_set.task_wait.take() {
-            Some((min, max)) if min == max => min,
-            Some((min, max)) => Duration::from_millis(
-                rand::thread_rng().gen_range(min.as_millis()..max.as_millis()) as u64,
-            ),
-            None => Duration::from_millis(0),
-        };
-
         next_task_delay.set(tokio::time::sleep(Duration::from_secs(0)).fuse());
         loop {
             select! {
@@ -106,6 +116,12 @@ pub(crate) async fn user_main(
                         Err(_) | Ok(GooseUserCommand::Exit) => {
                             break ;
                         }
+                        // Pause until the parent tells us to resume, or to exit.
+                        Ok(GooseUserCommand::Wait) => {
+                            if !wait_for_resume(thread_number, &mut thread_user, &thread_receiver).await {
+                                break;
+                            }
+                        }
                         Ok(command) => {
                             debug!("ignoring unexpected GooseUserCommand: {:?}", command);
                         }
@@ -152,6 +168,110 @@ pub(crate) async fn user_main(
     }
 }
 
+// Determine how long to wait between tasks.
+fn get_task_wait(thread_task_set: &mut GooseTaskSet) -> Duration {
+    match thread_task_set.task_wait.take() {
+        Some((min, max)) if min == max => min,
+        Some((min, max)) => Duration::from_millis(
+            rand::thread_rng().gen_range(min.as_millis()..max.as_millis()) as u64,
+        ),
+        None => Duration::from_millis(0),
+    }
+}
+
+// Each time the parent starts an iteration, run through all normal tasks one time, until
+// the parent tells us to stop.
+async fn run_iterations(
+    thread_number: usize,
+    thread_task_set: &mut GooseTaskSet,
+    thread_user: &mut GooseUser,
+    thread_receiver: &flume::Receiver<GooseUserCommand>,
+    iterations: GooseIterationReceiver,
+) {
+    let task_wait = get_task_wait(thread_task_set);
+
+    loop {
+        select! {
+            iteration = iterations.receiver.recv_async().fuse() => {
+                // The parent is no longer starting iterations.
+                if iteration.is_err() {
+                    break;
+                }
+
+                iterations.busy_users.fetch_add(1, Ordering::SeqCst);
+                debug!(
+                    "[user {}]: starting iteration of {}",
+                    thread_number, thread_task_set.name
+                );
+                let last_task = thread_task_set.weighted_tasks.len() - 1;
+                for (index, (thread_task_index, thread_task_name)) in
+                    thread_task_set.weighted_tasks.iter().enumerate()
+                {
+                    // Get a reference to the task function we're going to invoke next.
+                    let function = &thread_task_set.tasks[*thread_task_index].function;
+
+                    let now = Instant::now();
+                    // Invoke the task function.
+                    let _ = invoke_task_function(
+                        function,
+                        thread_user,
+                        *thread_task_index,
+                        thread_task_name,
+                    )
+                    .await;
+
+                    // Wait between tasks, but not after the last task in the iteration.
+                    let elapsed = now.elapsed();
+                    if index < last_task && elapsed < task_wait {
+                        tokio::time::sleep(task_wait - elapsed).await;
+                    }
+                }
+                iterations.busy_users.fetch_sub(1, Ordering::SeqCst);
+            },
+            message = thread_receiver.recv_async().fuse() => {
+                match message {
+                    // Time to exit, break out of iterations loop.
+                    Err(_) | Ok(GooseUserCommand::Exit) => {
+                        break;
+                    }
+                    // Pause until the parent tells us to resume, or to exit.
+                    Ok(GooseUserCommand::Wait) => {
+                        if !wait_for_resume(thread_number, thread_user, thread_receiver).await {
+                            break;
+                        }
+                    }
+                    Ok(command) => {
+                        debug!("ignoring unexpected GooseUserCommand: {:?}", command);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Wait for the parent to resume a paused load test. Returns true when resumed, or false
+// if the parent instead tells the user to exit.
+async fn wait_for_resume(
+    thread_number: usize,
+    thread_user: &mut GooseUser,
+    thread_receiver: &flume::Receiver<GooseUserCommand>,
+) -> bool {
+    debug!("[user {}]: paused", thread_number);
+    let paused = Instant::now();
+    let resumed = loop {
+        match thread_receiver.recv_async().await {
+            Ok(GooseUserCommand::Run) => break true,
+            Err(_) | Ok(GooseUserCommand::Exit) => break false,
+            Ok(GooseUserCommand::Wait) => {}
+        }
+    };
+    // Time spent paused isn't included when calculating the request cadence used by
+    // Coordinated Omission Mitigation.
+    thread_user.slept += paused.elapsed().as_millis() as u64;
+    debug!("[user {}]: resumed", thread_number);
+    resumed
+}
+
 // Invoke the task function, collecting task metrics.
 async fn invoke_task_function(
     function: &GooseTaskFunction,
@@ -174,7 +294,7 @@ async fn invoke_task_function(
     }
 
     let success = function(thread_user).await.is_ok();
-    raw_task.set_time(started.elapsed().as_millis(), success);
+    raw_task.set_time(started.elapsed(), success);
 
     // Exit if all metrics or task metrics are disabled.
     if thread_user.config.no_metrics || thread_user.config.no_task_metrics {
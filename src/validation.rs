@@ -0,0 +1,485 @@
+//! Optional declarative validation of responses.
+//!
+//! By default Goose considers any response with a 2xx status code a success. A
+//! [`GooseValidation`] describes what else a valid response looks like, such as its status
+//! code, text in its body, its headers, the title of an HTML page, values in a JSON body
+//! and how quickly the server responded. Validating a
+//! [`GooseResponse`](../goose/struct.GooseResponse.html) with
+//! [`GooseResponse::validate`](../goose/struct.GooseResponse.html#method.validate) reads
+//! the body and runs the checks in the order they were added. The first failed check
+//! marks the request as a failure with
+//! [`GooseUser::set_failure`](../goose/struct.GooseUser.html#method.set_failure).
+//!
+//! The error describes the failed check followed by the request name, for example
+//! `body missing "Welcome": /`. Errors only include expected values and the status code,
+//! not what the body actually contained, so they group well in the error summary. Enable
+//! the debug log to see the full response.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use goose::prelude::*;
+//! use goose::validation::GooseValidation;
+//! use std::time::Duration;
+//!
+//! let mut task = task!(loadtest_index_page);
+//!
+//! async fn loadtest_index_page(user: &mut GooseUser) -> GooseTaskResult {
+//!     let validation = GooseValidation::new()
+//!         .status(200)
+//!         .header_value("content-type", "text/html; charset=utf-8")
+//!         .title("Home")
+//!         .body_contains("Welcome")
+//!         .max_response_time(Duration::from_millis(500));
+//!
+//!     let goose = user.get("/").await?;
+//!     let validated = goose.validate(user, &validation).await?;
+//!     // The validated body is available for further processing.
+//!     let _length = validated.body.len();
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use reqwest::{header, StatusCode};
+use serde_json::Value;
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use crate::goose::{GooseResponse, GooseTaskError, GooseUser};
+use crate::metrics::GooseRequestMetric;
+use crate::GooseError;
+
+lazy_static! {
+    /// Matches the title of an HTML page.
+    static ref TITLE: Regex = Regex::new(r"(?is)<title[^>]*>(.*?)</title>").unwrap();
+}
+
+/// A single check run against the body and headers of a response.
+#[derive(Clone, Debug)]
+enum GooseCheck {
+    /// The body contains the text.
+    BodyContains(String),
+    /// The body matches the regular expression.
+    BodyMatches(Regex),
+    /// The header is present.
+    Header(String),
+    /// The header has the value.
+    HeaderValue(String, String),
+    /// The HTML title contains the text.
+    Title(String),
+    /// The value at the JSON pointer exists.
+    JsonExists(String),
+    /// The value at the JSON pointer equals the value.
+    JsonValue(String, Value),
+}
+
+/// A description of a valid response, see the [module documentation](index.html).
+///
+/// Checks are added with builder methods, and the same `GooseValidation` can be used to
+/// validate any number of responses.
+#[derive(Clone, Debug, Default)]
+pub struct GooseValidation {
+    /// Acceptable status codes, if not only 2xx status codes.
+    statuses: Vec<RangeInclusive<u16>>,
+    /// The maximum response time.
+    max_response_time: Option<Duration>,
+    /// Checks run against the body and headers, in order.
+    checks: Vec<GooseCheck>,
+}
+impl GooseValidation {
+    /// Create a validation without any checks, accepting any 2xx response.
+    pub fn new() -> Self {
+        GooseValidation::default()
+    }
+
+    /// Accept responses with the status code.
+    ///
+    /// Once a status code or range is configured, only the configured status codes are
+    /// valid, and a matching response without a 2xx status code is marked a success.
+    /// Can be called multiple times to accept more status codes.
+    pub fn status(self, status: u16) -> Self {
+        self.status_range(status..=status)
+    }
+
+    /// Accept responses with a status code in the range, for example `200..=299`.
+    ///
+    /// Can be called multiple times to accept more status codes.
+    pub fn status_range(mut self, statuses: RangeInclusive<u16>) -> Self {
+        self.statuses.push(statuses);
+        self
+    }
+
+    /// Require the body to contain the text.
+    pub fn body_contains(mut self, text: &str) -> Self {
+        self.checks.push(GooseCheck::BodyContains(text.to_string()));
+        self
+    }
+
+    /// Require the body to match the regular expression.
+    ///
+    /// Returns [`GooseError::InvalidPattern`](../enum.GooseError.html#variant.InvalidPattern)
+    /// if the regular expression is invalid.
+    pub fn body_matches(mut self, pattern: &str) -> Result<Self, GooseError> {
+        let regex = Regex::new(pattern).map_err(|e| GooseError::InvalidPattern {
+            pattern: pattern.to_string(),
+            detail: e.to_string(),
+        })?;
+        self.checks.push(GooseCheck::BodyMatches(regex));
+        Ok(self)
+    }
+
+    /// Require the header to be present. Header names aren't case sensitive.
+    pub fn header(mut self, name: &str) -> Self {
+        self.checks.push(GooseCheck::Header(name.to_string()));
+        self
+    }
+
+    /// Require the header to have the value.
+    pub fn header_value(mut self, name: &str, value: &str) -> Self {
+        self.checks
+            .push(GooseCheck::HeaderValue(name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Require the `<title>` of an HTML page to contain the text.
+    pub fn title(mut self, title: &str) -> Self {
+        self.checks.push(GooseCheck::Title(title.to_string()));
+        self
+    }
+
+    /// Require the body to be JSON with a value at the
+    /// [JSON pointer](https://tools.ietf.org/html/rfc6901), for example `/user/id`.
+    pub fn json_exists(mut self, pointer: &str) -> Self {
+        self.checks
+            .push(GooseCheck::JsonExists(pointer.to_string()));
+        self
+    }
+
+    /// Require the body to be JSON with the value at the
+    /// [JSON pointer](https://tools.ietf.org/html/rfc6901), for example
+    /// `json_value("/user/name", "alice".into())`.
+    pub fn json_value(mut self, pointer: &str, value: Value) -> Self {
+        self.checks
+            .push(GooseCheck::JsonValue(pointer.to_string(), value));
+        self
+    }
+
+    /// Require the server to respond within the duration.
+    pub fn max_response_time(mut self, max_response_time: Duration) -> Self {
+        self.max_response_time = Some(max_response_time);
+        self
+    }
+
+    /// Describe why the status code isn't valid, if it isn't.
+    fn check_status(&self, status: StatusCode) -> Option<String> {
+        if self
+            .statuses
+            .iter()
+            .any(|statuses| statuses.contains(&status.as_u16()))
+        {
+            return None;
+        }
+        let expected = self
+            .statuses
+            .iter()
+            .map(|statuses| {
+                if statuses.start() == statuses.end() {
+                    statuses.start().to_string()
+                } else {
+                    format!("{}-{}", statuses.start(), statuses.end())
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(" or ");
+        Some(format!("status {}, expected {}", status, expected))
+    }
+
+    /// Describe the first failed check against the body and headers, if any.
+    fn check_response(&self, headers: &header::HeaderMap, body: &str) -> Option<String> {
+        // Only parse JSON once, and only if needed.
+        let mut json: Option<Option<Value>> = None;
+        for check in &self.checks {
+            let failure = match check {
+                GooseCheck::BodyContains(text) => {
+                    if body.contains(text.as_str()) {
+                        None
+                    } else {
+                        Some(format!("body missing {:?}", text))
+                    }
+                }
+                GooseCheck::BodyMatches(regex) => {
+                    if regex.is_match(body) {
+                        None
+                    } else {
+                        Some(format!("body doesn't match /{}/", regex))
+                    }
+                }
+                GooseCheck::Header(name) => {
+                    if headers.contains_key(name.as_str()) {
+                        None
+                    } else {
+                        Some(format!("header {} missing", name))
+                    }
+                }
+                GooseCheck::HeaderValue(name, value) => match headers.get(name.as_str()) {
+                    Some(header_value) if header_value.as_bytes() == value.as_bytes() => None,
+                    Some(_) => Some(format!("header {} isn't {:?}", name, value)),
+                    None => Some(format!("header {} missing", name)),
+                },
+                GooseCheck::Title(title) => match get_title(body) {
+                    Some(page_title) if page_title.contains(title.as_str()) => None,
+                    Some(_) => Some(format!("title doesn't contain {:?}", title)),
+                    None => Some("title missing".to_string()),
+                },
+                GooseCheck::JsonExists(pointer) | GooseCheck::JsonValue(pointer, _) => {
+                    match json.get_or_insert_with(|| serde_json::from_str(body).ok()) {
+                        Some(json) => match (json.pointer(pointer), check) {
+                            (None, _) => Some(format!("json {} missing", pointer)),
+                            (Some(found), GooseCheck::JsonValue(_, value)) if found != value => {
+                                Some(format!("json {} isn't {}", pointer, value))
+                            }
+                            _ => None,
+                        },
+                        None => Some("body isn't json".to_string()),
+                    }
+                }
+            };
+            if failure.is_some() {
+                return failure;
+            }
+        }
+
+        None
+    }
+}
+
+/// A response that passed validation, with its body read.
+#[derive(Clone, Debug)]
+pub struct GooseValidatedResponse {
+    /// The request that this is a response to.
+    pub request: GooseRequestMetric,
+    /// The status code of the response.
+    pub status: StatusCode,
+    /// The headers of the response.
+    pub headers: header::HeaderMap,
+    /// The body of the response.
+    pub body: String,
+}
+
+impl GooseResponse {
+    /// Validate the response, marking the request as a failure if any check fails. See the
+    /// [`validation`](../validation/index.html) module.
+    ///
+    /// Returns the validated response with its body, or
+    /// [`GooseTaskError::RequestFailed`] if the request failed or a check failed.
+    ///
+    /// # Example
+    /// ```rust
+    /// use goose::prelude::*;
+    /// use goose::validation::GooseValidation;
+    ///
+    /// let mut task = task!(get_user);
+    ///
+    /// async fn get_user(user: &mut GooseUser) -> GooseTaskResult {
+    ///     let validation = GooseValidation::new()
+    ///         .status(200)
+    ///         .json_value("/name", "alice".into());
+    ///
+    ///     let goose = user.get("/user/1").await?;
+    ///     goose.validate(user, &validation).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn validate(
+        self,
+        user: &GooseUser,
+        validation: &GooseValidation,
+    ) -> Result<GooseValidatedResponse, GooseTaskError> {
+        let mut request = self.request;
+        // The request failed without a response, which is already recorded.
+        let response = match self.response {
+            Ok(response) => response,
+            Err(_) => {
+                return Err(GooseTaskError::RequestFailed {
+                    raw_request: request,
+                })
+            }
+        };
+        let status = response.status();
+        let headers = response.headers().clone();
+
+        if validation.statuses.is_empty() {
+            // Without configured status codes, responses without a 2xx status code are
+            // already recorded as failures.
+            if !request.success {
+                return Err(GooseTaskError::RequestFailed {
+                    raw_request: request,
+                });
+            }
+        } else if let Some(failure) = validation.check_status(status) {
+            return Err(fail(user, &failure, &mut request, &headers, None));
+        } else {
+            user.set_success(&mut request)?;
+        }
+
+        if let Some(max_response_time) = validation.max_response_time {
+            if request.response_time > max_response_time.as_millis() as u64 {
+                let failure = format!("response time over {:?}", max_response_time);
+                return Err(fail(user, &failure, &mut request, &headers, None));
+            }
+        }
+
+        let body = match response.text().await {
+            Ok(body) => body,
+            Err(_) => {
+                return Err(fail(
+                    user,
+                    "failed to read body",
+                    &mut request,
+                    &headers,
+                    None,
+                ));
+            }
+        };
+        if let Some(failure) = validation.check_response(&headers, &body) {
+            return Err(fail(user, &failure, &mut request, &headers, Some(&body)));
+        }
+
+        Ok(GooseValidatedResponse {
+            request,
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+/// Mark the request as a failure, naming the request after the failed check so errors
+/// group by request in the error summary.
+pub(crate) fn fail(
+    user: &GooseUser,
+    failure: &str,
+    request: &mut GooseRequestMetric,
+    headers: &header::HeaderMap,
+    body: Option<&str>,
+) -> GooseTaskError {
+    let tag = format!("{}: {}", failure, request.name);
+    match user.set_failure(&tag, request, Some(headers), body) {
+        Err(error) => error,
+        // Setting a failure always returns an error.
+        Ok(_) => GooseTaskError::RequestFailed {
+            raw_request: request.clone(),
+        },
+    }
+}
+
+/// Returns the title of an HTML page, if it has one.
+fn get_title(html: &str) -> Option<&str> {
+    TITLE
+        .captures(html)
+        .and_then(|captures| captures.get(1))
+        .map(|title| title.as_str().trim())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn status() {
+        let validation = GooseValidation::new().status(404).status_range(200..=299);
+        assert_eq!(validation.check_status(StatusCode::OK), None);
+        assert_eq!(validation.check_status(StatusCode::NOT_FOUND), None);
+        assert_eq!(
+            validation.check_status(StatusCode::INTERNAL_SERVER_ERROR),
+            Some("status 500 Internal Server Error, expected 404 or 200-299".to_string())
+        );
+    }
+
+    #[test]
+    fn checks() {
+        let mut headers = header::HeaderMap::new();
+        headers.insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static("text/html"),
+        );
+        let html = "<html><head><TITLE>\n  Goose Home\n</TITLE></head><body>Welcome 42</body>";
+        assert_eq!(get_title(html), Some("Goose Home"));
+        assert_eq!(get_title("<html></html>"), None);
+
+        let validation = GooseValidation::new()
+            .header("Content-Type")
+            .header_value("content-type", "text/html")
+            .title("Home")
+            .body_contains("Welcome")
+            .body_matches(r"Welcome \d+")
+            .unwrap();
+        assert_eq!(validation.check_response(&headers, html), None);
+
+        // Each failed check is described.
+        for (validation, failure) in [
+            (GooseValidation::new().header("etag"), "header etag missing"),
+            (
+                GooseValidation::new().header_value("content-type", "text/plain"),
+                "header content-type isn't \"text/plain\"",
+            ),
+            (
+                GooseValidation::new().title("About"),
+                "title doesn't contain \"About\"",
+            ),
+            (
+                GooseValidation::new().body_contains("Goodbye"),
+                "body missing \"Goodbye\"",
+            ),
+            (
+                GooseValidation::new().body_matches(r"^\d+$").unwrap(),
+                r"body doesn't match /^\d+$/",
+            ),
+            (GooseValidation::new().json_exists("/id"), "body isn't json"),
+        ] {
+            assert_eq!(
+                validation.check_response(&headers, html),
+                Some(failure.to_string())
+            );
+        }
+
+        // Only the first failed check is described.
+        let validation = GooseValidation::new()
+            .body_contains("Welcome")
+            .body_contains("Goodbye")
+            .title("About");
+        assert_eq!(
+            validation.check_response(&headers, html),
+            Some("body missing \"Goodbye\"".to_string())
+        );
+
+        assert!(GooseValidation::new().body_matches("(").is_err());
+    }
+
+    #[test]
+    fn json() {
+        let headers = header::HeaderMap::new();
+        let json = r#"{"user": {"id": 1, "name": "alice", "roles": ["admin"]}}"#;
+        let validation = GooseValidation::new()
+            .json_exists("/user/id")
+            .json_value("/user/name", "alice".into())
+            .json_value("/user/roles/0", "admin".into())
+            .json_value("/user/id", 1.into());
+        assert_eq!(validation.check_response(&headers, json), None);
+
+        let validation = GooseValidation::new().json_value("/user/id", 2.into());
+        assert_eq!(
+            validation.check_response(&headers, json),
+            Some("json /user/id isn't 2".to_string())
+        );
+        let validation = GooseValidation::new().json_exists("/user/email");
+        assert_eq!(
+            validation.check_response(&headers, json),
+            Some("json /user/email missing".to_string())
+        );
+    }
+}
@@ -0,0 +1,474 @@
+//! Optional WebSocket connections, load testing servers that push messages to clients.
+//!
+//! Each [`GooseUser`] can open a WebSocket connection to the load test target with
+//! [`GooseUser::websocket_connect`], which stays open across tasks until it's closed
+//! with [`GooseUser::websocket_close`], another connection is opened, or the user stops.
+//! Messages are sent with [`GooseUser::websocket_send`], and received with
+//! [`GooseUser::websocket_receive`] or [`GooseUser::websocket_wait_for`], which skips
+//! messages until one matches a predicate. [`GooseUser::websocket_round_trip`] sends a
+//! message and waits for its reply.
+//!
+//! WebSocket operations are recorded next to HTTP requests in the request metrics, with
+//! the path of the connection as their name (or the name of the task, if set), and the
+//! following methods:
+//!  - `WS CONNECT`: how long it took to open a connection;
+//!  - `WS SEND`: how many messages were sent, and how long it took to send them;
+//!  - `WS RECEIVE`: how many messages were received, and how long was spent waiting for
+//!    them;
+//!  - `WS ROUNDTRIP`: how long it took from sending a message until its reply was
+//!    received.
+//!
+//! Failures, such as a connection that was refused or closed, or a message that wasn't
+//! received in time, are counted as failed requests and included in the errors.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use goose::prelude::*;
+//! use goose::websocket::Message;
+//! use std::time::Duration;
+//!
+//! let mut task = task!(chat);
+//!
+//! async fn chat(user: &mut GooseUser) -> GooseTaskResult {
+//!     user.websocket_connect("/chat").await?;
+//!     user.websocket_send(Message::text("join")).await?;
+//!     let _welcome = user.websocket_receive(Duration::from_secs(5)).await?;
+//!     user.websocket_close().await
+//! }
+//! ```
+
+use futures::{SinkExt, StreamExt};
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{tungstenite, MaybeTlsStream, WebSocketStream};
+use url::Url;
+
+pub use tokio_tungstenite::tungstenite::Message;
+
+use crate::goose::{GooseMethod, GooseTaskError, GooseTaskResult, GooseUser};
+use crate::metrics::{GooseRawRequest, GooseRequestMetric};
+
+/// A WebSocket connection opened by a [`GooseUser`].
+pub(crate) struct GooseWebSocket {
+    /// The open connection.
+    stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    /// The URL the connection was opened to.
+    url: String,
+    /// The name operations on the connection are recorded with.
+    name: String,
+}
+
+impl GooseUser {
+    /// Open a WebSocket connection to a path and collect relevant metrics.
+    /// Automatically prepends the correct host, replacing `http` with `ws` and `https`
+    /// with `wss`.
+    ///
+    /// Any WebSocket connection already opened by the user is first closed. All
+    /// operations on the connection are recorded with the path as their name, or the
+    /// name of the task, if set.
+    ///
+    /// # Example
+    /// ```rust
+    /// use goose::prelude::*;
+    ///
+    /// let mut task = task!(connect);
+    ///
+    /// async fn connect(user: &mut GooseUser) -> GooseTaskResult {
+    ///     user.websocket_connect("/chat").await
+    /// }
+    /// ```
+    pub async fn websocket_connect(&mut self, path: &str) -> GooseTaskResult {
+        self.open_websocket(path, None).await
+    }
+
+    /// Open a WebSocket connection to a path, recording all operations on the
+    /// connection with a name.
+    ///
+    /// # Example
+    /// ```rust
+    /// use goose::prelude::*;
+    ///
+    /// let mut task = task!(connect);
+    ///
+    /// async fn connect(user: &mut GooseUser) -> GooseTaskResult {
+    ///     user.websocket_connect_named("/chat?room=42", "chat").await
+    /// }
+    /// ```
+    pub async fn websocket_connect_named(
+        &mut self,
+        path: &str,
+        request_name: &str,
+    ) -> GooseTaskResult {
+        self.open_websocket(path, Some(request_name)).await
+    }
+
+    /// Send a message over the WebSocket connection.
+    ///
+    /// Returns [`GooseTaskError::WebSocketNotConnected`] if the user hasn't opened a
+    /// connection, and closes the connection if the message can't be sent.
+    ///
+    /// # Example
+    /// ```rust
+    /// use goose::prelude::*;
+    /// use goose::websocket::Message;
+    ///
+    /// let mut task = task!(post_message);
+    ///
+    /// async fn post_message(user: &mut GooseUser) -> GooseTaskResult {
+    ///     user.websocket_send(Message::text("hello")).await
+    /// }
+    /// ```
+    pub async fn websocket_send(&mut self, message: Message) -> GooseTaskResult {
+        self.throttle_request().await?;
+
+        let mut websocket = self
+            .websocket
+            .take()
+            .ok_or(GooseTaskError::WebSocketNotConnected)?;
+        let mut request_metric = self.new_websocket_metric(
+            GooseMethod::WsSend,
+            &websocket.url,
+            &websocket.name,
+            Some(&message),
+        );
+
+        let started = Instant::now();
+        let sent = websocket.stream.send(message).await;
+        request_metric.set_response_time(started.elapsed());
+
+        match sent {
+            Ok(()) => {
+                self.websocket = Some(websocket);
+                self.record_websocket_metric(&mut request_metric).await
+            }
+            Err(error) => {
+                fail_websocket_metric(&mut request_metric, &error.to_string());
+                self.record_websocket_metric(&mut request_metric).await?;
+                Err(error.into())
+            }
+        }
+    }
+
+    /// Receive the next message from the WebSocket connection, waiting at most
+    /// `timeout`.
+    ///
+    /// Ping and pong messages are skipped. If no message is received in time, the
+    /// receive is recorded as a failure and [`GooseTaskError::RequestFailed`] is
+    /// returned.
+    ///
+    /// # Example
+    /// ```rust
+    /// use goose::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// let mut task = task!(read_message);
+    ///
+    /// async fn read_message(user: &mut GooseUser) -> GooseTaskResult {
+    ///     let message = user.websocket_receive(Duration::from_secs(5)).await?;
+    ///     println!("received: {}", message);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn websocket_receive(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<Message, GooseTaskError> {
+        self.exchange_websocket_messages(GooseMethod::WsReceive, None, |_| true, timeout)
+            .await
+    }
+
+    /// Wait at most `timeout` for a message matching a predicate to be received from
+    /// the WebSocket connection.
+    ///
+    /// Messages that don't match the predicate are discarded. The time spent waiting
+    /// is recorded as a single receive.
+    ///
+    /// # Example
+    /// ```rust
+    /// use goose::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// let mut task = task!(wait_for_order);
+    ///
+    /// async fn wait_for_order(user: &mut GooseUser) -> GooseTaskResult {
+    ///     let _shipped = user
+    ///         .websocket_wait_for(
+    ///             |message| message.to_text().unwrap_or("").contains("shipped"),
+    ///             Duration::from_secs(10),
+    ///         )
+    ///         .await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn websocket_wait_for<P>(
+        &mut self,
+        predicate: P,
+        timeout: Duration,
+    ) -> Result<Message, GooseTaskError>
+    where
+        P: FnMut(&Message) -> bool,
+    {
+        self.exchange_websocket_messages(GooseMethod::WsReceive, None, predicate, timeout)
+            .await
+    }
+
+    /// Send a message over the WebSocket connection, then wait at most `timeout` for a
+    /// reply matching a predicate.
+    ///
+    /// The time from sending the message until the reply is received is recorded as a
+    /// round trip. Messages that don't match the predicate are discarded.
+    ///
+    /// # Example
+    /// ```rust
+    /// use goose::prelude::*;
+    /// use goose::websocket::Message;
+    /// use std::time::Duration;
+    ///
+    /// let mut task = task!(ping);
+    ///
+    /// async fn ping(user: &mut GooseUser) -> GooseTaskResult {
+    ///     let _pong = user
+    ///         .websocket_round_trip(
+    ///             Message::text(r#"{"type":"ping"}"#),
+    ///             |message| message.to_text().unwrap_or("").contains("pong"),
+    ///             Duration::from_secs(5),
+    ///         )
+    ///         .await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn websocket_round_trip<P>(
+        &mut self,
+        message: Message,
+        predicate: P,
+        timeout: Duration,
+    ) -> Result<Message, GooseTaskError>
+    where
+        P: FnMut(&Message) -> bool,
+    {
+        self.exchange_websocket_messages(
+            GooseMethod::WsRoundTrip,
+            Some(message),
+            predicate,
+            timeout,
+        )
+        .await
+    }
+
+    /// Close the WebSocket connection, if open.
+    ///
+    /// # Example
+    /// ```rust
+    /// use goose::prelude::*;
+    ///
+    /// let mut task = task!(leave);
+    ///
+    /// async fn leave(user: &mut GooseUser) -> GooseTaskResult {
+    ///     user.websocket_close().await
+    /// }
+    /// ```
+    pub async fn websocket_close(&mut self) -> GooseTaskResult {
+        if let Some(mut websocket) = self.websocket.take() {
+            websocket.stream.close(None).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Open a WebSocket connection, replacing any already open.
+    async fn open_websocket(&mut self, path: &str, request_name: Option<&str>) -> GooseTaskResult {
+        self.throttle_request().await?;
+
+        // Errors closing the previous connection don't affect the new one.
+        let _ = self.websocket_close().await;
+
+        let mut url = Url::parse(&self.build_url(path)?)?;
+        let scheme = match url.scheme() {
+            "http" => Some("ws"),
+            "https" => Some("wss"),
+            _ => None,
+        };
+        if let Some(scheme) = scheme {
+            // Switching between these schemes can't fail.
+            let _ = url.set_scheme(scheme);
+        }
+        let name = self.get_request_name(url.path(), request_name).to_string();
+        let mut request_metric =
+            self.new_websocket_metric(GooseMethod::WsConnect, url.as_str(), &name, None);
+
+        let started = Instant::now();
+        let connected = tokio_tungstenite::connect_async(url.as_str()).await;
+        request_metric.set_response_time(started.elapsed());
+
+        match connected {
+            Ok((stream, response)) => {
+                request_metric.status_code = response.status().as_u16();
+                self.websocket = Some(GooseWebSocket {
+                    stream,
+                    url: url.to_string(),
+                    name,
+                });
+                self.record_websocket_metric(&mut request_metric).await
+            }
+            Err(error) => {
+                // The server refused to upgrade the connection.
+                if let tungstenite::Error::Http(response) = &error {
+                    request_metric.status_code = response.status().as_u16();
+                }
+                warn!("{:?}: {}", &request_metric.raw.url, error);
+                fail_websocket_metric(&mut request_metric, &error.to_string());
+                self.record_websocket_metric(&mut request_metric).await?;
+                Err(error.into())
+            }
+        }
+    }
+
+    /// Optionally send a message, then wait for a message matching a predicate.
+    async fn exchange_websocket_messages<P>(
+        &mut self,
+        method: GooseMethod,
+        message: Option<Message>,
+        mut predicate: P,
+        timeout: Duration,
+    ) -> Result<Message, GooseTaskError>
+    where
+        P: FnMut(&Message) -> bool,
+    {
+        // Only throttle messages sent by the user.
+        if message.is_some() {
+            self.throttle_request().await?;
+        }
+
+        let mut websocket = self
+            .websocket
+            .take()
+            .ok_or(GooseTaskError::WebSocketNotConnected)?;
+        let mut request_metric =
+            self.new_websocket_metric(method, &websocket.url, &websocket.name, message.as_ref());
+
+        let started = Instant::now();
+        let sent = match message {
+            Some(message) => websocket.stream.send(message).await,
+            None => Ok(()),
+        };
+        let received = match sent {
+            Ok(()) => {
+                tokio::time::timeout(
+                    timeout,
+                    next_matching_message(&mut websocket.stream, &mut predicate),
+                )
+                .await
+            }
+            Err(error) => Ok(Err(error)),
+        };
+        request_metric.set_response_time(started.elapsed());
+
+        match received {
+            Ok(Ok(Some(message))) => {
+                self.websocket = Some(websocket);
+                self.record_websocket_metric(&mut request_metric).await?;
+                Ok(message)
+            }
+            // The server closed the connection.
+            Ok(Ok(None)) => {
+                fail_websocket_metric(&mut request_metric, "connection closed");
+                self.record_websocket_metric(&mut request_metric).await?;
+                Err(GooseTaskError::RequestFailed {
+                    raw_request: request_metric,
+                })
+            }
+            Ok(Err(error)) => {
+                fail_websocket_metric(&mut request_metric, &error.to_string());
+                self.record_websocket_metric(&mut request_metric).await?;
+                Err(error.into())
+            }
+            // No matching message was received in time, the connection remains open.
+            Err(_) => {
+                self.websocket = Some(websocket);
+                fail_websocket_metric(&mut request_metric, "timed out");
+                self.record_websocket_metric(&mut request_metric).await?;
+                Err(GooseTaskError::RequestFailed {
+                    raw_request: request_metric,
+                })
+            }
+        }
+    }
+
+    /// Record information about a WebSocket operation before it is performed.
+    fn new_websocket_metric(
+        &self,
+        method: GooseMethod,
+        url: &str,
+        name: &str,
+        message: Option<&Message>,
+    ) -> GooseRequestMetric {
+        // If enabled, grab a copy of text messages, included in the request log and the
+        // debug log.
+        let body = match message {
+            Some(message) if self.config.request_body => message.to_text().unwrap_or(""),
+            _ => "",
+        };
+        let raw_request = GooseRawRequest::new(method, url, Vec::new(), body);
+
+        let mut request_metric = GooseRequestMetric::new(
+            raw_request,
+            name,
+            self.started.elapsed().as_millis(),
+            self.weighted_users_index,
+        );
+        request_metric.set_final_url(url);
+        request_metric
+    }
+
+    /// Send a WebSocket operation to the parent process, like a request.
+    async fn record_websocket_metric(
+        &self,
+        request_metric: &mut GooseRequestMetric,
+    ) -> GooseTaskResult {
+        // Waiting for messages pushed by the server doesn't follow the cadence of tasks.
+        if request_metric.raw.method != GooseMethod::WsReceive {
+            request_metric.user_cadence =
+                self.coordinated_omission_mitigation(request_metric).await?;
+        }
+
+        if !self.config.no_metrics {
+            self.send_request_metric_to_parent(request_metric.clone())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Mark a WebSocket operation as a failure.
+fn fail_websocket_metric(request_metric: &mut GooseRequestMetric, error: &str) {
+    request_metric.success = false;
+    request_metric.error = format!("{}: {}", error, request_metric.name);
+}
+
+/// Returns the next message matching the predicate, or `None` if the connection closed.
+async fn next_matching_message<P>(
+    stream: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
+    predicate: &mut P,
+) -> Result<Option<Message>, tungstenite::Error>
+where
+    P: FnMut(&Message) -> bool,
+{
+    while let Some(message) = stream.next().await {
+        match message? {
+            // Pings are answered automatically.
+            Message::Ping(_) | Message::Pong(_) => continue,
+            Message::Close(_) => return Ok(None),
+            message => {
+                if predicate(&message) {
+                    return Ok(Some(message));
+                }
+            }
+        }
+    }
+
+    Ok(None)
+}
@@ -10,7 +10,10 @@ const EMPTY_ARGS: Vec<&str> = vec![];
 
 use crate::goose::{GooseUser, GooseUserCommand};
 use crate::manager::GooseUserInitializer;
-use crate::metrics::{GooseErrorMetrics, GooseRequestMetrics, GooseTaskMetrics};
+use crate::metrics::{
+    GooseCustomMetrics, GooseErrorMetrics, GooseRequestMetrics, GooseTaskMetrics,
+    GooseTransactionMetrics,
+};
 use crate::{get_worker_id, AttackMode, GooseAttack, GooseConfiguration, WORKER_ID};
 
 /// Workers send GaggleMetrics to the Manager process to be aggregated together.
@@ -24,6 +27,10 @@ pub enum GaggleMetrics {
     Tasks(GooseTaskMetrics),
     /// Goose error metrics.
     Errors(GooseErrorMetrics),
+    /// Goose transaction metrics.
+    Transactions(GooseTransactionMetrics),
+    /// Goose custom metrics.
+    Custom(GooseCustomMetrics),
 }
 
 // If pipe closes unexpectedly, panic.
@@ -101,6 +108,8 @@ pub(crate) async fn worker_main(goose_attack: GooseAttack) -> GooseAttack {
         .expect("failed to generate default configuration");
     let mut weighted_users: Vec<GooseUser> = Vec::new();
     let mut run_time: usize = 0;
+    let mut feeder_partition: usize = 0;
+    let mut feeder_partitions: usize = 1;
 
     // Wait for the manager to send user parameters.
     info!("waiting for instructions from manager");
@@ -150,6 +159,8 @@ pub(crate) async fn worker_main(goose_attack: GooseAttack) -> GooseAttack {
         if weighted_users.is_empty() {
             config = initializer.config;
             run_time = initializer.run_time;
+            feeder_partition = initializer.feeder_partition;
+            feeder_partitions = initializer.feeder_partitions;
         }
         weighted_users.push(user);
     }
@@ -214,6 +225,11 @@ pub(crate) async fn worker_main(goose_attack: GooseAttack) -> GooseAttack {
 
     worker_goose_attack.started = Some(time::Instant::now());
     worker_goose_attack.task_sets = goose_attack.task_sets.clone();
+    // Only hand out the partition of feeder records assigned by the Manager.
+    worker_goose_attack.feeders = goose_attack.feeders.clone();
+    worker_goose_attack.partition_feeders(feeder_partition, feeder_partitions);
+    // Share global data set before the load test started.
+    worker_goose_attack.global_data = goose_attack.global_data.clone();
     // Use the run_time from the Manager so Worker can shut down in a timely manner.
     worker_goose_attack.run_time = run_time;
     worker_goose_attack.weighted_users = weighted_users;
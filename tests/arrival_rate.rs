@@ -0,0 +1,165 @@
+use httpmock::{Method::GET, Mock, MockServer};
+use std::time::Duration;
+
+mod common;
+
+use goose::config::GooseConfiguration;
+use goose::goose::GooseTaskSet;
+use goose::prelude::*;
+
+// Paths used in load tests performed during these tests.
+const INDEX_PATH: &str = "/";
+const ABOUT_PATH: &str = "/about.html";
+
+// Indexes to the above paths.
+const INDEX_KEY: usize = 0;
+const ABOUT_KEY: usize = 1;
+
+// Load tests start 10 iterations per second for 2 seconds.
+const EXPECT_ITERATIONS: usize = 20;
+
+// Test task.
+pub async fn get_index(user: &mut GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+// Test task.
+pub async fn get_about(user: &mut GooseUser) -> GooseTaskResult {
+    let _goose = user.get(ABOUT_PATH).await?;
+    Ok(())
+}
+
+// All tests in this file run against common endpoints, optionally delaying responses.
+fn setup_mock_server_endpoints(server: &MockServer, delay: Duration) -> Vec<Mock> {
+    vec![
+        // First set up INDEX_PATH, store in vector at INDEX_KEY.
+        server.mock(|when, then| {
+            when.method(GET).path(INDEX_PATH);
+            then.status(200).delay(delay);
+        }),
+        // Next set up ABOUT_PATH, store in vector at ABOUT_KEY.
+        server.mock(|when, then| {
+            when.method(GET).path(ABOUT_PATH);
+            then.status(200).delay(delay);
+        }),
+    ]
+}
+
+// Build appropriate configuration for these tests.
+fn common_build_configuration(server: &MockServer, custom: &mut Vec<&str>) -> GooseConfiguration {
+    // Common elements in all our tests.
+    let mut configuration = vec![
+        "--arrival-rate",
+        "10",
+        "--run-time",
+        "2",
+        "--no-reset-metrics",
+    ];
+
+    // Custom elements in some tests.
+    configuration.append(custom);
+
+    // Return the resulting configuration.
+    common::build_configuration(server, configuration)
+}
+
+// Returns the appropriate taskset needed to build these tests.
+fn get_tasks() -> GooseTaskSet {
+    taskset!("LoadTest")
+        .register_task(task!(get_index))
+        .register_task(task!(get_about))
+}
+
+#[tokio::test]
+// Start iterations at a fixed rate with enough users to keep up.
+async fn test_arrival_rate() {
+    // Start the mock server, responding quickly.
+    let server = MockServer::start();
+    let mock_endpoints = setup_mock_server_endpoints(&server, Duration::from_millis(10));
+
+    // Build configuration with a pool of 2 users.
+    let configuration = common_build_configuration(&server, &mut vec!["--users", "2"]);
+
+    // Run the Goose Attack.
+    let goose_metrics = common::run_load_test(
+        common::build_load_test(configuration, &get_tasks(), None, None),
+        None,
+    )
+    .await;
+
+    let iterations = goose_metrics.iterations.unwrap();
+    println!("iterations: {:?}", iterations);
+
+    // Confirm roughly the configured number of iterations were scheduled.
+    assert!(iterations.scheduled >= EXPECT_ITERATIONS - 2);
+    assert!(iterations.scheduled <= EXPECT_ITERATIONS + 2);
+
+    // The pool was large enough to run all iterations on time.
+    assert!(iterations.delayed == 0);
+    assert!(iterations.dropped == 0);
+    assert!(iterations.peak_users == 2);
+    assert!(goose_metrics.users == 2);
+
+    // Each iteration runs each task one time, and every started iteration completes.
+    mock_endpoints[INDEX_KEY].assert_hits(iterations.scheduled);
+    mock_endpoints[ABOUT_KEY].assert_hits(iterations.scheduled);
+}
+
+#[tokio::test]
+// Start iterations at a fixed rate against a slow server, growing the pool of users
+// until it's full, and then dropping iterations.
+async fn test_arrival_rate_max_users() {
+    // Start the mock server, responding slowly.
+    let server = MockServer::start();
+    let mock_endpoints = setup_mock_server_endpoints(&server, Duration::from_millis(500));
+
+    // Build configuration with a pool of 1 user that can grow to 3 users.
+    let configuration =
+        common_build_configuration(&server, &mut vec!["--users", "1", "--max-users", "3"]);
+
+    // Run the Goose Attack.
+    let goose_metrics = common::run_load_test(
+        common::build_load_test(configuration, &get_tasks(), None, None),
+        None,
+    )
+    .await;
+
+    let iterations = goose_metrics.iterations.unwrap();
+    println!("iterations: {:?}", iterations);
+
+    // The pool grew to the maximum, delaying the iterations that launched new users.
+    assert!(iterations.peak_users == 3);
+    assert!(goose_metrics.users == 3);
+    assert!(iterations.delayed == 2);
+
+    // The server is too slow for 3 users to keep up, so iterations were dropped.
+    assert!(iterations.dropped > 0);
+
+    // Only iterations that weren't dropped were started.
+    let started = iterations.scheduled - iterations.dropped;
+    mock_endpoints[INDEX_KEY].assert_hits(started);
+}
+
+#[test]
+// Test that invalid configurations are rejected.
+fn test_arrival_rate_invalid() {
+    let server = MockServer::start();
+
+    for invalid in [
+        // The arrival rate must be greater than 0.
+        vec!["--arrival-rate", "0"],
+        vec!["--arrival-rate", "fast"],
+        // Maximum users requires an arrival rate.
+        vec!["--max-users", "5"],
+        // Maximum users can't be less than users.
+        vec!["--arrival-rate", "1", "--users", "5", "--max-users", "2"],
+        // Arrival rate and coordinated omission mitigation are incompatible.
+        vec!["--arrival-rate", "1", "--co-mitigation", "average"],
+    ] {
+        let configuration = common::build_configuration(&server, invalid);
+        let goose_attack = common::build_load_test(configuration, &get_tasks(), None, None);
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        assert!(runtime.block_on(goose_attack.execute()).is_err());
+    }
+}
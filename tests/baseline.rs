@@ -0,0 +1,185 @@
+use httpmock::{Method::GET, Mock, MockServer};
+use std::time::Duration;
+
+mod common;
+
+use goose::goose::GooseTaskSet;
+use goose::prelude::*;
+
+// Paths used in load tests performed during these tests.
+const INDEX_PATH: &str = "/";
+const ERROR_PATH: &str = "/error";
+
+// Indexes to the above paths.
+const INDEX_KEY: usize = 0;
+const ERROR_KEY: usize = 1;
+
+// Test task.
+pub async fn get_index(user: &mut GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+// Test task.
+pub async fn get_error(user: &mut GooseUser) -> GooseTaskResult {
+    let _goose = user.get(ERROR_PATH).await?;
+    Ok(())
+}
+
+// All tests in this file run against common endpoints.
+fn setup_mock_server_endpoints(server: &MockServer) -> Vec<Mock> {
+    vec![
+        // First set up INDEX_PATH, store in vector at INDEX_KEY.
+        server.mock(|when, then| {
+            when.method(GET).path(INDEX_PATH);
+            then.status(200);
+        }),
+        // Next set up ERROR_PATH, store in vector at ERROR_KEY.
+        server.mock(|when, then| {
+            when.method(GET).path(ERROR_PATH);
+            then.status(503);
+        }),
+    ]
+}
+
+// Returns the appropriate taskset needed to build these tests.
+fn get_tasks() -> GooseTaskSet {
+    taskset!("LoadTest")
+        .set_wait_time(Duration::from_millis(100), Duration::from_millis(100))
+        .unwrap()
+        .register_task(task!(get_index))
+        .register_task(task!(get_error))
+}
+
+// Run a load test, with optional additional options.
+async fn run_load_test(server: &MockServer, options: Vec<&str>) -> GooseMetrics {
+    let mut configuration = vec!["--users", "2", "--hatch-rate", "4", "--run-time", "2"];
+    configuration.extend(options);
+    let configuration = common::build_configuration(server, configuration);
+    common::run_load_test(
+        common::build_load_test(configuration, &get_tasks(), None, None),
+        None,
+    )
+    .await
+}
+
+#[tokio::test]
+// Compare a load test against a baseline saved with `--summary-json`.
+async fn test_baseline() {
+    let baseline_file = "baseline-test.json";
+    let report_file = "baseline-test.html";
+
+    let server = MockServer::start();
+    let mock_endpoints = setup_mock_server_endpoints(&server);
+
+    // Save a baseline.
+    run_load_test(&server, vec!["--summary-json", baseline_file]).await;
+
+    // Make the baseline of GET / much faster than this load test will be, so it
+    // regresses.
+    let mut baseline: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(baseline_file).unwrap()).unwrap();
+    baseline["requests"][0]["requests_per_second"] = serde_json::json!(1_000.0);
+    std::fs::write(baseline_file, baseline.to_string()).unwrap();
+
+    let goose_metrics = run_load_test(
+        &server,
+        vec![
+            "--baseline",
+            baseline_file,
+            "--baseline-tolerance",
+            "50",
+            "--report-file",
+            report_file,
+        ],
+    )
+    .await;
+    assert!(mock_endpoints[INDEX_KEY].hits() > 0);
+    assert!(mock_endpoints[ERROR_KEY].hits() > 0);
+
+    let comparison = goose_metrics.baseline.as_ref().unwrap();
+    assert_eq!(comparison.baseline, baseline_file);
+    assert_eq!(comparison.tolerance, 50);
+
+    // Each request was compared, followed by all requests aggregated together.
+    let names: Vec<&str> = comparison
+        .requests
+        .iter()
+        .map(|row| row.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["GET /", "GET /error", "Aggregated"]);
+    for row in &comparison.requests {
+        assert!(row.requests_per_second.baseline.is_some());
+        assert!(row.average.baseline.is_some());
+        assert!(row.p95.baseline.is_some());
+        assert!(row.p99.baseline.is_some());
+        assert!(row.failure_rate.baseline.is_some());
+    }
+
+    // Requests per second of GET / regressed.
+    let index = &comparison.requests[0];
+    assert!(index.requests_per_second.regression);
+    assert!(index.regression());
+    // The failure rate of GET /error was 100% both times, so didn't regress.
+    let error = &comparison.requests[1];
+    assert_eq!(error.failure_rate.baseline, Some(100.0));
+    assert_eq!(error.failure_rate.current, 100.0);
+    assert!(!error.failure_rate.regression);
+
+    // Each task was compared.
+    let names: Vec<&str> = comparison
+        .tasks
+        .iter()
+        .map(|row| row.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["1.1", "1.2"]);
+    assert!(comparison.tasks[0].requests_per_second.baseline.is_some());
+
+    // The comparison is displayed with the metrics, and in the html report.
+    let displayed = goose_metrics.to_string();
+    assert!(displayed.contains("=== BASELINE ==="));
+    assert!(displayed.contains("* marks regressions beyond 50%"));
+    let report = std::fs::read_to_string(report_file).unwrap();
+    assert!(report.contains("<h2>Baseline Comparison</h2>"));
+    assert!(report.contains(r#"<td class="regression">"#));
+
+    common::cleanup_files(vec![baseline_file, report_file]);
+}
+
+#[test]
+// Test that invalid configurations are rejected.
+fn test_baseline_invalid() {
+    let invalid_file = "baseline-invalid.json";
+    let newer_file = "baseline-newer.json";
+    std::fs::write(invalid_file, "{\"requests\": ").unwrap();
+    std::fs::write(
+        newer_file,
+        format!(
+            "{{\"schema_version\": {}}}",
+            goose::summary::SUMMARY_SCHEMA_VERSION + 1
+        ),
+    )
+    .unwrap();
+
+    let server = MockServer::start();
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+
+    for options in vec![
+        // The baseline must exist.
+        vec!["--baseline", "baseline-missing.json"],
+        // The baseline must be a JSON summary.
+        vec!["--baseline", invalid_file],
+        // The baseline can't be from a newer schema.
+        vec!["--baseline", newer_file],
+        // A tolerance requires a baseline.
+        vec!["--baseline-tolerance", "5"],
+        // Metrics can't be compared if they're disabled.
+        vec!["--baseline", invalid_file, "--no-metrics"],
+    ] {
+        let configuration = common::build_configuration(&server, options);
+        let goose_attack = common::build_load_test(configuration, &get_tasks(), None, None);
+        assert!(runtime.block_on(goose_attack.execute()).is_err());
+    }
+
+    common::cleanup_files(vec![invalid_file, newer_file]);
+}
@@ -16,9 +16,9 @@ type WorkerHandles = Vec<tokio::task::JoinHandle<GooseMetrics>>;
 /// The following options are configured by default, if not set to a custom value
 /// and if not building a Worker configuration:
 ///  --host <mock-server>
-///  --users 1
-///  --hatch-rate 1
-///  --run-time 1
+///  --users 1 (unless --test-plan is set)
+///  --hatch-rate 1 (unless --test-plan is set)
+///  --run-time 1 (unless --test-plan is set)
 ///  --co-mitigation disabled
 #[allow(dead_code)]
 pub fn build_configuration(server: &MockServer, custom: Vec<&str>) -> GooseConfiguration {
@@ -37,19 +37,22 @@ pub fn build_configuration(server: &MockServer, custom: Vec<&str>) -> GooseConfi
             configuration.extend_from_slice(&["--host", &server_url]);
         }
 
-        // Default to testing with 1 user if not otherwise configured.
-        if !configuration.contains(&"--users") {
-            configuration.extend_from_slice(&["--users", "1"]);
-        }
-
-        // Default to hatch 1 user per second if not otherwise configured.
-        if !configuration.contains(&"--hatch-rate") {
-            configuration.extend_from_slice(&["--hatch-rate", "1"]);
-        }
-
-        // Default to running for 1 second if not otherwise configured.
-        if !configuration.contains(&"--run-time") {
-            configuration.extend_from_slice(&["--run-time", "1"]);
+        // A test plan defines users, hatch rate and run time.
+        if !configuration.contains(&"--test-plan") {
+            // Default to testing with 1 user if not otherwise configured.
+            if !configuration.contains(&"--users") {
+                configuration.extend_from_slice(&["--users", "1"]);
+            }
+
+            // Default to hatch 1 user per second if not otherwise configured.
+            if !configuration.contains(&"--hatch-rate") {
+                configuration.extend_from_slice(&["--hatch-rate", "1"]);
+            }
+
+            // Default to running for 1 second if not otherwise configured.
+            if !configuration.contains(&"--run-time") {
+                configuration.extend_from_slice(&["--run-time", "1"]);
+            }
         }
 
         // Default to disabling coordinated omission mitigation if not otherwise configured
@@ -0,0 +1,110 @@
+use gumdrop::Options;
+use httpmock::{Method::GET, Mock, MockServer};
+use std::time::Duration;
+
+use goose::config::GooseConfiguration;
+use goose::goose::GooseTaskSet;
+use goose::prelude::*;
+
+// Paths used in load tests performed during these tests.
+const INDEX_PATH: &str = "/";
+
+// Test task.
+pub async fn get_index(user: &mut GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+// All tests in this file run against a common endpoint.
+fn setup_mock_server_endpoints(server: &MockServer) -> Vec<Mock> {
+    vec![server.mock(|when, then| {
+        when.method(GET).path(INDEX_PATH);
+        then.status(200);
+    })]
+}
+
+// Returns the appropriate taskset needed to build these tests.
+fn get_tasks() -> GooseTaskSet {
+    taskset!("LoadTest")
+        .set_wait_time(Duration::from_millis(100), Duration::from_millis(100))
+        .unwrap()
+        .register_task(task!(get_index))
+}
+
+// Build a load test from command line options, without the defaults set by
+// common::build_configuration so they can be set with environment variables.
+fn build_load_test(options: Vec<&str>) -> GooseAttack {
+    let configuration = GooseConfiguration::parse_args_default(&options).unwrap();
+    GooseAttack::initialize_with_config(configuration)
+        .unwrap()
+        .register_taskset(get_tasks())
+}
+
+#[tokio::test]
+// Load options from environment variables. Environment variables are shared by all tests
+// in this file, so this is the only test.
+async fn test_config_env() {
+    let server = MockServer::start();
+    let mock_endpoints = setup_mock_server_endpoints(&server);
+
+    // The configuration file is removed with its temporary directory, even if the test
+    // fails.
+    let config_dir = tempfile::tempdir().unwrap();
+    let config_file = config_dir.path().join("config-env-test.toml");
+    std::fs::write(
+        &config_file,
+        "users = 4\nhatch-rate = 4\nrun-time = \"10\"\n",
+    )
+    .unwrap();
+
+    std::env::set_var("GOOSE_HOST", server.base_url());
+    std::env::set_var("GOOSE_USERS", "3");
+    std::env::set_var("GOOSE_RUN_TIME", "2");
+    std::env::set_var("GOOSE_CO_MITIGATION", "disabled");
+    std::env::set_var("GOOSE_STATUS_CODES", "true");
+    std::env::set_var("GOOSE_THRESHOLD", "GET /:requests>0;errors<1%");
+    std::env::set_var("GOOSE_CONFIG", &config_file);
+    // Variables that aren't valid UTF-8 are ignored.
+    #[cfg(unix)]
+    std::env::set_var(
+        "GOOSE_NOT_UTF8",
+        <std::ffi::OsStr as std::os::unix::ffi::OsStrExt>::from_bytes(b"\xff"),
+    );
+
+    // Options set on the command line take precedence over environment variables, which
+    // take precedence over the configuration file and defaults.
+    let goose_metrics = build_load_test(vec!["--users", "2"])
+        .set_default(GooseDefault::RunTime, 20)
+        .unwrap()
+        .execute()
+        .await
+        .unwrap();
+
+    assert!(mock_endpoints[0].hits() > 0);
+    assert_eq!(goose_metrics.users, 2);
+    assert_eq!(goose_metrics.duration, 2);
+    assert!(goose_metrics.hosts.contains(&server.url("/")));
+    let index = goose_metrics.requests.get(&format!("GET {}", INDEX_PATH));
+    assert!(index.unwrap().status_code_counts.contains_key(&200));
+    assert_eq!(goose_metrics.thresholds.len(), 2);
+
+    // Invalid values are rejected, naming the environment variable.
+    std::env::set_var("GOOSE_USERS", "many");
+    match build_load_test(vec![]).execute().await {
+        Err(GooseError::InvalidOption { option, .. }) => assert_eq!(option, "GOOSE_USERS"),
+        _ => panic!("GOOSE_USERS was not rejected"),
+    }
+
+    for variable in &[
+        "GOOSE_HOST",
+        "GOOSE_USERS",
+        "GOOSE_RUN_TIME",
+        "GOOSE_CO_MITIGATION",
+        "GOOSE_STATUS_CODES",
+        "GOOSE_THRESHOLD",
+        "GOOSE_CONFIG",
+        "GOOSE_NOT_UTF8",
+    ] {
+        std::env::remove_var(variable);
+    }
+}
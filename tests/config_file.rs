@@ -0,0 +1,215 @@
+use gumdrop::Options;
+use httpmock::{Method::GET, Mock, MockServer};
+use std::time::Duration;
+
+mod common;
+
+use goose::config::GooseConfiguration;
+use goose::goose::GooseTaskSet;
+use goose::prelude::*;
+
+// Paths used in load tests performed during these tests.
+const INDEX_PATH: &str = "/";
+
+// Test task.
+pub async fn get_index(user: &mut GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+// All tests in this file run against a common endpoint.
+fn setup_mock_server_endpoints(server: &MockServer) -> Vec<Mock> {
+    vec![server.mock(|when, then| {
+        when.method(GET).path(INDEX_PATH);
+        then.status(200);
+    })]
+}
+
+// Returns the appropriate taskset needed to build these tests.
+fn get_tasks() -> GooseTaskSet {
+    taskset!("LoadTest")
+        .set_wait_time(Duration::from_millis(100), Duration::from_millis(100))
+        .unwrap()
+        .register_task(task!(get_index))
+}
+
+// Build a load test from command line options, without the defaults set by
+// common::build_configuration so they can be set in the configuration file.
+fn build_load_test(options: Vec<&str>) -> GooseAttack {
+    let configuration = GooseConfiguration::parse_args_default(&options).unwrap();
+    GooseAttack::initialize_with_config(configuration)
+        .unwrap()
+        .register_taskset(get_tasks())
+}
+
+#[tokio::test]
+// Load options from TOML, YAML and JSON configuration files.
+async fn test_config_file() {
+    let server = MockServer::start();
+    let mock_endpoints = setup_mock_server_endpoints(&server);
+
+    let toml_file = "config-test.toml";
+    let yaml_file = "config-test.yaml";
+    let json_file = "config-test.json";
+    std::fs::write(
+        toml_file,
+        format!(
+            r#"
+# Load test profile.
+host = "{}"
+users = 3
+hatch-rate = 4
+run_time = "2"
+co-mitigation = "disabled"
+status-codes = true
+"#,
+            server.base_url()
+        ),
+    )
+    .unwrap();
+    std::fs::write(
+        yaml_file,
+        format!(
+            r#"
+# Load test profile.
+host: {}
+users: 3
+hatch-rate: 4
+run_time: "2"
+co-mitigation: disabled
+status-codes: true
+"#,
+            server.base_url()
+        ),
+    )
+    .unwrap();
+    std::fs::write(
+        json_file,
+        format!(
+            r#"{{
+    "host": "{}",
+    "users": 3,
+    "hatch-rate": 4,
+    "run_time": "2",
+    "co-mitigation": "disabled",
+    "status-codes": true
+}}"#,
+            server.base_url()
+        ),
+    )
+    .unwrap();
+
+    for file in &[toml_file, yaml_file, json_file] {
+        // Options set on the command line take precedence over the file, which takes
+        // precedence over defaults.
+        let goose_metrics = build_load_test(vec!["--config", file, "--users", "2"])
+            .set_default(GooseDefault::RunTime, 10)
+            .unwrap()
+            .set_default(GooseDefault::ReportFile, "config-test.html")
+            .unwrap()
+            .execute()
+            .await
+            .unwrap();
+
+        assert_eq!(goose_metrics.users, 2);
+        assert_eq!(goose_metrics.duration, 2);
+        assert!(goose_metrics.hosts.contains(&server.url("/")));
+        // Flags are enabled.
+        let index = goose_metrics.requests.get(&format!("GET {}", INDEX_PATH));
+        assert!(index.unwrap().status_code_counts.contains_key(&200));
+        // Defaults not set in the file are still applied.
+        assert!(std::path::Path::new("config-test.html").exists());
+        common::cleanup_files(vec!["config-test.html"]);
+    }
+    assert!(mock_endpoints[0].hits() > 0);
+
+    common::cleanup_files(vec![toml_file, yaml_file, json_file]);
+}
+
+#[test]
+// Test that invalid configuration files are rejected.
+fn test_config_file_invalid() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+
+    for (file, contents, error) in vec![
+        // The file must exist.
+        (
+            "config-missing.toml",
+            None,
+            "Failed to read configuration file",
+        ),
+        // The format is determined by the extension.
+        (
+            "config-invalid.ini",
+            Some("users = 1"),
+            "unsupported file extension",
+        ),
+        // Only top level options can be set.
+        (
+            "config-invalid.toml",
+            Some("[load]\nusers = 1"),
+            "unknown option `load`",
+        ),
+        (
+            "config-invalid.yaml",
+            Some("load:\n  users: 1"),
+            "unknown option `load`",
+        ),
+        (
+            "config-invalid.json",
+            Some("[\"users\"]"),
+            "expected an object",
+        ),
+        // Syntax errors are reported.
+        (
+            "config-invalid.toml",
+            Some("host = local.dev"),
+            "did you mean to use a quoted string? at line 1",
+        ),
+        (
+            "config-invalid.json",
+            Some("{\"users\": "),
+            "EOF while parsing",
+        ),
+        // Options must exist.
+        (
+            "config-invalid.toml",
+            Some("userz = 1"),
+            "unknown option `userz`",
+        ),
+        (
+            "config-invalid.toml",
+            Some("config = \"a.toml\""),
+            "unknown option `config`",
+        ),
+        // Values must be valid.
+        (
+            "config-invalid.toml",
+            Some("users = \"many\""),
+            "invalid value for `users`",
+        ),
+        (
+            "config-invalid.yaml",
+            Some("host: [a, b]"),
+            "invalid value for `host`",
+        ),
+        (
+            "config-invalid.json",
+            Some("{\"verbose\": \"v\"}"),
+            "invalid value for `verbose`",
+        ),
+    ] {
+        if let Some(contents) = contents {
+            std::fs::write(file, contents).unwrap();
+        }
+        let goose_attack = build_load_test(vec!["--config", file]);
+        match runtime.block_on(goose_attack.execute()) {
+            Err(GooseError::InvalidOption { option, detail, .. }) => {
+                assert_eq!(option, "--config");
+                assert!(detail.contains(error), "{}: {}", file, detail);
+            }
+            _ => panic!("{} was not rejected", file),
+        }
+        common::cleanup_files(vec![file]);
+    }
+}
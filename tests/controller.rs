@@ -22,6 +22,8 @@ const INDEX_KEY: usize = 0;
 const ABOUT_KEY: usize = 1;
 
 const USERS: usize = 5;
+// The running load test is scaled down to this many users before it's stopped.
+const RUNNING_USERS: usize = 2;
 const HATCH_RATE: usize = 10;
 const RUN_TIME: usize = 10;
 
@@ -38,6 +40,8 @@ enum TestType {
 struct TestState {
     // A buffer for the telnet Controller.
     buf: [u8; 2048],
+    // The complete reply from the telnet Controller, which can span many reads.
+    telnet_response: Vec<u8>,
     // Track iterations through GooseControllerCommands.
     position: usize,
     // Track the steps within a given iteration.
@@ -48,7 +52,14 @@ struct TestState {
     telnet_stream: Option<TcpStream>,
     // A TCP socket if testing the WebSocket Controller.
     #[cfg(not(feature = "rustls-tls"))]
-    websocket_stream: Option<tokio_tungstenite::tungstenite::WebSocket<std::net::TcpStream>>,
+    websocket_stream: Option<
+        tokio_tungstenite::tungstenite::WebSocket<
+            tokio_tungstenite::tungstenite::stream::Stream<
+                std::net::TcpStream,
+                native_tls::TlsStream<std::net::TcpStream>,
+            >,
+        >,
+    >,
     #[cfg(feature = "rustls-tls")]
     websocket_stream: Option<
         tokio_tungstenite::tungstenite::WebSocket<
@@ -136,8 +147,8 @@ fn validate_one_taskset(
     assert!(index_metrics.fail_count == 0);
     assert!(about_metrics.fail_count == 0);
 
-    // Users were correctly configured through the controller.
-    assert!(goose_metrics.users == USERS);
+    // Users were correctly reconfigured through the controller while running.
+    assert!(goose_metrics.users == RUNNING_USERS);
 
     // Host was not configured at start time.
     assert!(configuration.host.is_empty());
@@ -174,7 +185,7 @@ async fn run_standalone_test(test_type: TestType) {
     let configuration = common_build_configuration(&server, &mut configuration_flags);
 
     // Create a new thread from which to test the Controller.
-    let _controller_handle = tokio::spawn(async move {
+    let mut controller_handle = tokio::spawn(async move {
         // Sleep a half a second allowing the GooseAttack to start.
         tokio::time::sleep(time::Duration::from_millis(500)).await;
 
@@ -185,20 +196,32 @@ async fn run_standalone_test(test_type: TestType) {
             let response;
             let websocket_response: GooseControllerWebSocketResponse;
             if let Some(stream) = test_state.telnet_stream.as_mut() {
-                let _ = match stream.read(&mut test_state.buf) {
-                    Ok(data) => data,
-                    Err(_) => {
-                        panic!("ERROR: server disconnected!");
+                // Read until the reply ends with a prompt, or the Controller exits.
+                test_state.telnet_response.clear();
+                loop {
+                    // Reads block, so let the runtime run the Controller meanwhile.
+                    let buf = &mut test_state.buf;
+                    let n = match tokio::task::block_in_place(|| stream.read(buf)) {
+                        Ok(data) => data,
+                        Err(_) => {
+                            panic!("ERROR: server disconnected!");
+                        }
+                    };
+                    test_state
+                        .telnet_response
+                        .extend_from_slice(&test_state.buf[..n]);
+                    if n == 0 || test_state.telnet_response.ends_with(b"goose> ") {
+                        break;
                     }
-                };
-                response = str::from_utf8(&test_state.buf).unwrap();
+                }
+                response = str::from_utf8(&test_state.telnet_response).unwrap();
             // Process data received from the client in a loop.
             } else if let Some(stream) = test_state.websocket_stream.as_mut() {
                 if !test_state.websocket_expect_reply {
                     response = "";
                     test_state.websocket_expect_reply = true;
                 } else {
-                    match stream.read_message() {
+                    match tokio::task::block_in_place(|| stream.read_message()) {
                         Ok(message) => {
                             if let Ok(r) = message.into_text() {
                                 // Keep response around for the entire loop.
@@ -494,6 +517,21 @@ async fn run_standalone_test(test_type: TestType) {
                         }
                     }
                 }
+                GooseControllerCommand::Prometheus => {
+                    match test_state.step {
+                        // Request the running metrics in the Prometheus text format.
+                        0 => {
+                            make_request(&mut test_state, "prometheus\r\n");
+                        }
+                        // Confirm the metrics are returned in the Prometheus text format.
+                        _ => {
+                            assert!(response.starts_with("# HELP goose_attack_phase"));
+
+                            // Move onto the next command.
+                            test_state = update_state(Some(test_state), &test_type);
+                        }
+                    }
+                }
                 GooseControllerCommand::Start => {
                     match test_state.step {
                         // Try to stop an idle load test.
@@ -523,15 +561,65 @@ async fn run_standalone_test(test_type: TestType) {
                         }
                     }
                 }
+                GooseControllerCommand::Pause => {
+                    match test_state.step {
+                        // Pause the running load test.
+                        0 => {
+                            make_request(&mut test_state, "pause\r\n");
+                        }
+                        // Confirm a running load test can be paused.
+                        1 => {
+                            assert!(response.starts_with("load test paused"));
+
+                            // Try to pause the load test again.
+                            make_request(&mut test_state, "pause\r\n");
+                        }
+                        // Confirm a paused load test can not be paused.
+                        _ => {
+                            assert!(response.starts_with("load test not running"));
+
+                            // Move onto the next command.
+                            test_state = update_state(Some(test_state), &test_type);
+                        }
+                    }
+                }
+                GooseControllerCommand::Resume => {
+                    match test_state.step {
+                        // Resume the paused load test.
+                        0 => {
+                            make_request(&mut test_state, "resume\r\n");
+                        }
+                        // Confirm a paused load test can be resumed.
+                        1 => {
+                            assert!(response.starts_with("load test resumed"));
+
+                            // Try to resume the load test again.
+                            make_request(&mut test_state, "resume\r\n");
+                        }
+                        // Confirm a running load test can not be resumed.
+                        _ => {
+                            assert!(response.starts_with("load test not paused"));
+
+                            // Move onto the next command.
+                            test_state = update_state(Some(test_state), &test_type);
+                        }
+                    }
+                }
                 GooseControllerCommand::Stop => {
                     match test_state.step {
-                        // Try to configure users on a running load test.
+                        // Scale down the number of users on a running load test.
                         0 => {
-                            make_request(&mut test_state, "users 1\r\n");
+                            make_request(
+                                &mut test_state,
+                                &["users ", &RUNNING_USERS.to_string(), "\r\n"].concat(),
+                            );
                         }
-                        // Confirm users can not be configured on a running load test.
+                        // Confirm users can be configured on a running load test.
                         1 => {
-                            assert!(response.starts_with("load test not idle"));
+                            assert!(response.starts_with("users configured"));
+
+                            // Give Goose a second to stop the surplus users.
+                            tokio::time::sleep(time::Duration::from_secs(1)).await;
 
                             // Try to configure host on a running load test.
                             make_request(&mut test_state, "host http://localhost/\r\n");
@@ -565,26 +653,36 @@ async fn run_standalone_test(test_type: TestType) {
                         _ => {
                             assert!(response.starts_with("load test shut down"));
 
-                            // Move onto the next command.
-                            test_state = update_state(Some(test_state), &test_type);
+                            // All commands have been tested.
+                            break;
                         }
                     }
                 }
             }
-            // Flush the buffer.
-            test_state.buf = [0; 2048];
-
             // Give the parent process time to catch up.
             tokio::time::sleep(time::Duration::from_millis(100)).await;
         }
     });
 
     // Run the Goose Attack.
-    let goose_metrics = common::run_load_test(
+    let load_test = common::run_load_test(
         common::build_load_test(configuration.clone(), &get_tasks(), None, None),
         None,
-    )
-    .await;
+    );
+    tokio::pin!(load_test);
+
+    // Fail instead of hanging if the Controller thread panics, as the load test is
+    // then never shut down.
+    let goose_metrics = tokio::select! {
+        goose_metrics = &mut load_test => {
+            controller_handle.await.unwrap();
+            goose_metrics
+        }
+        controller = &mut controller_handle => {
+            controller.unwrap();
+            load_test.await
+        }
+    };
 
     // Confirm that the load test ran correctly.
     validate_one_taskset(
@@ -611,6 +709,9 @@ fn update_state(test_state: Option<TestState>, test_type: &TestType) -> TestStat
         GooseControllerCommand::ConfigJson,
         GooseControllerCommand::Metrics,
         GooseControllerCommand::MetricsJson,
+        GooseControllerCommand::Prometheus,
+        GooseControllerCommand::Pause,
+        GooseControllerCommand::Resume,
         GooseControllerCommand::Stop,
         GooseControllerCommand::Shutdown,
     ];
@@ -639,12 +740,17 @@ fn update_state(test_state: Option<TestState>, test_type: &TestType) -> TestStat
         let websocket_controller: bool;
         let websocket_stream = match test_type {
             TestType::WebSocket => {
-                let (mut stream, _) =
-                    tokio_tungstenite::tungstenite::client::connect("ws://127.0.0.1:5117").unwrap();
-                // Send an empty message so the client performs a handshake.
-                stream.write_message(Message::Text("".into())).unwrap();
-                // Ignore the error that comes back.
-                let _ = stream.read_message().unwrap();
+                // Connecting blocks, so let the runtime run the Controller meanwhile.
+                let stream = tokio::task::block_in_place(|| {
+                    let (mut stream, _) =
+                        tokio_tungstenite::tungstenite::client::connect("ws://127.0.0.1:5117")
+                            .unwrap();
+                    // Send an empty message so the client performs a handshake.
+                    stream.write_message(Message::Text("".into())).unwrap();
+                    // Ignore the error that comes back.
+                    let _ = stream.read_message().unwrap();
+                    stream
+                });
                 websocket_controller = true;
                 Some(stream)
             }
@@ -656,6 +762,7 @@ fn update_state(test_state: Option<TestState>, test_type: &TestType) -> TestStat
 
         TestState {
             buf: [0; 2048],
+            telnet_response: Vec::new(),
             position: 0,
             step: 0,
             command: commands_to_test.first().unwrap().clone(),
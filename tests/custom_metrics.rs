@@ -0,0 +1,132 @@
+use httpmock::{Method::GET, Mock, MockServer};
+use std::time::{Duration, Instant};
+
+mod common;
+
+use goose::goose::GooseTaskSet;
+use goose::metrics::GooseCustomMetricKind;
+use goose::prelude::*;
+use goose::summary::GooseSummary;
+
+// Paths used in load tests performed during these tests.
+const INDEX_PATH: &str = "/";
+const JOB_PATH: &str = "/job";
+
+// Indexes to the above paths.
+const INDEX_KEY: usize = 0;
+const JOB_KEY: usize = 1;
+
+// Names of the custom metrics.
+const PAGES: &str = "pages";
+const USER: &str = "user";
+const JOB: &str = "job";
+
+// Test task.
+pub async fn get_index(user: &mut GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    user.increment_counter(PAGES, 2)?;
+    user.set_gauge(USER, user.weighted_users_index as f64)
+}
+
+// Test task.
+pub async fn run_job(user: &mut GooseUser) -> GooseTaskResult {
+    let started = Instant::now();
+    let _goose = user.get(JOB_PATH).await?;
+    user.record_timing(JOB, started.elapsed())
+}
+
+// All tests in this file run against common endpoints.
+fn setup_mock_server_endpoints(server: &MockServer) -> Vec<Mock> {
+    vec![
+        server.mock(|when, then| {
+            when.method(GET).path(INDEX_PATH);
+            then.status(200);
+        }),
+        server.mock(|when, then| {
+            when.method(GET).path(JOB_PATH);
+            then.status(200).delay(Duration::from_millis(20));
+        }),
+    ]
+}
+
+// Returns the appropriate taskset needed to build these tests.
+fn get_tasks() -> GooseTaskSet {
+    taskset!("LoadTest")
+        .set_wait_time(Duration::from_millis(100), Duration::from_millis(100))
+        .unwrap()
+        .register_task(task!(get_index))
+        .register_task(task!(run_job))
+}
+
+#[tokio::test]
+// Record custom counters, gauges and timings from tasks.
+async fn test_custom_metrics() {
+    const REPORT_FILE: &str = "custom-metrics-report.html";
+
+    let server = MockServer::start();
+    let mock_endpoints = setup_mock_server_endpoints(&server);
+
+    let configuration = common::build_configuration(
+        &server,
+        vec![
+            "--users",
+            "2",
+            "--hatch-rate",
+            "4",
+            "--run-time",
+            "2",
+            "--no-reset-metrics",
+            "--report-file",
+            REPORT_FILE,
+        ],
+    );
+    let goose_metrics = common::run_load_test(
+        common::build_load_test(configuration.clone(), &get_tasks(), None, None),
+        None,
+    )
+    .await;
+
+    let index_hits = mock_endpoints[INDEX_KEY].hits();
+    let job_hits = mock_endpoints[JOB_KEY].hits();
+    assert!(index_hits > 0);
+    assert!(job_hits > 0);
+
+    // Counters add up all increments.
+    let pages = &goose_metrics.custom[PAGES];
+    assert_eq!(pages.kind, GooseCustomMetricKind::Counter);
+    assert!(pages.samples > 0);
+    assert!(pages.samples <= index_hits);
+    assert_eq!(pages.total, pages.samples as f64 * 2.0);
+
+    // Gauges track the range of values set by both users.
+    let user = &goose_metrics.custom[USER];
+    assert_eq!(user.kind, GooseCustomMetricKind::Gauge);
+    assert_eq!(user.minimum, 0.0);
+    assert_eq!(user.maximum, 1.0);
+
+    // Timings include the delay of the endpoint.
+    let job = &goose_metrics.custom[JOB];
+    assert_eq!(job.kind, GooseCustomMetricKind::Timing);
+    assert!(job.samples > 0);
+    assert!(job.samples <= job_hits);
+    assert_eq!(job.times.len(), job.samples as u64);
+    assert!(job.minimum >= 20.0);
+
+    // Custom metrics are included in all reports.
+    let displayed = format!("{}", goose_metrics);
+    assert!(displayed.contains("CUSTOM METRICS"));
+    let report = std::fs::read_to_string(REPORT_FILE).unwrap();
+    assert!(report.contains("<h2>Custom Metrics</h2>"));
+    let summary = GooseSummary::new(&goose_metrics, &configuration);
+    let names: Vec<&str> = summary
+        .custom
+        .iter()
+        .map(|custom| custom.name.as_str())
+        .collect();
+    assert_eq!(names, vec![JOB, PAGES, USER]);
+    assert!(summary.custom[0].times.is_some());
+    assert!(summary.custom[1].times.is_none());
+
+    // Cleanup from test.
+    common::cleanup_files(vec![REPORT_FILE]);
+}
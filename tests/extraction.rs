@@ -0,0 +1,164 @@
+use httpmock::{
+    Method::{GET, POST},
+    Mock, MockServer,
+};
+
+mod common;
+
+use goose::extraction::GooseExtraction;
+use goose::prelude::*;
+
+// Paths used in load tests performed during these tests.
+const FORM_PATH: &str = "/form";
+const COMMENT_PATH: &str = "/comment";
+const USER_PATH: &str = "/user";
+const PROFILE_PATH: &str = "/user/7";
+const EMPTY_PATH: &str = "/empty";
+
+// Indexes to the above paths.
+const FORM_KEY: usize = 0;
+const COMMENT_KEY: usize = 1;
+const USER_KEY: usize = 2;
+const PROFILE_KEY: usize = 3;
+const EMPTY_KEY: usize = 4;
+
+// The values that correlate requests.
+const FORM_BUILD_ID: &str = "form-123";
+const CSRF_TOKEN: &str = "abc";
+const USER_ID: &str = "7";
+
+// Test task.
+pub async fn post_comment(user: &mut GooseUser) -> GooseTaskResult {
+    let extraction = GooseExtraction::new()
+        .css_attribute("form_build_id", r#"input[name="form_build_id"]"#, "value")
+        .unwrap()
+        .header("token", "x-csrf-token");
+    let goose = user.get(FORM_PATH).await?;
+    goose.extract(user, &extraction).await?;
+
+    let body = user.expand_variables("form_build_id={{form_build_id}}&token={{token}}");
+    let _goose = user.post(COMMENT_PATH, &body).await?;
+    Ok(())
+}
+
+// Test task.
+pub async fn view_profile(user: &mut GooseUser) -> GooseTaskResult {
+    let extraction = GooseExtraction::new().json_path("uid", "$.id").unwrap();
+    let goose = user.get(USER_PATH).await?;
+    goose.extract(user, &extraction).await?;
+
+    assert_eq!(user.get_variable("uid"), Some(USER_ID));
+    let path = user.expand_variables("/user/{{uid}}");
+    let _goose = user.get(&path).await?;
+    Ok(())
+}
+
+// Test task.
+pub async fn get_empty(user: &mut GooseUser) -> GooseTaskResult {
+    // The page has no form, so the extraction fails.
+    let extraction = GooseExtraction::new().regex("nid", r"node/(\d+)").unwrap();
+    let goose = user.get(EMPTY_PATH).await?;
+    let _ = goose.extract(user, &extraction).await;
+    assert!(user.get_variable("nid").is_none());
+    Ok(())
+}
+
+// All tests in this file run against common endpoints.
+fn setup_mock_server_endpoints(server: &MockServer) -> Vec<Mock> {
+    vec![
+        server.mock(|when, then| {
+            when.method(GET).path(FORM_PATH);
+            then.status(200)
+                .header("x-csrf-token", CSRF_TOKEN)
+                .body(format!(
+                    r#"<form><input type="hidden" name="form_build_id" value="{}" /></form>"#,
+                    FORM_BUILD_ID
+                ));
+        }),
+        // Only matches requests including the extracted values.
+        server.mock(|when, then| {
+            when.method(POST).path(COMMENT_PATH).body(format!(
+                "form_build_id={}&token={}",
+                FORM_BUILD_ID, CSRF_TOKEN
+            ));
+            then.status(200);
+        }),
+        server.mock(|when, then| {
+            when.method(GET).path(USER_PATH);
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(format!(r#"{{"id": {}, "name": "alice"}}"#, USER_ID));
+        }),
+        server.mock(|when, then| {
+            when.method(GET).path(PROFILE_PATH);
+            then.status(200);
+        }),
+        server.mock(|when, then| {
+            when.method(GET).path(EMPTY_PATH);
+            then.status(200).body("<html></html>");
+        }),
+    ]
+}
+
+#[tokio::test]
+// Extract values from responses, and use them in later requests.
+async fn test_extraction() {
+    let server = MockServer::start();
+    let mock_endpoints = setup_mock_server_endpoints(&server);
+
+    let configuration = common::build_configuration(
+        &server,
+        vec![
+            "--users",
+            "2",
+            "--hatch-rate",
+            "4",
+            "--run-time",
+            "2",
+            "--no-reset-metrics",
+        ],
+    );
+    let goose_attack = common::build_load_test(
+        configuration,
+        &taskset!("LoadTest")
+            .register_task(task!(post_comment))
+            .register_task(task!(view_profile))
+            .register_task(task!(get_empty)),
+        None,
+        None,
+    );
+    let goose_metrics = common::run_load_test(goose_attack, None).await;
+
+    let metrics = |method: &str, path: &str| {
+        goose_metrics
+            .requests
+            .get(&format!("{} {}", method, path))
+            .unwrap()
+    };
+
+    // Later requests included the extracted values, so they all matched.
+    for (key, method, path) in &[
+        (FORM_KEY, "GET", FORM_PATH),
+        (COMMENT_KEY, "POST", COMMENT_PATH),
+        (USER_KEY, "GET", USER_PATH),
+        (PROFILE_KEY, "GET", PROFILE_PATH),
+    ] {
+        assert!(mock_endpoints[*key].hits() > 0);
+        mock_endpoints[*key].assert_hits(metrics(method, path).success_count);
+        assert_eq!(metrics(method, path).fail_count, 0);
+    }
+
+    // Responses missing a value are failures.
+    assert!(mock_endpoints[EMPTY_KEY].hits() > 0);
+    mock_endpoints[EMPTY_KEY].assert_hits(metrics("GET", EMPTY_PATH).fail_count);
+    assert_eq!(goose_metrics.errors.len(), 1);
+    let error_metric = goose_metrics.errors.values().next().unwrap();
+    assert_eq!(
+        error_metric.error,
+        r"nid not found by regex /node/(\d+)/: /empty"
+    );
+    assert_eq!(
+        error_metric.occurrences,
+        metrics("GET", EMPTY_PATH).fail_count
+    );
+}
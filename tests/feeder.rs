@@ -0,0 +1,154 @@
+use httpmock::{Method::GET, Mock, MockServer};
+use std::time::Duration;
+
+mod common;
+
+use goose::feeder::{GooseFeeder, GooseFeederExhaustion, GooseFeederStrategy};
+use goose::goose::GooseTaskSet;
+use goose::prelude::*;
+
+// Paths used in load tests performed during these tests.
+const PRODUCT_PATHS: [&str; 3] = ["/product/1", "/product/2", "/product/3"];
+const LOGIN_PATHS: [&str; 2] = ["/login/alice", "/login/bob"];
+
+// Test task.
+pub async fn login(user: &mut GooseUser) -> GooseTaskResult {
+    let record = user.get_record("users")?;
+    let username = record.get_str("username").unwrap().to_string();
+    // The same user always gets the same record.
+    assert_eq!(user.get_record("users")?, record);
+    let _goose = user.get(&format!("/login/{}", username)).await?;
+    Ok(())
+}
+
+// Test task.
+pub async fn get_product(user: &mut GooseUser) -> GooseTaskResult {
+    let record = user.get_record("products")?;
+    let id = record.get("id").unwrap().as_u64().unwrap();
+    let _goose = user.get(&format!("/product/{}", id)).await?;
+    Ok(())
+}
+
+// All tests in this file run against common endpoints.
+fn setup_mock_server_endpoints(server: &MockServer) -> Vec<Mock> {
+    PRODUCT_PATHS
+        .iter()
+        .chain(LOGIN_PATHS.iter())
+        .map(|path| {
+            server.mock(|when, then| {
+                when.method(GET).path(*path);
+                then.status(200);
+            })
+        })
+        .collect()
+}
+
+// Returns the appropriate taskset needed to build these tests.
+fn get_tasks() -> GooseTaskSet {
+    taskset!("LoadTest")
+        .set_wait_time(Duration::from_millis(100), Duration::from_millis(100))
+        .unwrap()
+        .register_task(task!(get_product))
+}
+
+#[tokio::test]
+// Distribute records from CSV and JSON lines feeders to users.
+async fn test_feeder() {
+    let users_file = "feeder-users.csv";
+    let products_file = "feeder-products.jsonl";
+    std::fs::write(
+        users_file,
+        "username,password\nalice,secret1\nbob,secret2\n",
+    )
+    .unwrap();
+    std::fs::write(products_file, "{\"id\": 1}\n{\"id\": 2}\n{\"id\": 3}\n").unwrap();
+
+    let server = MockServer::start();
+    let mock_endpoints = setup_mock_server_endpoints(&server);
+
+    let configuration = common::build_configuration(
+        &server,
+        vec!["--users", "2", "--hatch-rate", "4", "--run-time", "2"],
+    );
+    let goose_attack = common::build_load_test(
+        configuration,
+        // Each user logs in with its own record from a feeder registered on the task set.
+        &get_tasks()
+            .register_task(task!(login).set_on_start())
+            .register_feeder(
+                GooseFeeder::from_csv("users", users_file)
+                    .unwrap()
+                    .set_strategy(GooseFeederStrategy::UniquePerUser),
+            ),
+        None,
+        None,
+    )
+    // All users get products in order from a feeder registered on the load test.
+    .register_feeder(GooseFeeder::from_json_lines("products", products_file).unwrap());
+    common::run_load_test(goose_attack, None).await;
+
+    // Each product was requested, as the feeder started over once exhausted.
+    for (index, path) in PRODUCT_PATHS.iter().enumerate() {
+        assert!(
+            mock_endpoints[index].hits() > 1,
+            "{} wasn't requested",
+            path
+        );
+    }
+    // Each user logged in with a different record.
+    for index in 0..LOGIN_PATHS.len() {
+        assert_eq!(mock_endpoints[PRODUCT_PATHS.len() + index].hits(), 1);
+    }
+
+    common::cleanup_files(vec![users_file, products_file]);
+}
+
+#[tokio::test]
+// Stop the load test once a feeder configured to stop runs out of records.
+async fn test_feeder_exhausted() {
+    let products_file = "feeder-exhausted.jsonl";
+    std::fs::write(products_file, "{\"id\": 1}\n{\"id\": 2}\n{\"id\": 3}\n").unwrap();
+
+    let server = MockServer::start();
+    let mock_endpoints = setup_mock_server_endpoints(&server);
+
+    let configuration = common::build_configuration(
+        &server,
+        vec!["--users", "1", "--hatch-rate", "4", "--run-time", "10"],
+    );
+    let goose_attack = common::build_load_test(configuration, &get_tasks(), None, None)
+        .register_feeder(
+            GooseFeeder::from_json_lines("products", products_file)
+                .unwrap()
+                .set_exhaustion(GooseFeederExhaustion::Stop),
+        );
+    let goose_metrics = common::run_load_test(goose_attack, None).await;
+
+    // Each product was requested once, then the load test stopped early.
+    for index in 0..PRODUCT_PATHS.len() {
+        assert_eq!(mock_endpoints[index].hits(), 1);
+    }
+    assert!(goose_metrics.duration < 10);
+
+    common::cleanup_files(vec![products_file]);
+}
+
+#[test]
+// Test that invalid feeder files are rejected.
+fn test_feeder_invalid() {
+    let invalid_file = "feeder-invalid.csv";
+    std::fs::write(invalid_file, "username,password\nalice\n").unwrap();
+
+    for result in vec![
+        GooseFeeder::from_csv("users", "feeder-missing.csv"),
+        GooseFeeder::from_csv("users", invalid_file),
+        GooseFeeder::from_json_lines("users", invalid_file),
+    ] {
+        match result {
+            Err(GooseError::InvalidFeeder { name, .. }) => assert_eq!(name, "users"),
+            _ => panic!("invalid feeder was not rejected"),
+        }
+    }
+
+    common::cleanup_files(vec![invalid_file]);
+}
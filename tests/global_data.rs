@@ -0,0 +1,106 @@
+use httpmock::{
+    Method::{DELETE, GET, POST},
+    Mock, MockServer,
+};
+
+mod common;
+
+use goose::prelude::*;
+
+// Paths used in load tests performed during these tests.
+const LOGIN_PATH: &str = "/login";
+const PRODUCT_PATHS: [&str; 2] = ["/product/1", "/product/2"];
+const LOGOUT_PATH: &str = "/logout";
+
+// The token returned when logging in.
+const TOKEN: &str = "secret-token";
+
+// Global data set by test_start.
+struct AuthToken(String);
+
+// Global data set before the load test starts.
+struct Products(Vec<usize>);
+
+// Test task.
+pub async fn login(user: &mut GooseUser) -> GooseTaskResult {
+    let goose = user.post(LOGIN_PATH, "username=foo&password=bar").await?;
+    let token = goose.response?.text().await?;
+    user.set_global_data(AuthToken(token));
+    Ok(())
+}
+
+// Test task.
+pub async fn get_products(user: &mut GooseUser) -> GooseTaskResult {
+    let token = user.get_global_data::<AuthToken>().unwrap();
+    let products = user.get_global_data::<Products>().unwrap();
+    for id in &products.0 {
+        let request_builder = user.goose_get(&format!("/product/{}", id))?;
+        let _goose = user
+            .goose_send(request_builder.bearer_auth(&token.0), None)
+            .await?;
+    }
+    Ok(())
+}
+
+// Test task.
+pub async fn logout(user: &mut GooseUser) -> GooseTaskResult {
+    let token = user.get_global_data::<AuthToken>().unwrap();
+    let request_builder = user.goose_delete(LOGOUT_PATH)?;
+    let _goose = user
+        .goose_send(request_builder.bearer_auth(&token.0), None)
+        .await?;
+    Ok(())
+}
+
+// All tests in this file run against common endpoints.
+fn setup_mock_server_endpoints(server: &MockServer) -> Vec<Mock> {
+    let authorization = format!("Bearer {}", TOKEN);
+    let mut endpoints = vec![server.mock(|when, then| {
+        when.method(POST).path(LOGIN_PATH);
+        then.status(200).body(TOKEN);
+    })];
+    for path in &PRODUCT_PATHS {
+        endpoints.push(server.mock(|when, then| {
+            when.method(GET)
+                .path(*path)
+                .header("authorization", &authorization);
+            then.status(200);
+        }));
+    }
+    endpoints.push(server.mock(|when, then| {
+        when.method(DELETE)
+            .path(LOGOUT_PATH)
+            .header("authorization", &authorization);
+        then.status(200);
+    }));
+    endpoints
+}
+
+#[tokio::test]
+// Share global data set by test_start and before the load test with all users and test_stop.
+async fn test_global_data() {
+    let server = MockServer::start();
+    let mock_endpoints = setup_mock_server_endpoints(&server);
+
+    let configuration = common::build_configuration(
+        &server,
+        vec!["--users", "2", "--hatch-rate", "4", "--run-time", "2"],
+    );
+    let goose_attack = common::build_load_test(
+        configuration,
+        &taskset!("LoadTest").register_task(task!(get_products)),
+        Some(&task!(login)),
+        Some(&task!(logout)),
+    )
+    .set_global_data(Products(vec![1, 2]));
+    common::run_load_test(goose_attack, None).await;
+
+    // test_start logged in once.
+    assert_eq!(mock_endpoints[0].hits(), 1);
+    // Users requested all products with the token from test_start.
+    for index in 0..PRODUCT_PATHS.len() {
+        assert!(mock_endpoints[1 + index].hits() > 0);
+    }
+    // test_stop logged out with the token from test_start.
+    assert_eq!(mock_endpoints[1 + PRODUCT_PATHS.len()].hits(), 1);
+}
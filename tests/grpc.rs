@@ -0,0 +1,279 @@
+#![cfg(feature = "grpc")]
+
+use futures::stream;
+use httpmock::MockServer;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tonic::body::{empty_body, BoxBody};
+use tonic::codec::ProstCodec;
+use tonic::codegen::{http, Body, BoxFuture, BoxStream, Context, Poll, Service, StdError};
+use tonic::server::{Grpc, ServerStreamingService, UnaryService};
+use tonic::transport::{NamedService, Server};
+use tonic::{Code, Request, Response, Status};
+
+mod common;
+
+use goose::goose::GooseTaskSet;
+use goose::prelude::*;
+
+// Paths of the gRPC methods called during these tests.
+const SAY_PATH: &str = "/test.Echo/Say";
+const COUNT_PATH: &str = "/test.Echo/Count";
+const MISSING_PATH: &str = "/test.Echo/Missing";
+
+// The name the test server doesn't know.
+const UNKNOWN: &str = "unknown";
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SayRequest {
+    #[prost(string, tag = "1")]
+    pub name: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SayReply {
+    #[prost(string, tag = "1")]
+    pub message: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CountRequest {
+    #[prost(uint32, tag = "1")]
+    pub count: u32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CountReply {
+    #[prost(uint32, tag = "1")]
+    pub number: u32,
+}
+
+// Test task.
+pub async fn say(user: &mut GooseUser) -> GooseTaskResult {
+    let goose = user
+        .grpc_unary::<_, SayReply>(
+            SAY_PATH,
+            SayRequest {
+                name: "Goose".to_string(),
+            },
+        )
+        .await?;
+    assert!(goose.request.success);
+    assert_eq!(goose.response.unwrap().into_inner().message, "Hello Goose");
+
+    // A status other than Ok is a failure.
+    let goose = user
+        .grpc_unary::<_, SayReply>(
+            SAY_PATH,
+            SayRequest {
+                name: UNKNOWN.to_string(),
+            },
+        )
+        .await?;
+    assert!(!goose.request.success);
+    assert_eq!(goose.response.unwrap_err().code(), Code::NotFound);
+    Ok(())
+}
+
+// Test task.
+pub async fn count(user: &mut GooseUser) -> GooseTaskResult {
+    let goose = user
+        .grpc_server_streaming::<_, CountReply>(COUNT_PATH, CountRequest { count: 3 })
+        .await?;
+    assert!(goose.request.success);
+    let numbers: Vec<u32> = goose
+        .response
+        .unwrap()
+        .into_inner()
+        .iter()
+        .map(|reply| reply.number)
+        .collect();
+    assert_eq!(numbers, vec![1, 2, 3]);
+    Ok(())
+}
+
+// Test task.
+pub async fn missing(user: &mut GooseUser) -> GooseTaskResult {
+    let goose = user
+        .grpc_unary::<_, SayReply>(
+            MISSING_PATH,
+            SayRequest {
+                name: "Goose".to_string(),
+            },
+        )
+        .await?;
+    assert_eq!(goose.response.unwrap_err().code(), Code::Unimplemented);
+    Ok(())
+}
+
+// A gRPC service implemented without code generation.
+#[derive(Clone)]
+struct EchoServer;
+
+impl NamedService for EchoServer {
+    const NAME: &'static str = "test.Echo";
+}
+
+struct SayService;
+
+impl UnaryService<SayRequest> for SayService {
+    type Response = SayReply;
+    type Future = BoxFuture<Response<SayReply>, Status>;
+
+    fn call(&mut self, request: Request<SayRequest>) -> Self::Future {
+        Box::pin(async move {
+            let name = request.into_inner().name;
+            if name == UNKNOWN {
+                return Err(Status::not_found(name));
+            }
+            Ok(Response::new(SayReply {
+                message: format!("Hello {}", name),
+            }))
+        })
+    }
+}
+
+struct CountService;
+
+impl ServerStreamingService<CountRequest> for CountService {
+    type Response = CountReply;
+    type ResponseStream = BoxStream<CountReply>;
+    type Future = BoxFuture<Response<Self::ResponseStream>, Status>;
+
+    fn call(&mut self, request: Request<CountRequest>) -> Self::Future {
+        Box::pin(async move {
+            let replies = (1..=request.into_inner().count).map(|number| Ok(CountReply { number }));
+            let stream: Self::ResponseStream = Box::pin(stream::iter(replies));
+            Ok(Response::new(stream))
+        })
+    }
+}
+
+impl<B> Service<http::Request<B>> for EchoServer
+where
+    B: Body + Send + Sync + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = std::convert::Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        match request.uri().path() {
+            SAY_PATH => Box::pin(async move {
+                let mut grpc = Grpc::new(ProstCodec::default());
+                Ok(grpc.unary(SayService, request).await)
+            }),
+            COUNT_PATH => Box::pin(async move {
+                let mut grpc = Grpc::new(ProstCodec::default());
+                Ok(grpc.server_streaming(CountService, request).await)
+            }),
+            _ => Box::pin(async move {
+                Ok(http::Response::builder()
+                    .status(200)
+                    .header("grpc-status", "12")
+                    .header("content-type", "application/grpc")
+                    .body(empty_body())
+                    .unwrap())
+            }),
+        }
+    }
+}
+
+// Start the gRPC server.
+async fn start_grpc_server() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let incoming = stream::unfold(listener, |listener| async move {
+        let socket = listener.accept().await.map(|(socket, _)| socket);
+        Some((socket, listener))
+    });
+    tokio::spawn(
+        Server::builder()
+            .add_service(EchoServer)
+            .serve_with_incoming(incoming),
+    );
+    format!("http://{}", address)
+}
+
+// Returns the appropriate taskset needed to build these tests.
+fn get_tasks() -> GooseTaskSet {
+    taskset!("LoadTest")
+        .set_wait_time(Duration::from_millis(100), Duration::from_millis(100))
+        .unwrap()
+        .register_task(task!(say))
+        .register_task(task!(count))
+        .register_task(task!(missing))
+}
+
+#[tokio::test]
+// Make unary and server-streaming gRPC calls, recorded as requests.
+async fn test_grpc() {
+    let server = MockServer::start();
+    let host = start_grpc_server().await;
+
+    let configuration = common::build_configuration(
+        &server,
+        vec![
+            "--host",
+            &host,
+            "--users",
+            "2",
+            "--hatch-rate",
+            "4",
+            "--run-time",
+            "2",
+            "--no-reset-metrics",
+            "--status-codes",
+        ],
+    );
+    let goose_metrics = common::run_load_test(
+        common::build_load_test(configuration, &get_tasks(), None, None),
+        None,
+    )
+    .await;
+
+    // Calls are recorded with the path of the method as their name, and their gRPC status
+    // code instead of an HTTP status code.
+    let say = &goose_metrics.requests["GRPC /test.Echo/Say"];
+    assert!(say.success_count > 0);
+    assert_eq!(say.success_count, say.fail_count);
+    assert!(say.status_code_counts.is_empty());
+    assert_eq!(
+        say.grpc_status_code_counts[&(Code::Ok as u16)],
+        say.success_count
+    );
+    assert_eq!(
+        say.grpc_status_code_counts[&(Code::NotFound as u16)],
+        say.fail_count
+    );
+
+    let count = &goose_metrics.requests["GRPC /test.Echo/Count"];
+    assert!(count.success_count > 0);
+    assert_eq!(count.fail_count, 0);
+
+    let missing = &goose_metrics.requests["GRPC /test.Echo/Missing"];
+    assert!(missing.fail_count > 0);
+    assert_eq!(missing.success_count, 0);
+    assert_eq!(
+        missing.grpc_status_code_counts[&(Code::Unimplemented as u16)],
+        missing.fail_count
+    );
+
+    // gRPC status codes are labelled when displayed.
+    assert!(format!("{}", goose_metrics).contains(&format!(
+        "{} [gRPC {}]",
+        missing.fail_count,
+        Code::Unimplemented as u16
+    )));
+
+    // Failed calls are included in the errors.
+    assert!(goose_metrics
+        .errors
+        .values()
+        .any(|error| error.error == format!("{}: {}", Code::Unimplemented, MISSING_PATH)));
+}
@@ -0,0 +1,196 @@
+use gumdrop::Options;
+use httpmock::{
+    Method::{GET, POST},
+    Mock, MockServer,
+};
+use std::time::Duration;
+
+mod common;
+
+use goose::config::GooseConfiguration;
+use goose::goose::GooseTaskSet;
+use goose::prelude::*;
+
+// Paths used in load tests performed during these tests.
+const LOGIN_PATH: &str = "/login";
+const INDEX_PATH: &str = "/";
+const LOGOUT_PATH: &str = "/logout";
+
+// Indexes to the above paths.
+const LOGIN_KEY: usize = 0;
+const INDEX_KEY: usize = 1;
+const LOGOUT_KEY: usize = 2;
+
+// Ramp up to 4 users, down to 2 users, back up to 4 users, then down to 0 users.
+const RAMP_TEST_PLAN: &str = "4,1s;2,1s;4,1s;0,1s";
+// Launch 4 users, then 2 more, and stop 2. In total 6 users are launched.
+const RAMP_TOTAL_USERS: usize = 6;
+// All users are stopped by the final stage.
+const RAMP_RUNNING_USERS: usize = 0;
+
+// Launch 3 users at once, hold them for 2 seconds, then stop the load test.
+const SPIKE_TEST_PLAN: &str = "3,0;3,2s";
+const SPIKE_TOTAL_USERS: usize = 3;
+const SPIKE_RUNNING_USERS: usize = 3;
+
+// Test task.
+pub async fn login(user: &mut GooseUser) -> GooseTaskResult {
+    let _goose = user.post(LOGIN_PATH, "").await?;
+    Ok(())
+}
+
+// Test task.
+pub async fn get_index(user: &mut GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+// Test task.
+pub async fn logout(user: &mut GooseUser) -> GooseTaskResult {
+    let _goose = user.get(LOGOUT_PATH).await?;
+    Ok(())
+}
+
+// All tests in this file run against common endpoints.
+fn setup_mock_server_endpoints(server: &MockServer) -> Vec<Mock> {
+    vec![
+        // First set up LOGIN_PATH, store in vector at LOGIN_KEY.
+        server.mock(|when, then| {
+            when.method(POST).path(LOGIN_PATH);
+            then.status(200);
+        }),
+        // Next set up INDEX_PATH, store in vector at INDEX_KEY.
+        server.mock(|when, then| {
+            when.method(GET).path(INDEX_PATH);
+            then.status(200);
+        }),
+        // Next set up LOGOUT_PATH, store in vector at LOGOUT_KEY.
+        server.mock(|when, then| {
+            when.method(GET).path(LOGOUT_PATH);
+            then.status(200);
+        }),
+    ]
+}
+
+// Build appropriate configuration for these tests.
+fn common_build_configuration(server: &MockServer, test_plan: &str) -> GooseConfiguration {
+    common::build_configuration(server, vec!["--test-plan", test_plan, "--no-reset-metrics"])
+}
+
+// Helper to confirm all variations generate appropriate results.
+fn validate_test(
+    goose_metrics: &GooseMetrics,
+    mock_endpoints: &[Mock],
+    total_users: usize,
+    running_users: usize,
+) {
+    // Confirm that the on_start and on_stop tasks ran once per launched GooseUser, so
+    // users stopped when ramping down were stopped gracefully.
+    mock_endpoints[LOGIN_KEY].assert_hits(total_users);
+    mock_endpoints[LOGOUT_KEY].assert_hits(total_users);
+
+    // Confirm that the users generated load.
+    assert!(mock_endpoints[INDEX_KEY].hits() > 0);
+
+    // Confirm that Goose tracked how many users were still running when the test ended.
+    assert!(goose_metrics.users == running_users);
+}
+
+// Returns the appropriate taskset needed to build these tests.
+fn get_tasks() -> GooseTaskSet {
+    taskset!("LoadTest")
+        .set_wait_time(Duration::from_millis(100), Duration::from_millis(100))
+        .unwrap()
+        .register_task(task!(login).set_on_start())
+        .register_task(task!(get_index))
+        .register_task(task!(logout).set_on_stop())
+}
+
+#[tokio::test]
+// Test a load profile that ramps the number of users up and down.
+async fn test_load_profile_ramp() {
+    // Start the mock server.
+    let server = MockServer::start();
+
+    // Setup the mock endpoints needed for this test.
+    let mock_endpoints = setup_mock_server_endpoints(&server);
+
+    // Build common configuration.
+    let configuration = common_build_configuration(&server, RAMP_TEST_PLAN);
+
+    // Run the Goose Attack.
+    let goose_metrics = common::run_load_test(
+        common::build_load_test(configuration, &get_tasks(), None, None),
+        None,
+    )
+    .await;
+
+    // Confirm the load test ran correctly.
+    validate_test(
+        &goose_metrics,
+        &mock_endpoints,
+        RAMP_TOTAL_USERS,
+        RAMP_RUNNING_USERS,
+    );
+}
+
+#[tokio::test]
+// Test a load profile configured with GooseDefault::TestPlan that launches all users
+// at once and stops while they're still running.
+async fn test_load_profile_spike_defaults() {
+    // Start the mock server.
+    let server = MockServer::start();
+
+    // Setup the mock endpoints needed for this test.
+    let mock_endpoints = setup_mock_server_endpoints(&server);
+
+    // Only set the host, the load profile is configured with a default.
+    let configuration = GooseConfiguration::parse_args_default(&[
+        "--host",
+        &server.base_url(),
+        "--co-mitigation",
+        "disabled",
+    ])
+    .unwrap();
+
+    // Run the Goose Attack.
+    let goose_metrics = common::run_load_test(
+        *common::build_load_test(configuration, &get_tasks(), None, None)
+            .set_default(GooseDefault::TestPlan, SPIKE_TEST_PLAN)
+            .unwrap()
+            .set_default(GooseDefault::NoResetMetrics, true)
+            .unwrap(),
+        None,
+    )
+    .await;
+
+    // Confirm the load test ran correctly.
+    validate_test(
+        &goose_metrics,
+        &mock_endpoints,
+        SPIKE_TOTAL_USERS,
+        SPIKE_RUNNING_USERS,
+    );
+}
+
+#[test]
+// Test that incompatible options are rejected.
+fn test_load_profile_invalid() {
+    let server = MockServer::start();
+
+    // An invalid load profile can't be parsed.
+    assert!(GooseConfiguration::parse_args_default(&["--test-plan", "10"]).is_err());
+
+    // A load profile can't be combined with options it replaces.
+    for invalid in [
+        vec!["--test-plan", RAMP_TEST_PLAN, "--users", "1"],
+        vec!["--test-plan", RAMP_TEST_PLAN, "--hatch-rate", "1"],
+        vec!["--test-plan", RAMP_TEST_PLAN, "--run-time", "1"],
+        vec!["--test-plan", RAMP_TEST_PLAN, "--startup-time", "1"],
+    ] {
+        let configuration = common::build_configuration(&server, invalid);
+        let goose_attack = common::build_load_test(configuration, &get_tasks(), None, None);
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        assert!(runtime.block_on(goose_attack.execute()).is_err());
+    }
+}
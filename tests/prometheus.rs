@@ -0,0 +1,183 @@
+use httpmock::{Method::GET, Mock, MockServer};
+use std::time::Duration;
+
+mod common;
+
+use goose::goose::GooseTaskSet;
+use goose::prelude::*;
+
+// Paths used in load tests performed during these tests.
+const INDEX_PATH: &str = "/";
+const ERROR_PATH: &str = "/error";
+
+// Indexes to the above paths.
+const INDEX_KEY: usize = 0;
+const ERROR_KEY: usize = 1;
+
+// The Prometheus metrics endpoint used in these tests.
+const PROMETHEUS_PORT: &str = "15118";
+const PROMETHEUS_URL: &str = "http://127.0.0.1:15118";
+
+// Test task.
+pub async fn get_index(user: &mut GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+// Test task.
+pub async fn get_error(user: &mut GooseUser) -> GooseTaskResult {
+    let _goose = user.get(ERROR_PATH).await?;
+    Ok(())
+}
+
+// All tests in this file run against common endpoints.
+fn setup_mock_server_endpoints(server: &MockServer) -> Vec<Mock> {
+    vec![
+        // First set up INDEX_PATH, store in vector at INDEX_KEY.
+        server.mock(|when, then| {
+            when.method(GET).path(INDEX_PATH);
+            then.status(200);
+        }),
+        // Next set up ERROR_PATH, store in vector at ERROR_KEY.
+        server.mock(|when, then| {
+            when.method(GET).path(ERROR_PATH);
+            then.status(503);
+        }),
+    ]
+}
+
+// Returns the appropriate taskset needed to build these tests.
+fn get_tasks() -> GooseTaskSet {
+    taskset!("LoadTest")
+        .set_wait_time(Duration::from_millis(100), Duration::from_millis(100))
+        .unwrap()
+        .register_task(task!(get_index))
+        .register_task(task!(get_error))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+// Scrape the Prometheus metrics endpoint while the load test is running.
+async fn test_prometheus() {
+    // Start the mock server.
+    let server = MockServer::start();
+
+    // Setup the mock endpoints needed for this test.
+    let mock_endpoints = setup_mock_server_endpoints(&server);
+
+    // Build configuration enabling the Prometheus metrics endpoint.
+    let configuration = common::build_configuration(
+        &server,
+        vec![
+            "--users",
+            "2",
+            "--hatch-rate",
+            "4",
+            "--run-time",
+            "3",
+            "--prometheus",
+            "--prometheus-host",
+            "127.0.0.1",
+            "--prometheus-port",
+            PROMETHEUS_PORT,
+        ],
+    );
+
+    // Scrape the endpoint from another thread after the load test starts running.
+    let scrape_handle = tokio::spawn(async {
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        let client = reqwest::Client::new();
+
+        let metrics = client
+            .get(format!("{}/metrics", PROMETHEUS_URL))
+            .send()
+            .await
+            .unwrap();
+        assert!(metrics.status() == 200);
+        assert!(metrics.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("text/plain; version=0.0.4"));
+        let metrics = metrics.text().await.unwrap();
+
+        // Other paths are not found.
+        let not_found = client.get(PROMETHEUS_URL).send().await.unwrap();
+        assert!(not_found.status() == 404);
+
+        metrics
+    });
+
+    // Run the Goose Attack.
+    let goose_metrics = common::run_load_test(
+        common::build_load_test(configuration, &get_tasks(), None, None),
+        None,
+    )
+    .await;
+
+    // Confirm the load test ran.
+    assert!(mock_endpoints[INDEX_KEY].hits() > 0);
+    assert!(mock_endpoints[ERROR_KEY].hits() > 0);
+    assert!(goose_metrics.users == 2);
+
+    let metrics = scrape_handle.await.unwrap();
+    let lines: Vec<&str> = metrics.lines().collect();
+
+    // The load test was running with all users when it was scraped.
+    assert!(lines.contains(&r#"goose_attack_phase{phase="running"} 1"#));
+    assert!(lines.contains(&r#"goose_attack_phase{phase="idle"} 0"#));
+    assert!(lines.contains(&"goose_users 2"));
+
+    // Requests were counted, and status codes are tracked even without --status-codes.
+    let sample = |prefix: &str| -> usize {
+        lines
+            .iter()
+            .find_map(|line| line.strip_prefix(prefix))
+            .unwrap()
+            .trim()
+            .parse()
+            .unwrap()
+    };
+    let index_requests = sample(r#"goose_requests_total{method="GET",name="/"}"#);
+    assert!(index_requests > 0);
+    assert!(sample(r#"goose_request_failures_total{method="GET",name="/"}"#) == 0);
+    assert!(
+        sample(r#"goose_request_status_codes_total{method="GET",name="/",status_code="200"}"#)
+            == index_requests
+    );
+    let error_requests = sample(r#"goose_requests_total{method="GET",name="/error"}"#);
+    assert!(
+        sample(r#"goose_request_failures_total{method="GET",name="/error"}"#) == error_requests
+    );
+    assert!(
+        sample(r#"goose_request_status_codes_total{method="GET",name="/error",status_code="503"}"#)
+            == error_requests
+    );
+
+    // Every response time is in the histogram.
+    assert!(
+        sample(r#"goose_response_time_seconds_bucket{method="GET",name="/",le="+Inf"}"#)
+            == index_requests
+    );
+    assert!(
+        sample(r#"goose_response_time_seconds_count{method="GET",name="/"}"#) == index_requests
+    );
+
+    // Tasks were counted.
+    assert!(lines
+        .iter()
+        .any(|line| line.starts_with(r#"goose_tasks_total{index="1.1",taskset="LoadTest""#)));
+    assert!(lines.iter().any(
+        |line| line.starts_with(r#"goose_task_failures_total{index="1.2",taskset="LoadTest""#)
+    ));
+}
+
+#[test]
+// Test that invalid configurations are rejected.
+fn test_prometheus_invalid() {
+    let server = MockServer::start();
+
+    // Metrics can't be exported if they're disabled.
+    let configuration = common::build_configuration(&server, vec!["--prometheus", "--no-metrics"]);
+    let goose_attack = common::build_load_test(configuration, &get_tasks(), None, None);
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    assert!(runtime.block_on(goose_attack.execute()).is_err());
+}
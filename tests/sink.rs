@@ -0,0 +1,362 @@
+use async_trait::async_trait;
+use httpmock::{
+    Method::{GET, POST},
+    Mock, MockServer,
+};
+use std::net::UdpSocket;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+mod common;
+
+use goose::goose::GooseTaskSet;
+use goose::metrics::{GooseRequestMetric, GooseTaskMetric};
+use goose::prelude::*;
+use goose::sink::GooseMetricsSink;
+
+// Paths used in load tests performed during these tests.
+const INDEX_PATH: &str = "/";
+const ERROR_PATH: &str = "/error";
+const WRITE_PATH: &str = "/write";
+
+// Indexes to the above paths.
+const INDEX_KEY: usize = 0;
+const ERROR_KEY: usize = 1;
+const WRITE_KEY: usize = 2;
+
+// Test task.
+pub async fn get_index(user: &mut GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+// Test task.
+pub async fn get_error(user: &mut GooseUser) -> GooseTaskResult {
+    let _goose = user.get(ERROR_PATH).await?;
+    Ok(())
+}
+
+// All tests in this file run against common endpoints.
+fn setup_mock_server_endpoints(server: &MockServer) -> Vec<Mock> {
+    vec![
+        // First set up INDEX_PATH, store in vector at INDEX_KEY.
+        server.mock(|when, then| {
+            when.method(GET).path(INDEX_PATH);
+            then.status(200);
+        }),
+        // Next set up ERROR_PATH, store in vector at ERROR_KEY.
+        server.mock(|when, then| {
+            when.method(GET).path(ERROR_PATH);
+            then.status(503);
+        }),
+        // Last set up WRITE_PATH to accept InfluxDB writes, store in vector at WRITE_KEY.
+        server.mock(|when, then| {
+            when.method(POST)
+                .path(WRITE_PATH)
+                .body_contains("goose_requests,method=GET,name=/,status_code=200,env=test");
+            then.status(204);
+        }),
+    ]
+}
+
+// Returns the appropriate taskset needed to build these tests.
+fn get_tasks() -> GooseTaskSet {
+    taskset!("LoadTest")
+        .set_wait_time(Duration::from_millis(100), Duration::from_millis(100))
+        .unwrap()
+        .register_task(task!(get_index))
+        .register_task(task!(get_error))
+}
+
+// Bind a local UDP listener, standing in for StatsD or InfluxDB.
+fn bind_listener() -> (UdpSocket, String) {
+    let listener = UdpSocket::bind("127.0.0.1:0").unwrap();
+    listener
+        .set_read_timeout(Some(Duration::from_millis(500)))
+        .unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    (listener, address)
+}
+
+// Read all lines received by a local UDP listener.
+fn received_lines(listener: &UdpSocket) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut buffer = [0; 65536];
+    while let Ok(length) = listener.recv(&mut buffer) {
+        let datagram = String::from_utf8_lossy(&buffer[..length]).to_string();
+        lines.extend(datagram.lines().map(|line| line.to_string()));
+    }
+    lines
+}
+
+// Sum the counter with this prefix from StatsD lines, ie `goose.requests:3|c|#...`.
+fn statsd_counter(lines: &[String], name: &str, tags: &str) -> i64 {
+    lines
+        .iter()
+        .filter(|line| line.ends_with(tags))
+        .filter_map(|line| line.strip_prefix(&format!("{}:", name)))
+        .filter_map(|line| line.split_once("|c|"))
+        .map(|(value, _)| value.parse::<i64>().unwrap())
+        .sum()
+}
+
+// Sum an integer field from InfluxDB lines with this prefix.
+fn influxdb_field(lines: &[String], prefix: &str, field: &str) -> i64 {
+    lines
+        .iter()
+        .filter(|line| line.starts_with(prefix))
+        .filter_map(|line| {
+            line.split(' ')
+                .nth(1)
+                .unwrap()
+                .split(',')
+                .find_map(|value| value.strip_prefix(&format!("{}=", field)))
+                .map(|value| value.trim_end_matches('i').parse::<i64>().unwrap())
+        })
+        .sum()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+// Send metrics to a StatsD listener.
+async fn test_statsd() {
+    let server = MockServer::start();
+    let mock_endpoints = setup_mock_server_endpoints(&server);
+    let (listener, address) = bind_listener();
+
+    let configuration = common::build_configuration(
+        &server,
+        vec![
+            "--users",
+            "2",
+            "--hatch-rate",
+            "4",
+            "--run-time",
+            "2",
+            "--statsd",
+            &address,
+            "--sink-tags",
+            "env=test",
+        ],
+    );
+    common::run_load_test(
+        common::build_load_test(configuration, &get_tasks(), None, None),
+        None,
+    )
+    .await;
+
+    let lines = received_lines(&listener);
+
+    // Every request was counted, including requests made before metrics were reset.
+    let index_tags = "|#method:GET,name:/,status_code:200,env:test";
+    let error_tags = "|#method:GET,name:/error,status_code:503,env:test";
+    let index_hits = mock_endpoints[INDEX_KEY].hits() as i64;
+    let error_hits = mock_endpoints[ERROR_KEY].hits() as i64;
+    assert!(index_hits > 0);
+    assert_eq!(
+        statsd_counter(&lines, "goose.requests", index_tags),
+        index_hits
+    );
+    assert_eq!(
+        statsd_counter(&lines, "goose.request_failures", index_tags),
+        0
+    );
+    assert_eq!(
+        statsd_counter(&lines, "goose.requests", error_tags),
+        error_hits
+    );
+    assert_eq!(
+        statsd_counter(&lines, "goose.request_failures", error_tags),
+        error_hits
+    );
+
+    // Every response time was sent as a timing.
+    let timings = lines
+        .iter()
+        .filter(|line| line.starts_with("goose.response_time:") && line.ends_with(index_tags))
+        .count() as i64;
+    assert_eq!(timings, index_hits);
+
+    // Tasks were counted.
+    assert!(
+        statsd_counter(
+            &lines,
+            "goose.tasks",
+            "|#index:1.1,taskset:LoadTest,env:test"
+        ) > 0
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+// Send metrics to an InfluxDB UDP listener.
+async fn test_influxdb_udp() {
+    let server = MockServer::start();
+    let mock_endpoints = setup_mock_server_endpoints(&server);
+    let (listener, address) = bind_listener();
+    let url = format!("udp://{}", address);
+
+    let configuration = common::build_configuration(
+        &server,
+        vec![
+            "--users",
+            "2",
+            "--hatch-rate",
+            "4",
+            "--run-time",
+            "2",
+            "--influxdb",
+            &url,
+            "--sink-tags",
+            "env=test",
+        ],
+    );
+    common::run_load_test(
+        common::build_load_test(configuration, &get_tasks(), None, None),
+        None,
+    )
+    .await;
+
+    let lines = received_lines(&listener);
+
+    let index_prefix = "goose_requests,method=GET,name=/,status_code=200,env=test ";
+    let error_prefix = "goose_requests,method=GET,name=/error,status_code=503,env=test ";
+    let index_hits = mock_endpoints[INDEX_KEY].hits() as i64;
+    let error_hits = mock_endpoints[ERROR_KEY].hits() as i64;
+    assert!(index_hits > 0);
+    assert_eq!(influxdb_field(&lines, index_prefix, "count"), index_hits);
+    assert_eq!(influxdb_field(&lines, index_prefix, "failures"), 0);
+    assert_eq!(influxdb_field(&lines, error_prefix, "count"), error_hits);
+    assert_eq!(influxdb_field(&lines, error_prefix, "failures"), error_hits);
+
+    // Points include response times and a timestamp.
+    let point = lines
+        .iter()
+        .find(|line| line.starts_with(index_prefix))
+        .unwrap();
+    assert!(point.contains(",response_time_mean="));
+    assert!(point.rsplit(' ').next().unwrap().parse::<u128>().is_ok());
+
+    // Tasks were counted.
+    assert!(
+        influxdb_field(
+            &lines,
+            "goose_tasks,index=1.2,taskset=LoadTest,env=test ",
+            "count"
+        ) > 0
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+// Send metrics to InfluxDB over HTTP.
+async fn test_influxdb_http() {
+    let server = MockServer::start();
+    let mock_endpoints = setup_mock_server_endpoints(&server);
+    let url = server.url(format!("{}?db=goose", WRITE_PATH));
+
+    let configuration = common::build_configuration(
+        &server,
+        vec![
+            "--users",
+            "2",
+            "--hatch-rate",
+            "4",
+            "--run-time",
+            "2",
+            "--influxdb",
+            &url,
+            "--sink-tags",
+            "env=test",
+        ],
+    );
+    common::run_load_test(
+        common::build_load_test(configuration, &get_tasks(), None, None),
+        None,
+    )
+    .await;
+
+    assert!(mock_endpoints[INDEX_KEY].hits() > 0);
+    assert!(mock_endpoints[WRITE_KEY].hits() > 0);
+}
+
+// A custom sink that counts what it receives.
+struct CountingSink {
+    requests: Arc<AtomicUsize>,
+    tasks: Arc<AtomicUsize>,
+    flushes: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl GooseMetricsSink for CountingSink {
+    fn record_request(&mut self, _request: &GooseRequestMetric) {
+        self.requests.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn record_task(&mut self, _task: &GooseTaskMetric, taskset: &str) {
+        assert_eq!(taskset, "LoadTest");
+        self.tasks.fetch_add(1, Ordering::SeqCst);
+    }
+
+    async fn flush(&mut self) -> Result<(), GooseError> {
+        self.flushes.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+// Register a custom metrics sink.
+async fn test_custom_sink() {
+    let server = MockServer::start();
+    let mock_endpoints = setup_mock_server_endpoints(&server);
+
+    let requests = Arc::new(AtomicUsize::new(0));
+    let tasks = Arc::new(AtomicUsize::new(0));
+    let flushes = Arc::new(AtomicUsize::new(0));
+    let sink = CountingSink {
+        requests: requests.clone(),
+        tasks: tasks.clone(),
+        flushes: flushes.clone(),
+    };
+
+    let configuration = common::build_configuration(
+        &server,
+        vec!["--users", "2", "--hatch-rate", "4", "--run-time", "2"],
+    );
+    common::run_load_test(
+        common::build_load_test(configuration, &get_tasks(), None, None)
+            .register_metrics_sink(sink),
+        None,
+    )
+    .await;
+
+    // The sink received every request and task, and was flushed at least once.
+    let hits = mock_endpoints[INDEX_KEY].hits() + mock_endpoints[ERROR_KEY].hits();
+    assert_eq!(requests.load(Ordering::SeqCst), hits);
+    assert_eq!(tasks.load(Ordering::SeqCst), hits);
+    assert!(flushes.load(Ordering::SeqCst) > 0);
+}
+
+#[test]
+// Test that invalid configurations are rejected.
+fn test_sink_invalid() {
+    let server = MockServer::start();
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+
+    for options in vec![
+        // StatsD requires a port.
+        vec!["--statsd", "127.0.0.1"],
+        // InfluxDB requires a supported scheme.
+        vec!["--influxdb", "tcp://127.0.0.1:8089"],
+        // InfluxDB over UDP requires a port.
+        vec!["--influxdb", "udp://127.0.0.1"],
+        // Tags must be key=value pairs.
+        vec!["--statsd", "127.0.0.1:8125", "--sink-tags", "env"],
+        // The flush interval can't be 0.
+        vec!["--statsd", "127.0.0.1:8125", "--sink-flush-interval", "0"],
+        // Metrics can't be sent if they're disabled.
+        vec!["--statsd", "127.0.0.1:8125", "--no-metrics"],
+    ] {
+        let configuration = common::build_configuration(&server, options);
+        let goose_attack = common::build_load_test(configuration, &get_tasks(), None, None);
+        assert!(runtime.block_on(goose_attack.execute()).is_err());
+    }
+}
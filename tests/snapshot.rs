@@ -0,0 +1,247 @@
+use httpmock::{Method::GET, Mock, MockServer};
+use serial_test::serial;
+
+mod common;
+
+use goose::config::GooseConfiguration;
+use goose::goose::GooseTaskSet;
+use goose::prelude::*;
+
+// Paths used in load tests performed during these tests.
+const INDEX_PATH: &str = "/";
+const ERROR_PATH: &str = "/error";
+
+// Indexes to the above paths.
+const INDEX_KEY: usize = 0;
+const ERROR_KEY: usize = 1;
+
+// Request keys used in the snapshots.
+const INDEX_REQUEST: &str = "GET /";
+const ERROR_REQUEST: &str = "GET /error";
+
+// Number of seconds to run the load test, recording a snapshot every second.
+const RUN_TIME: usize = 3;
+
+// Number of users to simulate.
+const USERS: usize = 2;
+
+// Number of Workers to launch in Gaggle tests.
+const EXPECT_WORKERS: usize = 2;
+
+// Test task.
+pub async fn get_index(user: &mut GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+// Test task.
+pub async fn get_error(user: &mut GooseUser) -> GooseTaskResult {
+    let _goose = user.get(ERROR_PATH).await?;
+    Ok(())
+}
+
+// All tests in this file run against common endpoints.
+fn setup_mock_server_endpoints(server: &MockServer) -> Vec<Mock> {
+    vec![
+        // First set up INDEX_PATH, store in vector at INDEX_KEY.
+        server.mock(|when, then| {
+            when.method(GET).path(INDEX_PATH);
+            then.status(200);
+        }),
+        // Next set up ERROR_PATH, store in vector at ERROR_KEY.
+        server.mock(|when, then| {
+            when.method(GET).path(ERROR_PATH);
+            then.status(503);
+        }),
+    ]
+}
+
+// Build appropriate configuration for these tests.
+fn common_build_configuration(server: &MockServer, custom: &mut Vec<&str>) -> GooseConfiguration {
+    // Common elements in all our tests, launching all USERS at once and running for
+    // RUN_TIME seconds.
+    let mut configuration = vec![
+        "--users",
+        "2",
+        "--hatch-rate",
+        "2",
+        "--run-time",
+        "3",
+        "--snapshot-interval",
+        "1",
+        "--no-reset-metrics",
+    ];
+
+    // Custom elements in some tests.
+    configuration.append(custom);
+
+    // Return the resulting configuration.
+    common::build_configuration(server, configuration)
+}
+
+// Helper to confirm all variations generate appropriate results.
+fn validate_snapshots(goose_metrics: &GooseMetrics, mock_endpoints: &[Mock]) {
+    // A snapshot was recorded each second, plus a final partial snapshot.
+    assert!(goose_metrics.snapshots.len() >= RUN_TIME);
+
+    for (key, request) in [(INDEX_KEY, INDEX_REQUEST), (ERROR_KEY, ERROR_REQUEST)] {
+        // Together the snapshots include every request that was made.
+        let requests: f32 = goose_metrics
+            .snapshots
+            .iter()
+            .filter_map(|snapshot| {
+                snapshot
+                    .requests
+                    .get(request)
+                    .map(|request| request.requests_per_second * snapshot.interval)
+            })
+            .sum();
+        assert!(requests.round() as usize == mock_endpoints[key].hits());
+    }
+
+    // The aggregated metrics include all requests.
+    let requests: f32 = goose_metrics
+        .snapshots
+        .iter()
+        .map(|snapshot| snapshot.aggregated.requests_per_second * snapshot.interval)
+        .sum();
+    assert!(
+        requests.round() as usize
+            == mock_endpoints[INDEX_KEY].hits() + mock_endpoints[ERROR_KEY].hits()
+    );
+
+    for snapshot in &goose_metrics.snapshots {
+        assert!(snapshot.users <= USERS);
+        if let Some(index) = snapshot.requests.get(INDEX_REQUEST) {
+            // Requests to the index never fail.
+            assert!(index.failures_per_second == 0.0);
+            // A response time is recorded for each of the default percentiles.
+            assert!(index.response_time_percentiles.len() == 6);
+        }
+        if let Some(error) = snapshot.requests.get(ERROR_REQUEST) {
+            // Requests to the error page always fail.
+            assert!(error.failures_per_second == error.requests_per_second);
+        }
+    }
+
+    // Snapshots are included when serializing metrics, for example with `metricsjson`.
+    let json = serde_json::to_value(goose_metrics).unwrap();
+    assert!(json["snapshots"].as_array().unwrap().len() == goose_metrics.snapshots.len());
+}
+
+// Returns the appropriate taskset needed to build these tests.
+fn get_tasks() -> GooseTaskSet {
+    taskset!("LoadTest")
+        .register_task(task!(get_index))
+        .register_task(task!(get_error))
+}
+
+#[tokio::test]
+// Record snapshots every second.
+async fn test_snapshots() {
+    // Start the mock server.
+    let server = MockServer::start();
+
+    // Setup the mock endpoints needed for this test.
+    let mock_endpoints = setup_mock_server_endpoints(&server);
+
+    // Build common configuration.
+    let configuration = common_build_configuration(&server, &mut vec![]);
+
+    // Run the Goose Attack.
+    let goose_metrics = common::run_load_test(
+        common::build_load_test(configuration, &get_tasks(), None, None),
+        None,
+    )
+    .await;
+
+    // Confirm the load test recorded snapshots correctly.
+    validate_snapshots(&goose_metrics, &mock_endpoints);
+}
+
+#[tokio::test]
+// The html report includes charts, recording snapshots even if not configured.
+async fn test_snapshots_report() {
+    const REPORT_FILE: &str = "snapshots-report.html";
+
+    // Start the mock server.
+    let server = MockServer::start();
+
+    // Setup the mock endpoints needed for this test.
+    let mock_endpoints = setup_mock_server_endpoints(&server);
+
+    // Build configuration with a report file, without configuring snapshots.
+    let configuration = common::build_configuration(&server, vec!["--report-file", REPORT_FILE]);
+
+    // Run the Goose Attack.
+    let goose_metrics = common::run_load_test(
+        common::build_load_test(configuration, &get_tasks(), None, None),
+        None,
+    )
+    .await;
+
+    // Confirm the load test ran.
+    assert!(mock_endpoints[INDEX_KEY].hits() > 0);
+
+    // A final snapshot was recorded for the charts.
+    assert!(!goose_metrics.snapshots.is_empty());
+    let report = std::fs::read_to_string(REPORT_FILE).unwrap();
+    assert!(report.matches(r#"<svg class="chart""#).count() == 4);
+
+    // Cleanup from test.
+    common::cleanup_files(vec![REPORT_FILE]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+#[cfg_attr(not(feature = "gaggle"), ignore)]
+#[serial]
+// Record snapshots every second on the Manager, in Gaggle mode.
+async fn test_snapshots_gaggle() {
+    // Start the mock server.
+    let server = MockServer::start();
+
+    // Setup the mock endpoints needed for this test.
+    let mock_endpoints = setup_mock_server_endpoints(&server);
+
+    // Each worker has the same identical configuration.
+    let worker_configuration = common::build_configuration(&server, vec!["--worker"]);
+
+    // Workers launched in own threads, store thread handles.
+    let worker_handles = common::launch_gaggle_workers(EXPECT_WORKERS, || {
+        common::build_load_test(worker_configuration.clone(), &get_tasks(), None, None)
+    });
+
+    // Build common configuration elements, adding Manager Gaggle flags.
+    let manager_configuration = common_build_configuration(
+        &server,
+        &mut vec!["--manager", "--expect-workers", &EXPECT_WORKERS.to_string()],
+    );
+
+    // Run the Goose Attack.
+    let goose_metrics = common::run_load_test(
+        common::build_load_test(manager_configuration, &get_tasks(), None, None),
+        Some(worker_handles),
+    )
+    .await;
+
+    // Confirm the load test recorded snapshots correctly.
+    validate_snapshots(&goose_metrics, &mock_endpoints);
+}
+
+#[test]
+// Test that invalid configurations are rejected.
+fn test_snapshots_invalid() {
+    let server = MockServer::start();
+
+    for invalid in [
+        // The snapshot interval must be greater than 0.
+        vec!["--snapshot-interval", "0"],
+        // Snapshots require metrics.
+        vec!["--snapshot-interval", "1", "--no-metrics"],
+    ] {
+        let configuration = common::build_configuration(&server, invalid);
+        let goose_attack = common::build_load_test(configuration, &get_tasks(), None, None);
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        assert!(runtime.block_on(goose_attack.execute()).is_err());
+    }
+}
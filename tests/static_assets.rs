@@ -0,0 +1,145 @@
+use httpmock::{Method::GET, Mock, MockServer};
+
+mod common;
+
+use goose::prelude::*;
+use goose::static_assets::GooseStaticAssets;
+
+// Paths used in load tests performed during these tests.
+const INDEX_PATH: &str = "/";
+const CSS_PATH: &str = "/style.css";
+const JS_PATH: &str = "/app.js";
+const IMAGE_PATH: &str = "/logo.png";
+const MISSING_PATH: &str = "/missing.png";
+
+// Indexes to the above paths.
+const INDEX_KEY: usize = 0;
+const CSS_KEY: usize = 1;
+const JS_KEY: usize = 2;
+const IMAGE_KEY: usize = 3;
+const IMAGE_NOT_MODIFIED_KEY: usize = 4;
+const MISSING_KEY: usize = 5;
+
+// The request name assets are recorded under.
+const ASSET_NAME: &str = "asset";
+
+// The page, referencing each asset twice and an asset on another host.
+const INDEX_HTML: &str = r#"<html><head>
+    <title>Goose</title>
+    <link rel="stylesheet" href="/style.css"><link rel="stylesheet" href="style.css">
+    <script src="/app.js"></script>
+</head><body>
+    <img src="/logo.png"><img src="/logo.png"><img src="/missing.png">
+    <img src="http://other.invalid/logo.png">
+</body></html>"#;
+
+// Test task.
+pub async fn enable_static_assets(user: &mut GooseUser) -> GooseTaskResult {
+    user.set_static_assets(Some(
+        GooseStaticAssets::new()
+            .parallelism(2)
+            .request_name(ASSET_NAME),
+    ));
+    Ok(())
+}
+
+// Test task.
+pub async fn get_index(user: &mut GooseUser) -> GooseTaskResult {
+    let goose = user.get(INDEX_PATH).await?;
+    // The body can still be read after the static assets were loaded.
+    let html = goose.response?.text().await?;
+    assert_eq!(html, INDEX_HTML);
+    Ok(())
+}
+
+// All tests in this file run against common endpoints.
+fn setup_mock_server_endpoints(server: &MockServer) -> Vec<Mock> {
+    vec![
+        server.mock(|when, then| {
+            when.method(GET).path(INDEX_PATH);
+            then.status(200)
+                .header("content-type", "text/html; charset=utf-8")
+                .body(INDEX_HTML);
+        }),
+        server.mock(|when, then| {
+            when.method(GET).path(CSS_PATH);
+            then.status(200).body("body {}");
+        }),
+        server.mock(|when, then| {
+            when.method(GET).path(JS_PATH);
+            then.status(200).body("app();");
+        }),
+        // The image is cached by its ETag, and then revalidated.
+        server.mock(|when, then| {
+            when.method(GET)
+                .path(IMAGE_PATH)
+                // Only matches requests that aren't revalidating.
+                .matches(|request| {
+                    !request
+                        .headers
+                        .iter()
+                        .flatten()
+                        .any(|(name, _)| name == "if-none-match")
+                });
+            then.status(200).header("etag", "\"v1\"").body("png");
+        }),
+        server.mock(|when, then| {
+            when.method(GET)
+                .path(IMAGE_PATH)
+                .header("if-none-match", "\"v1\"");
+            then.status(304);
+        }),
+        server.mock(|when, then| {
+            when.method(GET).path(MISSING_PATH);
+            then.status(404);
+        }),
+    ]
+}
+
+#[tokio::test]
+// Load the static assets of HTML pages like a browser.
+async fn test_static_assets() {
+    let server = MockServer::start();
+    let mock_endpoints = setup_mock_server_endpoints(&server);
+
+    let configuration = common::build_configuration(
+        &server,
+        vec![
+            "--users",
+            "2",
+            "--hatch-rate",
+            "4",
+            "--run-time",
+            "2",
+            "--no-reset-metrics",
+        ],
+    );
+    let goose_attack = common::build_load_test(
+        configuration,
+        &taskset!("LoadTest")
+            .register_task(task!(enable_static_assets).set_on_start())
+            .register_task(task!(get_index)),
+        None,
+        None,
+    );
+    let goose_metrics = common::run_load_test(goose_attack, None).await;
+
+    let index_hits = mock_endpoints[INDEX_KEY].hits();
+    assert!(index_hits > 0);
+
+    // Each asset is loaded once per page, and the image is loaded once per user before
+    // it is revalidated.
+    mock_endpoints[CSS_KEY].assert_hits(index_hits);
+    mock_endpoints[JS_KEY].assert_hits(index_hits);
+    mock_endpoints[MISSING_KEY].assert_hits(index_hits);
+    mock_endpoints[IMAGE_KEY].assert_hits(2);
+    mock_endpoints[IMAGE_NOT_MODIFIED_KEY].assert_hits(index_hits - 2);
+
+    // All assets are recorded under one request name, a 304 is a success.
+    let asset_metrics = goose_metrics
+        .requests
+        .get(&format!("GET {}", ASSET_NAME))
+        .unwrap();
+    assert_eq!(asset_metrics.success_count, index_hits * 3);
+    assert_eq!(asset_metrics.fail_count, index_hits);
+}
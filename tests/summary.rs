@@ -0,0 +1,260 @@
+use httpmock::{Method::GET, Mock, MockServer};
+use std::time::Duration;
+
+mod common;
+
+use goose::goose::GooseTaskSet;
+use goose::prelude::*;
+use goose::summary::{GooseSummary, SUMMARY_SCHEMA_VERSION};
+
+// Paths used in load tests performed during these tests.
+const INDEX_PATH: &str = "/";
+const ERROR_PATH: &str = "/error";
+
+// Indexes to the above paths.
+const INDEX_KEY: usize = 0;
+const ERROR_KEY: usize = 1;
+
+// Test task.
+pub async fn get_index(user: &mut GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+// Test task.
+pub async fn get_error(user: &mut GooseUser) -> GooseTaskResult {
+    let _goose = user.get(ERROR_PATH).await?;
+    Ok(())
+}
+
+// All tests in this file run against common endpoints.
+fn setup_mock_server_endpoints(server: &MockServer) -> Vec<Mock> {
+    vec![
+        // First set up INDEX_PATH, store in vector at INDEX_KEY.
+        server.mock(|when, then| {
+            when.method(GET).path(INDEX_PATH);
+            then.status(200);
+        }),
+        // Next set up ERROR_PATH, store in vector at ERROR_KEY.
+        server.mock(|when, then| {
+            when.method(GET).path(ERROR_PATH);
+            then.status(503);
+        }),
+    ]
+}
+
+// Returns the appropriate taskset needed to build these tests.
+fn get_tasks() -> GooseTaskSet {
+    taskset!("LoadTest")
+        .set_wait_time(Duration::from_millis(100), Duration::from_millis(100))
+        .unwrap()
+        .register_task(task!(get_index))
+        .register_task(task!(get_error))
+}
+
+#[tokio::test]
+// Write JSON and CSV summaries of the load test.
+async fn test_summary() {
+    let json_file = "summary-test.json";
+    let csv_file = "summary-test.csv";
+
+    let server = MockServer::start();
+    let mock_endpoints = setup_mock_server_endpoints(&server);
+
+    let configuration = common::build_configuration(
+        &server,
+        vec![
+            "--users",
+            "2",
+            "--hatch-rate",
+            "4",
+            "--run-time",
+            "2",
+            "--no-reset-metrics",
+            "--status-codes",
+            "--percentiles",
+            "50,95",
+            "--threshold",
+            "GET /:p95<5s",
+            "--summary-json",
+            json_file,
+            "--summary-csv",
+            csv_file,
+        ],
+    );
+    common::run_load_test(
+        common::build_load_test(configuration, &get_tasks(), None, None),
+        None,
+    )
+    .await;
+
+    let index_hits = mock_endpoints[INDEX_KEY].hits();
+    let error_hits = mock_endpoints[ERROR_KEY].hits();
+    assert!(index_hits > 0);
+    assert!(error_hits > 0);
+
+    // The JSON summary can be loaded back into a GooseSummary.
+    let summary: GooseSummary =
+        serde_json::from_str(&std::fs::read_to_string(json_file).unwrap()).unwrap();
+    assert_eq!(summary.schema_version, SUMMARY_SCHEMA_VERSION);
+    assert!(summary.starting.is_some());
+    assert!(summary.stopped.is_some());
+    assert_eq!(summary.hosts, vec![server.url("/")]);
+    // The 99th percentile is always included, for comparing against a baseline.
+    assert_eq!(summary.percentiles, vec![50.0, 95.0, 99.0]);
+    assert_eq!(summary.configuration["users"], 2);
+
+    // Requests are sorted by name, and include status codes and percentiles.
+    let names: Vec<&str> = summary
+        .requests
+        .iter()
+        .map(|request| request.name.as_str())
+        .collect();
+    assert_eq!(names, vec![INDEX_PATH, ERROR_PATH]);
+    let index = &summary.requests[0];
+    assert_eq!(index.requests, index_hits);
+    assert_eq!(index.failures, 0);
+    assert_eq!(index.status_codes[&200], index_hits);
+    assert!(index.response_times.percentiles.contains_key("p95"));
+    let error = &summary.requests[1];
+    assert_eq!(error.failures, error_hits);
+    assert_eq!(error.failure_rate, 100.0);
+    assert_eq!(error.status_codes[&503], error_hits);
+    assert_eq!(summary.aggregated.requests, index_hits + error_hits);
+    assert_eq!(summary.aggregated.failures, error_hits);
+
+    // Tasks, errors and thresholds are included.
+    assert_eq!(summary.tasks.len(), 2);
+    assert_eq!(summary.tasks[1].index, "1.2");
+    assert_eq!(summary.tasks[1].taskset, "LoadTest");
+    assert_eq!(summary.tasks[1].runs, error_hits);
+    assert_eq!(summary.errors.len(), 1);
+    assert_eq!(summary.errors[0].name, ERROR_PATH);
+    assert_eq!(summary.errors[0].occurrences, error_hits);
+    assert_eq!(summary.thresholds.len(), 1);
+    assert!(summary.thresholds[0].passed);
+
+    // The CSV summary has one row per value.
+    let csv = std::fs::read_to_string(csv_file).unwrap();
+    let rows: Vec<&str> = csv.lines().collect();
+    assert_eq!(rows[0], "section,key,field,value");
+    assert!(rows.contains(&format!("summary,,schema_version,{}", SUMMARY_SCHEMA_VERSION).as_str()));
+    assert!(rows.contains(&"configuration,,users,2"));
+    assert!(rows.contains(&format!("request,GET /,requests,{}", index_hits).as_str()));
+    assert!(rows.contains(&format!("request,GET /error,status_codes.503,{}", error_hits).as_str()));
+    assert!(rows
+        .iter()
+        .any(|row| row.starts_with("request,GET /,response_times.percentiles.p95,")));
+    assert!(rows.contains(&format!("aggregated,,requests,{}", index_hits + error_hits).as_str()));
+    assert!(rows.contains(&"task,1.1,taskset,LoadTest"));
+    assert!(rows.contains(&"threshold,GET /:p95<5s,passed,true"));
+
+    common::cleanup_files(vec![json_file, csv_file]);
+}
+
+#[tokio::test]
+// Write a JUnit XML report, in which failed requests and thresholds fail test cases.
+async fn test_junit_xml() {
+    let junit_file = "junit-test.xml";
+
+    let server = MockServer::start();
+    let mock_endpoints = setup_mock_server_endpoints(&server);
+
+    let configuration = common::build_configuration(
+        &server,
+        vec![
+            "--users",
+            "2",
+            "--hatch-rate",
+            "4",
+            "--run-time",
+            "2",
+            "--no-reset-metrics",
+            "--threshold",
+            "GET /:p95<5s",
+            "--threshold",
+            "GET /:requests<1",
+            "--threshold",
+            "errors<1%",
+            "--junit-xml",
+            junit_file,
+        ],
+    );
+    let goose_attack = common::build_load_test(configuration, &get_tasks(), None, None);
+    // The report is written even though thresholds failed.
+    assert!(matches!(
+        goose_attack.execute().await,
+        Err(GooseError::ThresholdsFailed { .. })
+    ));
+
+    let index_hits = mock_endpoints[INDEX_KEY].hits();
+    let error_hits = mock_endpoints[ERROR_KEY].hits();
+    assert!(index_hits > 0);
+    assert!(error_hits > 0);
+
+    let xml = std::fs::read_to_string(junit_file).unwrap();
+    let lines: Vec<&str> = xml.lines().map(|line| line.trim()).collect();
+    assert_eq!(lines[0], r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    // Three request test cases including Aggregated, all failed, and two task test cases,
+    // which passed as the tasks didn't return errors.
+    assert!(lines[1].starts_with(r#"<testsuites name="goose" tests="5" failures="3" "#));
+    assert!(lines
+        .iter()
+        .any(|line| line.starts_with(r#"<testsuite name="requests" tests="3" failures="3" "#)));
+    assert!(lines
+        .iter()
+        .any(|line| line.starts_with(r#"<testsuite name="tasks" tests="2" failures="0" "#)));
+
+    // Request test cases fail with their errors and any failed thresholds.
+    let failure = |name: &str| {
+        let position = lines
+            .iter()
+            .position(|line| line.starts_with(&format!(r#"<testcase name="{}" "#, name)))
+            .unwrap();
+        lines[position + 1]
+    };
+    assert_eq!(
+        failure("GET /"),
+        format!(
+            r#"<failure message="threshold GET /:requests&lt;1 failed: {}"></failure>"#,
+            index_hits
+        )
+    );
+    assert_eq!(
+        failure("GET /error"),
+        format!(
+            r#"<failure message="{} of {} requests failed">503 Service Unavailable: /error ({} times)</failure>"#,
+            error_hits, error_hits, error_hits
+        )
+    );
+    assert!(
+        failure("Aggregated").starts_with(r#"<failure message="threshold errors&lt;1% failed: "#)
+    );
+    // Task test cases are grouped by task set.
+    for name in &["1.1", "1.2"] {
+        assert!(lines.iter().any(|line| line.starts_with(&format!(
+            r#"<testcase name="{}" classname="LoadTest" "#,
+            name
+        )) && line.ends_with("/>")));
+    }
+
+    common::cleanup_files(vec![junit_file]);
+}
+
+#[test]
+// Test that invalid configurations are rejected.
+fn test_summary_invalid() {
+    let server = MockServer::start();
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+
+    for options in vec![
+        // Summaries can't be written if metrics are disabled.
+        vec!["--summary-json", "summary.json", "--no-metrics"],
+        vec!["--summary-csv", "summary.csv", "--no-metrics"],
+        vec!["--junit-xml", "junit.xml", "--no-metrics"],
+    ] {
+        let configuration = common::build_configuration(&server, options);
+        let goose_attack = common::build_load_test(configuration, &get_tasks(), None, None);
+        assert!(runtime.block_on(goose_attack.execute()).is_err());
+    }
+}
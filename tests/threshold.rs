@@ -0,0 +1,190 @@
+use httpmock::{Method::GET, Mock, MockServer};
+use std::time::{Duration, Instant};
+
+mod common;
+
+use goose::goose::GooseTaskSet;
+use goose::prelude::*;
+
+// Paths used in load tests performed during these tests.
+const INDEX_PATH: &str = "/";
+const ERROR_PATH: &str = "/error";
+
+// Indexes to the above paths.
+const INDEX_KEY: usize = 0;
+const ERROR_KEY: usize = 1;
+
+// Test task.
+pub async fn get_index(user: &mut GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+// Test task.
+pub async fn get_error(user: &mut GooseUser) -> GooseTaskResult {
+    let _goose = user.get(ERROR_PATH).await?;
+    Ok(())
+}
+
+// All tests in this file run against common endpoints.
+fn setup_mock_server_endpoints(server: &MockServer) -> Vec<Mock> {
+    vec![
+        // First set up INDEX_PATH, store in vector at INDEX_KEY.
+        server.mock(|when, then| {
+            when.method(GET).path(INDEX_PATH);
+            then.status(200);
+        }),
+        // Next set up ERROR_PATH, store in vector at ERROR_KEY.
+        server.mock(|when, then| {
+            when.method(GET).path(ERROR_PATH);
+            then.status(503);
+        }),
+    ]
+}
+
+// Returns the appropriate taskset needed to build these tests.
+fn get_tasks() -> GooseTaskSet {
+    taskset!("LoadTest")
+        .set_wait_time(Duration::from_millis(100), Duration::from_millis(100))
+        .unwrap()
+        .register_task(task!(get_index))
+        .register_task(task!(get_error))
+}
+
+#[tokio::test]
+// All thresholds pass, the load test succeeds and the results are in the metrics.
+async fn test_thresholds_pass() {
+    let server = MockServer::start();
+    let mock_endpoints = setup_mock_server_endpoints(&server);
+
+    let configuration = common::build_configuration(
+        &server,
+        vec![
+            "--users",
+            "2",
+            "--hatch-rate",
+            "4",
+            "--run-time",
+            "2",
+            "--threshold",
+            "GET /:p95<5s",
+            "--threshold",
+            "GET /:errors<1%",
+        ],
+    );
+    let goose_attack = common::build_load_test(configuration, &get_tasks(), None, None)
+        .set_threshold("GET /error:errors>=1")
+        .unwrap();
+    let goose_metrics = common::run_load_test(goose_attack, None).await;
+
+    assert!(mock_endpoints[INDEX_KEY].hits() > 0);
+    assert!(mock_endpoints[ERROR_KEY].hits() > 0);
+
+    // Thresholds set with the builder are evaluated before those set with `--threshold`.
+    let thresholds: Vec<&str> = goose_metrics
+        .thresholds
+        .iter()
+        .map(|result| result.threshold.as_str())
+        .collect();
+    assert_eq!(
+        thresholds,
+        vec!["GET /error:errors>=1", "GET /:p95<5s", "GET /:errors<1%"]
+    );
+    assert!(goose_metrics.thresholds.iter().all(|result| result.passed));
+    assert_eq!(goose_metrics.thresholds[2].value, Some(0.0));
+    assert_eq!(goose_metrics.thresholds[2].unit, "%");
+}
+
+#[tokio::test]
+// A failed threshold returns an error.
+async fn test_thresholds_fail() {
+    let server = MockServer::start();
+    let mock_endpoints = setup_mock_server_endpoints(&server);
+
+    let configuration = common::build_configuration(
+        &server,
+        vec![
+            "--users",
+            "2",
+            "--hatch-rate",
+            "4",
+            "--run-time",
+            "2",
+            "--threshold",
+            "GET /:p95<5s",
+            "--threshold",
+            "errors<1%",
+        ],
+    );
+    let goose_attack = common::build_load_test(configuration, &get_tasks(), None, None);
+
+    match goose_attack.execute().await {
+        Err(GooseError::ThresholdsFailed { detail }) => {
+            // Only the failed threshold is included in the error.
+            assert!(detail.contains("errors<1%"));
+            assert!(!detail.contains("GET /:p95<5s"));
+        }
+        _ => panic!("thresholds didn't fail"),
+    }
+    assert!(mock_endpoints[ERROR_KEY].hits() > 0);
+}
+
+#[tokio::test]
+// The load test stops early once a threshold can no longer be met.
+async fn test_threshold_abort() {
+    let server = MockServer::start();
+    let mock_endpoints = setup_mock_server_endpoints(&server);
+
+    let configuration = common::build_configuration(
+        &server,
+        vec![
+            "--users",
+            "2",
+            "--hatch-rate",
+            "4",
+            "--run-time",
+            "30",
+            "--threshold",
+            "GET /error:errors<1",
+            "--threshold-abort",
+        ],
+    );
+    let goose_attack = common::build_load_test(configuration, &get_tasks(), None, None);
+
+    let started = Instant::now();
+    assert!(matches!(
+        goose_attack.execute().await,
+        Err(GooseError::ThresholdsFailed { .. })
+    ));
+    // The load test stopped long before the configured run time.
+    assert!(started.elapsed() < Duration::from_secs(15));
+    assert!(mock_endpoints[ERROR_KEY].hits() > 0);
+}
+
+#[test]
+// Test that invalid configurations are rejected.
+fn test_thresholds_invalid() {
+    let server = MockServer::start();
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+
+    for options in vec![
+        // Unknown metric.
+        vec!["--threshold", "GET /:latency<300ms"],
+        // Missing operator.
+        vec!["--threshold", "errors"],
+        // Thresholds can't be evaluated if metrics are disabled.
+        vec!["--threshold", "errors<1%", "--no-metrics"],
+    ] {
+        let configuration = common::build_configuration(&server, options);
+        let goose_attack = common::build_load_test(configuration, &get_tasks(), None, None);
+        assert!(runtime.block_on(goose_attack.execute()).is_err());
+    }
+
+    // Invalid thresholds are also rejected by the builder.
+    let configuration = common::build_configuration(&server, vec![]);
+    assert!(
+        common::build_load_test(configuration, &get_tasks(), None, None)
+            .set_threshold("p95<300ms<")
+            .is_err()
+    );
+}
@@ -0,0 +1,197 @@
+use httpmock::{
+    Method::{GET, POST},
+    Mock, MockServer,
+};
+use std::time::Duration;
+
+mod common;
+
+use goose::goose::GooseTaskSet;
+use goose::prelude::*;
+use goose::summary::GooseSummary;
+
+// Paths used in load tests performed during these tests.
+const LOGIN_FORM_PATH: &str = "/user/login";
+const LOGIN_PATH: &str = "/user/login";
+const CART_PATH: &str = "/cart";
+const CHECKOUT_PATH: &str = "/checkout";
+const ERROR_PATH: &str = "/error";
+
+// Indexes to the above paths.
+const LOGIN_FORM_KEY: usize = 0;
+const LOGIN_KEY: usize = 1;
+const CART_KEY: usize = 2;
+const CHECKOUT_KEY: usize = 3;
+const ERROR_KEY: usize = 4;
+
+// Names of the transactions.
+const LOGIN: &str = "login";
+const CHECKOUT: &str = "checkout";
+const BROKEN: &str = "broken";
+const RECOVERED: &str = "recovered";
+
+// Test task.
+pub async fn login(user: &mut GooseUser) -> GooseTaskResult {
+    user.begin_transaction(LOGIN);
+    let _goose = user.get(LOGIN_FORM_PATH).await?;
+    let _goose = user.post(LOGIN_PATH, "name=foo&pass=bar").await?;
+    user.end_transaction(LOGIN)
+}
+
+// Test task.
+pub async fn add_to_cart(user: &mut GooseUser) -> GooseTaskResult {
+    // The transaction ends in the next task.
+    user.begin_transaction(CHECKOUT);
+    let _goose = user.post(CART_PATH, "item=7").await?;
+    Ok(())
+}
+
+// Test task.
+pub async fn checkout(user: &mut GooseUser) -> GooseTaskResult {
+    let _goose = user.post(CHECKOUT_PATH, "confirm=1").await?;
+    user.end_transaction(CHECKOUT)
+}
+
+// Test task.
+pub async fn broken(user: &mut GooseUser) -> GooseTaskResult {
+    let _ = user
+        .transaction(BROKEN, |user| {
+            Box::pin(async move {
+                let _goose = user.get(ERROR_PATH).await?;
+                Ok(())
+            })
+        })
+        .await;
+
+    // A transaction can only end once.
+    assert!(matches!(
+        user.end_transaction(BROKEN),
+        Err(GooseTaskError::TransactionNotStarted { .. })
+    ));
+    Ok(())
+}
+
+// Test task.
+pub async fn recovered(user: &mut GooseUser) -> GooseTaskResult {
+    // A request fails before the transaction begins.
+    let mut goose = user.get(ERROR_PATH).await?;
+
+    user.begin_transaction(RECOVERED);
+    let _goose = user.get(ERROR_PATH).await?;
+    // Marking the earlier request a success doesn't hide the failure of the transaction.
+    user.set_success(&mut goose.request)?;
+    user.end_transaction(RECOVERED)
+}
+
+// All tests in this file run against common endpoints.
+fn setup_mock_server_endpoints(server: &MockServer) -> Vec<Mock> {
+    vec![
+        server.mock(|when, then| {
+            when.method(GET).path(LOGIN_FORM_PATH);
+            then.status(200);
+        }),
+        server.mock(|when, then| {
+            when.method(POST).path(LOGIN_PATH);
+            then.status(200);
+        }),
+        server.mock(|when, then| {
+            when.method(POST).path(CART_PATH);
+            then.status(200);
+        }),
+        server.mock(|when, then| {
+            when.method(POST).path(CHECKOUT_PATH);
+            then.status(200);
+        }),
+        server.mock(|when, then| {
+            when.method(GET).path(ERROR_PATH);
+            then.status(503);
+        }),
+    ]
+}
+
+// Returns the appropriate taskset needed to build these tests.
+fn get_tasks() -> GooseTaskSet {
+    taskset!("LoadTest")
+        .set_wait_time(Duration::from_millis(100), Duration::from_millis(100))
+        .unwrap()
+        .register_task(task!(login))
+        .register_task(task!(add_to_cart))
+        .register_task(task!(checkout))
+        .register_task(task!(broken))
+        .register_task(task!(recovered))
+}
+
+#[tokio::test]
+// Time groups of requests as transactions, within and across tasks.
+async fn test_transactions() {
+    const REPORT_FILE: &str = "transaction-report.html";
+
+    let server = MockServer::start();
+    let mock_endpoints = setup_mock_server_endpoints(&server);
+
+    let configuration = common::build_configuration(
+        &server,
+        vec![
+            "--users",
+            "2",
+            "--hatch-rate",
+            "4",
+            "--run-time",
+            "2",
+            "--no-reset-metrics",
+            "--report-file",
+            REPORT_FILE,
+        ],
+    );
+    let goose_metrics = common::run_load_test(
+        common::build_load_test(configuration.clone(), &get_tasks(), None, None),
+        None,
+    )
+    .await;
+
+    for key in &[LOGIN_FORM_KEY, LOGIN_KEY, CART_KEY, CHECKOUT_KEY, ERROR_KEY] {
+        assert!(mock_endpoints[*key].hits() > 0);
+    }
+
+    // Transactions are sorted by name.
+    let names: Vec<&str> = goose_metrics
+        .transactions
+        .keys()
+        .map(|name| name.as_str())
+        .collect();
+    assert_eq!(names, vec![BROKEN, CHECKOUT, LOGIN, RECOVERED]);
+
+    // Each transaction ended after its last request.
+    let login = &goose_metrics.transactions[LOGIN];
+    assert!(login.counter > 0);
+    assert!(login.counter <= mock_endpoints[LOGIN_KEY].hits());
+    assert_eq!(login.fail_count, 0);
+    let checkout = &goose_metrics.transactions[CHECKOUT];
+    assert!(checkout.counter > 0);
+    assert!(checkout.counter <= mock_endpoints[CHECKOUT_KEY].hits());
+    assert_eq!(checkout.fail_count, 0);
+
+    // A transaction with a failed request fails.
+    let broken = &goose_metrics.transactions[BROKEN];
+    assert!(broken.counter > 0);
+    assert!(broken.counter <= mock_endpoints[ERROR_KEY].hits());
+    assert_eq!(broken.fail_count, broken.counter);
+    assert_eq!(broken.success_count, 0);
+    let recovered = &goose_metrics.transactions[RECOVERED];
+    assert!(recovered.counter > 0);
+    assert_eq!(recovered.fail_count, recovered.counter);
+    assert_eq!(recovered.success_count, 0);
+
+    // Transactions are included in all reports.
+    let displayed = format!("{}", goose_metrics);
+    assert!(displayed.contains("PER TRANSACTION METRICS"));
+    let report = std::fs::read_to_string(REPORT_FILE).unwrap();
+    assert!(report.contains("<h2>Transaction Metrics</h2>"));
+    let summary = GooseSummary::new(&goose_metrics, &configuration);
+    assert_eq!(summary.transactions.len(), 4);
+    assert_eq!(summary.transactions[0].name, BROKEN);
+    assert_eq!(summary.transactions[0].failure_rate, 100.0);
+
+    // Cleanup from test.
+    common::cleanup_files(vec![REPORT_FILE]);
+}
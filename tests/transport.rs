@@ -0,0 +1,201 @@
+use async_trait::async_trait;
+use httpmock::MockServer;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+mod common;
+
+use goose::goose::GooseTaskSet;
+use goose::prelude::*;
+use goose::transport::GooseTransport;
+
+// Commands sent in load tests performed during these tests.
+const PING: &str = "PING";
+const BROKEN: &str = "BROKEN";
+
+// How many commands the line server received.
+static RECEIVED: AtomicUsize = AtomicUsize::new(0);
+
+// Sends a line over TCP, and reads the reply line.
+struct LineTransport {
+    stream: BufReader<TcpStream>,
+    url: String,
+}
+
+#[async_trait]
+impl GooseTransport for LineTransport {
+    type Request = String;
+    type Response = String;
+    type Error = String;
+
+    fn protocol(&self) -> &str {
+        "TCP"
+    }
+
+    fn url(&self) -> String {
+        self.url.clone()
+    }
+
+    async fn send(&mut self, request: String) -> Result<String, String> {
+        self.stream
+            .get_mut()
+            .write_all(format!("{}\n", request).as_bytes())
+            .await
+            .map_err(|e| e.to_string())?;
+        let mut reply = String::new();
+        self.stream
+            .read_line(&mut reply)
+            .await
+            .map_err(|e| e.to_string())?;
+        match reply.trim_end() {
+            "ERR" => Err("server error".to_string()),
+            reply => Ok(reply.to_string()),
+        }
+    }
+}
+
+// A transport that is never set.
+struct UnusedTransport;
+
+#[async_trait]
+impl GooseTransport for UnusedTransport {
+    type Request = ();
+    type Response = ();
+    type Error = String;
+
+    fn protocol(&self) -> &str {
+        "UNUSED"
+    }
+
+    async fn send(&mut self, _request: ()) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+// Test task.
+pub async fn connect(user: &mut GooseUser) -> GooseTaskResult {
+    let address = user.base_url.socket_addrs(|| None).unwrap()[0];
+    let stream = TcpStream::connect(address).await.unwrap();
+    user.set_transport(LineTransport {
+        stream: BufReader::new(stream),
+        url: format!("tcp://{}", address),
+    });
+    Ok(())
+}
+
+// Test task.
+pub async fn ping(user: &mut GooseUser) -> GooseTaskResult {
+    let goose = user
+        .transport_send::<LineTransport>("ping", PING.to_string())
+        .await?;
+    assert_eq!(goose.response.unwrap(), "PONG");
+    assert!(goose.request.success);
+    assert!(goose.request.raw.url.starts_with("tcp://127.0.0.1:"));
+    Ok(())
+}
+
+// Test task.
+pub async fn broken(user: &mut GooseUser) -> GooseTaskResult {
+    let goose = user
+        .transport_send::<LineTransport>("broken", BROKEN.to_string())
+        .await?;
+    assert_eq!(goose.response.unwrap_err(), "server error");
+    assert!(!goose.request.success);
+
+    // Only transports that were set can send requests.
+    assert!(matches!(
+        user.transport_send::<UnusedTransport>("unused", ()).await,
+        Err(GooseTaskError::TransportNotSet { .. })
+    ));
+    Ok(())
+}
+
+// Start a TCP server replying PONG to PING, and ERR to everything else.
+async fn start_line_server() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut stream = BufReader::new(socket);
+                let mut line = String::new();
+                while let Ok(length) = stream.read_line(&mut line).await {
+                    if length == 0 {
+                        break;
+                    }
+                    RECEIVED.fetch_add(1, Ordering::SeqCst);
+                    let reply = if line.trim_end() == PING {
+                        "PONG\n"
+                    } else {
+                        "ERR\n"
+                    };
+                    if stream.get_mut().write_all(reply.as_bytes()).await.is_err() {
+                        break;
+                    }
+                    line.clear();
+                }
+            });
+        }
+    });
+    format!("http://{}", address)
+}
+
+// Returns the appropriate taskset needed to build these tests.
+fn get_tasks() -> GooseTaskSet {
+    taskset!("LoadTest")
+        .set_wait_time(Duration::from_millis(100), Duration::from_millis(100))
+        .unwrap()
+        .register_task(task!(connect).set_on_start())
+        .register_task(task!(ping))
+        .register_task(task!(broken))
+}
+
+#[tokio::test]
+// Send requests of another protocol with a transport, recorded as requests.
+async fn test_transport() {
+    let server = MockServer::start();
+    let host = start_line_server().await;
+
+    let configuration = common::build_configuration(
+        &server,
+        vec![
+            "--host",
+            &host,
+            "--users",
+            "2",
+            "--hatch-rate",
+            "4",
+            "--run-time",
+            "2",
+            "--no-reset-metrics",
+        ],
+    );
+    let goose_metrics = common::run_load_test(
+        common::build_load_test(configuration, &get_tasks(), None, None),
+        None,
+    )
+    .await;
+
+    // Requests are recorded with the protocol as their method.
+    let ping = &goose_metrics.requests["TCP ping"];
+    assert!(ping.success_count > 0);
+    assert_eq!(ping.fail_count, 0);
+
+    // Requests the transport returned an error for are failures.
+    let broken = &goose_metrics.requests["TCP broken"];
+    assert!(broken.fail_count > 0);
+    assert_eq!(broken.success_count, 0);
+    assert!(goose_metrics
+        .errors
+        .values()
+        .any(|error| error.error == "server error: broken"));
+
+    // Each request was received by the server.
+    assert_eq!(
+        ping.success_count + broken.fail_count,
+        RECEIVED.load(Ordering::SeqCst)
+    );
+    assert!(!goose_metrics.requests.keys().any(|key| key.contains("unused")));
+}
@@ -0,0 +1,155 @@
+use httpmock::{Method::GET, Mock, MockServer};
+use std::time::Duration;
+
+mod common;
+
+use goose::goose::GooseMethod;
+use goose::prelude::*;
+use goose::validation::GooseValidation;
+
+// Paths used in load tests performed during these tests.
+const INDEX_PATH: &str = "/";
+const USER_PATH: &str = "/user";
+const GONE_PATH: &str = "/gone";
+const SLOW_PATH: &str = "/slow";
+
+// Indexes to the above paths.
+const INDEX_KEY: usize = 0;
+const USER_KEY: usize = 1;
+const GONE_KEY: usize = 2;
+const SLOW_KEY: usize = 3;
+
+// Test task.
+pub async fn get_index(user: &mut GooseUser) -> GooseTaskResult {
+    let validation = GooseValidation::new()
+        .status_range(200..=299)
+        .header_value("content-type", "text/html")
+        .title("Goose")
+        .body_contains("Welcome");
+    let goose = user.get(INDEX_PATH).await?;
+    let validated = goose.validate(user, &validation).await?;
+    assert!(validated.body.contains("Welcome"));
+    Ok(())
+}
+
+// Test task.
+pub async fn get_user(user: &mut GooseUser) -> GooseTaskResult {
+    // The user is named alice, not bob.
+    let validation = GooseValidation::new()
+        .json_exists("/id")
+        .json_value("/name", "bob".into());
+    let goose = user.get(USER_PATH).await?;
+    let _ = goose.validate(user, &validation).await;
+    Ok(())
+}
+
+// Test task.
+pub async fn get_gone(user: &mut GooseUser) -> GooseTaskResult {
+    // A 410 is expected, so it's a success.
+    let validation = GooseValidation::new().status(410);
+    let goose = user.get(GONE_PATH).await?;
+    goose.validate(user, &validation).await?;
+    Ok(())
+}
+
+// Test task.
+pub async fn get_slow(user: &mut GooseUser) -> GooseTaskResult {
+    let validation = GooseValidation::new().max_response_time(Duration::from_millis(10));
+    let goose = user.get(SLOW_PATH).await?;
+    let _ = goose.validate(user, &validation).await;
+    Ok(())
+}
+
+// All tests in this file run against common endpoints.
+fn setup_mock_server_endpoints(server: &MockServer) -> Vec<Mock> {
+    vec![
+        server.mock(|when, then| {
+            when.method(GET).path(INDEX_PATH);
+            then.status(200)
+                .header("content-type", "text/html")
+                .body("<html><head><title>Goose</title></head><body>Welcome</body></html>");
+        }),
+        server.mock(|when, then| {
+            when.method(GET).path(USER_PATH);
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(r#"{"id": 1, "name": "alice"}"#);
+        }),
+        server.mock(|when, then| {
+            when.method(GET).path(GONE_PATH);
+            then.status(410);
+        }),
+        server.mock(|when, then| {
+            when.method(GET).path(SLOW_PATH);
+            then.status(200).delay(Duration::from_millis(50));
+        }),
+    ]
+}
+
+#[tokio::test]
+// Validate responses, marking requests that fail a check as failures.
+async fn test_validation() {
+    let server = MockServer::start();
+    let mock_endpoints = setup_mock_server_endpoints(&server);
+
+    let configuration = common::build_configuration(
+        &server,
+        vec![
+            "--users",
+            "2",
+            "--hatch-rate",
+            "4",
+            "--run-time",
+            "2",
+            "--no-reset-metrics",
+        ],
+    );
+    let goose_attack = common::build_load_test(
+        configuration,
+        &taskset!("LoadTest")
+            .register_task(task!(get_index))
+            .register_task(task!(get_user))
+            .register_task(task!(get_gone))
+            .register_task(task!(get_slow)),
+        None,
+        None,
+    );
+    let goose_metrics = common::run_load_test(goose_attack, None).await;
+
+    let metrics = |path: &str| {
+        goose_metrics
+            .requests
+            .get(&format!("GET {}", path))
+            .unwrap()
+    };
+
+    // Valid responses are successes, including the expected 410.
+    for (key, path) in &[(INDEX_KEY, INDEX_PATH), (GONE_KEY, GONE_PATH)] {
+        assert!(mock_endpoints[*key].hits() > 0);
+        mock_endpoints[*key].assert_hits(metrics(path).success_count);
+        assert_eq!(metrics(path).fail_count, 0);
+    }
+
+    // Responses failing a check are failures.
+    for (key, path) in &[(USER_KEY, USER_PATH), (SLOW_KEY, SLOW_PATH)] {
+        assert!(mock_endpoints[*key].hits() > 0);
+        mock_endpoints[*key].assert_hits(metrics(path).fail_count);
+        assert_eq!(metrics(path).success_count, 0);
+    }
+
+    // Each failed check is grouped into a single error.
+    assert_eq!(goose_metrics.errors.len(), 2);
+    for (path, error) in &[
+        (USER_PATH, "json /name isn't \"bob\": /user"),
+        (SLOW_PATH, "response time over 10ms: /slow"),
+    ] {
+        let error_metric = goose_metrics
+            .errors
+            .values()
+            .find(|error_metric| error_metric.name == *path)
+            .unwrap();
+        assert_eq!(error_metric.error, *error);
+        assert_eq!(error_metric.method, GooseMethod::Get);
+        assert_eq!(error_metric.occurrences, metrics(path).fail_count);
+    }
+}
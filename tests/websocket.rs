@@ -0,0 +1,196 @@
+use futures::{SinkExt, StreamExt};
+use httpmock::MockServer;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::Message;
+
+mod common;
+
+use goose::goose::GooseTaskSet;
+use goose::prelude::*;
+
+// Paths used in load tests performed during these tests.
+const ECHO_PATH: &str = "/echo";
+const NO_WEBSOCKET_PATH: &str = "/no-websocket";
+
+// The message the echo server sends to each new connection.
+const WELCOME: &str = "welcome";
+
+// How many messages the echo server received.
+static RECEIVED: AtomicUsize = AtomicUsize::new(0);
+
+// Test task.
+pub async fn connect(user: &mut GooseUser) -> GooseTaskResult {
+    user.websocket_connect(ECHO_PATH).await?;
+    let welcome = user.websocket_receive(Duration::from_secs(1)).await?;
+    assert_eq!(welcome.to_text().unwrap(), WELCOME);
+    Ok(())
+}
+
+// Test task.
+pub async fn chat(user: &mut GooseUser) -> GooseTaskResult {
+    user.websocket_send(Message::text("one")).await?;
+    user.websocket_send(Message::text("two")).await?;
+    // The echo of "one" is skipped.
+    let two = user
+        .websocket_wait_for(
+            |message| message.to_text().unwrap_or("") == "two",
+            Duration::from_secs(1),
+        )
+        .await?;
+    assert_eq!(two.to_text().unwrap(), "two");
+
+    let three = user
+        .websocket_round_trip(
+            Message::text("three"),
+            |message| message.to_text().unwrap_or("") == "three",
+            Duration::from_secs(1),
+        )
+        .await?;
+    assert_eq!(three.to_text().unwrap(), "three");
+    Ok(())
+}
+
+// Test task.
+pub async fn wait_too_long(user: &mut GooseUser) -> GooseTaskResult {
+    let result = user
+        .websocket_wait_for(
+            |message| message.to_text().unwrap_or("") == "never",
+            Duration::from_millis(50),
+        )
+        .await;
+    assert!(matches!(result, Err(GooseTaskError::RequestFailed { .. })));
+    Ok(())
+}
+
+// Test task.
+pub async fn disconnect(user: &mut GooseUser) -> GooseTaskResult {
+    user.websocket_close().await?;
+    // The connection can no longer be used.
+    assert!(matches!(
+        user.websocket_send(Message::text("gone")).await,
+        Err(GooseTaskError::WebSocketNotConnected)
+    ));
+    Ok(())
+}
+
+// Test task.
+pub async fn connect_refused(user: &mut GooseUser) -> GooseTaskResult {
+    let result = user.websocket_connect(NO_WEBSOCKET_PATH).await;
+    assert!(matches!(result, Err(GooseTaskError::WebSocket(_))));
+    Ok(())
+}
+
+// Start a WebSocket server that welcomes each connection, then echoes all messages.
+async fn start_echo_server() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut stream = tokio_tungstenite::accept_async(socket).await.unwrap();
+                stream.send(Message::text(WELCOME)).await.unwrap();
+                while let Some(Ok(message)) = stream.next().await {
+                    if message.is_text() {
+                        RECEIVED.fetch_add(1, Ordering::SeqCst);
+                        if stream.send(message).await.is_err() {
+                            break;
+                        }
+                    }
+                }
+            });
+        }
+    });
+    format!("http://{}", address)
+}
+
+// Returns the appropriate taskset needed to build these tests.
+fn get_tasks() -> GooseTaskSet {
+    taskset!("LoadTest")
+        .set_wait_time(Duration::from_millis(100), Duration::from_millis(100))
+        .unwrap()
+        .register_task(task!(connect).set_on_start())
+        .register_task(task!(chat))
+        .register_task(task!(wait_too_long))
+        .register_task(task!(disconnect).set_on_stop())
+}
+
+#[tokio::test]
+// Open WebSocket connections, recording messages sent and received as requests.
+async fn test_websocket() {
+    let server = MockServer::start();
+    let host = start_echo_server().await;
+
+    let configuration = common::build_configuration(
+        &server,
+        vec![
+            "--host",
+            &host,
+            "--users",
+            "2",
+            "--hatch-rate",
+            "4",
+            "--run-time",
+            "2",
+            "--no-reset-metrics",
+            "--status-codes",
+        ],
+    );
+    let goose_metrics = common::run_load_test(
+        common::build_load_test(configuration, &get_tasks(), None, None),
+        None,
+    )
+    .await;
+
+    // Each user opened a connection, welcomed by the server.
+    let connect = &goose_metrics.requests["WS CONNECT /echo"];
+    assert_eq!(connect.success_count, 2);
+    assert_eq!(connect.fail_count, 0);
+    assert_eq!(connect.status_code_counts[&101], 2);
+
+    // Sent messages are counted, and each one was received by the server.
+    let send = &goose_metrics.requests["WS SEND /echo"];
+    let round_trip = &goose_metrics.requests["WS ROUNDTRIP /echo"];
+    assert!(send.success_count > 0);
+    assert_eq!(send.fail_count, 0);
+    assert!(round_trip.success_count > 0);
+    assert_eq!(round_trip.fail_count, 0);
+    assert_eq!(
+        send.success_count + round_trip.success_count,
+        RECEIVED.load(Ordering::SeqCst)
+    );
+
+    // Messages that weren't received in time are failures.
+    let receive = &goose_metrics.requests["WS RECEIVE /echo"];
+    assert!(receive.success_count > 0);
+    assert!(receive.fail_count > 0);
+    assert!(goose_metrics
+        .errors
+        .values()
+        .any(|error| error.error == "timed out: /echo"));
+}
+
+#[tokio::test]
+// Refused WebSocket connections are recorded as failed requests.
+async fn test_websocket_refused() {
+    // The mock server doesn't upgrade connections to WebSockets.
+    let server = MockServer::start();
+
+    let configuration = common::build_configuration(&server, vec!["--status-codes"]);
+    let goose_metrics = common::run_load_test(
+        common::build_load_test(
+            configuration,
+            &taskset!("LoadTest").register_task(task!(connect_refused)),
+            None,
+            None,
+        ),
+        None,
+    )
+    .await;
+
+    let connect = &goose_metrics.requests["WS CONNECT /no-websocket"];
+    assert!(connect.fail_count > 0);
+    assert_eq!(connect.success_count, 0);
+    assert!(connect.status_code_counts[&404] > 0);
+}